@@ -16,8 +16,13 @@ fn load_config(include_example_project: bool) -> Config {
 
     let mut config = Config::default();
     config.append(
-        &Config::read_file_path(&repo_root.join("vhdl_libraries").join("vhdl_ls.toml"))
-            .expect("Failed to read installed config file"),
+        &Config::read_file_path(
+            &repo_root
+                .join("vhdl_lang")
+                .join("vhdl_libraries")
+                .join("vhdl_ls.toml"),
+        )
+        .expect("Failed to read installed config file"),
         &mut MessagePrinter::default(),
     );
 
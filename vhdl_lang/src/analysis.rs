@@ -9,6 +9,7 @@ mod analyze;
 mod assignment;
 mod association;
 mod concurrent;
+mod declaration_details;
 mod declarative;
 mod design_unit;
 mod expression;
@@ -17,6 +18,7 @@ mod lock;
 mod names;
 mod overloaded;
 mod package_instance;
+mod psl;
 mod range;
 mod root;
 mod scope;
@@ -33,4 +35,5 @@ pub(crate) mod tests;
 
 pub(crate) use root::{Library, LockedUnit};
 
+pub use self::declaration_details::{DeclarationDetails, InterfaceDetails};
 pub use self::root::{DesignRoot, EntHierarchy};
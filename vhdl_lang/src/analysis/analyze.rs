@@ -10,11 +10,13 @@ use crate::ast::*;
 use crate::data::error_codes::ErrorCode;
 use crate::data::*;
 use crate::named_entity::*;
+use crate::standard::VHDLStandard;
 use crate::syntax::TokenAccess;
 use crate::TokenSpan;
 use fnv::FnvHashSet;
 use std::cell::RefCell;
 use std::ops::Deref;
+use std::sync::Arc;
 
 /// Indicates that a circular dependency is found at the position denoted by `reference`.
 ///
@@ -191,6 +193,11 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         }
     }
 
+    /// The VHDL standard of the library of the current unit
+    pub fn standard(&self) -> VHDLStandard {
+        self.root.library_standard(self.current_unit.library_name())
+    }
+
     pub fn work_library_name(&self) -> &Symbol {
         self.current_unit.library_name()
     }
@@ -310,6 +317,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         let name = self.current_unit.primary_name();
         units
             .get(&UnitKey::Secondary(name.clone(), name.clone()))
+            .map(Arc::as_ref)
             .filter(|&unit| unit.kind() == AnyKind::Secondary(SecondaryKind::PackageBody))
     }
 
@@ -317,7 +325,7 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         self.get_package_body().is_some()
     }
 
-    fn get_analysis(
+    pub(super) fn get_analysis(
         &self,
         use_pos: Option<&SrcPos>,
         unit: &'a LockedUnit,
@@ -337,7 +345,11 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         }
     }
 
-    fn get_primary_unit(&self, library_name: &Symbol, name: &Symbol) -> Option<&'a LockedUnit> {
+    pub(super) fn get_primary_unit(
+        &self,
+        library_name: &Symbol,
+        name: &Symbol,
+    ) -> Option<&'a LockedUnit> {
         let units = self.root.get_library_units(library_name)?;
         if let Some(unit) = units.get(&UnitKey::Primary(name.clone())) {
             return Some(unit);
@@ -403,6 +415,16 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             }
         }
 
+        if self.root.is_black_box_library(library_name)
+            || self
+                .get_primary_unit(library_name, entity_name)
+                .is_some_and(LockedUnit::is_stub)
+        {
+            // The architectures of an entity that is declared in a stub file
+            // or that is part of a black-box library are not available
+            return Err(EvalError::Unknown);
+        }
+
         bail!(
             diagnostics,
             Diagnostic::new(
@@ -446,6 +468,11 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             }
         }
 
+        if self.root.is_black_box_library(library_name) {
+            // Missing units of a black-box library are opaque
+            return Err(EvalError::Unknown);
+        }
+
         bail!(
             diagnostics,
             Diagnostic::new(
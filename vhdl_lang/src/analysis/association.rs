@@ -500,13 +500,29 @@ impl<'a> AnalyzeContext<'a, '_> {
                                     diagnostics,
                                 )?;
                             }
-                            self.expr_pos_with_ttyp(
-                                scope,
-                                resolved_formal.type_mark,
-                                actual.span,
-                                expr,
-                                diagnostics,
-                            )?;
+                            let is_out = matches!(
+                                resolved_formal.iface.kind(),
+                                AnyEntKind::Object(obj)
+                                    if obj.mode() == Some(&InterfaceMode::Simple(Mode::Out))
+                            );
+                            if let (Expression::Name(name), true) = (&mut *expr, is_out) {
+                                // The actual of a formal of mode out is not read
+                                self.expression_name_with_ttyp(
+                                    scope,
+                                    actual.span,
+                                    name.as_mut(),
+                                    resolved_formal.type_mark,
+                                    diagnostics,
+                                )?;
+                            } else {
+                                self.expr_pos_with_ttyp(
+                                    scope,
+                                    resolved_formal.type_mark,
+                                    actual.span,
+                                    expr,
+                                    diagnostics,
+                                )?;
+                            }
                         } else {
                             self.expr_pos_unknown_ttyp(scope, actual.span, expr, diagnostics)?;
                         }
@@ -215,7 +215,7 @@ impl<'a> AnalyzeContext<'a, '_> {
                             severity,
                         },
                 } = assert;
-                self.boolean_expr(scope, condition, diagnostics)?;
+                self.analyze_concurrent_assert_condition(scope, condition, diagnostics)?;
                 if let Some(expr) = report {
                     self.expr_with_ttyp(scope, self.string(), expr, diagnostics)?;
                 }
@@ -223,6 +223,9 @@ impl<'a> AnalyzeContext<'a, '_> {
                     self.expr_with_ttyp(scope, self.severity_level(), expr, diagnostics)?;
                 }
             }
+            ConcurrentStatement::PslDirective(ref mut directive) => {
+                self.analyze_psl_directive(scope, directive, diagnostics)?;
+            }
         };
         Ok(())
     }
@@ -282,7 +285,7 @@ impl<'a> AnalyzeContext<'a, '_> {
                     diagnostics,
                 ))?
                 else {
-                    return Ok(());
+                    return self.analyze_unknown_instance(scope, instance, diagnostics);
                 };
                 match resolved {
                     ResolvedName::Design(ent) => match ent.kind() {
@@ -346,6 +349,13 @@ impl<'a> AnalyzeContext<'a, '_> {
                 }
             }
             InstantiatedUnit::Component(ref mut component_name) => {
+                if let Name::Designator(designator) = &component_name.item {
+                    // The component might be declared by an opaque unit of a black-box library
+                    if scope.may_be_black_box_name(&designator.item) {
+                        return self.analyze_unknown_instance(scope, instance, diagnostics);
+                    }
+                }
+
                 let Some(resolved) = as_fatal(self.name_resolve(
                     scope,
                     component_name.span,
@@ -353,7 +363,7 @@ impl<'a> AnalyzeContext<'a, '_> {
                     diagnostics,
                 ))?
                 else {
-                    return Ok(());
+                    return self.analyze_unknown_instance(scope, instance, diagnostics);
                 };
 
                 let ent = match resolved {
@@ -428,6 +438,18 @@ impl<'a> AnalyzeContext<'a, '_> {
         }
     }
 
+    /// The instantiated unit is unknown, i.e. an opaque unit of a black-box library.
+    /// Any association is accepted, only the actuals are analyzed.
+    fn analyze_unknown_instance(
+        &self,
+        scope: &Scope<'a>,
+        instance: &mut InstantiationStatement,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        self.analyze_map_aspect(scope, &mut instance.generic_map, diagnostics)?;
+        self.analyze_map_aspect(scope, &mut instance.port_map, diagnostics)
+    }
+
     pub fn analyze_map_aspect(
         &self,
         scope: &Scope<'a>,
@@ -464,17 +486,19 @@ impl<'a> AnalyzeContext<'a, '_> {
                         ),
                         ErrorCode::DisallowedInSensitivityList,
                     )
-                } else if object_name.base.mode() == Some(&InterfaceMode::Simple(Mode::Out))
-                    && !object_name.base.is_port()
-                {
-                    diagnostics.add(
-                        name.pos(self.ctx),
-                        format!(
-                            "{} cannot be in a sensitivity list",
-                            object_name.base.describe_class()
-                        ),
-                        ErrorCode::DisallowedInSensitivityList,
-                    )
+                } else if object_name.base.mode() == Some(&InterfaceMode::Simple(Mode::Out)) {
+                    if object_name.base.is_port() {
+                        self.check_out_port_read(name.span, &name.item, diagnostics);
+                    } else {
+                        diagnostics.add(
+                            name.pos(self.ctx),
+                            format!(
+                                "{} cannot be in a sensitivity list",
+                                object_name.base.describe_class()
+                            ),
+                            ErrorCode::DisallowedInSensitivityList,
+                        )
+                    }
                 }
             }
         }
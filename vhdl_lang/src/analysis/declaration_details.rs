@@ -0,0 +1,424 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Details of a declaration beyond its formatted source, such as the comments documenting it,
+//! the interface of entities and components or the range of types.
+
+use super::root::DesignRoot;
+use crate::ast::search::*;
+use crate::ast::*;
+use crate::data::{SrcPos, Symbol};
+use crate::named_entity::*;
+use crate::syntax::{TokenAccess, TokenId};
+
+/// Details of a declaration, e.g. to show when hovering over it.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct DeclarationDetails {
+    /// The declaration formatted as VHDL
+    pub declaration: String,
+    /// The comment block directly above the declaration followed by the comment trailing it
+    pub comments: Vec<String>,
+    /// The generics of entities and components
+    pub generics: Vec<InterfaceDetails>,
+    /// The ports of entities and components
+    pub ports: Vec<InterfaceDetails>,
+    /// The entity and architecture an instance is bound to, e.g. `entity lib.ent(rtl)`
+    pub binding: Option<String>,
+    /// The base type of a subtype
+    pub base_type: Option<String>,
+    /// The range or index constraint of a type
+    pub range: Option<String>,
+}
+
+/// A generic or port of an entity or component
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct InterfaceDetails {
+    pub name: String,
+    pub mode: Option<String>,
+    pub typ: String,
+    pub default: Option<String>,
+}
+
+/// What the instantiated unit of an instance refers to
+enum InstantiatedDesign {
+    Entity(EntityId, Option<Symbol>),
+    Component(EntityId),
+    Configuration(EntityId),
+}
+
+pub(super) struct FindDeclarationDetails<'a> {
+    ent: EntRef<'a>,
+    pub(super) result: Option<DeclarationDetails>,
+    instantiated: Option<InstantiatedDesign>,
+}
+
+impl<'a> FindDeclarationDetails<'a> {
+    pub(super) fn new(ent: EntRef<'a>) -> FindDeclarationDetails<'a> {
+        FindDeclarationDetails {
+            ent,
+            result: None,
+            instantiated: None,
+        }
+    }
+}
+
+impl Searcher for FindDeclarationDetails<'_> {
+    fn search_decl(&mut self, ctx: &dyn TokenAccess, decl: FoundDeclaration<'_>) -> SearchState {
+        let Some(id) = decl.ent_id() else {
+            return NotFinished;
+        };
+
+        if self.ent.is_implicit_of(id) {
+            self.result = Some(DeclarationDetails {
+                declaration: format!(
+                    "-- {}\n\n-- Implicitly defined by:\n{}\n",
+                    self.ent.describe(),
+                    decl.ast,
+                ),
+                ..Default::default()
+            });
+            return Finished(Found);
+        } else if self.ent.id() != id {
+            return NotFinished;
+        }
+
+        let mut details = DeclarationDetails {
+            declaration: decl.ast.to_string(),
+            comments: self
+                .ent
+                .decl_pos()
+                .map(|pos| comments_of(ctx, pos))
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+
+        match decl.ast {
+            DeclarationItem::Entity(entity) => {
+                details.generics = interface_details(entity.generic_clause.as_ref());
+                details.ports = interface_details(entity.port_clause.as_ref());
+            }
+            DeclarationItem::Component(component) => {
+                details.generics = interface_details(component.generic_list.as_ref());
+                details.ports = interface_details(component.port_list.as_ref());
+            }
+            DeclarationItem::ConcurrentStatement(statement) => {
+                if let ConcurrentStatement::Instance(instance) = &statement.statement.item {
+                    self.instantiated = instantiated_design(&instance.unit);
+                }
+            }
+            DeclarationItem::Type(typ) => {
+                details.range = range_of(&typ.def);
+            }
+            _ => {}
+        }
+
+        self.result = Some(details);
+        Finished(Found)
+    }
+}
+
+impl DesignRoot {
+    /// Get the details of a declaration, see [DeclarationDetails]
+    pub fn declaration_details(&self, ent: EntRef<'_>) -> Option<DeclarationDetails> {
+        if let AnyEntKind::Library = ent.kind() {
+            return Some(DeclarationDetails {
+                declaration: format!("library {};", ent.designator()),
+                ..Default::default()
+            });
+        }
+
+        let ent = if let Related::InstanceOf(ent) = ent.related {
+            ent
+        } else {
+            ent
+        };
+
+        let mut searcher = FindDeclarationDetails::new(ent);
+        let _ = self.search(&mut searcher);
+        let mut details = searcher.result?;
+
+        if let Some(instantiated) = searcher.instantiated {
+            details.binding = self.binding_of(instantiated, ent);
+        }
+
+        if let Some(typ) = TypeEnt::from_any(ent) {
+            if let Type::Subtype(subtype) = typ.kind() {
+                let base_type = subtype.base_type();
+                details.base_type = Some(base_type.designator().to_string());
+                if details.range.is_none() {
+                    // An unconstrained subtype has the range of its type mark
+                    details.range = self
+                        .declaration_details(subtype.type_mark().into())
+                        .and_then(|type_mark| type_mark.range);
+                }
+            }
+        }
+
+        Some(details)
+    }
+
+    /// Describe the design entity of an instance.
+    /// Components are bound to the entity of the same name in the library of the instance.
+    fn binding_of(&self, instantiated: InstantiatedDesign, instance: EntRef<'_>) -> Option<String> {
+        match instantiated {
+            InstantiatedDesign::Entity(id, architecture) => {
+                let entity = self.get_ent(id);
+                let library_name = entity.library_name()?;
+                let architecture = architecture.or_else(|| {
+                    self.only_architecture_of(library_name, entity.designator().as_identifier()?)
+                });
+                Some(format_binding(library_name, entity, architecture))
+            }
+            InstantiatedDesign::Component(id) => {
+                let component = self.get_ent(id);
+                let name = component.designator().as_identifier()?;
+                let library_name = instance.library_name()?;
+                match self.get_design_entity(library_name, name) {
+                    Some(entity) => Some(format_binding(
+                        library_name,
+                        &entity,
+                        self.only_architecture_of(library_name, name),
+                    )),
+                    None => Some(format!("component {} (unbound)", component.designator())),
+                }
+            }
+            InstantiatedDesign::Configuration(id) => {
+                let configuration = self.get_ent(id);
+                Some(format!(
+                    "configuration {}.{}",
+                    configuration.library_name()?,
+                    configuration.designator()
+                ))
+            }
+        }
+    }
+
+    /// The architecture of an entity if there is exactly one.
+    /// With several architectures, the binding depends on the order of analysis.
+    fn only_architecture_of(&self, library_name: &Symbol, entity_name: &Symbol) -> Option<Symbol> {
+        let mut architectures =
+            self.get_library_units(library_name)?
+                .iter()
+                .filter_map(|(key, unit)| match key {
+                    UnitKey::Secondary(primary, architecture)
+                        if primary == entity_name
+                            && unit.kind() == AnyKind::Secondary(SecondaryKind::Architecture) =>
+                    {
+                        Some(architecture)
+                    }
+                    _ => None,
+                });
+        let architecture = architectures.next()?;
+        architectures.next().is_none().then(|| architecture.clone())
+    }
+}
+
+fn format_binding(
+    library_name: &Symbol,
+    entity: EntRef<'_>,
+    architecture: Option<Symbol>,
+) -> String {
+    match architecture {
+        Some(architecture) => format!(
+            "entity {library_name}.{}({architecture})",
+            entity.designator()
+        ),
+        None => format!("entity {library_name}.{}", entity.designator()),
+    }
+}
+
+fn instantiated_design(unit: &InstantiatedUnit) -> Option<InstantiatedDesign> {
+    match unit {
+        InstantiatedUnit::Entity(name, architecture) => Some(InstantiatedDesign::Entity(
+            name.item.get_suffix_reference()?,
+            architecture
+                .as_ref()
+                .map(|architecture| architecture.item.item.clone()),
+        )),
+        InstantiatedUnit::Component(name) => Some(InstantiatedDesign::Component(
+            name.item.get_suffix_reference()?,
+        )),
+        InstantiatedUnit::Configuration(name) => Some(InstantiatedDesign::Configuration(
+            name.item.get_suffix_reference()?,
+        )),
+    }
+}
+
+fn interface_details(list: Option<&InterfaceList>) -> Vec<InterfaceDetails> {
+    let Some(list) = list else {
+        return Vec::new();
+    };
+    let mut details = Vec::new();
+    for item in &list.items {
+        match item {
+            InterfaceDeclaration::Object(object) => {
+                let (mode, typ, default) = match &object.mode {
+                    ModeIndication::Simple(simple) => {
+                        let mode = match (&simple.mode, list.interface_type) {
+                            (Some(mode), _) => Some(mode.item.to_string()),
+                            (None, InterfaceType::Port) => Some(Mode::In.to_string()),
+                            (None, _) => None,
+                        };
+                        (
+                            mode,
+                            simple.subtype_indication.to_string(),
+                            simple.expression.as_ref().map(|expr| expr.to_string()),
+                        )
+                    }
+                    ModeIndication::View(view) => {
+                        let name = match view.kind {
+                            ModeViewIndicationKind::Array => format!("({})", view.name),
+                            ModeViewIndicationKind::Record => view.name.to_string(),
+                        };
+                        let typ = view
+                            .subtype_indication
+                            .as_ref()
+                            .map(|(_, typ)| typ.to_string())
+                            .unwrap_or_default();
+                        (Some(format!("view {name}")), typ, None)
+                    }
+                };
+                for ident in &object.idents {
+                    details.push(InterfaceDetails {
+                        name: ident.tree.item.to_string(),
+                        mode: mode.clone(),
+                        typ: typ.clone(),
+                        default: default.clone(),
+                    });
+                }
+            }
+            InterfaceDeclaration::File(file) => {
+                for ident in &file.idents {
+                    details.push(InterfaceDetails {
+                        name: ident.tree.item.to_string(),
+                        mode: None,
+                        typ: format!("file {}", file.subtype_indication),
+                        default: None,
+                    });
+                }
+            }
+            InterfaceDeclaration::Type(ident) => details.push(InterfaceDetails {
+                name: ident.tree.item.to_string(),
+                mode: None,
+                typ: "type".to_string(),
+                default: None,
+            }),
+            InterfaceDeclaration::Subprogram(subprogram) => {
+                let (name, typ) = match &subprogram.specification {
+                    SubprogramSpecification::Function(function) => {
+                        (function.designator.to_string(), "function")
+                    }
+                    SubprogramSpecification::Procedure(procedure) => {
+                        (procedure.designator.to_string(), "procedure")
+                    }
+                };
+                details.push(InterfaceDetails {
+                    name,
+                    mode: None,
+                    typ: typ.to_string(),
+                    default: subprogram
+                        .default
+                        .as_ref()
+                        .map(|default| default.to_string()),
+                })
+            }
+            InterfaceDeclaration::Package(package) => details.push(InterfaceDetails {
+                name: package.ident.tree.item.to_string(),
+                mode: None,
+                typ: format!("package {}", package.package_name),
+                default: None,
+            }),
+        }
+    }
+    details
+}
+
+fn range_of(def: &TypeDefinition) -> Option<String> {
+    match def {
+        TypeDefinition::Enumeration(literals) => {
+            let first = literals.first()?;
+            let last = literals.last()?;
+            Some(format!("{} to {}", first.tree.item, last.tree.item))
+        }
+        TypeDefinition::Numeric(range) => Some(range.to_string()),
+        TypeDefinition::Physical(physical) => Some(physical.range.to_string()),
+        TypeDefinition::Array(indexes, ..) => Some(format!(
+            "({})",
+            indexes
+                .iter()
+                .map(|index| index.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        TypeDefinition::Subtype(subtype_indication) => {
+            let constraint = subtype_indication.constraint.as_ref()?.item.to_string();
+            // Range constraints are formatted with a leading ` range `
+            Some(
+                constraint
+                    .trim_start()
+                    .strip_prefix("range ")
+                    .unwrap_or(&constraint)
+                    .to_string(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// The comment block directly above the line of the token at `pos`,
+/// followed by the comment trailing that line
+fn comments_of(ctx: &dyn TokenAccess, pos: &SrcPos) -> Vec<String> {
+    let token_at = |idx: usize| ctx.get_token(TokenId::new(idx));
+    let Some(idx) = (0..)
+        .map_while(token_at)
+        .position(|token| token.pos == *pos)
+    else {
+        return Vec::new();
+    };
+    let line = pos.start().line;
+    let on_line = |idx: usize| token_at(idx).filter(|token| token.pos.start().line == line);
+
+    // The leading comments of the first token of the line are the ones above that line
+    let first = (0..idx)
+        .rev()
+        .take_while(|&idx| on_line(idx).is_some())
+        .last()
+        .unwrap_or(idx);
+    let mut comments = Vec::new();
+    if let Some(leading) = on_line(first).and_then(|token| token.comments.as_ref()) {
+        let mut next_line = line;
+        for comment in leading.leading.iter().rev() {
+            if comment.range.end.line + 1 != next_line {
+                break;
+            }
+            comments.push(comment);
+            next_line = comment.range.start.line;
+        }
+        comments.reverse();
+    }
+    let trailing = (idx..)
+        .map_while(on_line)
+        .find_map(|token| token.comments.as_ref()?.trailing.as_ref());
+    comments.extend(trailing);
+
+    let mut lines: Vec<String> = comments
+        .into_iter()
+        .flat_map(|comment| comment.value.lines())
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(['-', '*'])
+                .trim()
+                .to_string()
+        })
+        .collect();
+    while lines.first().is_some_and(String::is_empty) {
+        lines.remove(0);
+    }
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines
+}
@@ -11,6 +11,7 @@ use crate::ast::*;
 use crate::data::error_codes::ErrorCode;
 use crate::data::*;
 use crate::named_entity::{Signature, *};
+use crate::syntax::is_verilog_file;
 use crate::{ast, named_entity, HasTokenSpan};
 use analyze::*;
 use fnv::FnvHashMap;
@@ -64,6 +65,7 @@ impl Declaration {
                     | Package(_)
                     | Configuration(_)
                     | View(_)
+                    | Psl(_)
             ),
             // LRM: configuration_declarative_item
             AnyEntKind::Design(Design::Configuration) => {
@@ -83,6 +85,7 @@ impl Declaration {
                     | Use(_)
                     | Package(_)
                     | View(_)
+                    | Psl(_)
             ),
             // LRM: package_body_declarative_item
             AnyEntKind::Design(Design::PackageBody(..) | Design::UninstPackage(..))
@@ -122,6 +125,7 @@ impl Declaration {
                     | Use(_)
                     | Package(_)
                     | View(_)
+                    | Psl(PslDeclaration::Property(_))
             ),
             _ => {
                 // AnyEntKind::Library is used in tests for a generic declarative region
@@ -513,6 +517,7 @@ impl<'a> AnalyzeContext<'a, '_> {
                 unsafe {
                     ent.set_kind(kind);
                 }
+                self.check_verilog_component(ent, diagnostics)?;
 
                 scope.add(ent, diagnostics);
             }
@@ -624,6 +629,9 @@ impl<'a> AnalyzeContext<'a, '_> {
                     scope.add(view, diagnostics);
                 }
             }
+            Declaration::Psl(ref mut psl) => {
+                self.analyze_psl_declaration(scope, parent, psl, src_span, diagnostics)?;
+            }
             Declaration::Type(..) => unreachable!("Handled elsewhere"),
         };
 
@@ -715,6 +723,102 @@ impl<'a> AnalyzeContext<'a, '_> {
         Ok(())
     }
 
+    /// A component is bound by default to the entity with the same name in the work library.
+    /// When that entity is the module header of a Verilog file, the generics and ports
+    /// of the component must exist in the module.
+    fn check_verilog_component(
+        &self,
+        component: EntRef<'a>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let (AnyEntKind::Component(component_region), Designator::Identifier(name)) =
+            (component.kind(), component.designator())
+        else {
+            return Ok(());
+        };
+        let Some(unit) = self.get_primary_unit(self.work_library_name(), name) else {
+            return Ok(());
+        };
+        if !is_verilog_file(unit.source().file_name()) {
+            return Ok(());
+        }
+        let data = self.get_analysis(component.decl_pos(), unit)?;
+        let AnyDesignUnit::Primary(primary) = &*data else {
+            return Ok(());
+        };
+        let Some(module) = primary
+            .ent_id()
+            .map(|id| self.arena.get(id))
+            .and_then(DesignEnt::from_any)
+        else {
+            return Ok(());
+        };
+        let Design::Entity(_, module_region) = module.kind() else {
+            return Ok(());
+        };
+
+        let (module_ports, module_generics) = module_region.ports_and_generics();
+        let (ports, generics) = component_region.ports_and_generics();
+        for (interfaces, module_interfaces, class) in [
+            (generics, module_generics, "parameter"),
+            (ports, module_ports, "port"),
+        ] {
+            for iface in interfaces {
+                let Some(pos) = iface.decl_pos() else {
+                    continue;
+                };
+                match module_interfaces
+                    .iter()
+                    .find(|module_iface| module_iface.designator() == iface.designator())
+                {
+                    Some(module_iface) => {
+                        let mode = |iface: &InterfaceEnt<'_>| match iface.kind() {
+                            AnyEntKind::Object(obj) => match obj.mode() {
+                                Some(InterfaceMode::Simple(mode)) => Some(*mode),
+                                _ => None,
+                            },
+                            _ => None,
+                        };
+                        if let (Some(expected), Some(got)) = (mode(module_iface), mode(&iface)) {
+                            if expected != got {
+                                let mut diagnostic = Diagnostic::new(
+                                    pos,
+                                    format!(
+                                        "Port '{}' has mode {} in Verilog module '{}'",
+                                        iface.designator(),
+                                        expected,
+                                        module.designator()
+                                    ),
+                                    ErrorCode::ComponentMismatch,
+                                );
+                                if let Some(module_pos) = module_iface.decl_pos() {
+                                    diagnostic.add_related(module_pos, "Declared here");
+                                }
+                                diagnostics.push(diagnostic);
+                            }
+                        }
+                    }
+                    None => {
+                        let mut diagnostic = Diagnostic::new(
+                            pos,
+                            format!(
+                                "No {class} '{}' in Verilog module '{}'",
+                                iface.designator(),
+                                module.designator()
+                            ),
+                            ErrorCode::ComponentMismatch,
+                        );
+                        if let Some(module_pos) = module.decl_pos() {
+                            diagnostic.add_related(module_pos, "Module declared here");
+                        }
+                        diagnostics.push(diagnostic);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn find_deferred_constant_declaration(
         &self,
         scope: &Scope<'a>,
@@ -1286,6 +1390,7 @@ fn get_entity_class(ent: EntRef<'_>) -> Option<EntityClass> {
             Design::Context(_) => None,
         },
         AnyEntKind::View(_) => None,
+        AnyEntKind::Psl(_) => None,
     }
 }
 
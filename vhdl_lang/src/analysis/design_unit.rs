@@ -659,6 +659,11 @@ impl<'a> AnalyzeContext<'a, '_> {
             let Some(context_item) =
                 as_fatal(self.resolve_context_item_name(diagnostics, scope, name))?
             else {
+                if matches!(name.item, Name::SelectedAll(..))
+                    && self.is_within_black_box_library(&name.item)
+                {
+                    scope.make_black_box_visible();
+                }
                 continue;
             };
             match context_item {
@@ -708,6 +713,26 @@ impl<'a> AnalyzeContext<'a, '_> {
         Ok(())
     }
 
+    /// Returns true if the prefix of a selected name is a black-box library
+    fn is_within_black_box_library(&self, name: &Name) -> bool {
+        match name {
+            Name::Selected(prefix, _) | Name::SelectedAll(prefix) => {
+                self.is_within_black_box_library(&prefix.item)
+            }
+            Name::Designator(designator) => designator
+                .reference
+                .get()
+                .map(|id| self.arena.get(id))
+                .is_some_and(|ent| {
+                    matches!(ent.kind(), AnyEntKind::Library)
+                        && self
+                            .root
+                            .is_black_box_library(ent.designator().expect_identifier())
+                }),
+            _ => false,
+        }
+    }
+
     /// Returns a reference to the uninstantiated package
     pub fn analyze_package_instance_name(
         &self,
@@ -18,7 +18,7 @@ use crate::ast::*;
 use crate::data::error_codes::ErrorCode;
 use crate::data::*;
 use crate::named_entity::*;
-use crate::{TokenId, TokenSpan};
+use crate::{TokenId, TokenSpan, VHDLStandard};
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum ExpressionType<'a> {
@@ -473,6 +473,30 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
         self.expr_pos_type(scope, expr.span, &mut expr.item, diagnostics)
     }
 
+    /// Ports of mode out can only be read since VHDL 2008
+    pub fn check_out_port_read(
+        &self,
+        span: TokenSpan,
+        name: &Name,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        if self.standard() >= VHDLStandard::VHDL2008 {
+            return;
+        }
+        let Some(id) = name.get_suffix_reference_disregard_index() else {
+            return;
+        };
+        if let AnyEntKind::Object(object) = self.arena.get(id).actual_kind() {
+            if object.is_port() && object.mode() == Some(&InterfaceMode::Simple(Mode::Out)) {
+                diagnostics.push(Diagnostic::requires_newer_standard(
+                    span.pos(self.ctx),
+                    "Reading port of mode out",
+                    VHDLStandard::VHDL2008,
+                ));
+            }
+        }
+    }
+
     pub fn expr_pos_type(
         &self,
         scope: &Scope<'a>,
@@ -487,9 +511,11 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             Expression::Unary(ref mut op, ref mut inner) => {
                 self.operator_type(scope, op, &mut [inner.as_mut()], diagnostics)
             }
-            Expression::Name(ref mut name) => self
-                .expression_name_types(scope, span, name.as_mut(), diagnostics)
-                .map(ExpressionType::from),
+            Expression::Name(ref mut name) => {
+                let types = self.expression_name_types(scope, span, name.as_mut(), diagnostics)?;
+                self.check_out_port_read(span, name, diagnostics);
+                Ok(ExpressionType::from(types))
+            }
             Expression::Aggregate(_) => Ok(ExpressionType::Aggregate),
             Expression::Qualified(ref mut qexpr) => {
                 let typ = self.analyze_qualified_expression(scope, qexpr, diagnostics)?;
@@ -746,13 +772,16 @@ impl<'a, 't> AnalyzeContext<'a, 't> {
             Expression::Literal(ref mut lit) => {
                 self.analyze_literal_with_target_type(scope, target_type, span, lit, diagnostics)?
             }
-            Expression::Name(ref mut name) => self.expression_name_with_ttyp(
-                scope,
-                span,
-                name.as_mut(),
-                target_type,
-                diagnostics,
-            )?,
+            Expression::Name(ref mut name) => {
+                self.expression_name_with_ttyp(
+                    scope,
+                    span,
+                    name.as_mut(),
+                    target_type,
+                    diagnostics,
+                )?;
+                self.check_out_port_read(span, name, diagnostics);
+            }
             Expression::Qualified(ref mut qexpr) => {
                 if let Some(type_mark) =
                     as_fatal(self.analyze_qualified_expression(scope, qexpr, diagnostics))?
@@ -97,6 +97,13 @@ impl<T, R> AnalysisLock<T, R> {
     }
 }
 
+/// The copy is not analyzed
+impl<T: Clone, R> Clone for AnalysisLock<T, R> {
+    fn clone(&self) -> Self {
+        AnalysisLock::new(self.state.read().data.clone())
+    }
+}
+
 /// A view into a thread-safe r/w-lock on an [`AnalysisState`](struct.AnalysisState.html).
 ///
 /// Instances of this type are created by
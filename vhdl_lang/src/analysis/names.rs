@@ -185,7 +185,7 @@ impl<'a> ResolvedName<'a> {
                 type_mark: Some(subtype.type_mark()),
             }),
             AnyEntKind::Type(_) => ResolvedName::Type(TypeEnt::from_any(ent).unwrap()),
-            AnyEntKind::View(_) => ResolvedName::Final(ent),
+            AnyEntKind::View(_) | AnyEntKind::Psl(_) => ResolvedName::Final(ent),
             AnyEntKind::Overloaded(_) => {
                 return Err((
                     "Internal error. Unreachable as overloaded is handled outside".to_owned(),
@@ -256,6 +256,7 @@ impl<'a> ResolvedName<'a> {
             }
             AnyEntKind::File(_)
             | AnyEntKind::View(_)
+            | AnyEntKind::Psl(_)
             | AnyEntKind::InterfaceFile(_)
             | AnyEntKind::Component(_)
             | AnyEntKind::Concurrent(_)
@@ -805,6 +806,40 @@ impl<'a> AnalyzeContext<'a, '_> {
         }
     }
 
+    /// Returns the mirror type `name` declared in the `std.reflection` package
+    fn reflection_type(
+        &self,
+        pos: &SrcPos,
+        name: &str,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> EvalResult<TypeEnt<'a>> {
+        let package = self.lookup_in_library(
+            diagnostics,
+            &self.root.symbol_utf8("std"),
+            pos,
+            &Designator::Identifier(self.root.symbol_utf8("reflection")),
+        )?;
+        let designator = Designator::Identifier(self.root.symbol_utf8(name));
+        let typ = match package.kind() {
+            Design::Package(_, region) => region
+                .lookup_immediate(&designator)
+                .and_then(|named| named.clone().into_non_overloaded().ok())
+                .and_then(TypeEnt::from_any),
+            _ => None,
+        };
+        let Some(typ) = typ else {
+            bail!(
+                diagnostics,
+                Diagnostic::new(
+                    pos,
+                    format!("No declaration of '{name}' within package 'std.reflection'"),
+                    ErrorCode::Unresolved
+                )
+            );
+        };
+        Ok(typ)
+    }
+
     pub(crate) fn resolve_view_ent(
         &self,
         resolved: &ResolvedName<'a>,
@@ -1089,6 +1124,25 @@ impl<'a> AnalyzeContext<'a, '_> {
             AttributeDesignator::Type(attr) => self
                 .resolve_type_attribute_suffix(prefix, prefix_pos, &attr, name_pos, diagnostics)
                 .map(|typ| ResolvedName::Type(typ.base().into())),
+            AttributeDesignator::Reflect => {
+                check_no_attr_argument(self.ctx, attr, diagnostics);
+                let mirror = match prefix {
+                    ResolvedName::Type(_) => "subtype_mirror",
+                    ResolvedName::ObjectName(_) => "value_mirror",
+                    _ => {
+                        diagnostics.push(Diagnostic::cannot_be_prefix_of_attribute(
+                            &name_pos.pos(self.ctx),
+                            prefix,
+                            attr,
+                        ));
+                        return Err(EvalError::Unknown);
+                    }
+                };
+                let typ = self.reflection_type(&name_pos.pos(self.ctx), mirror, diagnostics)?;
+                Ok(ResolvedName::Expression(DisambiguatedType::Unambiguous(
+                    typ,
+                )))
+            }
             AttributeDesignator::Converse => {
                 let view = self.resolve_view_ent(prefix, diagnostics, prefix_pos)?;
                 // Since we do not check the actual mode of the view,
@@ -1905,6 +1959,13 @@ impl Declaration {
             Declaration::Package(_) => "package instantiation",
             Declaration::Configuration(_) => "configuration",
             Declaration::View(_) => "view",
+            Declaration::Psl(PslDeclaration::Property(PslPropertyDeclaration { kind, .. })) => {
+                match kind {
+                    PslDeclarationKind::Property => "property",
+                    PslDeclarationKind::Sequence => "sequence",
+                }
+            }
+            Declaration::Psl(PslDeclaration::DefaultClock(_)) => "default clock",
         }
     }
 }
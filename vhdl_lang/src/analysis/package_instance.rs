@@ -392,6 +392,7 @@ impl<'a> AnalyzeContext<'a, '_> {
                 }
             },
             AnyEntKind::View(typ) => AnyEntKind::View(self.map_subtype(mapping, *typ, scope)),
+            AnyEntKind::Psl(psl) => AnyEntKind::Psl(*psl),
         })
     }
 
@@ -0,0 +1,236 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::ast::token_range::WithTokenSpan;
+use crate::ast::*;
+use crate::data::*;
+use crate::named_entity::*;
+use crate::TokenSpan;
+use analyze::*;
+
+/// Built-in PSL functions that may be used within PSL booleans
+const PSL_BUILTIN_FUNCTIONS: [&[u8]; 9] = [
+    b"prev",
+    b"stable",
+    b"rose",
+    b"fell",
+    b"ended",
+    b"isunknown",
+    b"countones",
+    b"onehot",
+    b"onehot0",
+];
+
+impl<'a> AnalyzeContext<'a, '_> {
+    pub fn analyze_psl_declaration(
+        &self,
+        scope: &Scope<'a>,
+        parent: EntRef<'a>,
+        decl: &mut PslDeclaration,
+        src_span: TokenSpan,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        match decl {
+            PslDeclaration::Property(property) => {
+                let kind = match property.kind {
+                    PslDeclarationKind::Property => Psl::Property,
+                    PslDeclarationKind::Sequence => Psl::Sequence,
+                };
+                let ent = self.define(&mut property.ident, parent, AnyEntKind::Psl(kind), src_span);
+
+                let nested = scope.nested();
+                for parameter in property.parameters.iter_mut() {
+                    for ident in parameter.idents.iter_mut() {
+                        nested.add(
+                            self.define(ident, ent, AnyEntKind::Psl(Psl::Parameter), src_span),
+                            diagnostics,
+                        );
+                    }
+                }
+                self.analyze_psl_expression(&nested, &mut property.expression, diagnostics)?;
+                scope.add(ent, diagnostics);
+            }
+            PslDeclaration::DefaultClock(clock) => {
+                self.analyze_psl_expression(scope, clock, diagnostics)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn analyze_psl_directive(
+        &self,
+        scope: &Scope<'a>,
+        directive: &mut PslDirective,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        let PslDirective {
+            kind: _,
+            property,
+            report,
+            severity,
+        } = directive;
+        self.analyze_psl_expression(scope, property, diagnostics)?;
+        if let Some(expr) = report {
+            self.expr_with_ttyp(scope, self.string(), expr, diagnostics)?;
+        }
+        if let Some(expr) = severity {
+            self.expr_with_ttyp(scope, self.severity_level(), expr, diagnostics)?;
+        }
+        Ok(())
+    }
+
+    /// The condition of a concurrent assertion that may also be
+    /// a PSL property or sequence instance, i.e. `assert handshake(req, ack);`
+    pub fn analyze_concurrent_assert_condition(
+        &self,
+        scope: &Scope<'a>,
+        condition: &mut WithTokenSpan<Expression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        if self.refers_to_psl(scope, &condition.item) {
+            self.analyze_psl_boolean(scope, condition.span, &mut condition.item, diagnostics)
+        } else {
+            self.boolean_expr(scope, condition, diagnostics)
+        }
+    }
+
+    fn analyze_psl_expression(
+        &self,
+        scope: &Scope<'a>,
+        expr: &mut WithTokenSpan<PslExpression>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        match &mut expr.item {
+            PslExpression::Hdl(hdl) => self.analyze_psl_boolean(scope, expr.span, hdl, diagnostics),
+            PslExpression::Operation(operation) => {
+                for argument in operation.arguments.iter_mut() {
+                    self.analyze_psl_boolean(
+                        scope,
+                        argument.span,
+                        &mut argument.item,
+                        diagnostics,
+                    )?;
+                }
+                for operand in operation.operands.iter_mut() {
+                    self.analyze_psl_expression(scope, operand, diagnostics)?;
+                }
+                Ok(())
+            }
+            PslExpression::Braced(inner) | PslExpression::Parenthesized(inner) => {
+                self.analyze_psl_expression(scope, inner, diagnostics)
+            }
+        }
+    }
+
+    /// Analyzes a VHDL expression that occurs within a PSL property.
+    /// Such expressions may refer to PSL properties, sequences and parameters
+    /// as well as built-in PSL functions. These have no VHDL type and are therefore
+    /// resolved here while the remaining parts are analyzed as ordinary VHDL expressions.
+    fn analyze_psl_boolean(
+        &self,
+        scope: &Scope<'a>,
+        span: TokenSpan,
+        expr: &mut Expression,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> FatalResult {
+        if !self.refers_to_psl(scope, expr) {
+            return self.expr_pos_unknown_ttyp(scope, span, expr, diagnostics);
+        }
+
+        match expr {
+            Expression::Binary(_, left, right) => {
+                self.analyze_psl_boolean(scope, left.span, &mut left.item, diagnostics)?;
+                self.analyze_psl_boolean(scope, right.span, &mut right.item, diagnostics)?;
+            }
+            Expression::Unary(_, operand) | Expression::Parenthesized(operand) => {
+                self.analyze_psl_boolean(scope, operand.span, &mut operand.item, diagnostics)?;
+            }
+            Expression::Name(name) => match name.as_mut() {
+                Name::Designator(designator) => {
+                    if let Some(ent) = self.psl_entity(scope, &designator.item) {
+                        designator.set_unique_reference(ent);
+                    }
+                }
+                Name::CallOrIndexed(call) => {
+                    if let Name::Designator(designator) = &mut call.name.item {
+                        if let Some(ent) = self.psl_entity(scope, &designator.item) {
+                            designator.set_unique_reference(ent);
+                        }
+                    }
+                    for param in call.parameters.items.iter_mut() {
+                        if let ActualPart::Expression(actual) = &mut param.actual.item {
+                            self.analyze_psl_boolean(
+                                scope,
+                                param.actual.span,
+                                actual,
+                                diagnostics,
+                            )?;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Returns true if the expression refers to a PSL entity or to a built-in PSL function
+    fn refers_to_psl(&self, scope: &Scope<'a>, expr: &Expression) -> bool {
+        match expr {
+            Expression::Binary(_, left, right) => {
+                self.refers_to_psl(scope, &left.item) || self.refers_to_psl(scope, &right.item)
+            }
+            Expression::Unary(_, operand) | Expression::Parenthesized(operand) => {
+                self.refers_to_psl(scope, &operand.item)
+            }
+            Expression::Name(name) => match name.as_ref() {
+                Name::Designator(designator) => {
+                    self.psl_entity(scope, &designator.item).is_some()
+                        || self.is_psl_builtin(scope, &designator.item, b"inf")
+                }
+                Name::CallOrIndexed(call) => {
+                    if let Name::Designator(designator) = &call.name.item {
+                        if self.psl_entity(scope, &designator.item).is_some()
+                            || PSL_BUILTIN_FUNCTIONS
+                                .iter()
+                                .any(|name| self.is_psl_builtin(scope, &designator.item, name))
+                        {
+                            return true;
+                        }
+                    }
+                    call.parameters.items.iter().any(|param| {
+                        if let ActualPart::Expression(actual) = &param.actual.item {
+                            self.refers_to_psl(scope, actual)
+                        } else {
+                            false
+                        }
+                    })
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn psl_entity(&self, scope: &Scope<'a>, designator: &Designator) -> Option<EntRef<'a>> {
+        match scope.lookup(designator) {
+            Ok(NamedEntities::Single(ent)) if matches!(ent.kind(), AnyEntKind::Psl(_)) => Some(ent),
+            _ => None,
+        }
+    }
+
+    /// A built-in PSL name is only used when it is not hidden by a VHDL declaration
+    fn is_psl_builtin(&self, scope: &Scope<'a>, designator: &Designator, name: &[u8]) -> bool {
+        match designator {
+            Designator::Identifier(symbol) => {
+                symbol.name().bytes.eq_ignore_ascii_case(name) && scope.lookup(designator).is_err()
+            }
+            _ => false,
+        }
+    }
+}
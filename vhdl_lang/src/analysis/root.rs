@@ -14,20 +14,31 @@ use crate::ast::search::*;
 use crate::ast::*;
 use crate::data::error_codes::ErrorCode;
 use crate::data::*;
+use crate::standard::VHDLStandard;
 use crate::syntax::{Symbols, Token, TokenAccess};
 use crate::{HasTokenSpan, TokenSpan};
 use fnv::{FnvHashMap, FnvHashSet};
 use parking_lot::RwLock;
 use std::collections::hash_map::Entry;
+use std::collections::BTreeSet;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::sync::Arc;
+#[cfg(feature = "analysis-cache")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "analysis-cache")]
+mod cache;
 
 /// A design unit with design unit data
 pub(crate) struct AnalysisData {
     pub diagnostics: Vec<Diagnostic>,
     pub has_circular_dependency: bool,
     pub arena: FinalArena,
+    /// The fingerprint of the cache entry of the analysis once it has been restored or stored,
+    /// `None` if the analysis cannot be cached
+    #[cfg(feature = "analysis-cache")]
+    pub cached: OnceLock<Option<u64>>,
 }
 
 pub(super) type UnitReadGuard<'a> = ReadGuard<'a, AnyDesignUnit, AnalysisData>;
@@ -41,6 +52,9 @@ pub(crate) struct LockedUnit {
     unit_id: UnitId,
     pub unit: AnalysisLock<AnyDesignUnit, AnalysisData>,
     pub tokens: Vec<Token>,
+    /// The unit is declared in a stub file and only describes the interface of a unit
+    /// whose sources are not available
+    is_stub: bool,
 }
 
 impl HasSrcPos for LockedUnit {
@@ -75,6 +89,26 @@ impl LockedUnit {
             unit_id,
             unit: AnalysisLock::new(unit),
             tokens,
+            is_stub: false,
+        }
+    }
+
+    pub(crate) fn is_stub(&self) -> bool {
+        self.is_stub
+    }
+}
+
+/// The copy is not analyzed, such that a design unit that is shared with a snapshot
+/// can be analyzed again without changing the snapshot
+impl Clone for LockedUnit {
+    fn clone(&self) -> Self {
+        LockedUnit {
+            ident: self.ident.clone(),
+            arena_id: self.arena_id,
+            unit_id: self.unit_id.clone(),
+            unit: self.unit.clone(),
+            tokens: self.tokens.clone(),
+            is_stub: self.is_stub,
         }
     }
 }
@@ -96,9 +130,27 @@ pub struct Library {
     arena: FinalArena,
     id: EntityId,
 
-    units: FnvHashMap<UnitKey, LockedUnit>,
+    units: FnvHashMap<UnitKey, Arc<LockedUnit>>,
     units_by_source: FnvHashMap<Source, FnvHashSet<UnitId>>,
 
+    /// The standard of the library if it differs from the standard of the design
+    standard: Option<VHDLStandard>,
+
+    /// Files that only declare the interface of units whose sources are not available.
+    /// Units of these files are superseded by units with the same name from other files.
+    stubs: FnvHashSet<FilePath>,
+
+    /// Units of the library that do not exist are treated as opaque units
+    black_box: bool,
+
+    /// Units of the library are only analyzed when they are used by another unit
+    /// or when their source is open
+    lazy: bool,
+
+    /// The analysis of the units of the library is restored from the cache if possible
+    #[cfg(feature = "analysis-cache")]
+    cached: bool,
+
     /// Units removed since last analysis.
     removed: FnvHashSet<UnitId>,
     /// Units added since last analysis.
@@ -106,7 +158,7 @@ pub struct Library {
 
     /// Design units which were not added since they were duplicates.
     /// They need to be kept for later refresh which might make them not duplicates.
-    duplicates: Vec<(SrcPos, LockedUnit)>,
+    duplicates: Vec<(SrcPos, Arc<LockedUnit>)>,
 }
 
 impl Library {
@@ -129,6 +181,12 @@ impl Library {
             arena: arena.finalize(),
             units: FnvHashMap::default(),
             units_by_source: FnvHashMap::default(),
+            standard: None,
+            stubs: FnvHashSet::default(),
+            black_box: false,
+            lazy: false,
+            #[cfg(feature = "analysis-cache")]
+            cached: false,
             added: FnvHashSet::default(),
             removed: FnvHashSet::default(),
             duplicates: Vec::new(),
@@ -139,12 +197,28 @@ impl Library {
         &self.name
     }
 
-    fn add_design_unit(&mut self, unit: LockedUnit) {
+    fn add_design_unit(&mut self, unit: Arc<LockedUnit>) {
         let unit_id = unit.unit_id().clone();
         match self.units.entry(unit.key().clone()) {
-            Entry::Occupied(entry) => {
-                self.duplicates
-                    .push((entry.get().ident().pos(&entry.get().tokens).clone(), unit));
+            Entry::Occupied(mut entry) => {
+                if entry.get().is_stub() && !unit.is_stub() {
+                    // The actual unit replaces the stub which is kept in case
+                    // the actual unit is removed later on
+                    let stub = entry.insert(unit);
+                    if let Some(ids) = self.units_by_source.get_mut(stub.source()) {
+                        ids.remove(stub.unit_id());
+                    }
+                    self.removed.insert(stub.unit_id().clone());
+                    self.added.insert(unit_id.clone());
+                    self.units_by_source
+                        .entry(entry.get().source().clone())
+                        .or_default()
+                        .insert(unit_id);
+                    self.duplicates.push((entry.get().pos().clone(), stub));
+                } else {
+                    self.duplicates
+                        .push((entry.get().ident().pos(&entry.get().tokens).clone(), unit));
+                }
             }
             Entry::Vacant(entry) => {
                 self.added.insert(unit_id);
@@ -165,7 +239,9 @@ impl Library {
 
     fn add_design_file(&mut self, design_file: DesignFile) {
         for (tokens, design_unit) in design_file.design_units {
-            self.add_design_unit(LockedUnit::new(self.name(), design_unit, tokens));
+            let mut unit = LockedUnit::new(self.name(), design_unit, tokens);
+            unit.is_stub = self.stubs.contains(unit.source().file_path());
+            self.add_design_unit(Arc::new(unit));
         }
     }
 
@@ -176,6 +252,10 @@ impl Library {
 
     fn append_duplicate_diagnostics(&self, diagnostics: &mut dyn DiagnosticHandler) {
         for (prev_pos, unit) in self.duplicates.iter() {
+            if unit.is_stub() {
+                // A stub that is superseded by the actual unit is not an error
+                continue;
+            }
             let tokens = &unit.tokens;
             let diagnostic = match unit.key() {
                 UnitKey::Primary(ref primary_name) => Diagnostic::new(
@@ -211,16 +291,44 @@ impl Library {
     /// Remove all design units defined in source.
     /// This is used for incremental analysis where only a single source file is updated.
     fn remove_source(&mut self, source: &Source) {
+        self.remove_source_units(source, |_| None);
+    }
+
+    /// Remove the design units defined in source except those for which `keep` returns
+    /// the number of lines that they moved.
+    /// Kept design units retain their analysis results unless they moved.
+    fn remove_source_units(&mut self, source: &Source, keep: impl Fn(&LockedUnit) -> Option<i64>) {
         let removed = &mut self.removed;
+        let added = &mut self.added;
+        let units_by_source = &mut self.units_by_source;
         self.units.retain(|_, value| {
             if value.source() != source {
                 true
+            } else if let Some(lines) = keep(value) {
+                if lines != 0 {
+                    // The positions of the analysis results are outdated
+                    for token in Arc::make_mut(value).tokens.iter_mut() {
+                        token.shift_lines(lines);
+                    }
+                    removed.insert(value.unit_id().clone());
+                    added.insert(value.unit_id().clone());
+                }
+                true
             } else {
                 removed.insert(value.unit_id().clone());
+                if let Some(ids) = units_by_source.get_mut(source) {
+                    ids.remove(value.unit_id());
+                }
                 false
             }
         });
-        self.units_by_source.remove(source);
+        if self
+            .units_by_source
+            .get(source)
+            .is_some_and(|ids| ids.is_empty())
+        {
+            self.units_by_source.remove(source);
+        }
         self.duplicates
             .retain(|(_, value)| value.source() != source);
 
@@ -229,7 +337,7 @@ impl Library {
         let duplicates =
             std::mem::replace(&mut self.duplicates, Vec::with_capacity(num_duplicates));
         for (prev_pos, design_unit) in duplicates.into_iter() {
-            if prev_pos.source() == source {
+            if prev_pos.source() == source && !self.units.contains_key(design_unit.key()) {
                 self.add_design_unit(design_unit);
             } else {
                 self.duplicates.push((prev_pos, design_unit));
@@ -251,8 +359,42 @@ impl Library {
         result
     }
 
+    /// A copy of the library that shares the design units that have been analyzed,
+    /// the other design units are left out
+    fn snapshot(&self) -> Library {
+        let units: FnvHashMap<_, _> = self
+            .units
+            .iter()
+            .filter(|(_, unit)| unit.unit.is_analyzed())
+            .map(|(key, unit)| (key.clone(), unit.clone()))
+            .collect();
+        let mut units_by_source: FnvHashMap<Source, FnvHashSet<UnitId>> = FnvHashMap::default();
+        for unit in units.values() {
+            units_by_source
+                .entry(unit.source().clone())
+                .or_default()
+                .insert(unit.unit_id().clone());
+        }
+        Library {
+            name: self.name.clone(),
+            arena: self.arena.clone(),
+            id: self.id,
+            units,
+            units_by_source,
+            standard: self.standard,
+            stubs: self.stubs.clone(),
+            black_box: self.black_box,
+            lazy: self.lazy,
+            #[cfg(feature = "analysis-cache")]
+            cached: self.cached,
+            removed: FnvHashSet::default(),
+            added: FnvHashSet::default(),
+            duplicates: Vec::new(),
+        }
+    }
+
     pub(crate) fn get_unit(&self, key: &UnitKey) -> Option<&LockedUnit> {
-        self.units.get(key)
+        self.units.get(key).map(Arc::as_ref)
     }
 
     pub fn id(&self) -> EntityId {
@@ -260,12 +402,12 @@ impl Library {
     }
 
     pub(crate) fn units(&self) -> impl Iterator<Item = &LockedUnit> {
-        self.units.values()
+        self.units.values().map(Arc::as_ref)
     }
 
     pub(crate) fn primary_units(&self) -> impl Iterator<Item = &LockedUnit> {
         self.units.iter().filter_map(|(key, value)| match key {
-            UnitKey::Primary(_) => Some(value),
+            UnitKey::Primary(_) => Some(value.as_ref()),
             UnitKey::Secondary(_, _) => None,
         })
     }
@@ -275,13 +417,15 @@ impl Library {
         primary: &'a Symbol,
     ) -> impl Iterator<Item = &'a LockedUnit> {
         self.units.iter().filter_map(move |(key, value)| match key {
-            UnitKey::Secondary(sym, _) if primary == sym => Some(value),
+            UnitKey::Secondary(sym, _) if primary == sym => Some(value.as_ref()),
             _ => None,
         })
     }
 
     pub(crate) fn primary_unit(&self, symbol: &Symbol) -> Option<&LockedUnit> {
-        self.units.get(&UnitKey::Primary(symbol.clone()))
+        self.units
+            .get(&UnitKey::Primary(symbol.clone()))
+            .map(Arc::as_ref)
     }
 }
 
@@ -297,6 +441,10 @@ pub struct DesignRoot {
     pub(super) standard_types: Option<StandardTypes>,
     pub(super) std_ulogic: Option<EntityId>,
     libraries: FnvHashMap<Symbol, Library>,
+    standard: VHDLStandard,
+
+    // Sources that are open in an editor, their units are analyzed even in lazy libraries
+    open_sources: FnvHashSet<Source>,
 
     // Arena storage of all declaration in the design
     pub(super) arenas: FinalArena,
@@ -312,11 +460,25 @@ pub struct DesignRoot {
     // Tracks which units have a "use library.all;" clause.
     // library name  =>  set(affected)
     users_of_library_all: RwLock<FnvHashMap<Symbol, FnvHashSet<UnitId>>>,
+
+    // Cache of the analysis of the units of third-party libraries
+    #[cfg(feature = "analysis-cache")]
+    cache: Option<cache::AnalysisCache>,
+}
+
+/// Search a unit if it has been analyzed.
+/// Units of lazy libraries are not analyzed until they are used.
+fn search_analyzed(unit: &LockedUnit, searcher: &mut impl Searcher) -> SearchResult {
+    match unit.unit.get() {
+        Some(analyzed) => analyzed.search(&unit.tokens, searcher),
+        None => NotFound,
+    }
 }
 
 impl DesignRoot {
     pub fn new(symbols: Arc<Symbols>) -> DesignRoot {
         DesignRoot {
+            standard: symbols.standard(),
             universal: None,
             standard_pkg_id: None,
             standard_arena: None,
@@ -325,9 +487,39 @@ impl DesignRoot {
             symbols,
             arenas: FinalArena::default(),
             libraries: FnvHashMap::default(),
+            open_sources: FnvHashSet::default(),
             users_of: RwLock::new(FnvHashMap::default()),
             missing_unit: RwLock::new(FnvHashMap::default()),
             users_of_library_all: RwLock::new(FnvHashMap::default()),
+            #[cfg(feature = "analysis-cache")]
+            cache: None,
+        }
+    }
+
+    /// A read-only copy of the design as of its last analysis,
+    /// the analysis results are shared with the design until it is analyzed again.
+    pub(crate) fn snapshot(&self) -> DesignRoot {
+        DesignRoot {
+            symbols: self.symbols.clone(),
+            standard_pkg_id: self.standard_pkg_id,
+            standard_arena: self.standard_arena.clone(),
+            universal: self.universal,
+            standard_types: self.standard_types.clone(),
+            std_ulogic: self.std_ulogic,
+            libraries: self
+                .libraries
+                .iter()
+                .map(|(name, library)| (name.clone(), library.snapshot()))
+                .collect(),
+            standard: self.standard,
+            open_sources: self.open_sources.clone(),
+            arenas: self.arenas.clone(),
+            users_of: RwLock::new(self.users_of.read().clone()),
+            missing_unit: RwLock::new(self.missing_unit.read().clone()),
+            users_of_library_all: RwLock::new(self.users_of_library_all.read().clone()),
+            // The snapshot is not analyzed again
+            #[cfg(feature = "analysis-cache")]
+            cache: None,
         }
     }
 
@@ -347,10 +539,69 @@ impl DesignRoot {
         self.get_or_create_library(name);
     }
 
+    /// Set the standard of a library that differs from the standard of the design
+    pub fn set_library_standard(&mut self, name: Symbol, standard: VHDLStandard) {
+        self.get_or_create_library(name).standard = Some(standard);
+    }
+
+    /// Mark files of a library as stub files, see [`LockedUnit::is_stub`].
+    /// This must be done before the files are added to the library.
+    pub(crate) fn set_library_stubs(
+        &mut self,
+        name: Symbol,
+        stubs: impl IntoIterator<Item = FilePath>,
+    ) {
+        self.get_or_create_library(name).stubs.extend(stubs);
+    }
+
+    /// Treat units of a library that do not exist as opaque units,
+    /// i.e. vendor primitives whose sources are not available
+    pub fn set_library_black_box(&mut self, name: Symbol) {
+        self.get_or_create_library(name).black_box = true;
+    }
+
+    /// Only analyze the units of a library when they are used by a unit of
+    /// another library or when their source is open
+    pub fn set_library_lazy(&mut self, name: Symbol) {
+        self.get_or_create_library(name).lazy = true;
+    }
+
+    /// Mark a source as open or closed in an editor.
+    /// The units of open sources are analyzed even if they belong to a lazy library.
+    pub fn set_source_open(&mut self, source: &Source, is_open: bool) {
+        if is_open {
+            self.open_sources.insert(source.clone());
+        } else {
+            self.open_sources.remove(source);
+        }
+    }
+
+    pub(crate) fn take_open_sources(&mut self) -> FnvHashSet<Source> {
+        std::mem::take(&mut self.open_sources)
+    }
+
+    pub(crate) fn set_open_sources(&mut self, open_sources: FnvHashSet<Source>) {
+        self.open_sources = open_sources;
+    }
+
+    pub(crate) fn is_black_box_library(&self, name: &Symbol) -> bool {
+        self.libraries
+            .get(name)
+            .is_some_and(|library| library.black_box)
+    }
+
+    /// The VHDL standard that the units of a library are analyzed with
+    pub fn library_standard(&self, name: &Symbol) -> VHDLStandard {
+        self.libraries
+            .get(name)
+            .and_then(|library| library.standard)
+            .unwrap_or(self.standard)
+    }
+
     pub(super) fn get_library_units(
         &self,
         library_name: &Symbol,
-    ) -> Option<&FnvHashMap<UnitKey, LockedUnit>> {
+    ) -> Option<&FnvHashMap<UnitKey, Arc<LockedUnit>>> {
         self.libraries
             .get(library_name)
             .map(|library| &library.units)
@@ -409,6 +660,44 @@ impl DesignRoot {
             .remove_source(source);
     }
 
+    /// Remove the design units of a source except those whose first token
+    /// starts at one of the `kept` positions, which are moved by the given number of lines
+    pub(crate) fn remove_source_units(
+        &mut self,
+        library_name: Symbol,
+        source: &Source,
+        kept: &FnvHashMap<Position, i64>,
+    ) {
+        self.get_or_create_library(library_name)
+            .remove_source_units(source, |unit| {
+                unit.tokens
+                    .first()
+                    .and_then(|token| kept.get(&token.pos.start()).copied())
+            });
+    }
+
+    /// The tokens of the design units of a source in a library in the order of the file
+    pub(crate) fn source_unit_tokens(
+        &self,
+        library_name: &Symbol,
+        source: &Source,
+    ) -> Vec<&[Token]> {
+        let Some(library) = self.libraries.get(library_name) else {
+            return Vec::new();
+        };
+        let Some(unit_ids) = library.units_by_source.get(source) else {
+            return Vec::new();
+        };
+        let mut tokens: Vec<&[Token]> = unit_ids
+            .iter()
+            .filter_map(|unit_id| library.get_unit(unit_id.key()))
+            .filter(|unit| !unit.tokens.is_empty())
+            .map(|unit| unit.tokens.as_slice())
+            .collect();
+        tokens.sort_by_key(|tokens| tokens[0].pos.start());
+        tokens
+    }
+
     /// Search for reference at position
     /// Character offset on a line in a document (zero-based). Assuming that the line is
     /// represented as a string, the `character` value represents the gap between the
@@ -423,11 +712,11 @@ impl DesignRoot {
     ) -> Option<(SrcPos, EntRef<'_>)> {
         let mut searcher = ItemAtCursor::new(self, cursor);
 
-        for unit in self.units_by_source(source) {
-            let _ = unit
-                .unit
-                .expect_analyzed()
-                .search(&unit.tokens, &mut searcher);
+        for locked_unit in self.units_by_source(source) {
+            let Some(unit) = locked_unit.unit.get() else {
+                continue;
+            };
+            let _ = unit.search(&locked_unit.tokens, &mut searcher);
 
             if searcher.result.is_some() {
                 return searcher.result;
@@ -588,7 +877,9 @@ impl DesignRoot {
 
         for unit_id in unit_ids {
             let locked_unit = library.units.get(unit_id.key()).unwrap();
-            let unit = locked_unit.unit.expect_analyzed();
+            let Some(unit) = locked_unit.unit.get() else {
+                continue;
+            };
             let Some(ent_id) = unit.data().ent_id() else {
                 continue;
             };
@@ -690,7 +981,7 @@ impl DesignRoot {
         for library in self.libraries.values() {
             for unit_id in library.sorted_unit_ids() {
                 let unit = library.units.get(unit_id.key()).unwrap();
-                return_if_found!(unit.unit.expect_analyzed().search(&unit.tokens, searcher));
+                return_if_found!(search_analyzed(unit, searcher));
             }
         }
         NotFound
@@ -716,7 +1007,7 @@ impl DesignRoot {
     /// Search all units in a source file denoted by `source`.
     pub fn search_source(&self, source: &Source, searcher: &mut impl Searcher) -> SearchResult {
         for unit in self.units_by_source(source) {
-            return_if_found!(unit.unit.expect_analyzed().search(&unit.tokens, searcher));
+            return_if_found!(search_analyzed(unit, searcher));
         }
         NotFound
     }
@@ -729,7 +1020,7 @@ impl DesignRoot {
         if let Some(library) = self.libraries.get(library_name) {
             for unit_id in library.sorted_unit_ids() {
                 let unit = library.units.get(unit_id.key()).unwrap();
-                return_if_found!(unit.unit.expect_analyzed().search(&unit.tokens, searcher));
+                return_if_found!(search_analyzed(unit, searcher));
             }
         }
         NotFound
@@ -766,6 +1057,8 @@ impl DesignRoot {
                     arena: arena.finalize(),
                     diagnostics,
                     has_circular_dependency,
+                    #[cfg(feature = "analysis-cache")]
+                    cached: OnceLock::new(),
                 }
             }
 
@@ -781,6 +1074,8 @@ impl DesignRoot {
                     arena: arena.finalize(),
                     diagnostics,
                     has_circular_dependency,
+                    #[cfg(feature = "analysis-cache")]
+                    cached: OnceLock::new(),
                 }
             }
         };
@@ -791,6 +1086,11 @@ impl DesignRoot {
     pub(super) fn get_analysis<'a>(&self, locked_unit: &'a LockedUnit) -> UnitReadGuard<'a> {
         match locked_unit.unit.entry() {
             AnalysisEntry::Vacant(mut unit) => {
+                #[cfg(feature = "analysis-cache")]
+                if let Some((result, _)) = self.restore_analysis(locked_unit, &mut unit) {
+                    unit.finish(result);
+                    return unit.downgrade();
+                }
                 self.analyze_unit(
                     locked_unit.arena_id,
                     locked_unit.unit_id(),
@@ -808,12 +1108,19 @@ impl DesignRoot {
         self.libraries
             .get(unit_id.library_name())
             .and_then(|library| library.units.get(unit_id.key()))
+            .map(Arc::as_ref)
     }
 
-    fn reset_affected(&self, mut affected: FnvHashSet<UnitId>) {
+    fn reset_affected(&mut self, mut affected: FnvHashSet<UnitId>) {
         // Reset analysis state of all design units
         for unit_id in affected.drain() {
-            if let Some(unit) = self.get_unit(&unit_id) {
+            let unit = self
+                .libraries
+                .get_mut(unit_id.library_name())
+                .and_then(|library| library.units.get_mut(unit_id.key()));
+            if let Some(unit) = unit {
+                // A unit that is shared with a snapshot is copied instead
+                let unit = Arc::make_mut(unit);
                 unit.unit.reset();
 
                 // Ensure no remaining references from previous analysis
@@ -953,10 +1260,11 @@ impl DesignRoot {
             }
         }
 
-        self.reset_affected(get_all_affected(&users_of, affected));
+        let affected = get_all_affected(&users_of, affected);
         drop(users_of);
         drop(users_of_library_all);
         drop(missing_unit);
+        self.reset_affected(affected);
 
         let mut users_of = self.users_of.write();
         let mut users_of_library_all = self.users_of_library_all.write();
@@ -995,6 +1303,17 @@ impl DesignRoot {
         let AnalysisEntry::Vacant(mut unit) = locked_unit.unit.entry() else {
             return;
         };
+
+        #[cfg(feature = "analysis-cache")]
+        if let Some((result, standard)) = self.restore_standard_package(locked_unit, &mut unit) {
+            self.standard_pkg_id = Some(standard.id);
+            self.universal = Some(standard.universal);
+            self.standard_types = Some(standard.types);
+            self.standard_arena = Some(result.arena.clone());
+            unit.finish(result);
+            return;
+        }
+
         // Clear to ensure the analysis of standard package does not believe it has the standard package
         let arena = Arena::new_std();
         self.standard_pkg_id = None;
@@ -1106,6 +1425,8 @@ impl DesignRoot {
             arena,
             diagnostics,
             has_circular_dependency: false,
+            #[cfg(feature = "analysis-cache")]
+            cached: OnceLock::new(),
         };
 
         unit.finish(result);
@@ -1143,12 +1464,24 @@ impl DesignRoot {
 
     // Returns the units that where re-analyzed
     pub fn analyze(&mut self, diagnostics: &mut dyn DiagnosticHandler) -> Vec<UnitId> {
+        self.analyze_with_progress(diagnostics, None)
+    }
+
+    /// Like `analyze` but reports each analyzed design unit to the progress handler
+    pub fn analyze_with_progress(
+        &mut self,
+        diagnostics: &mut dyn DiagnosticHandler,
+        progress: Option<&mut dyn ProgressHandler>,
+    ) -> Vec<UnitId> {
         self.reset();
 
         let mut units = Vec::default();
         for library in self.libraries.values() {
             for unit in library.units.values() {
-                if !unit.unit.is_analyzed() {
+                // Units of lazy libraries are analyzed once another unit uses them
+                if !unit.unit.is_analyzed()
+                    && (!library.lazy || self.open_sources.contains(unit.source()))
+                {
                     units.push(unit.unit_id().clone());
                 }
             }
@@ -1158,6 +1491,9 @@ impl DesignRoot {
             library.refresh(diagnostics);
         }
 
+        #[cfg(feature = "analysis-cache")]
+        self.refresh_cache_sources();
+
         // Rebuild declaration arenas of named entities
         self.arenas.clear();
 
@@ -1174,9 +1510,23 @@ impl DesignRoot {
 
         use rayon::prelude::*;
 
-        units.par_iter().for_each(|id| {
-            self.get_analysis(self.get_unit(id).unwrap());
-        });
+        let mut totals: FnvHashMap<Symbol, usize> = FnvHashMap::default();
+        for id in units.iter() {
+            *totals.entry(id.library_name().clone()).or_default() += 1;
+        }
+        let this = &*self;
+        with_progress(
+            progress,
+            ProgressStage::Analyzing,
+            units.len(),
+            totals,
+            |reporter| {
+                units.par_iter().for_each(|id| {
+                    this.get_analysis(this.get_unit(id).unwrap());
+                    reporter.done([id.library_name()]);
+                });
+            },
+        );
 
         for library in self.libraries.values() {
             self.arenas.link(&library.arena);
@@ -1187,17 +1537,92 @@ impl DesignRoot {
             }
         }
 
+        #[cfg(feature = "analysis-cache")]
+        self.store_analysis();
+
         // Emit diagnostics sorted within a file
         for library in self.libraries.values() {
             for unit_id in library.sorted_unit_ids() {
                 let unit = library.units.get(unit_id.key()).unwrap();
-                diagnostics.append(unit.unit.expect_analyzed().result().diagnostics.clone());
+                if let Some(unit) = unit.unit.get() {
+                    diagnostics.append(unit.result().diagnostics.clone());
+                }
             }
         }
 
         units
     }
 
+    /// Returns the source files of all libraries ordered such that each file comes
+    /// after the files containing the design units it depends on.
+    ///
+    /// Dependencies are only known for design units that have been analyzed.
+    /// Files that depend on each other are emitted in the order of their names.
+    pub fn source_order(&self) -> Vec<(Symbol, Source)> {
+        let mut nodes: Vec<(Symbol, Source)> = self
+            .libraries
+            .values()
+            .flat_map(|library| {
+                library
+                    .units_by_source
+                    .keys()
+                    .map(|source| (library.name.clone(), source.clone()))
+            })
+            .collect();
+        nodes.sort_by(|(lib_a, source_a), (lib_b, source_b)| {
+            (lib_a.name_utf8(), source_a).cmp(&(lib_b.name_utf8(), source_b))
+        });
+
+        let index: FnvHashMap<(Symbol, Source), usize> = nodes
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(idx, node)| (node, idx))
+            .collect();
+        let node_of = |unit_id: &UnitId| -> Option<usize> {
+            let unit = self.get_unit(unit_id)?;
+            index
+                .get(&(unit_id.library_name().clone(), unit.source().clone()))
+                .copied()
+        };
+
+        let mut dependencies: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); nodes.len()];
+        for (unit_id, users) in self.users_of.read().iter() {
+            let Some(used) = node_of(unit_id) else {
+                continue;
+            };
+            for user in users.iter().filter_map(node_of) {
+                if user != used {
+                    dependencies[user].insert(used);
+                }
+            }
+        }
+
+        fn visit(
+            node: usize,
+            dependencies: &[BTreeSet<usize>],
+            visited: &mut [bool],
+            order: &mut Vec<usize>,
+        ) {
+            if visited[node] {
+                return;
+            }
+            visited[node] = true;
+            for &dependency in dependencies[node].iter() {
+                visit(dependency, dependencies, visited, order);
+            }
+            order.push(node);
+        }
+
+        let mut visited = vec![false; nodes.len()];
+        let mut order = Vec::with_capacity(nodes.len());
+        for node in 0..nodes.len() {
+            visit(node, &dependencies, &mut visited, &mut order);
+        }
+
+        order.into_iter().map(|idx| nodes[idx].clone()).collect()
+    }
+
     /// Get the named entity
     pub fn get_ent(&self, id: EntityId) -> EntRef<'_> {
         self.arenas.get(id)
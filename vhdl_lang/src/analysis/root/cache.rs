@@ -0,0 +1,505 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Restores the analysis of the design units of third-party libraries from the cache
+//! and stores the analysis of those that had to be analyzed again.
+
+use super::*;
+use crate::ast::search::for_each_reference;
+use crate::cache::{
+    design_region, ArenaKey, Decoder, DesignFileCache, Encoder, UnitCacheKey, UnitEntry, UnitRef,
+};
+use std::path::{Path, PathBuf};
+
+/// The cache of the analysis together with the sources that cached positions refer to
+pub(super) struct AnalysisCache {
+    files: DesignFileCache,
+    sources: FnvHashMap<PathBuf, Source>,
+}
+
+impl AnalysisCache {
+    pub fn new(directory: &Path) -> AnalysisCache {
+        AnalysisCache {
+            files: DesignFileCache::new(directory),
+            sources: FnvHashMap::default(),
+        }
+    }
+}
+
+/// The entities of the standard package that are needed to analyze all other units
+pub(super) struct StandardPackage {
+    pub id: EntityId,
+    pub universal: UniversalTypes,
+    pub types: StandardTypes,
+}
+
+impl DesignRoot {
+    /// Cache the analysis of the units of third-party libraries in a directory
+    pub(crate) fn set_cache_directory(&mut self, directory: &Path) {
+        self.cache = Some(AnalysisCache::new(directory));
+    }
+
+    /// Restore the analysis of the units of a library from the cache if possible
+    pub(crate) fn set_library_cached(&mut self, name: Symbol) {
+        self.get_or_create_library(name).cached = true;
+    }
+
+    /// Collect the sources of the design, positions of cached entities refer to them
+    pub(super) fn refresh_cache_sources(&mut self) {
+        let Some(cache) = self.cache.as_mut() else {
+            return;
+        };
+        cache.sources = self
+            .libraries
+            .values()
+            .flat_map(|library| library.units.values())
+            .map(|unit| (unit.source().file_name().to_owned(), unit.source().clone()))
+            .collect();
+    }
+
+    fn is_standard_package(&self, unit_id: &UnitId) -> bool {
+        unit_id == &UnitId::package(&self.symbol_utf8("std"), &self.symbol_utf8("standard"))
+    }
+
+    /// The id of the arena that the entities of a unit are allocated in
+    fn unit_arena_id(&self, unit: &LockedUnit) -> ArenaId {
+        if self.is_standard_package(unit.unit_id()) {
+            ArenaId::std()
+        } else {
+            unit.arena_id
+        }
+    }
+
+    fn get_cached_unit(&self, unit: &UnitRef) -> Option<&LockedUnit> {
+        self.libraries
+            .get(unit.library_name())?
+            .units
+            .get(&unit.key())
+            .map(Arc::as_ref)
+    }
+
+    fn unit_cache_key(&self, unit: &LockedUnit) -> UnitCacheKey {
+        let unit_id = unit.unit_id();
+        // A package without body may not have deferred constants
+        let has_package_body = unit_id.kind() == AnyKind::Primary(PrimaryKind::Package)
+            && self
+                .libraries
+                .get(unit_id.library_name())
+                .and_then(|library| {
+                    library.get_unit(&UnitKey::Secondary(
+                        unit_id.primary_name().clone(),
+                        unit_id.primary_name().clone(),
+                    ))
+                })
+                .is_some_and(|body| body.kind() == AnyKind::Secondary(SecondaryKind::PackageBody));
+
+        UnitCacheKey::new(
+            UnitRef::new(unit_id),
+            &unit.tokens,
+            self.library_standard(unit_id.library_name()),
+            has_package_body,
+            self.libraries
+                .values()
+                .filter(|library| library.black_box)
+                .map(|library| &library.name),
+        )
+    }
+
+    /// Restore the analysis of a unit of a cached library.
+    /// The units that it depends on must have been restored from the same entries
+    /// that they were stored in when the unit was stored.
+    /// Returns `None` if the unit is not cached or if it cannot be restored.
+    pub(super) fn restore_analysis(
+        &self,
+        locked_unit: &LockedUnit,
+        unit: &mut UnitWriteGuard<'_>,
+    ) -> Option<(AnalysisData, Vec<Option<EntityId>>)> {
+        let cache = self.cache.as_ref()?;
+        let unit_id = locked_unit.unit_id();
+        if !self.libraries.get(unit_id.library_name())?.cached {
+            return None;
+        }
+
+        let (fingerprint, entry) = cache.files.load_unit(
+            &self.symbols,
+            locked_unit.source(),
+            &self.unit_cache_key(locked_unit),
+        )?;
+
+        // The unit would have been analyzed differently if a unit that it looked up was added
+        if entry
+            .missing
+            .iter()
+            .any(|missing| self.get_cached_unit(missing).is_some())
+        {
+            return None;
+        }
+        for missing in entry.missing.iter() {
+            self.make_use_of_missing_unit(
+                unit_id,
+                missing.library_name(),
+                missing.primary_name(),
+                missing.secondary_name(),
+            );
+        }
+
+        for (used, used_fingerprint) in entry.uses.iter() {
+            let used = self.get_cached_unit(used)?;
+            self.make_use_of(None, unit_id, used.unit_id()).ok()?;
+            let data = self.get_analysis(used);
+            if data.result().cached.get() != Some(&Some(*used_fingerprint)) {
+                return None;
+            }
+        }
+
+        let arena_id = self.unit_arena_id(locked_unit);
+        let arena = Arena::new(arena_id);
+        let mut arena_ids = vec![arena_id];
+        for owner in entry.arenas.iter().skip(1) {
+            let id = match owner {
+                ArenaKey::Library(name) => {
+                    let library = self.libraries.get(name)?;
+                    arena.link(&library.arena);
+                    library.id.arena_id()
+                }
+                ArenaKey::Unit(used) => {
+                    let used = self.get_cached_unit(used)?;
+                    arena.link(&used.unit.get()?.result().arena);
+                    self.unit_arena_id(used)
+                }
+            };
+            arena_ids.push(id);
+        }
+
+        let sources = entry
+            .sources
+            .iter()
+            .map(|file_name| cache.sources.get(file_name).cloned())
+            .collect::<Option<_>>()?;
+
+        let (diagnostics, references, standard_types) = {
+            let decoder = Decoder::new(&arena, arena_ids, sources);
+            decoder.restore(&entry.entities)?;
+            let diagnostics = entry
+                .diagnostics
+                .iter()
+                .map(|diagnostic| decoder.diagnostic(diagnostic))
+                .collect::<Option<Vec<_>>>()?;
+            let references = entry
+                .references
+                .iter()
+                .map(|key| match key {
+                    Some(key) => Some(Some(decoder.id(*key)?)),
+                    None => Some(None),
+                })
+                .collect::<Option<Vec<_>>>()?;
+            let standard_types = entry
+                .standard_types
+                .iter()
+                .map(|key| match key {
+                    Some(key) => Some(Some(decoder.id(*key)?)),
+                    None => Some(None),
+                })
+                .collect::<Option<Vec<_>>>()?;
+            (diagnostics, references, standard_types)
+        };
+
+        let mut references = references.into_iter();
+        let mut is_complete = true;
+        for_each_reference(&**unit, &locked_unit.tokens, |reference| {
+            match references.next() {
+                Some(id) => reference.restore(id),
+                None => is_complete = false,
+            }
+        });
+        if !is_complete || references.next().is_some() {
+            clear_references(&mut **unit, &locked_unit.tokens);
+            return None;
+        }
+
+        let result = AnalysisData {
+            diagnostics,
+            has_circular_dependency: false,
+            arena: arena.finalize(),
+            cached: OnceLock::from(Some(fingerprint)),
+        };
+        Some((result, standard_types))
+    }
+
+    /// Restore the analysis of the standard package together with the entities
+    /// that are needed to analyze all other units
+    pub(super) fn restore_standard_package(
+        &self,
+        locked_unit: &LockedUnit,
+        unit: &mut UnitWriteGuard<'_>,
+    ) -> Option<(AnalysisData, StandardPackage)> {
+        let (result, standard_types) = self.restore_analysis(locked_unit, unit)?;
+        let id = match &**unit {
+            AnyDesignUnit::Primary(primary) => primary.ent_id(),
+            AnyDesignUnit::Secondary(_) => None,
+        };
+        let standard = id.and_then(|id| standard_package_from_ids(id, &standard_types));
+        if standard.is_none() {
+            clear_references(&mut **unit, &locked_unit.tokens);
+        }
+        Some((result, standard?))
+    }
+
+    /// Store the analysis of the units of cached libraries that were analyzed again
+    pub(super) fn store_analysis(&self) {
+        let Some(cache) = self.cache.as_ref() else {
+            return;
+        };
+
+        let mut analyzed = Vec::new();
+        for library in self.libraries.values().filter(|library| library.cached) {
+            for unit in library.units.values() {
+                if let Some(data) = unit.unit.get() {
+                    if data.result().cached.get().is_none() {
+                        analyzed.push(unit.as_ref());
+                    }
+                }
+            }
+        }
+        if analyzed.is_empty() {
+            return;
+        }
+
+        let mut owners = FnvHashMap::default();
+        for library in self.libraries.values() {
+            owners.insert(
+                library.id.arena_id(),
+                ArenaKey::Library(library.name.clone()),
+            );
+            for unit in library.units.values() {
+                owners.insert(
+                    self.unit_arena_id(unit),
+                    ArenaKey::Unit(UnitRef::new(unit.unit_id())),
+                );
+            }
+        }
+
+        // The regions that can be made visible are identified by the entity that owns them
+        let mut regions = FnvHashMap::default();
+        for arena_id in self.arenas.arena_ids() {
+            for ent in self.arenas.entities_of(arena_id) {
+                if let AnyEntKind::Design(design) = ent.kind() {
+                    if let Some(region) = design_region(design) {
+                        regions.insert(region as *const Region<'_> as usize, ent.id());
+                    }
+                }
+            }
+        }
+
+        let mut uses: FnvHashMap<UnitId, Vec<UnitId>> = FnvHashMap::default();
+        for (used, users) in self.users_of.read().iter() {
+            for user in users.iter() {
+                uses.entry(user.clone()).or_default().push(used.clone());
+            }
+        }
+
+        let mut missing: FnvHashMap<UnitId, Vec<UnitRef>> = FnvHashMap::default();
+        for ((library_name, primary_name, secondary_name), users) in self.missing_unit.read().iter()
+        {
+            for user in users.iter() {
+                missing
+                    .entry(user.clone())
+                    .or_default()
+                    .push(UnitRef::missing(
+                        library_name,
+                        primary_name,
+                        secondary_name.as_ref(),
+                    ));
+            }
+        }
+
+        // Units that are sensitive to any unit being added to a library are analyzed again on every start
+        let mut uncached: FnvHashSet<UnitId> = FnvHashSet::default();
+        for users in self.users_of_library_all.read().values() {
+            uncached.extend(users.iter().cloned());
+        }
+
+        let mut store = UnitStore {
+            root: self,
+            files: &cache.files,
+            owners,
+            regions,
+            uses,
+            missing,
+            uncached,
+            storing: FnvHashSet::default(),
+        };
+        for unit in analyzed {
+            store.fingerprint(unit);
+        }
+    }
+}
+
+/// Stores units after the units that they depend on,
+/// such that their entries contain the fingerprints of the entries of those units
+struct UnitStore<'r> {
+    root: &'r DesignRoot,
+    files: &'r DesignFileCache,
+    owners: FnvHashMap<ArenaId, ArenaKey>,
+    regions: FnvHashMap<usize, EntityId>,
+    uses: FnvHashMap<UnitId, Vec<UnitId>>,
+    missing: FnvHashMap<UnitId, Vec<UnitRef>>,
+    uncached: FnvHashSet<UnitId>,
+    storing: FnvHashSet<UnitId>,
+}
+
+impl UnitStore<'_> {
+    /// The fingerprint of the entry of a unit, the unit is stored first if necessary.
+    /// Returns `None` if the unit cannot be cached.
+    fn fingerprint(&mut self, unit: &LockedUnit) -> Option<u64> {
+        let data = unit.unit.get()?;
+        if let Some(fingerprint) = data.result().cached.get() {
+            return *fingerprint;
+        }
+        if !self.storing.insert(unit.unit_id().clone()) {
+            return None;
+        }
+        let fingerprint = self.store(unit, &data);
+        self.storing.remove(unit.unit_id());
+        *data.result().cached.get_or_init(|| fingerprint)
+    }
+
+    fn store(&mut self, unit: &LockedUnit, data: &UnitReadGuard<'_>) -> Option<u64> {
+        let root = self.root;
+        let unit_id = unit.unit_id();
+        if !root.libraries.get(unit_id.library_name())?.cached
+            || data.result().has_circular_dependency
+            || self.uncached.contains(unit_id)
+        {
+            return None;
+        }
+
+        let arena_id = root.unit_arena_id(unit);
+        let mut encoder = Encoder::new(&self.owners, &self.regions, arena_id)?;
+        let entities = data
+            .result()
+            .arena
+            .entities_of(arena_id)
+            .map(|ent| encoder.entity(ent))
+            .collect::<Option<_>>()?;
+
+        let mut references = Vec::new();
+        for_each_reference(data.deref(), &unit.tokens, |reference| {
+            references.push(reference.get());
+        });
+        let references = references
+            .into_iter()
+            .map(|id| match id {
+                Some(id) => Some(Some(encoder.id(id)?)),
+                None => Some(None),
+            })
+            .collect::<Option<_>>()?;
+
+        let diagnostics = data
+            .result()
+            .diagnostics
+            .iter()
+            .map(|diagnostic| encoder.diagnostic(diagnostic))
+            .collect();
+
+        let standard_types = if arena_id == ArenaId::std() {
+            standard_package_ids(root.universal?, root.standard_types.as_ref()?)
+                .into_iter()
+                .map(|id| match id {
+                    Some(id) => Some(Some(encoder.id(id)?)),
+                    None => Some(None),
+                })
+                .collect::<Option<_>>()?
+        } else {
+            Vec::new()
+        };
+
+        let (arenas, sources) = encoder.into_arenas_and_sources();
+
+        // Units whose entities are referenced might not be direct dependencies
+        let mut used_units: Vec<UnitRef> = arenas
+            .iter()
+            .skip(1)
+            .filter_map(|owner| match owner {
+                ArenaKey::Unit(used) => Some(used.clone()),
+                ArenaKey::Library(_) => None,
+            })
+            .collect();
+        for used in self.uses.get(unit_id).into_iter().flatten() {
+            let used = UnitRef::new(used);
+            if !used_units.contains(&used) {
+                used_units.push(used);
+            }
+        }
+        let mut uses = Vec::with_capacity(used_units.len());
+        for used in used_units {
+            let fingerprint = self.fingerprint(root.get_cached_unit(&used)?)?;
+            uses.push((used, fingerprint));
+        }
+
+        let entry = UnitEntry {
+            arenas,
+            uses,
+            missing: self.missing.get(unit_id).cloned().unwrap_or_default(),
+            sources,
+            entities,
+            references,
+            diagnostics,
+            standard_types,
+        };
+        self.files.store_unit(&root.unit_cache_key(unit), &entry)
+    }
+}
+
+fn standard_package_ids(universal: UniversalTypes, types: &StandardTypes) -> Vec<Option<EntityId>> {
+    vec![
+        Some(universal.integer),
+        Some(universal.real),
+        Some(types.boolean),
+        types.boolean_vector,
+        Some(types.bit),
+        Some(types.bit_vector),
+        Some(types.character),
+        Some(types.string),
+        Some(types.integer),
+        Some(types.natural),
+        Some(types.real),
+        Some(types.time),
+        Some(types.file_open_kind),
+        Some(types.file_open_status),
+        Some(types.severity_level),
+    ]
+}
+
+fn standard_package_from_ids(id: EntityId, ids: &[Option<EntityId>]) -> Option<StandardPackage> {
+    let [integer, real, boolean, boolean_vector, bit, bit_vector, character, string, std_integer, natural, std_real, time, file_open_kind, file_open_status, severity_level] =
+        ids
+    else {
+        return None;
+    };
+    Some(StandardPackage {
+        id,
+        universal: UniversalTypes {
+            integer: (*integer)?,
+            real: (*real)?,
+        },
+        types: StandardTypes {
+            boolean: (*boolean)?,
+            boolean_vector: *boolean_vector,
+            bit: (*bit)?,
+            bit_vector: (*bit_vector)?,
+            character: (*character)?,
+            string: (*string)?,
+            integer: (*std_integer)?,
+            natural: (*natural)?,
+            real: (*std_real)?,
+            time: (*time)?,
+            file_open_kind: (*file_open_kind)?,
+            file_open_status: (*file_open_status)?,
+            severity_level: (*severity_level)?,
+        },
+    })
+}
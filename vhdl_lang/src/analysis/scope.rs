@@ -108,6 +108,19 @@ impl<'a> ScopeInner<'a> {
             .make_all_potentially_visible(visible_pos, region);
     }
 
+    pub fn make_black_box_visible(&mut self) {
+        self.cache.clear();
+        self.region.visibility.make_black_box_visible();
+    }
+
+    fn has_black_box_visibility(&self) -> bool {
+        self.region.visibility.has_black_box_visibility()
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.0.borrow().has_black_box_visibility())
+    }
+
     /// Used when using context clauses
     pub fn add_context_visibility(&mut self, visible_pos: Option<&SrcPos>, region: &Region<'a>) {
         self.cache.clear();
@@ -345,6 +358,23 @@ impl<'a> Scope<'a> {
         self.0.as_ref().borrow_mut().lookup(designator)
     }
 
+    /// Used when using all names of a unit of a black-box library
+    pub fn make_black_box_visible(&self) {
+        self.0.as_ref().borrow_mut().make_black_box_visible()
+    }
+
+    /// True if names may be visible from an opaque unit of a black-box library
+    pub fn has_black_box_visibility(&self) -> bool {
+        self.0.as_ref().borrow().has_black_box_visibility()
+    }
+
+    /// True if the designator is not declared but may be declared by an opaque unit
+    /// of a black-box library
+    pub fn may_be_black_box_name(&self, designator: &Designator) -> bool {
+        self.has_black_box_visibility()
+            && matches!(self.lookup(designator), Err(LookupError::Undeclared(_)))
+    }
+
     /// Used when using context clauses
     pub fn add_context_visibility(&self, visible_pos: Option<&SrcPos>, region: &Region<'a>) {
         self.0
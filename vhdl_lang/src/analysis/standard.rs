@@ -12,6 +12,7 @@ use crate::ast::Operator;
 use crate::data::DiagnosticHandler;
 use crate::syntax::Symbols;
 use crate::HasTokenSpan;
+use crate::VHDLStandard;
 use vhdl_lang::ast::token_range::WithTokenSpan;
 use vhdl_lang::TokenAccess;
 
@@ -51,9 +52,11 @@ impl UniversalTypes {
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct StandardTypes {
     pub boolean: EntityId,
-    pub boolean_vector: EntityId,
+    /// Not declared before VHDL-2008
+    pub boolean_vector: Option<EntityId>,
     pub bit: EntityId,
     pub bit_vector: EntityId,
     pub character: EntityId,
@@ -153,7 +156,7 @@ impl StandardTypes {
 
         Self {
             boolean: boolean.unwrap(),
-            boolean_vector: boolean_vector.unwrap(),
+            boolean_vector,
             bit: bit.unwrap(),
             bit_vector: bit_vector.unwrap(),
             character: character.unwrap(),
@@ -178,6 +181,11 @@ impl<'a> AnalyzeContext<'a, '_> {
         self.root.standard_types.as_ref().unwrap()
     }
 
+    /// True if implicit declarations that were added in VHDL-2008 are defined
+    fn has_vhdl2008_implicits(&self) -> bool {
+        self.standard() >= VHDLStandard::VHDL2008
+    }
+
     pub(crate) fn string(&self) -> TypeEnt<'a> {
         self.arena.get_type(self.standard_types().string)
     }
@@ -186,8 +194,10 @@ impl<'a> AnalyzeContext<'a, '_> {
         self.arena.get_type(self.standard_types().boolean)
     }
 
-    pub(crate) fn boolean_vector(&self) -> TypeEnt<'a> {
-        self.arena.get_type(self.standard_types().boolean_vector)
+    pub(crate) fn boolean_vector(&self) -> Option<TypeEnt<'a>> {
+        self.standard_types()
+            .boolean_vector
+            .map(|id| self.arena.get_type(id))
     }
 
     pub(crate) fn bit(&self) -> TypeEnt<'a> {
@@ -494,7 +504,7 @@ impl<'a> AnalyzeContext<'a, '_> {
         }
 
         // procedure FLUSH (file F: FT);
-        {
+        if self.has_vhdl2008_implicits() {
             let ent = self.implicit_subpgm(
                 file_type,
                 self.ident("FLUSH"),
@@ -561,6 +571,20 @@ impl<'a> AnalyzeContext<'a, '_> {
         .into()
     }
 
+    /// MINIMUM, MAXIMUM and TO_STRING of a scalar type, which were added in VHDL-2008
+    fn scalar_vhdl2008_implicits(&self, typ: TypeEnt<'a>) -> impl Iterator<Item = EntRef<'a>> {
+        self.has_vhdl2008_implicits()
+            .then(|| {
+                [
+                    self.minimum(typ),
+                    self.maximum(typ),
+                    self.create_to_string(typ),
+                ]
+            })
+            .into_iter()
+            .flatten()
+    }
+
     pub fn comparison(&self, op: Operator, typ: TypeEnt<'a>) -> EntRef<'a> {
         self.binary(op, typ, typ, typ, self.boolean())
     }
@@ -584,38 +608,35 @@ impl<'a> AnalyzeContext<'a, '_> {
     ) -> impl Iterator<Item = EntRef<'a>> {
         let integer = self.integer();
 
-        [
-            self.minimum(typ),
-            self.maximum(typ),
-            self.create_to_string(typ),
-            self.symmetric_unary(Operator::Minus, typ),
-            self.symmetric_unary(Operator::Plus, typ),
-            self.symmetric_binary(Operator::Plus, typ),
-            self.symmetric_binary(Operator::Minus, typ),
-            // 9.2.7 Multiplying operators
-            self.symmetric_binary(Operator::Times, typ),
-            self.symmetric_binary(Operator::Div, typ),
-            // 9.2.8 Miscellaneous operators
-            self.symmetric_unary(Operator::Abs, typ),
-            self.binary(Operator::Pow, typ, typ, integer, typ),
-        ]
-        .into_iter()
-        .chain(
-            if kind == UniversalType::Integer {
-                Some(
-                    [
-                        self.symmetric_binary(Operator::Mod, typ),
-                        self.symmetric_binary(Operator::Rem, typ),
-                    ]
-                    .into_iter(),
-                )
-            } else {
-                None
-            }
-            .into_iter()
-            .flatten(),
-        )
-        .chain(self.comparators(typ))
+        self.scalar_vhdl2008_implicits(typ)
+            .chain([
+                self.symmetric_unary(Operator::Minus, typ),
+                self.symmetric_unary(Operator::Plus, typ),
+                self.symmetric_binary(Operator::Plus, typ),
+                self.symmetric_binary(Operator::Minus, typ),
+                // 9.2.7 Multiplying operators
+                self.symmetric_binary(Operator::Times, typ),
+                self.symmetric_binary(Operator::Div, typ),
+                // 9.2.8 Miscellaneous operators
+                self.symmetric_unary(Operator::Abs, typ),
+                self.binary(Operator::Pow, typ, typ, integer, typ),
+            ])
+            .chain(
+                if kind == UniversalType::Integer {
+                    Some(
+                        [
+                            self.symmetric_binary(Operator::Mod, typ),
+                            self.symmetric_binary(Operator::Rem, typ),
+                        ]
+                        .into_iter(),
+                    )
+                } else {
+                    None
+                }
+                .into_iter()
+                .flatten(),
+            )
+            .chain(self.comparators(typ))
     }
 
     pub fn universal_implicits(
@@ -623,100 +644,98 @@ impl<'a> AnalyzeContext<'a, '_> {
         kind: UniversalType,
         typ: TypeEnt<'a>,
     ) -> impl Iterator<Item = EntRef<'a>> {
-        [
-            self.minimum(typ),
-            self.maximum(typ),
-            self.create_to_string(typ),
-            self.symmetric_unary(Operator::Minus, typ),
-            self.symmetric_unary(Operator::Plus, typ),
-            self.symmetric_binary(Operator::Plus, typ),
-            self.symmetric_binary(Operator::Minus, typ),
-            // 9.2.7 Multiplying operators
-            self.symmetric_binary(Operator::Times, typ),
-            self.symmetric_binary(Operator::Div, typ),
-            // 9.2.8 Miscellaneous operators
-            self.symmetric_unary(Operator::Abs, typ),
-            self.binary(
-                Operator::Pow,
-                typ,
-                typ,
-                self.universal_integer().into(),
-                typ,
-            ),
-        ]
-        .into_iter()
-        .chain(match kind {
-            UniversalType::Integer => itertools::Either::Left(
-                [
-                    self.symmetric_binary(Operator::Mod, typ),
-                    self.symmetric_binary(Operator::Rem, typ),
-                ]
-                .into_iter(),
-            ),
-            UniversalType::Real => {
-                // Universal real
-                itertools::Either::Right(
+        self.scalar_vhdl2008_implicits(typ)
+            .chain([
+                self.symmetric_unary(Operator::Minus, typ),
+                self.symmetric_unary(Operator::Plus, typ),
+                self.symmetric_binary(Operator::Plus, typ),
+                self.symmetric_binary(Operator::Minus, typ),
+                // 9.2.7 Multiplying operators
+                self.symmetric_binary(Operator::Times, typ),
+                self.symmetric_binary(Operator::Div, typ),
+                // 9.2.8 Miscellaneous operators
+                self.symmetric_unary(Operator::Abs, typ),
+                self.binary(
+                    Operator::Pow,
+                    typ,
+                    typ,
+                    self.universal_integer().into(),
+                    typ,
+                ),
+            ])
+            .chain(match kind {
+                UniversalType::Integer => itertools::Either::Left(
                     [
-                        self.binary(
-                            Operator::Times,
-                            typ,
-                            typ,
-                            self.universal_integer().into(),
-                            typ,
-                        ),
-                        self.binary(
-                            Operator::Times,
-                            typ,
-                            self.universal_integer().into(),
-                            typ,
-                            typ,
-                        ),
-                        self.binary(
-                            Operator::Div,
-                            typ,
-                            typ,
-                            self.universal_integer().into(),
-                            typ,
-                        ),
+                        self.symmetric_binary(Operator::Mod, typ),
+                        self.symmetric_binary(Operator::Rem, typ),
                     ]
                     .into_iter(),
-                )
-            }
-        })
-        .chain(self.comparators(typ))
+                ),
+                UniversalType::Real => {
+                    // Universal real
+                    itertools::Either::Right(
+                        [
+                            self.binary(
+                                Operator::Times,
+                                typ,
+                                typ,
+                                self.universal_integer().into(),
+                                typ,
+                            ),
+                            self.binary(
+                                Operator::Times,
+                                typ,
+                                self.universal_integer().into(),
+                                typ,
+                                typ,
+                            ),
+                            self.binary(
+                                Operator::Div,
+                                typ,
+                                typ,
+                                self.universal_integer().into(),
+                                typ,
+                            ),
+                        ]
+                        .into_iter(),
+                    )
+                }
+            })
+            .chain(self.comparators(typ))
     }
 
     pub fn physical_implicits(&self, typ: TypeEnt<'a>) -> impl Iterator<Item = EntRef<'a>> {
         let integer = self.integer();
         let real = self.real();
 
-        [
-            self.minimum(typ),
-            self.maximum(typ),
-            self.symmetric_unary(Operator::Minus, typ),
-            self.symmetric_unary(Operator::Plus, typ),
-            self.symmetric_unary(Operator::Abs, typ),
-            self.symmetric_binary(Operator::Plus, typ),
-            self.symmetric_binary(Operator::Minus, typ),
-            // 9.2.7 Multiplying operators
-            self.binary(Operator::Times, typ, typ, integer, typ),
-            self.binary(Operator::Times, typ, typ, real, typ),
-            self.binary(Operator::Times, typ, integer, typ, typ),
-            self.binary(Operator::Times, typ, real, typ, typ),
-            self.binary(Operator::Div, typ, typ, integer, typ),
-            self.binary(Operator::Div, typ, typ, real, typ),
-            self.binary(
-                Operator::Div,
-                typ,
-                typ,
-                typ,
-                self.universal_integer().into(),
-            ),
-            self.symmetric_binary(Operator::Mod, typ),
-            self.symmetric_binary(Operator::Rem, typ),
-        ]
-        .into_iter()
-        .chain(self.comparators(typ))
+        self.has_vhdl2008_implicits()
+            .then(|| [self.minimum(typ), self.maximum(typ)])
+            .into_iter()
+            .flatten()
+            .chain([
+                self.symmetric_unary(Operator::Minus, typ),
+                self.symmetric_unary(Operator::Plus, typ),
+                self.symmetric_unary(Operator::Abs, typ),
+                self.symmetric_binary(Operator::Plus, typ),
+                self.symmetric_binary(Operator::Minus, typ),
+                // 9.2.7 Multiplying operators
+                self.binary(Operator::Times, typ, typ, integer, typ),
+                self.binary(Operator::Times, typ, typ, real, typ),
+                self.binary(Operator::Times, typ, integer, typ, typ),
+                self.binary(Operator::Times, typ, real, typ, typ),
+                self.binary(Operator::Div, typ, typ, integer, typ),
+                self.binary(Operator::Div, typ, typ, real, typ),
+                self.binary(
+                    Operator::Div,
+                    typ,
+                    typ,
+                    typ,
+                    self.universal_integer().into(),
+                ),
+                self.symmetric_binary(Operator::Mod, typ),
+                self.symmetric_binary(Operator::Rem, typ),
+            ])
+            .chain(self.comparators(typ))
     }
 
     pub fn enum_implicits(
@@ -724,32 +743,27 @@ impl<'a> AnalyzeContext<'a, '_> {
         typ: TypeEnt<'a>,
         matching_op: bool,
     ) -> impl Iterator<Item = EntRef<'a>> {
-        [
-            self.create_to_string(typ),
-            self.minimum(typ),
-            self.maximum(typ),
-        ]
-        .into_iter()
-        .chain(self.comparators(typ))
-        .chain(
-            if matching_op {
-                Some(
-                    [
-                        self.symmetric_binary(Operator::QueEQ, typ),
-                        self.symmetric_binary(Operator::QueNE, typ),
-                        self.symmetric_binary(Operator::QueGT, typ),
-                        self.symmetric_binary(Operator::QueGTE, typ),
-                        self.symmetric_binary(Operator::QueLT, typ),
-                        self.symmetric_binary(Operator::QueLTE, typ),
-                    ]
-                    .into_iter(),
-                )
-            } else {
-                None
-            }
-            .into_iter()
-            .flatten(),
-        )
+        self.scalar_vhdl2008_implicits(typ)
+            .chain(self.comparators(typ))
+            .chain(
+                if matching_op {
+                    Some(
+                        [
+                            self.symmetric_binary(Operator::QueEQ, typ),
+                            self.symmetric_binary(Operator::QueNE, typ),
+                            self.symmetric_binary(Operator::QueGT, typ),
+                            self.symmetric_binary(Operator::QueGTE, typ),
+                            self.symmetric_binary(Operator::QueLT, typ),
+                            self.symmetric_binary(Operator::QueLTE, typ),
+                        ]
+                        .into_iter(),
+                    )
+                } else {
+                    None
+                }
+                .into_iter()
+                .flatten(),
+            )
     }
 
     pub fn record_implicits(&self, typ: TypeEnt<'a>) -> impl Iterator<Item = EntRef<'a>> {
@@ -817,12 +831,14 @@ impl<'a> AnalyzeContext<'a, '_> {
             self.comparison(Operator::NE, typ),
         ]
         .into_iter()
-        .chain(if is_one_dimensional && is_character_elem {
-            // To string is only defined for 1d array types with character elements
-            Some(self.create_to_string(typ)).into_iter()
-        } else {
-            None.into_iter()
-        })
+        .chain(
+            if is_one_dimensional && is_character_elem && self.has_vhdl2008_implicits() {
+                // To string is only defined for 1d array types with character elements
+                Some(self.create_to_string(typ)).into_iter()
+            } else {
+                None.into_iter()
+            },
+        )
         .chain(
             (if is_one_dimensional {
                 Some(self.concatenations(typ, *elem_type))
@@ -840,6 +856,19 @@ impl<'a> AnalyzeContext<'a, '_> {
                         self.comparison(Operator::GTE, typ),
                         self.comparison(Operator::LT, typ),
                         self.comparison(Operator::LTE, typ),
+                    ]
+                    .into_iter(),
+                )
+            } else {
+                None
+            })
+            .into_iter()
+            .flatten(),
+        )
+        .chain(
+            (if is_scalar && self.has_vhdl2008_implicits() {
+                Some(
+                    [
                         self.elementwise_min_or_maximum("MINIMUM", typ, *elem_type),
                         self.elementwise_min_or_maximum("MAXIMUM", typ, *elem_type),
                     ]
@@ -887,7 +916,9 @@ impl<'a> AnalyzeContext<'a, '_> {
         region: &mut Region<'a>,
         diagnostics: &mut dyn DiagnosticHandler,
     ) {
-        {
+        let vhdl2008 = self.has_vhdl2008_implicits();
+
+        if vhdl2008 {
             let time = self.time();
             let to_string = self.create_to_string(time);
 
@@ -917,10 +948,14 @@ impl<'a> AnalyzeContext<'a, '_> {
             }
         }
 
-        for (styp, atyp) in [
-            (self.boolean(), self.boolean_vector()),
-            (self.bit(), self.bit_vector()),
-        ] {
+        // BOOLEAN_VECTOR is not declared before VHDL-2008
+        let vectors = self
+            .boolean_vector()
+            .map(|atyp| (self.boolean(), atyp))
+            .into_iter()
+            .chain([(self.bit(), self.bit_vector())]);
+
+        for (styp, atyp) in vectors {
             let ops = [
                 Operator::And,
                 Operator::Or,
@@ -933,22 +968,24 @@ impl<'a> AnalyzeContext<'a, '_> {
 
             let implicits = ops.iter().flat_map(|op| {
                 let op = *op;
+                // Reduction operators and operators mixing arrays and scalars were added in VHDL-2008
                 [
                     // A op A -> A
-                    self.symmetric_binary(op, atyp),
+                    Some(self.symmetric_binary(op, atyp)),
                     if op == Operator::Not {
                         // op A -> A
-                        self.unary(op, atyp, atyp)
+                        Some(self.unary(op, atyp, atyp))
                     } else {
                         // op A -> S
-                        self.unary(op, atyp, styp)
+                        vhdl2008.then(|| self.unary(op, atyp, styp))
                     },
                     // A op S -> A
-                    self.binary(op, atyp, atyp, styp, atyp),
+                    vhdl2008.then(|| self.binary(op, atyp, atyp, styp, atyp)),
                     // S op A -> A
-                    self.binary(op, atyp, styp, atyp, atyp),
+                    vhdl2008.then(|| self.binary(op, atyp, styp, atyp, atyp)),
                 ]
                 .into_iter()
+                .flatten()
             });
 
             for ent in implicits {
@@ -960,6 +997,11 @@ impl<'a> AnalyzeContext<'a, '_> {
             }
         }
 
+        // The remaining implicit declarations were added in VHDL-2008
+        if !vhdl2008 {
+            return;
+        }
+
         // Predefined overloaded TO_STRING operations
         // function TO_STRING (VALUE: REAL; DIGITS: NATURAL) return STRING;
         {
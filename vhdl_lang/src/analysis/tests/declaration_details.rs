@@ -0,0 +1,226 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::analysis::{DeclarationDetails, InterfaceDetails};
+
+fn details_of(root: &DesignRoot, code: &Code, name: &str) -> DeclarationDetails {
+    let pos = code.s1(name);
+    root.declaration_details(root.search_reference(pos.source(), pos.start()).unwrap())
+        .unwrap()
+}
+
+fn interface(name: &str, mode: Option<&str>, typ: &str, default: Option<&str>) -> InterfaceDetails {
+    InterfaceDetails {
+        name: name.to_owned(),
+        mode: mode.map(str::to_owned),
+        typ: typ.to_owned(),
+        default: default.map(str::to_owned),
+    }
+}
+
+#[test]
+fn comments_directly_above_and_trailing_a_declaration() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  -- Not part of the documentation
+
+  -- The size of the data bus
+  -- in bits
+  constant width : natural := 8; -- Must be a power of two
+  constant depth : natural := 4;
+end package;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    assert_eq!(
+        details_of(&root, &code, "width").comments,
+        vec![
+            "The size of the data bus",
+            "in bits",
+            "Must be a power of two"
+        ]
+    );
+    assert!(details_of(&root, &code, "depth").comments.is_empty());
+}
+
+#[test]
+fn interface_of_entities_and_components() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  generic (
+    width : natural := 8;
+    type data_t
+  );
+  port (
+    clk, rst : bit;
+    data : out bit_vector(width - 1 downto 0)
+  );
+end entity;
+
+architecture a of ent is
+  component comp is
+    port (
+      q : inout bit := '0'
+    );
+  end component;
+begin
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let ent = details_of(&root, &code, "ent is");
+    assert_eq!(
+        ent.generics,
+        vec![
+            interface("width", None, "natural", Some("8")),
+            interface("data_t", None, "type", None),
+        ]
+    );
+    assert_eq!(
+        ent.ports,
+        vec![
+            interface("clk", Some("in"), "bit", None),
+            interface("rst", Some("in"), "bit", None),
+            interface("data", Some("out"), "bit_vector(width - 1 downto 0)", None),
+        ]
+    );
+
+    let comp = details_of(&root, &code, "comp is");
+    assert!(comp.generics.is_empty());
+    assert_eq!(
+        comp.ports,
+        vec![interface("q", Some("inout"), "bit", Some("'0'"))]
+    );
+}
+
+#[test]
+fn binding_of_instances() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity leaf is
+end entity;
+
+architecture rtl of leaf is
+begin
+end architecture;
+
+entity unbound is
+end entity;
+
+architecture a of unbound is
+begin
+end architecture;
+
+architecture b of unbound is
+begin
+end architecture;
+
+entity top is
+end entity;
+
+architecture structure of top is
+  component leaf is
+  end component;
+  component missing is
+  end component;
+begin
+  entity_inst : entity work.leaf;
+  component_inst : component leaf;
+  explicit_inst : entity work.unbound(b);
+  ambiguous_inst : entity work.unbound;
+  missing_inst : component missing;
+end architecture;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let binding = |name| details_of(&root, &code, name).binding;
+    assert_eq!(
+        binding("entity_inst"),
+        Some("entity libname.leaf(rtl)".to_owned())
+    );
+    assert_eq!(
+        binding("component_inst"),
+        Some("entity libname.leaf(rtl)".to_owned())
+    );
+    assert_eq!(
+        binding("explicit_inst"),
+        Some("entity libname.unbound(b)".to_owned())
+    );
+    assert_eq!(
+        binding("ambiguous_inst"),
+        Some("entity libname.unbound".to_owned())
+    );
+    assert_eq!(
+        binding("missing_inst"),
+        Some("component missing (unbound)".to_owned())
+    );
+}
+
+#[test]
+fn base_type_and_range_of_types() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+package pkg is
+  type state_t is (idle, busy, done);
+  type count_t is range 0 to 15;
+  subtype small_t is count_t range 0 to 3;
+  subtype alias_t is count_t;
+  subtype word_t is bit_vector(31 downto 0);
+end package;
+",
+    );
+
+    let (root, diagnostics) = builder.get_analyzed_root();
+    check_no_diagnostics(&diagnostics);
+
+    let base_type_and_range = |name| {
+        let details = details_of(&root, &code, name);
+        (details.base_type, details.range)
+    };
+    assert_eq!(
+        base_type_and_range("state_t"),
+        (None, Some("idle to done".to_owned()))
+    );
+    assert_eq!(
+        base_type_and_range("count_t"),
+        (None, Some("0 to 15".to_owned()))
+    );
+    assert_eq!(
+        base_type_and_range("small_t"),
+        (Some("count_t".to_owned()), Some("0 to 3".to_owned()))
+    );
+    assert_eq!(
+        base_type_and_range("alias_t"),
+        (Some("count_t".to_owned()), Some("0 to 15".to_owned()))
+    );
+    assert_eq!(
+        base_type_and_range("word_t"),
+        (
+            Some("BIT_VECTOR".to_owned()),
+            Some("(31 downto 0)".to_owned())
+        )
+    );
+}
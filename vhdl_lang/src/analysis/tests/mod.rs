@@ -9,6 +9,7 @@ mod association_formal;
 mod circular_dependencies;
 mod context_clause;
 mod custom_attributes;
+mod declaration_details;
 mod declarations;
 mod deferred_constant;
 mod discrete_ranges;
@@ -19,10 +20,12 @@ mod incomplete_type;
 mod incremental_analysis;
 mod package_instance;
 mod protected_type;
+mod psl;
 mod resolves_design_units;
 mod resolves_names;
 mod resolves_type_mark;
 mod sensitivity_list;
+mod standard_conformance;
 mod subprogram_arguments;
 mod subprogram_instance;
 mod tool_directive;
@@ -0,0 +1,182 @@
+//! This Source Code Form is subject to the terms of the Mozilla Public
+//! License, v. 2.0. If a copy of the MPL was not distributed with this file,
+//! You can obtain one at http://mozilla.org/MPL/2.0/.
+//!
+//! Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+
+#[test]
+fn psl_declarations_and_directives() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ent is
+  port (clk, req, ack, busy : in bit);
+end entity;
+
+architecture a of ent is
+  default clock is clk'event and clk = '1';
+  sequence transfer(boolean r, a) is {r; not a[*]; a};
+  property handshake(boolean r, a) is always r -> next a;
+  property no_overlap is never {req; req and busy};
+begin
+  assert handshake(req, ack) report \"no acknowledge\" severity error;
+  assert always {req} |=> {busy[*1 to 3]; ack};
+  assume always req -> next_e[1 to 4](ack);
+  restrict {not req[*]; transfer(req, ack)};
+  cover {transfer(req, ack)};
+  assert always rose(req) -> eventually! ack;
+  assert no_overlap;
+  -- psl check_stable : assert always stable(busy) until ack;
+  -- psl cover {req;
+  --            busy[+]; ack};
+end architecture;
+",
+    );
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn search_psl_property() {
+    check_search_reference(
+        "
+entity ent is
+  port (clk, req, ack : in bit);
+end entity;
+
+architecture a of ent is
+  property decl is always req -> next ack;
+begin
+  assert decl;
+  -- psl cover decl;
+end architecture;
+",
+    );
+}
+
+#[test]
+fn search_psl_sequence_parameter() {
+    check_search_reference(
+        "
+entity ent is
+  port (clk, req, ack : in bit);
+end entity;
+
+architecture a of ent is
+  sequence transfer(boolean decl, a) is {decl; a; not decl};
+begin
+  cover {transfer(req, ack)};
+end architecture;
+",
+    );
+}
+
+#[test]
+fn search_signal_within_psl_directive() {
+    check_search_reference(
+        "
+entity ent is
+  port (clk, req : in bit);
+end entity;
+
+architecture a of ent is
+  signal decl : bit;
+begin
+  assert always {req; decl[*2]} |-> next_event(decl)[2](req);
+end architecture;
+",
+    );
+}
+
+#[test]
+fn missing_names_within_psl_directive() {
+    let mut builder = LibraryBuilder::new();
+    let code = builder.code(
+        "libname",
+        "
+entity ent is
+  port (clk, req : in bit);
+end entity;
+
+architecture a of ent is
+begin
+  assert always req -> next missing;
+  cover {req; prev(missing)};
+end architecture;
+",
+    );
+    check_diagnostics(
+        builder.analyze(),
+        (1..=2).map(|idx| missing(&code, "missing", idx)).collect(),
+    );
+}
+
+#[test]
+fn psl_comment_in_process_is_a_comment() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+entity ent is
+  port (clk, a, b : in bit);
+end entity;
+
+architecture rtl of ent is
+begin
+  process (clk)
+  begin
+    -- psl assert always a -> next b;
+    if clk = '1' then
+    end if;
+  end process;
+end architecture;
+",
+    );
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn psl_comment_in_package_is_a_comment() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package pkg is
+  -- psl property never_both is never (a and b);
+  constant width : natural := 8;
+end package;
+",
+    );
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn psl_comment_written_as_prose_is_a_comment() {
+    let mut builder = LibraryBuilder::new();
+    builder.code(
+        "libname",
+        "
+package pkg is
+  -- psl default clock is the rising edge of clk, see below
+  constant width : natural := 8;
+end package;
+
+entity ent is
+  port (clk, a : in bit);
+end entity;
+
+architecture rtl of ent is
+  -- psl default clock is the rising edge of clk, see below
+  signal b : bit;
+begin
+  -- psl assert that the fifo can't overflow; it's checked by the testbench
+  -- psl cover the case where
+  --   both requests arrive
+  b <= a;
+end architecture;
+",
+    );
+    check_no_diagnostics(&builder.analyze());
+}
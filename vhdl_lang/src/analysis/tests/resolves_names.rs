@@ -2200,3 +2200,42 @@ end package;
     );
     check_no_diagnostics(&builder.analyze())
 }
+
+#[test]
+fn reflect_attribute() {
+    let mut builder = LibraryBuilder::with_standard(crate::VHDLStandard::VHDL2019);
+    builder.add_reflection();
+    let code = builder.code(
+        "libname",
+        "
+use std.reflection.all;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  constant c : natural := 0;
+begin
+  process
+    variable vm : value_mirror;
+    variable sm : subtype_mirror;
+  begin
+    vm := c'reflect;
+    sm := natural'reflect;
+    sm := c'reflect;
+    vm := ent'reflect;
+    wait;
+  end process;
+end architecture;
+",
+    );
+
+    let diagnostics = builder.analyze();
+    assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+    assert!(diagnostics
+        .iter()
+        .any(|diag| diag.pos == code.s1("sm := c'reflect").s1("c'reflect").pos()));
+    assert!(diagnostics
+        .iter()
+        .any(|diag| diag.pos == code.s1("ent'reflect").pos()));
+}
@@ -0,0 +1,147 @@
+//! This Source Code Form is subject to the terms of the Mozilla Public
+//! License, v. 2.0. If a copy of the MPL was not distributed with this file,
+//! You can obtain one at http://mozilla.org/MPL/2.0/.
+//!
+//! Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::*;
+use crate::data::error_codes::ErrorCode;
+use vhdl_lang::VHDLStandard::{VHDL1993, VHDL2008};
+
+const VHDL_2008_CONSTRUCTS: &str = "
+context ctx is
+  library std;
+end context;
+
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal s, t : bit;
+  signal v : bit_vector(0 to 1);
+begin
+  process (all) is
+    variable b : boolean;
+    variable x : bit;
+  begin
+    b := ?? s;
+    x := s ?= t;
+    x := and v;
+    x := s when b else t;
+  end process;
+end architecture;
+";
+
+#[test]
+fn vhdl_2008_constructs_are_reported_for_older_standards() {
+    let mut builder = LibraryBuilder::with_standard(VHDL1993);
+    let code = builder.code("libname", VHDL_2008_CONSTRUCTS);
+    let diagnostics = builder.analyze();
+    check_diagnostics(
+        diagnostics,
+        vec![
+            Diagnostic::requires_newer_standard(
+                code.s1("context"),
+                "Context declaration",
+                VHDL2008,
+            ),
+            Diagnostic::requires_newer_standard(code.s1("all"), "Sensitivity list 'all'", VHDL2008),
+            Diagnostic::requires_newer_standard(code.s1("??"), "Condition operator '??'", VHDL2008),
+            Diagnostic::requires_newer_standard(
+                code.s1("?="),
+                "Matching relational operator '?='",
+                VHDL2008,
+            ),
+            Diagnostic::requires_newer_standard(
+                code.s1("and v").s1("and"),
+                "Unary logical operator 'and'",
+                VHDL2008,
+            ),
+            Diagnostic::requires_newer_standard(
+                code.s1("when b").s1("b"),
+                "Conditional variable assignment",
+                VHDL2008,
+            ),
+            // The VHDL-93 standard package does not declare these operators
+            Diagnostic::new(
+                code.s1("??"),
+                "No declaration of operator '??'",
+                ErrorCode::Unresolved,
+            ),
+            Diagnostic::new(
+                code.s1("?="),
+                "No declaration of operator '?='",
+                ErrorCode::Unresolved,
+            ),
+            Diagnostic::new(
+                code.s1("and v").s1("and"),
+                "Found no match for operator \"and\"",
+                ErrorCode::Unresolved,
+            ),
+        ],
+    );
+}
+
+#[test]
+fn vhdl_2008_constructs_are_accepted_by_vhdl_2008() {
+    let mut builder = LibraryBuilder::with_standard(VHDL2008);
+    builder.code("libname", VHDL_2008_CONSTRUCTS);
+    check_no_diagnostics(&builder.analyze());
+}
+
+#[test]
+fn generic_package_requires_vhdl_2008() {
+    let mut builder = LibraryBuilder::with_standard(VHDL1993);
+    let code = builder.code(
+        "libname",
+        "
+package gpkg is
+  generic (width : natural);
+end package;
+",
+    );
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::requires_newer_standard(
+            code.s1("generic"),
+            "Generic package",
+            VHDL2008,
+        )],
+    );
+}
+
+#[test]
+fn reading_out_port_requires_vhdl_2008() {
+    let mut builder = LibraryBuilder::with_standard(VHDL1993);
+    let code = builder.code(
+        "libname",
+        "
+entity child is
+  port (o : out bit);
+end entity;
+
+architecture a of child is
+begin
+end architecture;
+
+entity ent is
+  port (o : out bit; o2 : out bit);
+end entity;
+
+architecture a of ent is
+  signal s : bit;
+begin
+  s <= o;
+  inst: entity work.child port map (o => o2);
+end architecture;
+",
+    );
+    check_diagnostics(
+        builder.analyze(),
+        vec![Diagnostic::requires_newer_standard(
+            code.s1("s <= o").s1("o"),
+            "Reading port of mode out",
+            VHDL2008,
+        )],
+    );
+}
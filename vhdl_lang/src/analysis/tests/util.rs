@@ -75,6 +75,11 @@ end architecture;"
         self.add_code("ieee", std_logic_1164);
     }
 
+    pub fn add_reflection(&mut self) {
+        let reflection = self.code_builder.code_from_source(reflection_package());
+        self.add_code("std", reflection);
+    }
+
     pub fn get_analyzed_root(&self) -> (DesignRoot, Vec<Diagnostic>) {
         let mut root = DesignRoot::new(self.code_builder.symbols.clone());
         let mut diagnostics = Vec::new();
@@ -113,26 +118,33 @@ end architecture;"
     }
 }
 
-fn standard_package() -> Source {
+fn standard_package(standard: VHDLStandard) -> Source {
+    let contents: &[u8] = if standard < VHDLStandard::VHDL2008 {
+        include_bytes!("../../../vhdl_libraries/std1993/standard.vhd")
+    } else {
+        include_bytes!("../../../vhdl_libraries/std/standard.vhd")
+    };
     Source::inline(
         Path::new("standard.vhd"),
-        &Latin1String::new(include_bytes!(
-            "../../../../vhdl_libraries/std/standard.vhd"
-        ))
-        .to_string(),
+        &Latin1String::new(contents).to_string(),
     )
 }
-fn textio_package() -> Source {
+fn textio_package(standard: VHDLStandard) -> Source {
+    let contents: &[u8] = if standard < VHDLStandard::VHDL2008 {
+        include_bytes!("../../../vhdl_libraries/std1993/textio.vhd")
+    } else {
+        include_bytes!("../../../vhdl_libraries/std/textio.vhd")
+    };
     Source::inline(
         Path::new("textio.vhd"),
-        &Latin1String::new(include_bytes!("../../../../vhdl_libraries/std/textio.vhd")).to_string(),
+        &Latin1String::new(contents).to_string(),
     )
 }
 
 fn env_package() -> Source {
     Source::inline(
         Path::new("env.vhd"),
-        &Latin1String::new(include_bytes!("../../../../vhdl_libraries/std/env.vhd")).to_string(),
+        &Latin1String::new(include_bytes!("../../../vhdl_libraries/std/env.vhd")).to_string(),
     )
 }
 
@@ -140,7 +152,17 @@ fn std_logic_1164_package() -> Source {
     Source::inline(
         Path::new("std_logic_1164.vhd"),
         &Latin1String::new(include_bytes!(
-            "../../../../vhdl_libraries/ieee2008/std_logic_1164.vhdl"
+            "../../../vhdl_libraries/ieee2008/std_logic_1164.vhdl"
+        ))
+        .to_string(),
+    )
+}
+
+fn reflection_package() -> Source {
+    Source::inline(
+        Path::new("reflection.vhd"),
+        &Latin1String::new(include_bytes!(
+            "../../../vhdl_libraries/std2019/reflection.vhd"
         ))
         .to_string(),
     )
@@ -151,8 +173,8 @@ pub fn add_standard_library(symbols: Arc<Symbols>, root: &mut DesignRoot) {
         symbols: symbols.clone(),
         standard: VHDLStandard::default(),
     };
-    let std_standard = builder.code_from_source(standard_package());
-    let std_textio = builder.code_from_source(textio_package());
+    let std_standard = builder.code_from_source(standard_package(symbols.standard()));
+    let std_textio = builder.code_from_source(textio_package(symbols.standard()));
     let std_env = builder.code_from_source(env_package());
     let std_sym = symbols.symtab().insert_utf8("std");
 
@@ -10,6 +10,7 @@ use crate::data::error_codes::ErrorCode;
 use crate::data::*;
 use crate::named_entity::{Signature, *};
 use crate::HasTokenSpan;
+use crate::VHDLStandard;
 use analyze::*;
 
 impl<'a> AnalyzeContext<'a, '_> {
@@ -522,6 +523,10 @@ impl<'a> AnalyzeContext<'a, '_> {
 
     /// The matching operators such as ?= are defined for 1d arrays of bit and std_ulogic element type
     fn has_matching_op(&self, typ: TypeEnt<'a>) -> bool {
+        if self.standard() < VHDLStandard::VHDL2008 {
+            // Matching operators were added in VHDL-2008
+            return false;
+        }
         if self.is_std_logic_1164 {
             // Within the std_logic_1164 we do not have efficient access to the types
             typ.designator() == &Designator::Identifier(self.root.symbol_utf8("std_ulogic"))
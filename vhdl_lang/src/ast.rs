@@ -23,10 +23,13 @@ use crate::named_entity::{EntityId, Reference};
 use crate::syntax::{Token, TokenAccess, TokenId};
 use crate::TokenSpan;
 pub(crate) use any_design_unit::*;
+#[cfg(feature = "analysis-cache")]
+use serde::{Deserialize, Serialize};
 use vhdl_lang::HasTokenSpan;
 
 /// LRM 15.8 Bit string literals
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum BaseSpecifier {
     B,
     O,
@@ -41,6 +44,7 @@ pub enum BaseSpecifier {
 }
 
 #[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Operator {
     And,
     Or,
@@ -86,6 +90,7 @@ pub enum Operator {
 
 /// LRM 8.6 Attribute names
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct AttributeName {
     pub name: WithTokenSpan<Name>,
     pub signature: Option<WithTokenSpan<Signature>>,
@@ -94,18 +99,21 @@ pub struct AttributeName {
 }
 
 #[derive(PartialEq, Debug, Copy, Clone, Eq)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum TypeAttribute {
     Subtype,
     Element,
 }
 
 #[derive(PartialEq, Debug, Copy, Clone, Eq)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum RangeAttribute {
     Range,
     ReverseRange,
 }
 
 #[derive(PartialEq, Debug, Clone, Eq)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum AttributeDesignator {
     Type(TypeAttribute),
     Range(RangeAttribute),
@@ -129,9 +137,11 @@ pub enum AttributeDesignator {
     InstanceName,
     PathName,
     Converse,
+    Reflect,
 }
 
 #[derive(PartialEq, Debug, Copy, Clone, Eq)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum SignalAttribute {
     Delayed,
     Stable,
@@ -148,6 +158,7 @@ pub enum SignalAttribute {
 
 /// LRM 8.7 External names
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ExternalObjectClass {
     Constant,
     Signal,
@@ -166,6 +177,7 @@ impl From<ExternalObjectClass> for ObjectClass {
 
 /// LRM 8.7 External names
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ExternalPath {
     Package(WithTokenSpan<Name>),
     Absolute(WithTokenSpan<Name>),
@@ -176,6 +188,7 @@ pub enum ExternalPath {
 
 /// LRM 8.7 External names
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ExternalName {
     pub class: ExternalObjectClass,
     pub path: WithTokenSpan<ExternalPath>,
@@ -185,6 +198,7 @@ pub struct ExternalName {
 
 /// LRM 8. Names
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Name {
     Designator(WithRef<Designator>),
     Selected(Box<WithTokenSpan<Name>>, WithToken<WithRef<Designator>>),
@@ -197,6 +211,7 @@ pub enum Name {
 
 /// LRM 9.3.4 Function calls
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct CallOrIndexed {
     pub name: WithTokenSpan<Name>,
     pub parameters: SeparatedList<AssociationElement>,
@@ -204,6 +219,7 @@ pub struct CallOrIndexed {
 
 /// LRM 9.3.3 Aggregates
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Choice {
     Expression(Expression),
     DiscreteRange(DiscreteRange),
@@ -212,6 +228,7 @@ pub enum Choice {
 
 /// LRM 9.3.3 Aggregates
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ElementAssociation {
     Positional(WithTokenSpan<Expression>),
     Named(Vec<WithTokenSpan<Choice>>, WithTokenSpan<Expression>),
@@ -219,6 +236,7 @@ pub enum ElementAssociation {
 
 /// LRM 6.5.7 Association Lists
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ActualPart {
     Expression(Expression),
     Open,
@@ -226,6 +244,7 @@ pub enum ActualPart {
 
 /// LRM 6.5.7 Association Lists
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct AssociationElement {
     pub formal: Option<WithTokenSpan<Name>>,
     pub actual: WithTokenSpan<ActualPart>,
@@ -233,6 +252,7 @@ pub struct AssociationElement {
 
 /// LRM 15.5 Abstract literals
 #[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum AbstractLiteral {
     Integer(u64),
     Real(f64),
@@ -240,6 +260,7 @@ pub enum AbstractLiteral {
 
 /// LRM 15.8 Bit string literals
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct BitString {
     pub length: Option<u32>,
     pub base: BaseSpecifier,
@@ -247,6 +268,7 @@ pub struct BitString {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct PhysicalLiteral {
     pub value: AbstractLiteral,
     pub unit: WithRef<Ident>,
@@ -254,6 +276,7 @@ pub struct PhysicalLiteral {
 
 /// LRM 9.3.2 Literals
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Literal {
     String(Latin1String),
     BitString(BitString),
@@ -265,6 +288,7 @@ pub enum Literal {
 
 /// LRM 9.3.7 Allocators
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Allocator {
     Qualified(QualifiedExpression),
     Subtype(SubtypeIndication),
@@ -272,6 +296,7 @@ pub enum Allocator {
 
 /// LRM 9.3.5 Qualified expressions
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct QualifiedExpression {
     pub type_mark: WithTokenSpan<Name>,
     pub expr: WithTokenSpan<Expression>,
@@ -279,6 +304,7 @@ pub struct QualifiedExpression {
 
 /// LRM 9. Expressions
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Expression {
     Binary(
         WithToken<WithRef<Operator>>,
@@ -308,6 +334,7 @@ pub enum Expression {
 pub type Ident = WithToken<Symbol>;
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Direction {
     Ascending,
     Descending,
@@ -319,12 +346,14 @@ pub enum Direction {
 ///     range_attribute_name
 ///   | simple_expression direction simple_expression
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum DiscreteRange {
     Discrete(WithTokenSpan<Name>, Option<Range>),
     Range(Range),
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct RangeConstraint {
     pub direction: Direction,
     pub left_expr: Box<WithTokenSpan<Expression>>,
@@ -338,6 +367,7 @@ impl RangeConstraint {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Range {
     Range(RangeConstraint),
     Attribute(Box<AttributeName>),
@@ -345,12 +375,14 @@ pub enum Range {
 
 /// LRM: record_element_constraint
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ElementConstraint {
     pub ident: Ident,
     pub constraint: Box<WithTokenSpan<SubtypeConstraint>>,
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum SubtypeConstraint {
     Range(Range),
     /// Empty Vec means Open
@@ -363,6 +395,7 @@ pub enum SubtypeConstraint {
 
 /// LRM 6.3 Subtype declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct RecordElementResolution {
     pub ident: Ident,
     pub resolution: Box<ResolutionIndication>,
@@ -370,6 +403,7 @@ pub struct RecordElementResolution {
 
 /// LRM 6.3 Subtype declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ResolutionIndication {
     FunctionName(WithTokenSpan<Name>),
     ArrayElement(WithTokenSpan<Name>),
@@ -396,6 +430,7 @@ impl HasTokenSpan for ResolutionIndication {
 
 /// LRM 6.3 Subtype declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct SubtypeIndication {
     pub resolution: Option<ResolutionIndication>,
     pub type_mark: WithTokenSpan<Name>,
@@ -404,6 +439,7 @@ pub struct SubtypeIndication {
 
 /// LRM 5.3 Array Types
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ArrayIndex {
     /// Unbounded
     /// {identifier} range <>
@@ -416,6 +452,7 @@ pub enum ArrayIndex {
 /// LRM 5.3.3 Record types
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ElementDeclaration {
     pub idents: Vec<WithDecl<Ident>>,
     pub colon_token: TokenId,
@@ -424,13 +461,17 @@ pub struct ElementDeclaration {
 
 /// LRM 5.6.2 Protected type declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ProtectedTypeDeclarativeItem {
     Subprogram(SubprogramDeclaration),
 }
 
 #[derive(PartialEq, Eq, Hash, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Designator {
-    Identifier(Symbol),
+    Identifier(
+        #[cfg_attr(feature = "analysis-cache", serde(with = "crate::cache::cached"))] Symbol,
+    ),
     OperatorSymbol(Operator),
     Character(u8),
     Anonymous(usize),
@@ -438,8 +479,14 @@ pub enum Designator {
 
 /// An item which has a reference to a declaration
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct WithRef<T> {
+    #[cfg_attr(
+        feature = "analysis-cache",
+        serde(with = "crate::cache::cached", bound = "T: crate::cache::Cached")
+    )]
     pub item: T,
+    #[cfg_attr(feature = "analysis-cache", serde(with = "crate::cache::cached"))]
     pub reference: Reference,
 }
 
@@ -454,8 +501,10 @@ impl<T> WithRef<T> {
 
 /// An item which declares a named entity
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct WithDecl<T> {
     pub tree: T,
+    #[cfg_attr(feature = "analysis-cache", serde(with = "crate::cache::cached"))]
     pub decl: Reference,
 }
 
@@ -494,6 +543,7 @@ impl HasDesignator for WithToken<WithRef<Designator>> {
 
 /// LRM 6.6 Alias declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct AliasDeclaration {
     pub designator: WithDecl<WithToken<Designator>>,
     pub subtype_indication: Option<SubtypeIndication>,
@@ -504,6 +554,7 @@ pub struct AliasDeclaration {
 
 /// LRM 6.7 Attribute declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct AttributeDeclaration {
     pub ident: WithDecl<Ident>,
     pub type_mark: WithTokenSpan<Name>,
@@ -511,6 +562,7 @@ pub struct AttributeDeclaration {
 
 /// LRM 7.2 Attribute specification
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct EntityTag {
     pub designator: WithToken<WithRef<Designator>>,
     pub signature: Option<WithTokenSpan<Signature>>,
@@ -518,6 +570,7 @@ pub struct EntityTag {
 
 /// LRM 7.2 Attribute specification
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum EntityName {
     Name(EntityTag),
     All,
@@ -527,6 +580,7 @@ pub enum EntityName {
 /// LRM 7.2 Attribute specification
 // @TODO there are more classes
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum EntityClass {
     Entity,
     Architecture,
@@ -551,6 +605,7 @@ pub enum EntityClass {
 
 /// LRM 7.2 Attribute specification
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct AttributeSpecification {
     pub ident: WithRef<Ident>,
     pub entity_name: EntityName,
@@ -561,6 +616,7 @@ pub struct AttributeSpecification {
 
 /// LRM 7.2 Attribute specification
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Attribute {
     Specification(AttributeSpecification),
     Declaration(AttributeDeclaration),
@@ -568,18 +624,21 @@ pub enum Attribute {
 
 /// LRM 5.6.2 Protected type declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ProtectedTypeDeclaration {
     pub items: Vec<ProtectedTypeDeclarativeItem>,
 }
 
 /// LRM 5.6.3 Protected type bodies
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ProtectedTypeBody {
     pub decl: Vec<WithTokenSpan<Declaration>>,
 }
 
 /// LRM 5.4.2 Physical type declaration
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct PhysicalTypeDeclaration {
     pub range: Range,
     pub units_token: TokenId,
@@ -589,13 +648,17 @@ pub struct PhysicalTypeDeclaration {
 
 /// LRM 5.2.2 Enumeration types
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum EnumerationLiteral {
-    Identifier(Symbol),
+    Identifier(
+        #[cfg_attr(feature = "analysis-cache", serde(with = "crate::cache::cached"))] Symbol,
+    ),
     Character(u8),
 }
 
 /// LRM 5 Types
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum TypeDefinition {
     /// LRM 5.2 Scalar Types
     /// LRM 5.2.2 Enumeration types
@@ -614,7 +677,9 @@ pub enum TypeDefinition {
     /// LRM 5.4 Access types
     Access(SubtypeIndication),
     /// LRM 5.4.2 Incomplete type declarations
-    Incomplete(Reference),
+    Incomplete(
+        #[cfg_attr(feature = "analysis-cache", serde(with = "crate::cache::cached"))] Reference,
+    ),
     /// LRM 5.5 File types
     File(WithTokenSpan<Name>),
     /// LRM 5.6 Protected types
@@ -627,6 +692,7 @@ pub enum TypeDefinition {
 /// LRM 6.2 Type declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct TypeDeclaration {
     pub ident: WithDecl<Ident>,
     pub def: TypeDefinition,
@@ -646,6 +712,7 @@ impl TypeDeclaration {
 
 /// LRM 6.4.2 Object Declarations
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ObjectClass {
     Signal,
     Constant,
@@ -654,6 +721,7 @@ pub enum ObjectClass {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum InterfaceType {
     Port,
     Generic,
@@ -661,6 +729,7 @@ pub enum InterfaceType {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ObjectDeclaration {
     pub class: ObjectClass,
     pub colon_token: TokenId,
@@ -670,6 +739,7 @@ pub struct ObjectDeclaration {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct FileDeclaration {
     pub idents: Vec<WithDecl<Ident>>,
     pub colon_token: TokenId,
@@ -679,13 +749,17 @@ pub struct FileDeclaration {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum SubprogramDesignator {
-    Identifier(Symbol),
+    Identifier(
+        #[cfg_attr(feature = "analysis-cache", serde(with = "crate::cache::cached"))] Symbol,
+    ),
     OperatorSymbol(Operator),
 }
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct InterfaceList {
     pub interface_type: InterfaceType,
     pub items: Vec<InterfaceDeclaration>,
@@ -694,6 +768,7 @@ pub struct InterfaceList {
 /// LRM 4.2 Subprogram declaration
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ProcedureSpecification {
     pub designator: WithDecl<WithToken<SubprogramDesignator>>,
     pub header: Option<SubprogramHeader>,
@@ -703,6 +778,7 @@ pub struct ProcedureSpecification {
 /// LRM 4.2 Subprogram declaration
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct FunctionSpecification {
     pub pure: bool,
     pub designator: WithDecl<WithToken<SubprogramDesignator>>,
@@ -714,6 +790,7 @@ pub struct FunctionSpecification {
 /// LRM 4.3 Subprogram bodies
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct SubprogramBody {
     pub specification: SubprogramSpecification,
     pub declarations: Vec<WithTokenSpan<Declaration>>,
@@ -728,12 +805,14 @@ pub struct SubprogramBody {
 /// Instead, the element that contains the header (e.g., procedure specifications)
 /// mark this element as optional.
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct SubprogramHeader {
     pub generic_list: InterfaceList,
     pub map_aspect: Option<MapAspect>,
 }
 
 #[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum SubprogramKind {
     Function,
     Procedure,
@@ -742,6 +821,7 @@ pub enum SubprogramKind {
 /// LRM 4.4 Subprogram Instantiation Statement
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct SubprogramInstantiation {
     pub kind: SubprogramKind,
     pub ident: WithDecl<Ident>,
@@ -752,12 +832,14 @@ pub struct SubprogramInstantiation {
 
 /// LRM 4.5.3 Signatures
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Signature {
     Function(Vec<WithTokenSpan<Name>>, WithTokenSpan<Name>),
     Procedure(Vec<WithTokenSpan<Name>>),
 }
 
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum SubprogramSpecification {
     Procedure(Box<ProcedureSpecification>),
     Function(FunctionSpecification),
@@ -766,12 +848,14 @@ pub enum SubprogramSpecification {
 /// LRM 4.2 Subprogram declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct SubprogramDeclaration {
     pub specification: SubprogramSpecification,
 }
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct InterfaceFileDeclaration {
     pub idents: Vec<WithDecl<Ident>>,
     pub colon_token: TokenId,
@@ -781,6 +865,7 @@ pub struct InterfaceFileDeclaration {
 /// LRM 6.5.2 Interface object declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct InterfaceObjectDeclaration {
     pub list_type: InterfaceType,
     pub colon_token: TokenId,
@@ -789,12 +874,14 @@ pub struct InterfaceObjectDeclaration {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ModeIndication {
     Simple(SimpleModeIndication),
     View(ModeViewIndication),
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct SimpleModeIndication {
     pub mode: Option<WithToken<Mode>>,
     pub class: ObjectClass,
@@ -804,6 +891,7 @@ pub struct SimpleModeIndication {
 }
 
 #[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ModeViewIndicationKind {
     Array,
     Record,
@@ -811,6 +899,7 @@ pub enum ModeViewIndicationKind {
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ModeViewIndication {
     pub kind: ModeViewIndicationKind,
     pub name: WithTokenSpan<Name>,
@@ -819,6 +908,7 @@ pub struct ModeViewIndication {
 
 /// LRM 6.5.5 Interface package declaration
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum InterfacePackageGenericMapAspect {
     Map(SeparatedList<AssociationElement>),
     Box,
@@ -828,6 +918,7 @@ pub enum InterfacePackageGenericMapAspect {
 /// LRM 6.5.5 Interface package declaration
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct InterfacePackageDeclaration {
     pub ident: WithDecl<Ident>,
     pub package_name: WithTokenSpan<Name>,
@@ -835,6 +926,7 @@ pub struct InterfacePackageDeclaration {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum SubprogramDefault {
     Name(WithTokenSpan<Name>),
     Box,
@@ -842,12 +934,14 @@ pub enum SubprogramDefault {
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct InterfaceSubprogramDeclaration {
     pub specification: SubprogramSpecification,
     pub default: Option<SubprogramDefault>,
 }
 
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum InterfaceDeclaration {
     Object(InterfaceObjectDeclaration),
     File(InterfaceFileDeclaration),
@@ -859,6 +953,7 @@ pub enum InterfaceDeclaration {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Mode {
     #[default]
     In,
@@ -871,6 +966,7 @@ pub enum Mode {
 /// LRM 6.8 Component declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ComponentDeclaration {
     pub ident: WithDecl<Ident>,
     pub is_token: Option<TokenId>,
@@ -881,6 +977,7 @@ pub struct ComponentDeclaration {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Declaration {
     Object(ObjectDeclaration),
     File(FileDeclaration),
@@ -895,6 +992,7 @@ pub enum Declaration {
     Package(PackageInstantiation),
     Configuration(ConfigurationSpecification),
     View(ModeViewDeclaration),
+    Psl(PslDeclaration),
 }
 
 impl Declaration {
@@ -911,6 +1009,9 @@ impl Declaration {
             | Declaration::SubprogramInstantiation(SubprogramInstantiation { ident, .. })
             | Declaration::Attribute(Attribute::Declaration(AttributeDeclaration {
                 ident, ..
+            }))
+            | Declaration::Psl(PslDeclaration::Property(PslPropertyDeclaration {
+                ident, ..
             })) => ident.decl.get().into_iter().collect(),
             Declaration::Alias(alias) => alias.designator.decl.get().into_iter().collect(),
             Declaration::SubprogramDeclaration(SubprogramDeclaration { specification, .. })
@@ -928,6 +1029,7 @@ impl Declaration {
 
 /// LRM 10.2 Wait statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct WaitStatement {
     pub sensitivity_clause: Option<Vec<WithTokenSpan<Name>>>,
     pub condition_clause: Option<WithTokenSpan<Expression>>,
@@ -936,6 +1038,7 @@ pub struct WaitStatement {
 
 /// LRM 10.3 Assertion statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct AssertStatement {
     pub condition: WithTokenSpan<Expression>,
     pub report: Option<WithTokenSpan<Expression>>,
@@ -944,6 +1047,7 @@ pub struct AssertStatement {
 
 /// LRM 10.4 Report statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ReportStatement {
     pub report: WithTokenSpan<Expression>,
     pub severity: Option<WithTokenSpan<Expression>>,
@@ -951,6 +1055,7 @@ pub struct ReportStatement {
 
 /// LRM 10.5 Signal assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Target {
     Name(Name),
     Aggregate(Vec<WithTokenSpan<ElementAssociation>>),
@@ -958,6 +1063,7 @@ pub enum Target {
 
 /// LRM 10.5 Signal assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct WaveformElement {
     pub value: WithTokenSpan<Expression>,
     pub after: Option<WithTokenSpan<Expression>>,
@@ -978,6 +1084,7 @@ impl HasTokenSpan for WaveformElement {
 
 /// LRM 10.5 Signal assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Waveform {
     Elements(Vec<WaveformElement>),
     Unaffected(TokenId),
@@ -985,6 +1092,7 @@ pub enum Waveform {
 
 /// LRM 10.5 Signal assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum DelayMechanism {
     Transport,
     Inertial {
@@ -994,6 +1102,7 @@ pub enum DelayMechanism {
 
 /// LRM 10.5 Signal assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct SignalAssignment {
     pub target: WithTokenSpan<Target>,
     pub delay_mechanism: Option<WithTokenSpan<DelayMechanism>>,
@@ -1001,12 +1110,14 @@ pub struct SignalAssignment {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ForceMode {
     In,
     Out,
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct SignalForceAssignment {
     pub target: WithTokenSpan<Target>,
     pub force_mode: Option<ForceMode>,
@@ -1015,6 +1126,7 @@ pub struct SignalForceAssignment {
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct SignalReleaseAssignment {
     pub target: WithTokenSpan<Target>,
     pub force_mode: Option<ForceMode>,
@@ -1022,6 +1134,7 @@ pub struct SignalReleaseAssignment {
 
 /// LRM 10.6 Variable assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct VariableAssignment {
     pub target: WithTokenSpan<Target>,
     pub rhs: AssignmentRightHand<WithTokenSpan<Expression>>,
@@ -1030,6 +1143,7 @@ pub struct VariableAssignment {
 /// LRM 10.5 Signal assignment statement
 /// LRM 10.6 Variable assignment statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum AssignmentRightHand<T> {
     Simple(T),
     Conditional(Conditionals<T>),
@@ -1037,12 +1151,14 @@ pub enum AssignmentRightHand<T> {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct Conditional<T> {
     pub condition: WithTokenSpan<Expression>,
     pub item: T,
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct Conditionals<T> {
     pub conditionals: Vec<Conditional<T>>,
     pub else_item: Option<(T, TokenId)>,
@@ -1050,19 +1166,22 @@ pub struct Conditionals<T> {
 
 /// LRM 10.8 If statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct IfStatement {
     pub conds: Conditionals<Vec<LabeledSequentialStatement>>,
-    pub end_label_pos: Option<SrcPos>,
+    pub end_label_pos: Option<TokenId>,
 }
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct Alternative<T> {
     pub choices: Vec<WithTokenSpan<Choice>>,
     pub item: T,
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct Selection<T> {
     pub expression: WithTokenSpan<Expression>,
     pub alternatives: Vec<Alternative<T>>,
@@ -1070,16 +1189,18 @@ pub struct Selection<T> {
 
 /// LRM 10.9 Case statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct CaseStatement {
     pub is_matching: bool,
     pub expression: WithTokenSpan<Expression>,
     pub alternatives: Vec<Alternative<Vec<LabeledSequentialStatement>>>,
     pub end_token: TokenId,
-    pub end_label_pos: Option<SrcPos>,
+    pub end_label_pos: Option<TokenId>,
 }
 
 /// LRM 10.10 Loop statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum IterationScheme {
     While(WithTokenSpan<Expression>),
     For(WithDecl<Ident>, DiscreteRange),
@@ -1087,16 +1208,18 @@ pub enum IterationScheme {
 
 /// LRM 10.10 Loop statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct LoopStatement {
     pub iteration_scheme: Option<IterationScheme>,
     pub loop_token: TokenId,
     pub statements: Vec<LabeledSequentialStatement>,
     pub end_token: TokenId,
-    pub end_label_pos: Option<SrcPos>,
+    pub end_label_pos: Option<TokenId>,
 }
 
 /// LRM 10.11 Next statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct NextStatement {
     pub loop_label: Option<WithRef<Ident>>,
     pub condition: Option<WithTokenSpan<Expression>>,
@@ -1104,6 +1227,7 @@ pub struct NextStatement {
 
 /// LRM 10.12 Exit statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ExitStatement {
     pub loop_label: Option<WithRef<Ident>>,
     pub condition: Option<WithTokenSpan<Expression>>,
@@ -1111,12 +1235,14 @@ pub struct ExitStatement {
 
 /// LRM 10.13 Return statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ReturnStatement {
     pub expression: Option<WithTokenSpan<Expression>>,
 }
 
 /// LRM 10. Sequential statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum SequentialStatement {
     Wait(WaitStatement),
     Assert(AssertStatement),
@@ -1137,6 +1263,7 @@ pub enum SequentialStatement {
 
 /// LRM 10. Sequential statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct LabeledSequentialStatement {
     pub label: WithDecl<Option<Ident>>,
     pub statement: WithTokenSpan<SequentialStatement>,
@@ -1145,6 +1272,7 @@ pub struct LabeledSequentialStatement {
 /// LRM 11.2 Block statement
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct BlockStatement {
     pub guard_condition: Option<WithTokenSpan<Expression>>,
     pub header: BlockHeader,
@@ -1153,11 +1281,12 @@ pub struct BlockStatement {
     pub begin_token: TokenId,
     pub statements: Vec<LabeledConcurrentStatement>,
     pub end_token: TokenId,
-    pub end_label_pos: Option<SrcPos>,
+    pub end_label_pos: Option<TokenId>,
 }
 
 /// LRM 11.2 Block statement
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct BlockHeader {
     pub generic_clause: Option<InterfaceList>,
     pub generic_map: Option<MapAspect>,
@@ -1166,6 +1295,7 @@ pub struct BlockHeader {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum SensitivityList {
     Names(Vec<WithTokenSpan<Name>>),
     All,
@@ -1174,6 +1304,7 @@ pub enum SensitivityList {
 /// LRM 11.3 Process statement
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ProcessStatement {
     pub postponed: bool,
     pub sensitivity_list: Option<WithTokenSpan<SensitivityList>>,
@@ -1182,11 +1313,12 @@ pub struct ProcessStatement {
     pub begin_token: TokenId,
     pub statements: Vec<LabeledSequentialStatement>,
     pub end_token: TokenId,
-    pub end_label_pos: Option<SrcPos>,
+    pub end_label_pos: Option<TokenId>,
 }
 
 /// LRM 11.4 Concurrent procedure call statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ConcurrentProcedureCall {
     pub postponed: bool,
     pub call: WithTokenSpan<CallOrIndexed>,
@@ -1194,13 +1326,171 @@ pub struct ConcurrentProcedureCall {
 
 /// LRM 11.5 Concurrent assertion statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ConcurrentAssertStatement {
     pub postponed: bool,
     pub statement: AssertStatement,
 }
 
+/// PSL (IEEE 1850) operators
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
+pub enum PslOperator {
+    Always,
+    Never,
+    /// `eventually!`
+    Eventually,
+    Next,
+    NextA,
+    NextE,
+    NextEvent,
+    NextEventA,
+    NextEventE,
+    Until,
+    /// `until_`
+    UntilInclusive,
+    Before,
+    /// `before_`
+    BeforeInclusive,
+    Abort,
+    AsyncAbort,
+    SyncAbort,
+    /// `->`
+    Implication,
+    /// `<->`
+    Equivalence,
+    /// `|->`
+    OverlappingImplication,
+    /// `|=>`
+    NonOverlappingImplication,
+    /// `;`
+    Concatenation,
+    /// `:`
+    Fusion,
+    /// `|`
+    SequenceOr,
+    /// `and` of properties
+    And,
+    /// `or` of properties
+    Or,
+    /// `not` of a property
+    Not,
+    /// `&&`
+    LengthMatchingAnd,
+    Within,
+    /// `[*n]`
+    ConsecutiveRepetition,
+    /// `[+]`
+    PlusRepetition,
+    /// `[=n]`
+    NonConsecutiveRepetition,
+    /// `[->n]`
+    GotoRepetition,
+    /// A strong sequence `{a; b}!`
+    Strong,
+    /// `@`
+    Clock,
+}
+
+/// A PSL operator and its operands
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
+pub struct PslOperation {
+    pub operator: WithToken<PslOperator>,
+    /// The strong variant of the operator is used, i.e. `next!` or `until!`
+    pub strong: bool,
+    /// The count, range or condition of the operator, i.e. `3` in `next[3]` or `b` in `next_event(b)`
+    pub arguments: Vec<WithTokenSpan<Expression>>,
+    pub operands: Vec<WithTokenSpan<PslExpression>>,
+}
+
+/// A PSL property, sequence or boolean
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
+pub enum PslExpression {
+    /// A VHDL expression that is used as a PSL boolean
+    /// or a property or sequence instance, i.e. `handshake(req, ack)`
+    Hdl(Expression),
+    Operation(PslOperation),
+    /// A sequential extended regular expression, i.e. `{a; b}`
+    Braced(Box<WithTokenSpan<PslExpression>>),
+    /// A property within parentheses, i.e. `(a -> next b)`
+    Parenthesized(Box<WithTokenSpan<PslExpression>>),
+}
+
+/// The kind of a formal parameter of a PSL property or sequence declaration
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
+pub enum PslParameterKind {
+    Const,
+    Boolean,
+    Bit,
+    Bitvector,
+    Numeric,
+    String,
+    Property,
+    Sequence,
+}
+
+/// Formal parameters of a PSL property or sequence declaration, i.e. `boolean req, ack`
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
+pub struct PslParameter {
+    pub kind: WithToken<PslParameterKind>,
+    pub idents: Vec<WithDecl<Ident>>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
+pub enum PslDeclarationKind {
+    Property,
+    Sequence,
+}
+
+/// PSL property or sequence declaration,
+/// i.e. `property handshake(boolean req, ack) is always req -> next ack;`
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
+pub struct PslPropertyDeclaration {
+    pub kind: PslDeclarationKind,
+    pub ident: WithDecl<Ident>,
+    pub parameters: Vec<PslParameter>,
+    pub expression: WithTokenSpan<PslExpression>,
+}
+
+/// PSL declarations within a VHDL declarative part
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
+pub enum PslDeclaration {
+    Property(PslPropertyDeclaration),
+    /// `default clock is rising_edge(clk);`
+    DefaultClock(WithTokenSpan<PslExpression>),
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
+pub enum PslDirectiveKind {
+    Assert,
+    Assume,
+    AssumeGuarantee,
+    Restrict,
+    RestrictGuarantee,
+    Cover,
+}
+
+/// PSL verification directive, i.e. `assert always req -> next ack;`
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
+pub struct PslDirective {
+    pub kind: PslDirectiveKind,
+    pub property: WithTokenSpan<PslExpression>,
+    pub report: Option<WithTokenSpan<Expression>>,
+    pub severity: Option<WithTokenSpan<Expression>>,
+}
+
 /// 11.6 Concurrent signal assignment statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ConcurrentSignalAssignment {
     pub postponed: bool,
     pub guarded: bool,
@@ -1209,6 +1499,7 @@ pub struct ConcurrentSignalAssignment {
 
 /// 11.7 Component instantiation statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum InstantiatedUnit {
     Component(WithTokenSpan<Name>),
     Entity(WithTokenSpan<Name>, Option<WithRef<Ident>>),
@@ -1228,6 +1519,7 @@ impl InstantiatedUnit {
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct MapAspect {
     pub list: SeparatedList<AssociationElement>,
 }
@@ -1242,6 +1534,7 @@ impl MapAspect {
 /// 11.7 Component instantiation statements
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct InstantiationStatement {
     pub unit: InstantiatedUnit,
     pub generic_map: Option<MapAspect>,
@@ -1257,6 +1550,7 @@ impl InstantiationStatement {
 
 /// 11.8 Generate statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct GenerateBody {
     pub alternative_label: Option<WithDecl<Ident>>,
     pub decl: Option<(Vec<WithTokenSpan<Declaration>>, TokenId)>,
@@ -1268,33 +1562,37 @@ pub struct GenerateBody {
 /// 11.8 Generate statements
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ForGenerateStatement {
     pub index_name: WithDecl<Ident>,
     pub discrete_range: DiscreteRange,
     pub generate_token: TokenId,
     pub body: GenerateBody,
     pub end_token: TokenId,
-    pub end_label_pos: Option<SrcPos>,
+    pub end_label_pos: Option<TokenId>,
 }
 
 /// 11.8 Generate statements
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct IfGenerateStatement {
     pub conds: Conditionals<GenerateBody>,
-    pub end_label_pos: Option<SrcPos>,
+    pub end_label_pos: Option<TokenId>,
 }
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct CaseGenerateStatement {
     pub sels: Selection<GenerateBody>,
     pub end_token: TokenId,
-    pub end_label_pos: Option<SrcPos>,
+    pub end_label_pos: Option<TokenId>,
 }
 
 /// LRM 6.5.2 Interface Object Declarations - Mode view declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ModeViewDeclaration {
     pub ident: WithDecl<Ident>,
     pub typ: SubtypeIndication,
@@ -1306,6 +1604,7 @@ pub struct ModeViewDeclaration {
 
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ModeViewElement {
     pub names: Vec<WithDecl<Ident>>,
     pub colon_token: TokenId,
@@ -1313,6 +1612,7 @@ pub struct ModeViewElement {
 }
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ElementMode {
     Simple(WithToken<Mode>),
     Record(WithTokenSpan<Name>),
@@ -1321,6 +1621,7 @@ pub enum ElementMode {
 
 /// LRM 11. Concurrent statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ConcurrentStatement {
     ProcedureCall(ConcurrentProcedureCall),
     Block(BlockStatement),
@@ -1331,10 +1632,12 @@ pub enum ConcurrentStatement {
     ForGenerate(ForGenerateStatement),
     IfGenerate(IfGenerateStatement),
     CaseGenerate(CaseGenerateStatement),
+    PslDirective(PslDirective),
 }
 
 /// LRM 11. Concurrent statements
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct LabeledConcurrentStatement {
     pub label: WithDecl<Option<Ident>>,
     pub statement: WithTokenSpan<ConcurrentStatement>,
@@ -1343,12 +1646,14 @@ pub struct LabeledConcurrentStatement {
 /// LRM 13. Design units and their analysis
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct LibraryClause {
     pub name_list: Vec<WithRef<Ident>>,
 }
 
 /// Represents a token-separated list of some generic type `T`
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct SeparatedList<T> {
     pub items: Vec<T>,
     pub tokens: Vec<TokenId>,
@@ -1388,6 +1693,7 @@ impl<T> SeparatedList<T> {
 /// LRM 12.4. Use clauses
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct UseClause {
     pub name_list: Vec<WithTokenSpan<Name>>,
 }
@@ -1395,12 +1701,14 @@ pub struct UseClause {
 /// LRM 13.4 Context clauses
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ContextReference {
     pub name_list: Vec<WithTokenSpan<Name>>,
 }
 
 /// LRM 13.4 Context clauses
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ContextItem {
     Use(UseClause),
     Library(LibraryClause),
@@ -1410,6 +1718,7 @@ pub enum ContextItem {
 /// LRM 13.4 Context clauses
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ContextDeclaration {
     pub ident: WithDecl<Ident>,
     pub items: ContextClause,
@@ -1420,6 +1729,7 @@ pub struct ContextDeclaration {
 /// LRM 4.9 Package instantiation declaration
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct PackageInstantiation {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1429,6 +1739,7 @@ pub struct PackageInstantiation {
 
 /// LRM 7.3 Configuration specification
 #[derive(PartialEq, Eq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum InstantiationList {
     Labels(Vec<Ident>),
     Others,
@@ -1437,6 +1748,7 @@ pub enum InstantiationList {
 
 /// LRM 7.3.2 Binding indication
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum EntityAspect {
     Entity(WithTokenSpan<Name>, Option<Ident>),
     Configuration(WithTokenSpan<Name>),
@@ -1446,6 +1758,7 @@ pub enum EntityAspect {
 /// LRM 7.3.2 Binding indication
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct BindingIndication {
     pub entity_aspect: Option<EntityAspect>,
     pub generic_map: Option<MapAspect>,
@@ -1455,6 +1768,7 @@ pub struct BindingIndication {
 /// LRM 7.3 Configuration specification
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ComponentSpecification {
     pub instantiation_list: InstantiationList,
     pub colon_token: TokenId,
@@ -1464,6 +1778,7 @@ pub struct ComponentSpecification {
 /// LRM 7.3.4 Verification unit binding indication
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct VUnitBindingIndication {
     pub vunit_list: Vec<WithTokenSpan<Name>>,
 }
@@ -1471,6 +1786,7 @@ pub struct VUnitBindingIndication {
 /// LRM 7.3 Configuration specification
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ConfigurationSpecification {
     pub spec: ComponentSpecification,
     pub bind_ind: BindingIndication,
@@ -1481,6 +1797,7 @@ pub struct ConfigurationSpecification {
 /// LRM 3.4 Configuration declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ComponentConfiguration {
     pub spec: ComponentSpecification,
     pub bind_ind: Option<BindingIndication>,
@@ -1490,6 +1807,7 @@ pub struct ComponentConfiguration {
 
 /// LRM 3.4 Configuration declarations
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum ConfigurationItem {
     Block(BlockConfiguration),
     Component(ComponentConfiguration),
@@ -1498,6 +1816,7 @@ pub enum ConfigurationItem {
 /// LRM 3.4 Configuration declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct BlockConfiguration {
     pub block_spec: WithTokenSpan<Name>,
     pub use_clauses: Vec<UseClause>,
@@ -1507,6 +1826,7 @@ pub struct BlockConfiguration {
 /// LRM 3.4 Configuration declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ConfigurationDeclaration {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1521,6 +1841,7 @@ pub struct ConfigurationDeclaration {
 /// LRM 3.2 Entity declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct EntityDeclaration {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1544,6 +1865,7 @@ impl EntityDeclaration {
 /// LRM 3.3 Architecture bodies
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct ArchitectureBody {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1579,6 +1901,7 @@ impl ArchitectureBody {
 /// LRM 4.7 Package declarations
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct PackageDeclaration {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1591,6 +1914,7 @@ pub struct PackageDeclaration {
 /// LRM 4.8 Package bodies
 #[with_token_span]
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct PackageBody {
     pub context_clause: ContextClause,
     pub ident: WithDecl<Ident>,
@@ -1601,6 +1925,7 @@ pub struct PackageBody {
 
 /// LRM 13.1 Design units
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum AnyPrimaryUnit {
     /// LRM 3.2 Entity declaration
     Entity(EntityDeclaration),
@@ -1620,6 +1945,7 @@ pub enum AnyPrimaryUnit {
 
 /// LRM 13.1 Design units
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum AnySecondaryUnit {
     /// LRM 3.3 Architecture bodies
     Architecture(ArchitectureBody),
@@ -1632,6 +1958,7 @@ pub type ContextClause = Vec<ContextItem>;
 
 /// LRM 13.1 Design units
 #[derive(PartialEq, Debug, Clone, TokenSpan)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum AnyDesignUnit {
     Primary(AnyPrimaryUnit),
     Secondary(AnySecondaryUnit),
@@ -1644,6 +1971,7 @@ impl AnyDesignUnit {
 }
 
 #[derive(PartialEq, Debug, Clone, Default)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct DesignFile {
     pub design_units: Vec<(Vec<Token>, AnyDesignUnit)>,
 }
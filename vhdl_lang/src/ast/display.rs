@@ -130,6 +130,7 @@ impl Display for AttributeDesignator {
             AttributeDesignator::InstanceName => write!(f, "instance_name"),
             AttributeDesignator::PathName => write!(f, "path_name"),
             AttributeDesignator::Converse => write!(f, "converse"),
+            AttributeDesignator::Reflect => write!(f, "reflect"),
         }
     }
 }
@@ -1139,6 +1140,60 @@ impl Display for ComponentDeclaration {
     }
 }
 
+impl Display for PslParameterKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            PslParameterKind::Const => write!(f, "const"),
+            PslParameterKind::Boolean => write!(f, "boolean"),
+            PslParameterKind::Bit => write!(f, "bit"),
+            PslParameterKind::Bitvector => write!(f, "bitvector"),
+            PslParameterKind::Numeric => write!(f, "numeric"),
+            PslParameterKind::String => write!(f, "string"),
+            PslParameterKind::Property => write!(f, "property"),
+            PslParameterKind::Sequence => write!(f, "sequence"),
+        }
+    }
+}
+
+impl Display for PslParameter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}", self.kind)?;
+        let mut first = true;
+        for ident in &self.idents {
+            if first {
+                write!(f, " {ident}")?;
+            } else {
+                write!(f, ", {ident}")?;
+            }
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+impl Display for PslPropertyDeclaration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        // Not used: expression
+        match self.kind {
+            PslDeclarationKind::Property => write!(f, "property {}", self.ident)?,
+            PslDeclarationKind::Sequence => write!(f, "sequence {}", self.ident)?,
+        }
+        let mut first = true;
+        for parameter in &self.parameters {
+            if first {
+                write!(f, "({parameter}")?;
+            } else {
+                write!(f, "; {parameter}")?;
+            }
+            first = false;
+        }
+        if !first {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
 impl Display for ForGenerateStatement {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         // Not used: body
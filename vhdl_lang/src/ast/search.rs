@@ -67,6 +67,8 @@ pub enum DeclarationItem<'a> {
     ConcurrentStatement(&'a LabeledConcurrentStatement),
     SequentialStatement(&'a LabeledSequentialStatement),
     View(&'a ModeViewDeclaration),
+    PslDeclaration(&'a PslPropertyDeclaration),
+    PslParameter(&'a PslParameter),
 }
 
 pub struct FoundDeclaration<'a> {
@@ -428,7 +430,7 @@ impl Search for LabeledSequentialStatement {
 
         if let Some(end_label_pos) = self.statement.item.end_label_pos() {
             return_if_found!(searcher
-                .search_pos_with_ref(ctx, end_label_pos, &self.label.decl)
+                .search_pos_with_ref(ctx, ctx.get_pos(end_label_pos), &self.label.decl)
                 .or_not_found());
         }
 
@@ -604,11 +606,22 @@ impl Search for LabeledConcurrentStatement {
                 return_if_found!(report.search(ctx, searcher));
                 return_if_found!(severity.search(ctx, searcher));
             }
+            ConcurrentStatement::PslDirective(ref directive) => {
+                let PslDirective {
+                    kind: _,
+                    property,
+                    report,
+                    severity,
+                } = directive;
+                return_if_found!(property.search(ctx, searcher));
+                return_if_found!(report.search(ctx, searcher));
+                return_if_found!(severity.search(ctx, searcher));
+            }
         };
 
         if let Some(end_label_pos) = self.statement.item.end_label_pos() {
             return_if_found!(searcher
-                .search_pos_with_ref(ctx, end_label_pos, &self.label.decl)
+                .search_pos_with_ref(ctx, ctx.get_pos(end_label_pos), &self.label.decl)
                 .or_not_found());
         }
 
@@ -1221,11 +1234,54 @@ impl Search for Declaration {
                 return_if_found!(typ.search(ctx, searcher));
                 return_if_found!(elements.search(ctx, searcher));
             }
+            Declaration::Psl(PslDeclaration::Property(decl)) => {
+                return_if_found!(searcher
+                    .search_decl(
+                        ctx,
+                        FoundDeclaration::new(
+                            &decl.ident.decl,
+                            DeclarationItem::PslDeclaration(decl)
+                        )
+                    )
+                    .or_not_found());
+                for parameter in &decl.parameters {
+                    for ident in &parameter.idents {
+                        return_if_found!(searcher
+                            .search_decl(
+                                ctx,
+                                FoundDeclaration::new(
+                                    &ident.decl,
+                                    DeclarationItem::PslParameter(parameter)
+                                )
+                            )
+                            .or_not_found());
+                    }
+                }
+                return_if_found!(decl.expression.search(ctx, searcher));
+            }
+            Declaration::Psl(PslDeclaration::DefaultClock(clock)) => {
+                return_if_found!(clock.search(ctx, searcher));
+            }
         }
         NotFound
     }
 }
 
+impl Search for WithTokenSpan<PslExpression> {
+    fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
+        match &self.item {
+            PslExpression::Hdl(expr) => search_pos_expr(ctx, &self.pos(ctx), expr, searcher),
+            PslExpression::Operation(operation) => {
+                return_if_found!(operation.arguments.search(ctx, searcher));
+                operation.operands.search(ctx, searcher)
+            }
+            PslExpression::Braced(expr) | PslExpression::Parenthesized(expr) => {
+                expr.search(ctx, searcher)
+            }
+        }
+    }
+}
+
 impl Search for ModeViewElement {
     fn search(&self, ctx: &dyn TokenAccess, searcher: &mut impl Searcher) -> SearchResult {
         for name in self.names.iter() {
@@ -1852,6 +1908,8 @@ impl FoundDeclaration<'_> {
             DeclarationItem::SequentialStatement(..) => None,
             DeclarationItem::SubprogramInstantiation(_) => None,
             DeclarationItem::View(view) => view.end_ident_pos,
+            DeclarationItem::PslDeclaration(..) => None,
+            DeclarationItem::PslParameter(..) => None,
         }
     }
 
@@ -1978,6 +2036,8 @@ impl std::fmt::Display for DeclarationItem<'_> {
                 }
             }
             DeclarationItem::View(value) => write!(f, "view {} of {}", value.ident, value.typ),
+            DeclarationItem::PslDeclaration(value) => write!(f, "{value}"),
+            DeclarationItem::PslParameter(value) => write!(f, "{value}"),
         }
     }
 }
@@ -2032,6 +2092,42 @@ pub fn clear_references(tree: &mut impl Search, ctx: &dyn TokenAccess) {
     let _ = tree.search(ctx, &mut searcher);
 }
 
+/// Visit the references of a tree in the order that they are searched.
+/// The order only depends on the structure of the tree, such that references
+/// that are collected from a tree can be restored into an equal tree.
+#[cfg(feature = "analysis-cache")]
+pub(crate) fn for_each_reference(
+    tree: &impl Search,
+    ctx: &dyn TokenAccess,
+    f: impl FnMut(&Reference),
+) {
+    struct ReferenceVisitor<F>(F);
+
+    impl<F: FnMut(&Reference)> Searcher for ReferenceVisitor<F> {
+        fn search_pos_with_ref(
+            &mut self,
+            _ctx: &dyn TokenAccess,
+            _pos: &SrcPos,
+            reference: &Reference,
+        ) -> SearchState {
+            (self.0)(reference);
+            NotFinished
+        }
+
+        fn search_decl(
+            &mut self,
+            _ctx: &dyn TokenAccess,
+            decl: FoundDeclaration<'_>,
+        ) -> SearchState {
+            (self.0)(decl.ent_id_ref());
+            NotFinished
+        }
+    }
+
+    let mut visitor = ReferenceVisitor(f);
+    let _ = tree.search(ctx, &mut visitor);
+}
+
 #[cfg(test)]
 #[allow(clippy::ptr_arg)]
 pub fn check_no_unresolved(tree: &mut impl Search, tokens: &Vec<Token>) {
@@ -10,10 +10,17 @@
 /// This is also the mechanic used to extract supplementary information, such as comments for
 /// documentation generation.
 use crate::{SrcPos, TokenAccess, TokenId, TokenSpan};
+#[cfg(feature = "analysis-cache")]
+use serde::{Deserialize, Serialize};
 
 /// A struct that associates some generic item to a single token.
 #[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct WithToken<T> {
+    #[cfg_attr(
+        feature = "analysis-cache",
+        serde(with = "crate::cache::cached", bound = "T: crate::cache::Cached")
+    )]
     pub item: T,
     pub token: TokenId,
 }
@@ -56,6 +63,7 @@ impl<T> WithToken<T> {
 
 /// A struct that associates some generic item to a contiguous span of tokens.
 #[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct WithTokenSpan<T> {
     pub item: T,
     pub span: TokenSpan,
@@ -504,22 +504,24 @@ impl ConcurrentStatement {
             Assignment(_) => None,
             Instance(_) => Some(Concurrent::Instance),
             ForGenerate(_) | IfGenerate(_) | CaseGenerate(_) => Some(Concurrent::Generate),
+            PslDirective(_) => None,
         }
     }
 
-    pub fn end_label_pos(&self) -> Option<&SrcPos> {
+    pub fn end_label_pos(&self) -> Option<TokenId> {
         use ConcurrentStatement::*;
 
         match self {
             ProcedureCall(_) => None,
-            Block(value) => value.end_label_pos.as_ref(),
-            Process(value) => value.end_label_pos.as_ref(),
+            Block(value) => value.end_label_pos,
+            Process(value) => value.end_label_pos,
             Assert(_) => None,
             Assignment(_) => None,
             Instance(_) => None,
-            ForGenerate(value) => value.end_label_pos.as_ref(),
-            IfGenerate(value) => value.end_label_pos.as_ref(),
-            CaseGenerate(value) => value.end_label_pos.as_ref(),
+            ForGenerate(value) => value.end_label_pos,
+            IfGenerate(value) => value.end_label_pos,
+            CaseGenerate(value) => value.end_label_pos,
+            PslDirective(_) => None,
         }
     }
 
@@ -550,7 +552,7 @@ impl SequentialStatement {
         }
     }
 
-    pub fn end_label_pos(&self) -> Option<&SrcPos> {
+    pub fn end_label_pos(&self) -> Option<TokenId> {
         use SequentialStatement::*;
         match self {
             Wait(_) => None,
@@ -561,9 +563,9 @@ impl SequentialStatement {
             SignalForceAssignment(_) => None,
             SignalReleaseAssignment(_) => None,
             ProcedureCall(_) => None,
-            If(value) => value.end_label_pos.as_ref(),
-            Case(value) => value.end_label_pos.as_ref(),
-            Loop(value) => value.end_label_pos.as_ref(),
+            If(value) => value.end_label_pos,
+            Case(value) => value.end_label_pos,
+            Loop(value) => value.end_label_pos,
             Next(_) => None,
             Exit(_) => None,
             Return(_) => None,
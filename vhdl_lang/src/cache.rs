@@ -0,0 +1,453 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Persistent cache of the parsed design files and of the analyzed design units
+//! of third-party libraries
+//!
+//! The syntax tree of a file is stored in one entry. The symbol table of a design unit is
+//! stored in another entry together with the fingerprints of the entries of the units that
+//! it depends on. The entities of a unit are allocated in arenas whose ids are assigned at
+//! run time, so cached entities refer to each other by their position within the arena
+//! of their unit, see [entities]. A unit is only restored if the units that it depends on
+//! were restored from the same entries, otherwise it is analyzed again and stored anew.
+
+use std::cell::RefCell;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use fnv::FnvHasher;
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::ast::DesignFile;
+use crate::data::{Latin1String, Range, Source, SrcPos, Symbol};
+use crate::named_entity::Reference;
+use crate::standard::VHDLStandard;
+use crate::syntax::{Symbols, Token, VHDLParser};
+
+mod entities;
+pub(crate) use entities::{
+    design_region, ArenaKey, CachedDiagnostic, CachedEnt, Decoder, Encoder, EntKey, UnitRef,
+};
+
+/// Stores a design file together with the key that it was parsed with.
+/// An entry is stale if the file, the standard, the defines or the version
+/// of vhdl_lang have changed since it was stored.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
+struct CacheKey {
+    version: String,
+    file_name: PathBuf,
+    contents: u64,
+    standard: String,
+    defines: u64,
+}
+
+impl CacheKey {
+    fn new(parser: &VHDLParser, source: &Source) -> CacheKey {
+        let mut hasher = FnvHasher::default();
+        let contents = source.contents();
+        for lineno in 0..contents.num_lines() {
+            contents.get_line(lineno).hash(&mut hasher);
+        }
+
+        let mut defines: Vec<_> = parser.defines.iter().collect();
+        defines.sort();
+        let mut defines_hasher = FnvHasher::default();
+        defines.hash(&mut defines_hasher);
+
+        CacheKey {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            file_name: source.file_name().to_owned(),
+            contents: hasher.finish(),
+            standard: parser.standard.as_ref().to_owned(),
+            defines: defines_hasher.finish(),
+        }
+    }
+}
+
+/// Identifies the analysis of a design unit.
+/// An entry is stale if the tokens of the unit, the standard of its library,
+/// the existence of the body of a package, the black-box libraries
+/// or the version of vhdl_lang have changed since it was stored.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub(crate) struct UnitCacheKey {
+    version: String,
+    unit: UnitRef,
+    file_name: PathBuf,
+    tokens: u64,
+    standard: String,
+    has_package_body: bool,
+    black_box: Vec<String>,
+}
+
+impl UnitCacheKey {
+    pub fn new<'a>(
+        unit: UnitRef,
+        tokens: &[Token],
+        standard: VHDLStandard,
+        has_package_body: bool,
+        black_box: impl Iterator<Item = &'a Symbol>,
+    ) -> UnitCacheKey {
+        let mut hasher = HashWriter(FnvHasher::default());
+        // Serializing into a hasher cannot fail
+        let _ = bincode::serialize_into(&mut hasher, tokens);
+
+        let mut black_box: Vec<_> = black_box.map(|name| name.name_utf8()).collect();
+        black_box.sort();
+
+        UnitCacheKey {
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            unit,
+            file_name: tokens
+                .first()
+                .map(|token| token.pos.file_name().to_owned())
+                .unwrap_or_default(),
+            tokens: hasher.0.finish(),
+            standard: standard.as_ref().to_owned(),
+            has_package_body,
+            black_box,
+        }
+    }
+}
+
+/// The symbol table of an analyzed design unit
+#[derive(Serialize, Deserialize)]
+pub(crate) struct UnitEntry {
+    /// The owners of the arenas that the entities refer to,
+    /// starting with the arena of the unit itself
+    pub arenas: Vec<ArenaKey>,
+    /// The units that the unit depends on and the fingerprints of their entries
+    pub uses: Vec<(UnitRef, u64)>,
+    /// The units that the unit looked up but that did not exist
+    pub missing: Vec<UnitRef>,
+    /// The files that positions refer to
+    pub sources: Vec<PathBuf>,
+    /// The entities of the arena of the unit in the order they were allocated
+    pub entities: Vec<CachedEnt>,
+    /// The references of the syntax tree of the unit in search order
+    pub references: Vec<Option<EntKey>>,
+    pub diagnostics: Vec<CachedDiagnostic>,
+    /// The universal types and the standard types if the unit is the standard package
+    pub standard_types: Vec<Option<EntKey>>,
+}
+
+struct HashWriter(FnvHasher);
+
+impl io::Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A directory that contains one entry per cached file and per cached design unit
+pub(crate) struct DesignFileCache {
+    directory: PathBuf,
+    /// The first error that occurred when storing an entry
+    write_error: Mutex<Option<io::Error>>,
+}
+
+impl DesignFileCache {
+    pub fn new(directory: &Path) -> DesignFileCache {
+        DesignFileCache {
+            directory: directory.to_owned(),
+            write_error: Mutex::default(),
+        }
+    }
+
+    /// The first error that occurred when storing an entry, if any
+    pub fn write_error(&self) -> Option<String> {
+        self.write_error.lock().as_ref().map(|err| err.to_string())
+    }
+
+    fn entry_name(&self, source: &Source) -> PathBuf {
+        let mut hasher = FnvHasher::default();
+        source.file_name().hash(&mut hasher);
+        self.directory.join(format!("{:016x}.bin", hasher.finish()))
+    }
+
+    /// Load the design file of a source.
+    /// Returns `None` if there is no entry or if the entry is stale or unreadable.
+    pub fn load(&self, parser: &VHDLParser, source: &Source) -> Option<DesignFile> {
+        let file = File::open(self.entry_name(source)).ok()?;
+        let mut reader = BufReader::new(file);
+        let key: CacheKey = bincode::deserialize_from(&mut reader).ok()?;
+        if key != CacheKey::new(parser, source) {
+            return None;
+        }
+        with_context(parser.symbols.clone(), source.clone(), || {
+            bincode::deserialize_from(&mut reader).ok()
+        })
+    }
+
+    /// Store the design file of a source, replacing a stale entry if there is one.
+    /// A failure is recorded as the write error of the cache.
+    pub fn store(&self, parser: &VHDLParser, source: &Source, design_file: &DesignFile) {
+        if let Err(err) = self.try_store(parser, source, design_file) {
+            self.write_error.lock().get_or_insert(err);
+        }
+    }
+
+    fn try_store(
+        &self,
+        parser: &VHDLParser,
+        source: &Source,
+        design_file: &DesignFile,
+    ) -> io::Result<()> {
+        self.write_entry(&self.entry_name(source), |writer| {
+            bincode::serialize_into(&mut *writer, &CacheKey::new(parser, source))?;
+            bincode::serialize_into(writer, design_file)
+        })
+    }
+
+    fn unit_entry_name(&self, key: &UnitCacheKey) -> PathBuf {
+        let mut hasher = HashWriter(FnvHasher::default());
+        let _ = bincode::serialize_into(&mut hasher, &key.unit);
+        key.file_name.hash(&mut hasher.0);
+        self.directory
+            .join("units")
+            .join(format!("{:016x}.bin", hasher.0.finish()))
+    }
+
+    /// Load the symbol table of a design unit together with the fingerprint of its entry.
+    /// Returns `None` if there is no entry or if the entry is stale or unreadable.
+    pub fn load_unit(
+        &self,
+        symbols: &Arc<Symbols>,
+        source: &Source,
+        key: &UnitCacheKey,
+    ) -> Option<(u64, UnitEntry)> {
+        let bytes = fs::read(self.unit_entry_name(key)).ok()?;
+        let mut reader = bytes.as_slice();
+        with_context(symbols.clone(), source.clone(), || {
+            let stored: UnitCacheKey = bincode::deserialize_from(&mut reader).ok()?;
+            if &stored != key {
+                return None;
+            }
+            let entry = bincode::deserialize_from(&mut reader).ok()?;
+            Some((fingerprint(&bytes), entry))
+        })
+    }
+
+    /// Store the symbol table of a design unit and return the fingerprint of the entry.
+    /// A failure to write the entry is ignored since the fingerprint only depends on
+    /// the contents of the entry, the unit is analyzed again on the next start.
+    pub fn store_unit(&self, key: &UnitCacheKey, entry: &UnitEntry) -> Option<u64> {
+        let mut bytes = bincode::serialize(key).ok()?;
+        bincode::serialize_into(&mut bytes, entry).ok()?;
+        let file_name = self.unit_entry_name(key);
+        let _ = file_name
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| self.write_entry(&file_name, |writer| Ok(writer.write_all(&bytes)?)));
+        Some(fingerprint(&bytes))
+    }
+
+    fn write_entry(
+        &self,
+        file_name: &Path,
+        write: impl FnOnce(&mut BufWriter<File>) -> bincode::Result<()>,
+    ) -> io::Result<()> {
+        fs::create_dir_all(&self.directory)?;
+        // Write to a temporary file first such that concurrent readers never see a partial entry
+        let tmp_file_name = file_name.with_extension(format!("{}.tmp", std::process::id()));
+        let mut writer = BufWriter::new(File::create(&tmp_file_name)?);
+        let result = write(&mut writer).and_then(|_| Ok(writer.flush()?));
+        drop(writer);
+        if let Err(err) = result {
+            let _ = fs::remove_file(&tmp_file_name);
+            return Err(io::Error::other(err));
+        }
+        fs::rename(tmp_file_name, file_name)
+    }
+}
+
+fn fingerprint(bytes: &[u8]) -> u64 {
+    let mut hasher = FnvHasher::default();
+    hasher.write(bytes);
+    hasher.finish()
+}
+
+/// The symbols and the source that symbols and positions are restored into
+/// while a design file is deserialized
+struct Context {
+    symbols: Arc<Symbols>,
+    source: Source,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<Context>> = const { RefCell::new(None) };
+}
+
+fn with_context<T>(symbols: Arc<Symbols>, source: Source, f: impl FnOnce() -> T) -> T {
+    CONTEXT.with(|context| *context.borrow_mut() = Some(Context { symbols, source }));
+    let result = f();
+    CONTEXT.with(|context| *context.borrow_mut() = None);
+    result
+}
+
+/// Serialization of the items of a design file that can only be restored within
+/// the context of the cache, i.e., symbols and positions.
+/// These types do not implement `Serialize` and `Deserialize` themselves
+/// since they cannot be restored elsewhere. Fields that hold them are annotated
+/// with `#[serde(with = "crate::cache::cached")]` instead.
+pub trait Cached: Sized {
+    fn store<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+    fn restore<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+impl<T: Serialize + DeserializeOwned> Cached for T {
+    fn store<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Serialize::serialize(self, serializer)
+    }
+
+    fn restore<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer)
+    }
+}
+
+impl Cached for Symbol {
+    fn store<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.name().serialize(serializer)
+    }
+
+    fn restore<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = Latin1String::deserialize(deserializer)?;
+        CONTEXT.with(|context| {
+            let context = context.borrow();
+            let symtab = context
+                .as_ref()
+                .ok_or_else(|| D::Error::custom("symbols can only be restored from the cache"))?
+                .symbols
+                .symtab();
+            Ok(if name.bytes.first() == Some(&b'\\') {
+                symtab.insert_extended(&name)
+            } else {
+                symtab.insert(&name)
+            })
+        })
+    }
+}
+
+/// Only the range is stored since all positions of a design file refer to its source
+impl Cached for SrcPos {
+    fn store<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.range.serialize(serializer)
+    }
+
+    fn restore<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let range = Range::deserialize(deserializer)?;
+        CONTEXT.with(|context| {
+            let context = context.borrow();
+            let source = &context
+                .as_ref()
+                .ok_or_else(|| D::Error::custom("positions can only be restored from the cache"))?
+                .source;
+            Ok(SrcPos::new(source.clone(), range))
+        })
+    }
+}
+
+/// References are only set by the analysis, a parsed design file has none
+impl Cached for Reference {
+    fn store<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_unit()
+    }
+
+    fn restore<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        <()>::deserialize(deserializer)?;
+        Ok(Reference::undefined())
+    }
+}
+
+/// For use with `#[serde(with = "crate::cache::cached")]`
+pub(crate) mod cached {
+    use super::Cached;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<T: Cached, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.store(serializer)
+    }
+
+    pub fn deserialize<'de, T: Cached, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::restore(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::NoDiagnostics;
+    use crate::standard::VHDLStandard;
+
+    #[test]
+    fn design_file_is_restored_from_cache() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = DesignFileCache::new(directory.path());
+        let parser = VHDLParser::new(VHDLStandard::VHDL2008);
+        let source = Source::inline(
+            Path::new("pkg.vhd"),
+            "
+library ieee;
+use ieee.std_logic_1164.all;
+
+-- Comments are restored as well
+package pkg is
+  constant \\Ext\\ : std_logic_vector(7 downto 0) := x\"Ab\";
+  function f(a : integer) return integer;
+end package;
+",
+        );
+        let design_file = parser.parse_design_source(&source, &mut NoDiagnostics);
+
+        assert_eq!(cache.load(&parser, &source), None);
+        cache.store(&parser, &source, &design_file);
+        assert_eq!(cache.write_error(), None);
+        assert_eq!(cache.load(&parser, &source), Some(design_file));
+    }
+
+    #[test]
+    fn stale_entries_are_not_loaded() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = DesignFileCache::new(directory.path());
+        let parser = VHDLParser::new(VHDLStandard::VHDL2008);
+        let source = Source::inline(Path::new("ent.vhd"), "entity ent is end entity;");
+        let design_file = parser.parse_design_source(&source, &mut NoDiagnostics);
+        cache.store(&parser, &source, &design_file);
+        assert_eq!(cache.write_error(), None);
+
+        let changed = Source::inline(Path::new("ent.vhd"), "entity ent2 is end entity;");
+        assert_eq!(cache.load(&parser, &changed), None);
+        assert_eq!(
+            cache.load(&parser.with_standard(VHDLStandard::VHDL1993), &source),
+            None
+        );
+        let mut defines = crate::syntax::Defines::default();
+        defines.insert("TOOL".to_owned(), "sim".to_owned());
+        assert_eq!(
+            cache.load(
+                &VHDLParser::new(VHDLStandard::VHDL2008).with_defines(defines),
+                &source
+            ),
+            None
+        );
+        assert!(cache.load(&parser, &source).is_some());
+    }
+}
@@ -0,0 +1,1122 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Named entities as they are stored in the cache
+//!
+//! An [EntityId] contains the id of the arena of the entity, which is assigned at run time.
+//! A cached entity is instead identified by its position within the arena and by the owner
+//! of the arena, i.e., the library or the design unit that it was analyzed for.
+//! Restoring the entities of a design unit allocates them in the same order, so that the
+//! positions stay the same.
+
+use std::path::PathBuf;
+
+use fnv::{FnvHashMap, FnvHashSet};
+use serde::{Deserialize, Serialize};
+
+use super::cached;
+use crate::ast::{
+    Designator, ExternalObjectClass, InterfaceType, Mode, ObjectClass, UnitId, UnitKey,
+};
+use crate::data::error_codes::ErrorCode;
+use crate::data::{Diagnostic, Range, Source, SrcPos, Symbol};
+use crate::named_entity::*;
+use crate::TokenSpan;
+
+/// Identifies a design unit of a library
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct UnitRef {
+    #[serde(with = "cached")]
+    library: Symbol,
+    #[serde(with = "cached")]
+    primary: Symbol,
+    /// The name of a secondary unit
+    secondary: Option<CachedSymbol>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+struct CachedSymbol(#[serde(with = "cached")] Symbol);
+
+impl UnitRef {
+    pub fn new(unit_id: &UnitId) -> UnitRef {
+        Self::missing(
+            unit_id.library_name(),
+            unit_id.primary_name(),
+            unit_id.secondary_name(),
+        )
+    }
+
+    /// The reference of a unit that is registered as missing
+    pub fn missing(
+        library_name: &Symbol,
+        primary_name: &Symbol,
+        secondary_name: Option<&Symbol>,
+    ) -> UnitRef {
+        UnitRef {
+            library: library_name.clone(),
+            primary: primary_name.clone(),
+            secondary: secondary_name.cloned().map(CachedSymbol),
+        }
+    }
+
+    pub fn library_name(&self) -> &Symbol {
+        &self.library
+    }
+
+    pub fn primary_name(&self) -> &Symbol {
+        &self.primary
+    }
+
+    pub fn secondary_name(&self) -> Option<&Symbol> {
+        self.secondary.as_ref().map(|CachedSymbol(name)| name)
+    }
+
+    pub fn key(&self) -> UnitKey {
+        match self.secondary {
+            Some(CachedSymbol(ref name)) => UnitKey::Secondary(self.primary.clone(), name.clone()),
+            None => UnitKey::Primary(self.primary.clone()),
+        }
+    }
+}
+
+/// The owner of an arena
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum ArenaKey {
+    /// The arena that only contains the entity of the library itself
+    Library(#[serde(with = "cached")] Symbol),
+    /// The arena of the entities that were declared when analyzing a design unit
+    Unit(UnitRef),
+}
+
+/// An entity of one of the arenas of a cache entry.
+/// The first arena of an entry is the one of the design unit itself.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) struct EntKey {
+    arena: u32,
+    index: u32,
+}
+
+impl EntKey {
+    /// The arena that the entity belongs to, as an index into the arenas of the entry
+    pub fn arena(&self) -> usize {
+        self.arena as usize
+    }
+}
+
+/// A position within one of the sources of a cache entry
+#[derive(Serialize, Deserialize, Clone)]
+pub(crate) struct CachedPos {
+    source: u32,
+    range: Range,
+}
+
+/// A diagnostic of the analysis, the error code is stored by name
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CachedDiagnostic {
+    pos: CachedPos,
+    message: String,
+    related: Vec<(CachedPos, String)>,
+    code: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CachedEnt {
+    designator: Designator,
+    parent: Option<EntKey>,
+    related: CachedRelated,
+    implicits: Vec<EntKey>,
+    kind: CachedKind,
+    decl_pos: Option<CachedPos>,
+    src_span: TokenSpan,
+    source: Option<u32>,
+    attrs: Vec<(CachedSymbol, CachedPos, EntKey)>,
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedRelated {
+    ImplicitOf(EntKey),
+    InstanceOf(EntKey),
+    DeclaredBy(EntKey),
+    DerivedFrom(EntKey),
+    None,
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedKind {
+    ExternalAlias {
+        class: ExternalObjectClass,
+        type_mark: EntKey,
+    },
+    ObjectAlias {
+        base_object: EntKey,
+        type_mark: EntKey,
+    },
+    File(EntKey),
+    InterfaceFile(EntKey),
+    Component(CachedRegion),
+    Attribute(EntKey),
+    Overloaded(CachedOverloaded),
+    Type(CachedType),
+    ElementDeclaration(EntKey),
+    Concurrent(Option<Concurrent>),
+    Sequential(Option<Sequential>),
+    Object(CachedObject),
+    LoopParameter(Option<EntKey>),
+    PhysicalLiteral(EntKey),
+    DeferredConstant(EntKey),
+    Library,
+    Design(CachedDesign),
+    View(EntKey),
+    Psl(Psl),
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedType {
+    Array {
+        indexes: Vec<Option<EntKey>>,
+        elem_type: EntKey,
+    },
+    Enum(Vec<Designator>),
+    Integer,
+    Real,
+    Physical,
+    Access(EntKey),
+    Record(Vec<EntKey>),
+    Incomplete,
+    Subtype(EntKey),
+    Protected(CachedRegion, bool),
+    File,
+    Interface,
+    Alias(EntKey),
+    Universal(UniversalType),
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedOverloaded {
+    SubprogramDecl(CachedSignature),
+    Subprogram(CachedSignature),
+    UninstSubprogramDecl(CachedSignature, CachedRegion),
+    UninstSubprogram(CachedSignature, CachedRegion),
+    InterfaceSubprogram(CachedSignature),
+    EnumLiteral(CachedSignature),
+    Alias(EntKey),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedSignature {
+    typ: InterfaceType,
+    formals: Vec<EntKey>,
+    return_type: Option<EntKey>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedObject {
+    class: ObjectClass,
+    iface: Option<CachedObjectInterface>,
+    subtype: EntKey,
+    has_default: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedObjectInterface {
+    Generic,
+    Port(CachedInterfaceMode),
+    Parameter(CachedInterfaceMode),
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedInterfaceMode {
+    Simple(Mode),
+    View(EntKey),
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedDesign {
+    Entity(CachedVisibility, CachedRegion),
+    Architecture(CachedVisibility, CachedRegion, EntKey),
+    Configuration,
+    Package(CachedVisibility, CachedRegion),
+    PackageBody(CachedVisibility, CachedRegion),
+    UninstPackage(CachedVisibility, CachedRegion),
+    PackageInstance(CachedRegion),
+    InterfacePackageInstance(CachedRegion),
+    Context(CachedRegion),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedRegion {
+    visibility: CachedVisibility,
+    entities: Vec<(Designator, CachedNamedEntities)>,
+    kind: RegionKind,
+}
+
+#[derive(Serialize, Deserialize)]
+enum CachedNamedEntities {
+    Single(EntKey),
+    Overloaded(Vec<(CachedSubprogramKey, EntKey)>),
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedSubprogramKey {
+    uninstantiated: bool,
+    formals: Vec<EntKey>,
+    return_type: Option<EntKey>,
+}
+
+/// The positions where an entity or a region was made visible together with the entity
+type CachedVisible = (Vec<Option<CachedPos>>, EntKey);
+
+#[derive(Serialize, Deserialize)]
+struct CachedVisibility {
+    /// The regions that were made visible by their design entity
+    all_in_regions: Vec<CachedVisible>,
+    visible: Vec<(Designator, Vec<CachedVisible>)>,
+    black_box: bool,
+}
+
+/// The region of a design entity that another region can make visible
+pub(crate) fn design_region<'a>(design: &'a Design<'a>) -> Option<&'a Region<'a>> {
+    match design {
+        Design::Entity(_, region)
+        | Design::Architecture(_, region, _)
+        | Design::Package(_, region)
+        | Design::PackageBody(_, region)
+        | Design::UninstPackage(_, region)
+        | Design::PackageInstance(region)
+        | Design::InterfacePackageInstance(region)
+        | Design::Context(region) => Some(region),
+        Design::Configuration => None,
+    }
+}
+
+/// Converts the entities of a design unit into their cached form.
+/// Returns `None` for entities that cannot be restored,
+/// such as entities that refer to an arena without owner.
+pub(crate) struct Encoder<'r> {
+    owners: &'r FnvHashMap<ArenaId, ArenaKey>,
+    /// The design entities that own the regions that can be made visible, by address
+    regions: &'r FnvHashMap<usize, EntityId>,
+    arenas: Vec<ArenaKey>,
+    arena_index: FnvHashMap<ArenaId, u32>,
+    sources: Vec<PathBuf>,
+    source_index: FnvHashMap<Source, u32>,
+}
+
+impl<'r> Encoder<'r> {
+    pub fn new(
+        owners: &'r FnvHashMap<ArenaId, ArenaKey>,
+        regions: &'r FnvHashMap<usize, EntityId>,
+        arena_id: ArenaId,
+    ) -> Option<Encoder<'r>> {
+        let mut encoder = Encoder {
+            owners,
+            regions,
+            arenas: Vec::new(),
+            arena_index: FnvHashMap::default(),
+            sources: Vec::new(),
+            source_index: FnvHashMap::default(),
+        };
+        encoder.arena(arena_id)?;
+        Some(encoder)
+    }
+
+    /// The owners of the arenas that the entities refer to,
+    /// starting with the arena of the design unit
+    pub fn into_arenas_and_sources(self) -> (Vec<ArenaKey>, Vec<PathBuf>) {
+        (self.arenas, self.sources)
+    }
+
+    fn arena(&mut self, arena_id: ArenaId) -> Option<u32> {
+        if let Some(idx) = self.arena_index.get(&arena_id) {
+            return Some(*idx);
+        }
+        let owner = self.owners.get(&arena_id)?;
+        let idx = self.arenas.len() as u32;
+        self.arenas.push(owner.clone());
+        self.arena_index.insert(arena_id, idx);
+        Some(idx)
+    }
+
+    pub fn id(&mut self, id: EntityId) -> Option<EntKey> {
+        Some(EntKey {
+            arena: self.arena(id.arena_id())?,
+            index: id.local_index(),
+        })
+    }
+
+    fn ent(&mut self, ent: &AnyEnt<'_>) -> Option<EntKey> {
+        self.id(ent.id())
+    }
+
+    fn opt_ent(&mut self, ent: Option<&AnyEnt<'_>>) -> Option<Option<EntKey>> {
+        match ent {
+            Some(ent) => Some(Some(self.ent(ent)?)),
+            None => Some(None),
+        }
+    }
+
+    fn source(&mut self, source: &Source) -> u32 {
+        if let Some(idx) = self.source_index.get(source) {
+            return *idx;
+        }
+        let idx = self.sources.len() as u32;
+        self.sources.push(source.file_name().to_owned());
+        self.source_index.insert(source.clone(), idx);
+        idx
+    }
+
+    pub fn pos(&mut self, pos: &SrcPos) -> CachedPos {
+        CachedPos {
+            source: self.source(&pos.source),
+            range: pos.range(),
+        }
+    }
+
+    pub fn diagnostic(&mut self, diagnostic: &Diagnostic) -> CachedDiagnostic {
+        CachedDiagnostic {
+            pos: self.pos(&diagnostic.pos),
+            message: diagnostic.message.clone(),
+            related: diagnostic
+                .related
+                .iter()
+                .map(|(pos, message)| (self.pos(pos), message.clone()))
+                .collect(),
+            code: diagnostic.code.as_str().to_owned(),
+        }
+    }
+
+    fn visible_pos(&mut self, visible_pos: &[Option<SrcPos>]) -> Vec<Option<CachedPos>> {
+        visible_pos
+            .iter()
+            .map(|pos| pos.as_ref().map(|pos| self.pos(pos)))
+            .collect()
+    }
+
+    pub fn entity(&mut self, ent: &AnyEnt<'_>) -> Option<CachedEnt> {
+        let related = match ent.related {
+            Related::ImplicitOf(ent) => CachedRelated::ImplicitOf(self.ent(ent)?),
+            Related::InstanceOf(ent) => CachedRelated::InstanceOf(self.ent(ent)?),
+            Related::DeclaredBy(ent) => CachedRelated::DeclaredBy(self.ent(ent)?),
+            Related::DerivedFrom(ent) => CachedRelated::DerivedFrom(self.ent(ent)?),
+            Related::None => CachedRelated::None,
+        };
+
+        let mut attrs = Vec::with_capacity(ent.attrs.len());
+        for (name, (pos, attr)) in ent.attrs.iter() {
+            attrs.push((
+                CachedSymbol(name.clone()),
+                self.pos(pos),
+                self.ent(attr.ent)?,
+            ));
+        }
+
+        Some(CachedEnt {
+            designator: ent.designator.clone(),
+            parent: self.opt_ent(ent.parent)?,
+            related,
+            implicits: ent
+                .implicits
+                .iter()
+                .map(|ent| self.ent(ent))
+                .collect::<Option<_>>()?,
+            kind: self.kind(&ent.kind)?,
+            decl_pos: ent.decl_pos.as_ref().map(|pos| self.pos(pos)),
+            src_span: ent.src_span,
+            source: ent.source.as_ref().map(|source| self.source(source)),
+            attrs,
+        })
+    }
+
+    fn kind(&mut self, kind: &AnyEntKind<'_>) -> Option<CachedKind> {
+        Some(match kind {
+            AnyEntKind::ExternalAlias { class, type_mark } => CachedKind::ExternalAlias {
+                class: *class,
+                type_mark: self.ent(type_mark)?,
+            },
+            AnyEntKind::ObjectAlias {
+                base_object,
+                type_mark,
+            } => CachedKind::ObjectAlias {
+                base_object: self.ent(base_object.ent)?,
+                type_mark: self.ent(type_mark)?,
+            },
+            AnyEntKind::File(subtype) => CachedKind::File(self.ent(&subtype.type_mark())?),
+            AnyEntKind::InterfaceFile(typ) => CachedKind::InterfaceFile(self.ent(typ)?),
+            AnyEntKind::Component(region) => CachedKind::Component(self.region(region)?),
+            AnyEntKind::Attribute(typ) => CachedKind::Attribute(self.ent(typ)?),
+            AnyEntKind::Overloaded(overloaded) => {
+                CachedKind::Overloaded(self.overloaded(overloaded)?)
+            }
+            AnyEntKind::Type(typ) => CachedKind::Type(self.typ(typ)?),
+            AnyEntKind::ElementDeclaration(subtype) => {
+                CachedKind::ElementDeclaration(self.ent(&subtype.type_mark())?)
+            }
+            AnyEntKind::Concurrent(concurrent) => CachedKind::Concurrent(*concurrent),
+            AnyEntKind::Sequential(sequential) => CachedKind::Sequential(*sequential),
+            AnyEntKind::Object(object) => CachedKind::Object(CachedObject {
+                class: object.class,
+                iface: match object.iface {
+                    Some(ref iface) => Some(self.object_interface(iface)?),
+                    None => None,
+                },
+                subtype: self.ent(&object.subtype.type_mark())?,
+                has_default: object.has_default,
+            }),
+            AnyEntKind::LoopParameter(typ) => {
+                CachedKind::LoopParameter(self.opt_ent(typ.as_deref())?)
+            }
+            AnyEntKind::PhysicalLiteral(typ) => CachedKind::PhysicalLiteral(self.ent(typ)?),
+            AnyEntKind::DeferredConstant(subtype) => {
+                CachedKind::DeferredConstant(self.ent(&subtype.type_mark())?)
+            }
+            AnyEntKind::Library => CachedKind::Library,
+            AnyEntKind::Design(design) => CachedKind::Design(self.design(design)?),
+            AnyEntKind::View(subtype) => CachedKind::View(self.ent(&subtype.type_mark())?),
+            AnyEntKind::Psl(psl) => CachedKind::Psl(*psl),
+        })
+    }
+
+    fn typ(&mut self, typ: &Type<'_>) -> Option<CachedType> {
+        Some(match typ {
+            Type::Array { indexes, elem_type } => CachedType::Array {
+                indexes: indexes
+                    .iter()
+                    .map(|index| self.opt_ent(index.as_deref()))
+                    .collect::<Option<_>>()?,
+                elem_type: self.ent(elem_type)?,
+            },
+            Type::Enum(literals) => CachedType::Enum(literals.iter().cloned().collect()),
+            Type::Integer => CachedType::Integer,
+            Type::Real => CachedType::Real,
+            Type::Physical => CachedType::Physical,
+            Type::Access(subtype) => CachedType::Access(self.ent(&subtype.type_mark())?),
+            Type::Record(region) => CachedType::Record(
+                region
+                    .elems
+                    .iter()
+                    .map(|elem| self.ent(elem.ent))
+                    .collect::<Option<_>>()?,
+            ),
+            Type::Incomplete => CachedType::Incomplete,
+            Type::Subtype(subtype) => CachedType::Subtype(self.ent(&subtype.type_mark())?),
+            Type::Protected(region, has_body) => {
+                CachedType::Protected(self.region(region)?, *has_body)
+            }
+            Type::File => CachedType::File,
+            Type::Interface => CachedType::Interface,
+            Type::Alias(typ) => CachedType::Alias(self.ent(typ)?),
+            Type::Universal(universal) => CachedType::Universal(*universal),
+        })
+    }
+
+    fn overloaded(&mut self, overloaded: &Overloaded<'_>) -> Option<CachedOverloaded> {
+        Some(match overloaded {
+            Overloaded::SubprogramDecl(signature) => {
+                CachedOverloaded::SubprogramDecl(self.signature(signature)?)
+            }
+            Overloaded::Subprogram(signature) => {
+                CachedOverloaded::Subprogram(self.signature(signature)?)
+            }
+            Overloaded::UninstSubprogramDecl(signature, region) => {
+                CachedOverloaded::UninstSubprogramDecl(
+                    self.signature(signature)?,
+                    self.region(region)?,
+                )
+            }
+            Overloaded::UninstSubprogram(signature, region) => {
+                CachedOverloaded::UninstSubprogram(self.signature(signature)?, self.region(region)?)
+            }
+            Overloaded::InterfaceSubprogram(signature) => {
+                CachedOverloaded::InterfaceSubprogram(self.signature(signature)?)
+            }
+            Overloaded::EnumLiteral(signature) => {
+                CachedOverloaded::EnumLiteral(self.signature(signature)?)
+            }
+            Overloaded::Alias(ent) => CachedOverloaded::Alias(self.ent(ent.ent)?),
+        })
+    }
+
+    fn signature(&mut self, signature: &Signature<'_>) -> Option<CachedSignature> {
+        Some(CachedSignature {
+            typ: signature.formals.typ,
+            formals: signature
+                .formals
+                .entities
+                .iter()
+                .map(|formal| self.ent(formal.inner()))
+                .collect::<Option<_>>()?,
+            return_type: self.opt_ent(signature.return_type.as_deref())?,
+        })
+    }
+
+    fn object_interface(&mut self, iface: &ObjectInterface<'_>) -> Option<CachedObjectInterface> {
+        let mut mode = |mode: &InterfaceMode<'_>| -> Option<CachedInterfaceMode> {
+            Some(match mode {
+                InterfaceMode::Simple(mode) => CachedInterfaceMode::Simple(*mode),
+                InterfaceMode::View(view) => CachedInterfaceMode::View(self.ent(view.ent)?),
+            })
+        };
+        Some(match iface {
+            ObjectInterface::Generic => CachedObjectInterface::Generic,
+            ObjectInterface::Port(port) => CachedObjectInterface::Port(mode(port)?),
+            ObjectInterface::Parameter(param) => CachedObjectInterface::Parameter(mode(param)?),
+        })
+    }
+
+    fn design(&mut self, design: &Design<'_>) -> Option<CachedDesign> {
+        Some(match design {
+            Design::Entity(visibility, region) => {
+                CachedDesign::Entity(self.visibility(visibility)?, self.region(region)?)
+            }
+            Design::Architecture(visibility, region, entity) => CachedDesign::Architecture(
+                self.visibility(visibility)?,
+                self.region(region)?,
+                self.ent(entity)?,
+            ),
+            Design::Configuration => CachedDesign::Configuration,
+            Design::Package(visibility, region) => {
+                CachedDesign::Package(self.visibility(visibility)?, self.region(region)?)
+            }
+            Design::PackageBody(visibility, region) => {
+                CachedDesign::PackageBody(self.visibility(visibility)?, self.region(region)?)
+            }
+            Design::UninstPackage(visibility, region) => {
+                CachedDesign::UninstPackage(self.visibility(visibility)?, self.region(region)?)
+            }
+            Design::PackageInstance(region) => CachedDesign::PackageInstance(self.region(region)?),
+            Design::InterfacePackageInstance(region) => {
+                CachedDesign::InterfacePackageInstance(self.region(region)?)
+            }
+            Design::Context(region) => CachedDesign::Context(self.region(region)?),
+        })
+    }
+
+    fn region(&mut self, region: &Region<'_>) -> Option<CachedRegion> {
+        let mut entities = Vec::with_capacity(region.entities.len());
+        for (designator, named_entities) in region.entities.iter() {
+            let named_entities = match named_entities {
+                NamedEntities::Single(ent) => CachedNamedEntities::Single(self.ent(ent)?),
+                NamedEntities::Overloaded(overloaded) => {
+                    let mut keyed = Vec::with_capacity(overloaded.len());
+                    for (key, ent) in overloaded.entities.iter() {
+                        let (uninstantiated, key) = match key {
+                            SubprogramKey::Normal(key) => (false, key),
+                            SubprogramKey::Uninstantiated(key) => (true, key),
+                        };
+                        let key = CachedSubprogramKey {
+                            uninstantiated,
+                            formals: key
+                                .formals
+                                .iter()
+                                .map(|typ| self.ent(typ))
+                                .collect::<Option<_>>()?,
+                            return_type: self.opt_ent(key.return_type.as_deref())?,
+                        };
+                        keyed.push((key, self.ent(ent.ent)?));
+                    }
+                    CachedNamedEntities::Overloaded(keyed)
+                }
+            };
+            entities.push((designator.clone(), named_entities));
+        }
+
+        Some(CachedRegion {
+            visibility: self.visibility(&region.visibility)?,
+            entities,
+            kind: region.kind,
+        })
+    }
+
+    fn visibility(&mut self, visibility: &Visibility<'_>) -> Option<CachedVisibility> {
+        let mut all_in_regions = Vec::with_capacity(visibility.all_in_regions.len());
+        for visible_region in visibility.all_in_regions.iter() {
+            let address = visible_region.region as *const Region<'_> as usize;
+            let owner = self.regions.get(&address)?;
+            all_in_regions.push((
+                self.visible_pos(&visible_region.visible_pos),
+                self.id(*owner)?,
+            ));
+        }
+
+        let mut visible = Vec::with_capacity(visibility.visible.len());
+        for (designator, entities) in visibility.visible.iter() {
+            let mut cached_entities = Vec::with_capacity(entities.len());
+            for visible_ent in entities.values() {
+                cached_entities.push((
+                    self.visible_pos(&visible_ent.visible_pos),
+                    self.ent(visible_ent.entity)?,
+                ));
+            }
+            visible.push((designator.clone(), cached_entities));
+        }
+
+        Some(CachedVisibility {
+            all_in_regions,
+            visible,
+            black_box: visibility.black_box,
+        })
+    }
+}
+
+/// Restores cached entities into the arena of a design unit.
+/// Returns `None` if the cached entities refer to an entity or a source that does not exist.
+pub(crate) struct Decoder<'a> {
+    arena: &'a Arena,
+    /// The arenas of the entry, starting with the arena of the design unit
+    arenas: Vec<ArenaId>,
+    sources: Vec<Source>,
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(arena: &'a Arena, arenas: Vec<ArenaId>, sources: Vec<Source>) -> Decoder<'a> {
+        Decoder {
+            arena,
+            arenas,
+            sources,
+        }
+    }
+
+    pub fn id(&self, key: EntKey) -> Option<EntityId> {
+        let arena_id = *self.arenas.get(key.arena())?;
+        let id = EntityId::from_local_index(arena_id, key.index);
+        self.arena.is_valid_id(id).then_some(id)
+    }
+
+    fn ent(&self, key: EntKey) -> Option<EntRef<'a>> {
+        self.id(key).map(|id| self.arena.get(id))
+    }
+
+    fn opt_ent(&self, key: Option<EntKey>) -> Option<Option<EntRef<'a>>> {
+        match key {
+            Some(key) => Some(Some(self.ent(key)?)),
+            None => Some(None),
+        }
+    }
+
+    fn typ(&self, key: EntKey) -> Option<TypeEnt<'a>> {
+        self.ent(key).map(TypeEnt::from_any_unchecked)
+    }
+
+    fn base_type(&self, key: EntKey) -> Option<BaseType<'a>> {
+        self.ent(key).map(BaseType::from_any_unchecked)
+    }
+
+    fn subtype(&self, key: EntKey) -> Option<Subtype<'a>> {
+        self.typ(key).map(Subtype::new)
+    }
+
+    fn source(&self, idx: u32) -> Option<Source> {
+        self.sources.get(idx as usize).cloned()
+    }
+
+    pub fn pos(&self, pos: &CachedPos) -> Option<SrcPos> {
+        Some(SrcPos::new(self.source(pos.source)?, pos.range))
+    }
+
+    pub fn diagnostic(&self, diagnostic: &CachedDiagnostic) -> Option<Diagnostic> {
+        Some(Diagnostic {
+            pos: self.pos(&diagnostic.pos)?,
+            message: diagnostic.message.clone(),
+            related: diagnostic
+                .related
+                .iter()
+                .map(|(pos, message)| Some((self.pos(pos)?, message.clone())))
+                .collect::<Option<_>>()?,
+            code: ErrorCode::try_from(diagnostic.code.as_str()).ok()?,
+        })
+    }
+
+    fn visible_pos(&self, visible_pos: &[Option<CachedPos>]) -> Option<Vec<Option<SrcPos>>> {
+        visible_pos
+            .iter()
+            .map(|pos| match pos {
+                Some(pos) => Some(Some(self.pos(pos)?)),
+                None => Some(None),
+            })
+            .collect()
+    }
+
+    /// Allocate the entities of the design unit in their cached order.
+    /// All entities are allocated before any of them is filled in since they refer to each other.
+    /// An entity is filled in once the regions that it makes visible have been filled in.
+    pub fn restore(&self, entities: &[CachedEnt]) -> Option<()> {
+        let own_arena = *self.arenas.first()?;
+        for (idx, ent) in entities.iter().enumerate() {
+            let decl_pos = match ent.decl_pos {
+                Some(ref pos) => Some(self.pos(pos)?),
+                None => None,
+            };
+            let source = match ent.source {
+                Some(source) => Some(self.source(source)?),
+                None => None,
+            };
+            let allocated = self.arena.alloc(
+                ent.designator.clone(),
+                None,
+                Related::None,
+                AnyEntKind::Library,
+                decl_pos,
+                ent.src_span,
+                source,
+            );
+            if allocated.id() != EntityId::from_local_index(own_arena, idx as u32) {
+                return None;
+            }
+        }
+
+        let mut pending: Vec<usize> = (0..entities.len()).collect();
+        let mut restored = vec![false; entities.len()];
+        while !pending.is_empty() {
+            let mut remaining = Vec::new();
+            for idx in pending.iter().copied() {
+                let mut dependencies = FnvHashSet::default();
+                entities[idx].kind.visible_regions(&mut dependencies);
+                let is_ready = dependencies.iter().all(|key| {
+                    key.arena() != 0 || restored.get(key.index as usize).copied() == Some(true)
+                });
+                if is_ready {
+                    self.fill_in(idx, &entities[idx])?;
+                    restored[idx] = true;
+                } else {
+                    remaining.push(idx);
+                }
+            }
+            if remaining.len() == pending.len() {
+                // The regions make each other visible
+                return None;
+            }
+            pending = remaining;
+        }
+        Some(())
+    }
+
+    fn fill_in(&self, idx: usize, ent: &CachedEnt) -> Option<()> {
+        let id = EntityId::from_local_index(*self.arenas.first()?, idx as u32);
+        let related = match ent.related {
+            CachedRelated::ImplicitOf(key) => Related::ImplicitOf(self.ent(key)?),
+            CachedRelated::InstanceOf(key) => Related::InstanceOf(self.ent(key)?),
+            CachedRelated::DeclaredBy(key) => Related::DeclaredBy(self.ent(key)?),
+            CachedRelated::DerivedFrom(key) => Related::DerivedFrom(self.ent(key)?),
+            CachedRelated::None => Related::None,
+        };
+        let implicits = ent
+            .implicits
+            .iter()
+            .map(|key| self.ent(*key))
+            .collect::<Option<_>>()?;
+        let mut attrs = FnvHashMap::default();
+        for (CachedSymbol(name), pos, key) in ent.attrs.iter() {
+            attrs.insert(
+                name.clone(),
+                (
+                    self.pos(pos)?,
+                    AttributeEnt {
+                        ent: self.ent(*key)?,
+                    },
+                ),
+            );
+        }
+        let parent = self.opt_ent(ent.parent)?;
+        let kind = self.kind(&ent.kind)?;
+
+        // This is safe since the entity is not used until the whole arena has been restored
+        unsafe {
+            self.arena
+                .get(id)
+                .restore(parent, related, implicits, kind, attrs);
+        }
+        Some(())
+    }
+
+    fn kind(&self, kind: &CachedKind) -> Option<AnyEntKind<'a>> {
+        Some(match kind {
+            CachedKind::ExternalAlias { class, type_mark } => AnyEntKind::ExternalAlias {
+                class: *class,
+                type_mark: self.typ(*type_mark)?,
+            },
+            CachedKind::ObjectAlias {
+                base_object,
+                type_mark,
+            } => AnyEntKind::ObjectAlias {
+                base_object: ObjectEnt {
+                    ent: self.ent(*base_object)?,
+                },
+                type_mark: self.typ(*type_mark)?,
+            },
+            CachedKind::File(key) => AnyEntKind::File(self.subtype(*key)?),
+            CachedKind::InterfaceFile(key) => AnyEntKind::InterfaceFile(self.typ(*key)?),
+            CachedKind::Component(region) => AnyEntKind::Component(self.region(region)?),
+            CachedKind::Attribute(key) => AnyEntKind::Attribute(self.typ(*key)?),
+            CachedKind::Overloaded(overloaded) => {
+                AnyEntKind::Overloaded(self.overloaded(overloaded)?)
+            }
+            CachedKind::Type(typ) => AnyEntKind::Type(self.type_kind(typ)?),
+            CachedKind::ElementDeclaration(key) => {
+                AnyEntKind::ElementDeclaration(self.subtype(*key)?)
+            }
+            CachedKind::Concurrent(concurrent) => AnyEntKind::Concurrent(*concurrent),
+            CachedKind::Sequential(sequential) => AnyEntKind::Sequential(*sequential),
+            CachedKind::Object(object) => AnyEntKind::Object(Object {
+                class: object.class,
+                iface: match object.iface {
+                    Some(ref iface) => Some(self.object_interface(iface)?),
+                    None => None,
+                },
+                subtype: self.subtype(object.subtype)?,
+                has_default: object.has_default,
+            }),
+            CachedKind::LoopParameter(key) => AnyEntKind::LoopParameter(match key {
+                Some(key) => Some(self.base_type(*key)?),
+                None => None,
+            }),
+            CachedKind::PhysicalLiteral(key) => AnyEntKind::PhysicalLiteral(self.typ(*key)?),
+            CachedKind::DeferredConstant(key) => AnyEntKind::DeferredConstant(self.subtype(*key)?),
+            CachedKind::Library => AnyEntKind::Library,
+            CachedKind::Design(design) => AnyEntKind::Design(self.design(design)?),
+            CachedKind::View(key) => AnyEntKind::View(self.subtype(*key)?),
+            CachedKind::Psl(psl) => AnyEntKind::Psl(*psl),
+        })
+    }
+
+    fn type_kind(&self, typ: &CachedType) -> Option<Type<'a>> {
+        Some(match typ {
+            CachedType::Array { indexes, elem_type } => Type::Array {
+                indexes: indexes
+                    .iter()
+                    .map(|index| match index {
+                        Some(key) => Some(Some(self.base_type(*key)?)),
+                        None => Some(None),
+                    })
+                    .collect::<Option<_>>()?,
+                elem_type: self.typ(*elem_type)?,
+            },
+            CachedType::Enum(literals) => Type::Enum(literals.iter().cloned().collect()),
+            CachedType::Integer => Type::Integer,
+            CachedType::Real => Type::Real,
+            CachedType::Physical => Type::Physical,
+            CachedType::Access(key) => Type::Access(self.subtype(*key)?),
+            CachedType::Record(elems) => Type::Record(RecordRegion {
+                elems: elems
+                    .iter()
+                    .map(|key| {
+                        Some(RecordElement {
+                            ent: self.ent(*key)?,
+                        })
+                    })
+                    .collect::<Option<_>>()?,
+            }),
+            CachedType::Incomplete => Type::Incomplete,
+            CachedType::Subtype(key) => Type::Subtype(self.subtype(*key)?),
+            CachedType::Protected(region, has_body) => {
+                Type::Protected(self.region(region)?, *has_body)
+            }
+            CachedType::File => Type::File,
+            CachedType::Interface => Type::Interface,
+            CachedType::Alias(key) => Type::Alias(self.typ(*key)?),
+            CachedType::Universal(universal) => Type::Universal(*universal),
+        })
+    }
+
+    fn overloaded(&self, overloaded: &CachedOverloaded) -> Option<Overloaded<'a>> {
+        Some(match overloaded {
+            CachedOverloaded::SubprogramDecl(signature) => {
+                Overloaded::SubprogramDecl(self.signature(signature)?)
+            }
+            CachedOverloaded::Subprogram(signature) => {
+                Overloaded::Subprogram(self.signature(signature)?)
+            }
+            CachedOverloaded::UninstSubprogramDecl(signature, region) => {
+                Overloaded::UninstSubprogramDecl(self.signature(signature)?, self.region(region)?)
+            }
+            CachedOverloaded::UninstSubprogram(signature, region) => {
+                Overloaded::UninstSubprogram(self.signature(signature)?, self.region(region)?)
+            }
+            CachedOverloaded::InterfaceSubprogram(signature) => {
+                Overloaded::InterfaceSubprogram(self.signature(signature)?)
+            }
+            CachedOverloaded::EnumLiteral(signature) => {
+                Overloaded::EnumLiteral(self.signature(signature)?)
+            }
+            CachedOverloaded::Alias(key) => Overloaded::Alias(OverloadedEnt {
+                ent: self.ent(*key)?,
+            }),
+        })
+    }
+
+    fn signature(&self, signature: &CachedSignature) -> Option<Signature<'a>> {
+        let mut formals = FormalRegion::new(signature.typ);
+        formals.entities = signature
+            .formals
+            .iter()
+            .map(|key| self.ent(*key).map(InterfaceEnt::from_any_unchecked))
+            .collect::<Option<_>>()?;
+        let return_type = match signature.return_type {
+            Some(key) => Some(self.typ(key)?),
+            None => None,
+        };
+        Some(Signature::new(formals, return_type))
+    }
+
+    fn object_interface(&self, iface: &CachedObjectInterface) -> Option<ObjectInterface<'a>> {
+        let mode = |mode: &CachedInterfaceMode| -> Option<InterfaceMode<'a>> {
+            Some(match mode {
+                CachedInterfaceMode::Simple(mode) => InterfaceMode::Simple(*mode),
+                CachedInterfaceMode::View(key) => InterfaceMode::View(ViewEnt {
+                    ent: self.ent(*key)?,
+                }),
+            })
+        };
+        Some(match iface {
+            CachedObjectInterface::Generic => ObjectInterface::Generic,
+            CachedObjectInterface::Port(port) => ObjectInterface::Port(mode(port)?),
+            CachedObjectInterface::Parameter(param) => ObjectInterface::Parameter(mode(param)?),
+        })
+    }
+
+    fn design(&self, design: &CachedDesign) -> Option<Design<'a>> {
+        Some(match design {
+            CachedDesign::Entity(visibility, region) => {
+                Design::Entity(self.visibility(visibility)?, self.region(region)?)
+            }
+            CachedDesign::Architecture(visibility, region, entity) => Design::Architecture(
+                self.visibility(visibility)?,
+                self.region(region)?,
+                DesignEnt(self.ent(*entity)?),
+            ),
+            CachedDesign::Configuration => Design::Configuration,
+            CachedDesign::Package(visibility, region) => {
+                Design::Package(self.visibility(visibility)?, self.region(region)?)
+            }
+            CachedDesign::PackageBody(visibility, region) => {
+                Design::PackageBody(self.visibility(visibility)?, self.region(region)?)
+            }
+            CachedDesign::UninstPackage(visibility, region) => {
+                Design::UninstPackage(self.visibility(visibility)?, self.region(region)?)
+            }
+            CachedDesign::PackageInstance(region) => Design::PackageInstance(self.region(region)?),
+            CachedDesign::InterfacePackageInstance(region) => {
+                Design::InterfacePackageInstance(self.region(region)?)
+            }
+            CachedDesign::Context(region) => Design::Context(self.region(region)?),
+        })
+    }
+
+    fn region(&self, region: &CachedRegion) -> Option<Region<'a>> {
+        let mut entities = FnvHashMap::default();
+        for (designator, named_entities) in region.entities.iter() {
+            let named_entities = match named_entities {
+                CachedNamedEntities::Single(key) => NamedEntities::Single(self.ent(*key)?),
+                CachedNamedEntities::Overloaded(keyed) => {
+                    let mut overloaded = FnvHashMap::default();
+                    for (key, ent) in keyed.iter() {
+                        let signature_key = SignatureKey {
+                            formals: key
+                                .formals
+                                .iter()
+                                .map(|key| self.base_type(*key))
+                                .collect::<Option<_>>()?,
+                            return_type: match key.return_type {
+                                Some(key) => Some(self.base_type(key)?),
+                                None => None,
+                            },
+                        };
+                        let key = if key.uninstantiated {
+                            SubprogramKey::Uninstantiated(signature_key)
+                        } else {
+                            SubprogramKey::Normal(signature_key)
+                        };
+                        overloaded.insert(
+                            key,
+                            OverloadedEnt {
+                                ent: self.ent(*ent)?,
+                            },
+                        );
+                    }
+                    NamedEntities::Overloaded(OverloadedName {
+                        entities: overloaded,
+                    })
+                }
+            };
+            entities.insert(designator.clone(), named_entities);
+        }
+
+        Some(Region {
+            visibility: self.visibility(&region.visibility)?,
+            entities,
+            kind: region.kind,
+        })
+    }
+
+    fn visibility(&self, visibility: &CachedVisibility) -> Option<Visibility<'a>> {
+        let mut all_in_regions = Vec::with_capacity(visibility.all_in_regions.len());
+        for (visible_pos, owner) in visibility.all_in_regions.iter() {
+            let AnyEntKind::Design(design) = self.ent(*owner)?.kind() else {
+                return None;
+            };
+            all_in_regions.push(VisibleRegion {
+                visible_pos: self.visible_pos(visible_pos)?,
+                region: design_region(design)?,
+            });
+        }
+
+        let mut visible = FnvHashMap::default();
+        for (designator, entities) in visibility.visible.iter() {
+            let mut visible_entities = FnvHashMap::default();
+            for (visible_pos, key) in entities.iter() {
+                let entity = self.ent(*key)?;
+                visible_entities.insert(
+                    entity.id(),
+                    VisibleEntity {
+                        visible_pos: self.visible_pos(visible_pos)?,
+                        entity,
+                    },
+                );
+            }
+            visible.insert(designator.clone(), visible_entities);
+        }
+
+        Some(Visibility {
+            all_in_regions,
+            visible,
+            black_box: visibility.black_box,
+        })
+    }
+}
+
+impl CachedKind {
+    /// The design entities whose regions are made visible by the regions of this kind
+    fn visible_regions(&self, owners: &mut FnvHashSet<EntKey>) {
+        match self {
+            CachedKind::Component(region)
+            | CachedKind::Type(CachedType::Protected(region, _))
+            | CachedKind::Overloaded(CachedOverloaded::UninstSubprogramDecl(_, region))
+            | CachedKind::Overloaded(CachedOverloaded::UninstSubprogram(_, region)) => {
+                region.visibility.visible_regions(owners)
+            }
+            CachedKind::Design(design) => match design {
+                CachedDesign::Entity(visibility, region)
+                | CachedDesign::Architecture(visibility, region, _)
+                | CachedDesign::Package(visibility, region)
+                | CachedDesign::PackageBody(visibility, region)
+                | CachedDesign::UninstPackage(visibility, region) => {
+                    visibility.visible_regions(owners);
+                    region.visibility.visible_regions(owners);
+                }
+                CachedDesign::PackageInstance(region)
+                | CachedDesign::InterfacePackageInstance(region)
+                | CachedDesign::Context(region) => region.visibility.visible_regions(owners),
+                CachedDesign::Configuration => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+impl CachedVisibility {
+    fn visible_regions(&self, owners: &mut FnvHashSet<EntKey>) {
+        owners.extend(self.all_in_regions.iter().map(|(_, owner)| *owner));
+    }
+}
@@ -6,20 +6,29 @@
 
 //! Configuration of the design hierarchy and other settings
 
+#[cfg(feature = "embedded-libraries")]
+mod embedded;
+mod fusesoc;
+mod import;
+mod infer;
+mod stubs;
+mod vunit;
+
 use std::collections::BTreeSet;
-use std::env;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
 use std::path::Path;
 
-use fnv::FnvHashMap;
+use fnv::{FnvHashMap, FnvHashSet};
 use subst::VariableMap;
 use toml::{Table, Value};
 
 use crate::data::error_codes::ErrorCode;
 use crate::data::*;
 use crate::standard::VHDLStandard;
+use crate::syntax::Defines;
+pub(crate) use stubs::{is_json_stub, read_json_stub};
 
 #[derive(Clone, PartialEq, Eq, Default, Debug)]
 pub struct Config {
@@ -28,6 +37,18 @@ pub struct Config {
     standard: VHDLStandard,
     // Defines the severity that diagnostics are displayed with
     severities: SeverityMap,
+    // Files outside of the configuration file that libraries were imported from
+    imported_files: Vec<PathBuf>,
+    // The configuration file that the standard was read from
+    standard_origin: Option<PathBuf>,
+    // User defined identifiers for conditional analysis
+    defines: Defines,
+    // Directory where the parsed files of third-party libraries are cached
+    cache_directory: Option<PathBuf>,
+    // Only analyze the units of third-party libraries that are used
+    lazy_analysis: bool,
+    // Settings of included files that are not applied, as (included file, key)
+    ignored_included_keys: Vec<(PathBuf, String)>,
 }
 
 #[derive(Clone, PartialEq, Eq, Default, Debug)]
@@ -35,7 +56,20 @@ pub struct LibraryConfig {
     name: String,
     patterns: Vec<String>,
     exclude_patterns: Vec<String>,
+    // Files that declare the interface of units whose sources are encrypted or unavailable
+    stub_patterns: Vec<String>,
+    // In-memory sources that are part of the library in addition to the files
+    sources: Vec<Source>,
     pub(crate) is_third_party: bool,
+    // Units of the library that are not available are treated as opaque units
+    pub(crate) black_box: bool,
+    // The standard of the files of this library if it differs from the project standard
+    standard: Option<VHDLStandard>,
+    // Files whose standard differs from the standard of the library, e.g. the
+    // `vhdlSource-93` files of a FuseSoC library that also contains `vhdlSource-2008` files
+    file_standards: Vec<(String, VHDLStandard)>,
+    // A map from file, exclude and stub pattern to the file that it was read from
+    origins: FnvHashMap<String, PathBuf>,
 }
 
 impl LibraryConfig {
@@ -50,45 +84,333 @@ impl LibraryConfig {
         Vec::from_iter(result)
     }
 
+    /// Returns true if a file belongs to the library according to its file and exclude patterns,
+    /// e.g., a file that was created after the file names have been read
+    pub fn matches_file(&self, file_name: &Path) -> bool {
+        matches_file_patterns(&self.patterns, file_name)
+            && !matches_file_patterns(&self.exclude_patterns, file_name)
+    }
+
+    /// Return the names of the stub files of the library.
+    /// A stub file declares the interface of design units, i.e. entities and components,
+    /// whose sources are encrypted or otherwise unavailable.
+    pub fn stub_file_names(&self, messages: &mut dyn MessageHandler) -> Vec<PathBuf> {
+        Vec::from_iter(match_file_patterns(&self.stub_patterns, messages))
+    }
+
     /// Returns the name of the library
     pub fn name(&self) -> &str {
         self.name.as_str()
     }
+
+    /// Returns the in-memory sources of the library, i.e., embedded standard libraries
+    pub fn sources(&self) -> &[Source] {
+        &self.sources
+    }
+
+    /// Returns the VHDL standard of the library if it is set for this library only
+    pub fn standard(&self) -> Option<VHDLStandard> {
+        self.standard
+    }
+
+    /// Returns the VHDL standard of a file if it differs from the standard of the library
+    pub fn file_standard(&self, file_name: &Path) -> Option<VHDLStandard> {
+        self.file_standards
+            .iter()
+            .find(|(pattern, _)| matches_file_patterns(std::slice::from_ref(pattern), file_name))
+            .map(|(_, standard)| *standard)
+    }
+
+    /// Returns the file that a file, exclude or stub pattern was read from, if known
+    pub fn origin(&self, pattern: &str) -> Option<&Path> {
+        self.origins.get(pattern).map(PathBuf::as_path)
+    }
+
+    fn set_origin(&mut self, origin: &Path) {
+        for pattern in self
+            .patterns
+            .iter()
+            .chain(self.exclude_patterns.iter())
+            .chain(self.stub_patterns.iter())
+        {
+            if !self.origins.contains_key(pattern) {
+                self.origins.insert(pattern.clone(), origin.to_owned());
+            }
+        }
+    }
+
+    /// Merge a definition of the same library from another file,
+    /// i.e., the union of the file and exclude patterns
+    fn merge(&mut self, other: &LibraryConfig) {
+        for pattern in other.patterns.iter() {
+            if !self.patterns.contains(pattern) {
+                self.patterns.push(pattern.clone());
+            }
+        }
+        for pattern in other.exclude_patterns.iter() {
+            if !self.exclude_patterns.contains(pattern) {
+                self.exclude_patterns.push(pattern.clone());
+            }
+        }
+        for pattern in other.stub_patterns.iter() {
+            if !self.stub_patterns.contains(pattern) {
+                self.stub_patterns.push(pattern.clone());
+            }
+        }
+        self.sources.extend(other.sources.iter().cloned());
+        self.is_third_party |= other.is_third_party;
+        self.black_box |= other.black_box;
+        self.standard = self.standard.or(other.standard);
+        self.file_standards
+            .extend(other.file_standards.iter().cloned());
+        for (pattern, origin) in other.origins.iter() {
+            if !self.origins.contains_key(pattern) {
+                self.origins.insert(pattern.clone(), origin.clone());
+            }
+        }
+    }
 }
 
 impl Config {
     pub fn from_str(string: &str, parent: &Path) -> Result<Config, String> {
+        Self::parse(string, parent, None, &mut Vec::new())
+    }
+
+    /// Parse a configuration file.
+    /// `origin` is the name of the file, if any, and `includes` are the files
+    /// that are currently being included which is used to detect circular includes.
+    fn parse(
+        string: &str,
+        parent: &Path,
+        origin: Option<&Path>,
+        includes: &mut Vec<PathBuf>,
+    ) -> Result<Config, String> {
         let config = string.parse::<Value>().map_err(|err| err.to_string())?;
         let mut libraries = FnvHashMap::default();
+        let mut imported_files = Vec::new();
+        let mut ignored_included_keys = Vec::new();
 
-        let standard = if let Some(std) = config.get("standard") {
+        let (standard, standard_origin) = if let Some(std) = config.get("standard") {
             let std_str = std.as_str().ok_or("standard must be a string")?;
-            VHDLStandard::try_from(std_str)
-                .map_err(|_| format!("Unsupported standard '{std_str}'"))?
+            let standard = VHDLStandard::try_from(std_str)
+                .map_err(|_| format!("Unsupported standard '{std_str}'"))?;
+            (standard, origin.map(Path::to_owned))
         } else {
-            VHDLStandard::default()
+            (VHDLStandard::default(), None)
+        };
+
+        let mut defines = Defines::default();
+        if let Some(table) = config.get("defines") {
+            let table = table.as_table().ok_or("defines must be a table")?;
+            for (name, value) in table.iter() {
+                let value = value
+                    .as_str()
+                    .ok_or_else(|| format!("Value of define {name} must be a string"))?;
+                defines.insert(name.to_owned(), value.to_owned());
+            }
+        }
+
+        if let Some(libs) = config.get("libraries") {
+            let libs = libs.as_table().ok_or("libraries must be a table")?;
+            Self::read_libraries(libs, parent, &mut libraries)?;
+        } else if ["import", "fusesoc", "vunit_export", "include"]
+            .iter()
+            .all(|key| config.get(key).is_none())
+        {
+            return Err("missing field libraries".to_owned());
+        }
+
+        if let Some(include) = config.get("include") {
+            let include = include.as_array().ok_or("include must be an array")?;
+            for file_name in Self::find_included_files(include, parent)? {
+                // Paths such as `../b/vhdl_ls.toml` must be normalized to detect a cycle
+                let canonical_name =
+                    dunce::canonicalize(&file_name).unwrap_or_else(|_| file_name.clone());
+                if includes.contains(&canonical_name) {
+                    return Err(format!(
+                        "Circular include of {}",
+                        file_name.to_string_lossy()
+                    ));
+                }
+                let contents = std::fs::read_to_string(&file_name).map_err(|err| {
+                    format!(
+                        "Could not read included file {}: {err}",
+                        file_name.to_string_lossy()
+                    )
+                })?;
+                includes.push(canonical_name);
+                let included = Self::parse(
+                    &contents,
+                    file_name.parent().unwrap_or(parent),
+                    Some(&file_name),
+                    includes,
+                )
+                .map_err(|err| format!("In {}: {err}", file_name.to_string_lossy()))?;
+                includes.pop();
+
+                for library in included.libraries.into_values() {
+                    match libraries.get_mut(&library.name) {
+                        Some(existing) => existing.merge(&library),
+                        None => {
+                            libraries.insert(library.name.clone(), library);
+                        }
+                    }
+                }
+                // Defines of the including file take precedence
+                for (name, value) in included.defines {
+                    defines.entry(name).or_insert(value);
+                }
+                // Only libraries and defines are included
+                let ignored_keys = [
+                    ("standard", included.standard_origin.is_some()),
+                    ("lint", included.severities != SeverityMap::default()),
+                    ("cache", included.cache_directory.is_some()),
+                    ("lazy_analysis", included.lazy_analysis),
+                ];
+                for (key, _) in ignored_keys.into_iter().filter(|(_, is_set)| *is_set) {
+                    ignored_included_keys.push((file_name.clone(), key.to_owned()));
+                }
+                ignored_included_keys.extend(included.ignored_included_keys);
+                imported_files.push(file_name);
+                for file_name in included.imported_files {
+                    if !imported_files.contains(&file_name) {
+                        imported_files.push(file_name);
+                    }
+                }
+            }
+        }
+
+        if let Some(imports) = config.get("import") {
+            let imports = imports.as_array().ok_or("import must be an array")?;
+            for file_name in check_file_patterns(imports, parent)? {
+                for mut library in import::import_libraries(Path::new(&file_name))? {
+                    library.set_origin(Path::new(&file_name));
+                    // Libraries defined in the configuration file take precedence
+                    if !libraries.contains_key(&library.name) {
+                        libraries.insert(library.name.clone(), library);
+                    }
+                }
+                imported_files.push(PathBuf::from(file_name));
+            }
+        }
+
+        let cache_directory = match config.get("cache") {
+            Some(cache) => {
+                let cache = cache.as_str().ok_or("cache must be a string")?;
+                let cache = substitute_environment_variables(cache, &subst::Env)?;
+                Some(parent.join(cache))
+            }
+            None => None,
         };
 
-        let libs = config
-            .get("libraries")
-            .ok_or("missing field libraries")?
-            .as_table()
-            .ok_or("libraries must be a table")?;
+        let lazy_analysis = match config.get("lazy_analysis") {
+            Some(lazy) => lazy.as_bool().ok_or("lazy_analysis must be a boolean")?,
+            None => false,
+        };
 
-        for (name, lib) in libs.iter() {
-            if name.to_lowercase() == "work" {
-                return Err(format!(
-                    "The '{}' library is not a valid library.\nHint: To use a library that contains all files, use a common name for all libraries, i.e., 'defaultlib'",
-                    name
-                ));
+        if let Some(vunit_export) = config.get("vunit_export") {
+            let vunit_export = vunit_export
+                .as_str()
+                .ok_or("vunit_export must be a string")?;
+            let file_name = substitute_environment_variables(vunit_export, &subst::Env)?;
+            let file_name = parent.join(file_name);
+            for mut library in vunit::import_vunit_export(&file_name)? {
+                library.set_origin(&file_name);
+                // Libraries defined in the configuration file take precedence
+                if !libraries.contains_key(&library.name) {
+                    libraries.insert(library.name.clone(), library);
+                }
+            }
+            imported_files.push(file_name);
+        }
+
+        if let Some(fusesoc) = config.get("fusesoc") {
+            let fusesoc = fusesoc.as_table().ok_or("fusesoc must be a table")?;
+            let core_dirs = match fusesoc.get("cores") {
+                Some(cores) => {
+                    check_file_patterns(cores.as_array().ok_or("cores is not array")?, parent)?
+                }
+                None => vec![parent.to_string_lossy().into_owned()],
+            };
+            let library_dirs = match fusesoc.get("libraries") {
+                Some(libs) => {
+                    check_file_patterns(libs.as_array().ok_or("libraries is not array")?, parent)?
+                }
+                None => Vec::new(),
+            };
+            for library in fusesoc::import_cores(&core_dirs, &library_dirs)? {
+                // Libraries defined in the configuration file take precedence
+                if !libraries.contains_key(&library.name) {
+                    libraries.insert(library.name.clone(), library);
+                }
+            }
+        }
+
+        if let Some(origin) = origin {
+            for library in libraries.values_mut() {
+                library.set_origin(origin);
+            }
+        }
+
+        let severities = if let Some(lint) = config.get("lint") {
+            Self::read_severity_overwrites(lint.as_table().ok_or("lint must be a table")?)?
+        } else {
+            SeverityMap::default()
+        };
+
+        Ok(Config {
+            libraries,
+            severities,
+            standard,
+            imported_files,
+            standard_origin,
+            defines,
+            cache_directory,
+            lazy_analysis,
+            ignored_included_keys,
+        })
+    }
+
+    /// The configuration files that match the `include` patterns, in alphabetical order
+    fn find_included_files(include: &[Value], parent: &Path) -> Result<Vec<PathBuf>, String> {
+        let mut file_names = BTreeSet::new();
+        for pattern in check_file_patterns(include, parent)? {
+            if is_literal(&pattern) {
+                file_names.insert(PathBuf::from(pattern));
+                continue;
             }
+            let paths =
+                glob::glob(&pattern).map_err(|err| format!("Invalid pattern '{pattern}' {err}"))?;
+            file_names.extend(paths.flatten());
+        }
+        Ok(file_names.into_iter().collect())
+    }
 
-            let file_arr = lib
-                .get("files")
-                .ok_or_else(|| format!("missing field files for library {name}"))?
-                .as_array()
-                .ok_or_else(|| format!("files for library {name} is not array"))?;
-            let patterns = check_file_patterns(file_arr, parent)?;
+    fn read_libraries(
+        libs: &Table,
+        parent: &Path,
+        libraries: &mut FnvHashMap<String, LibraryConfig>,
+    ) -> Result<(), String> {
+        for (name, lib) in libs.iter() {
+            check_library_name(name)?;
+
+            // A library that only excludes files from the same library of an included file,
+            // that only consists of stubs or that is a black box does not need any files of its own
+            let patterns = match lib.get("files") {
+                Some(file_arr) => {
+                    let file_arr = file_arr
+                        .as_array()
+                        .ok_or_else(|| format!("files for library {name} is not array"))?;
+                    check_file_patterns(file_arr, parent)?
+                }
+                None if ["exclude", "stubs", "black_box"]
+                    .iter()
+                    .any(|key| lib.get(key).is_some()) =>
+                {
+                    Vec::new()
+                }
+                None => return Err(format!("missing field files for library {name}")),
+            };
 
             let mut exclude_patterns = Vec::new();
             if let Some(opt) = lib.get("exclude") {
@@ -99,6 +421,15 @@ impl Config {
                 }
             }
 
+            let mut stub_patterns = Vec::new();
+            if let Some(opt) = lib.get("stubs") {
+                if let Some(opt) = opt.as_array() {
+                    stub_patterns = check_file_patterns(opt, parent)?;
+                } else {
+                    return Err(format!("stubs for library {name} is not array"));
+                }
+            }
+
             let mut is_third_party = false;
             if let Some(opt) = lib.get("is_third_party") {
                 if let Some(opt) = opt.as_bool() {
@@ -110,28 +441,44 @@ impl Config {
                 }
             }
 
+            let mut black_box = false;
+            if let Some(opt) = lib.get("black_box") {
+                if let Some(opt) = opt.as_bool() {
+                    black_box = opt;
+                } else {
+                    return Err(format!(
+                        "Expected black_box to be boolean for library {name}"
+                    ));
+                }
+            }
+
+            let standard = match lib.get("standard") {
+                Some(std) => {
+                    let std_str = std
+                        .as_str()
+                        .ok_or_else(|| format!("standard for library {name} must be a string"))?;
+                    Some(VHDLStandard::try_from(std_str).map_err(|_| {
+                        format!("Unsupported standard '{std_str}' for library {name}")
+                    })?)
+                }
+                None => None,
+            };
+
             libraries.insert(
                 name.to_owned(),
                 LibraryConfig {
                     name: name.to_owned(),
                     patterns,
                     exclude_patterns,
+                    stub_patterns,
                     is_third_party,
+                    black_box,
+                    standard,
+                    ..Default::default()
                 },
             );
         }
-
-        let severities = if let Some(lint) = config.get("lint") {
-            Self::read_severity_overwrites(lint.as_table().ok_or("lint must be a table")?)?
-        } else {
-            SeverityMap::default()
-        };
-
-        Ok(Config {
-            libraries,
-            severities,
-            standard,
-        })
+        Ok(())
     }
 
     fn read_severity_overwrites(severity_overwrites: &Table) -> Result<SeverityMap, String> {
@@ -164,7 +511,43 @@ impl Config {
 
         let parent = file_name.parent().unwrap();
 
-        Config::from_str(&contents, parent).map_err(|msg| io::Error::new(io::ErrorKind::Other, msg))
+        Config::parse(
+            &contents,
+            parent,
+            Some(file_name),
+            &mut vec![dunce::canonicalize(file_name).unwrap_or_else(|_| file_name.to_owned())],
+        )
+        .map_err(|msg| io::Error::new(io::ErrorKind::Other, msg))
+    }
+
+    /// Create a configuration from the library mapping of a simulator.
+    /// `file_name` is either a `modelsim.ini` file, a GHDL library file such as `lib-obj08.cf`
+    /// or a NVC library directory.
+    pub fn import_file_path(file_name: &Path) -> io::Result<Config> {
+        let mut libraries = FnvHashMap::default();
+        for mut library in import::import_libraries(file_name).map_err(io::Error::other)? {
+            library.set_origin(file_name);
+            libraries.insert(library.name.clone(), library);
+        }
+        Ok(Config {
+            libraries,
+            imported_files: vec![file_name.to_owned()],
+            ..Default::default()
+        })
+    }
+
+    /// Create a configuration from a VUnit `--export-json` file.
+    pub fn read_vunit_export(file_name: &Path) -> io::Result<Config> {
+        let mut libraries = FnvHashMap::default();
+        for mut library in vunit::import_vunit_export(file_name).map_err(io::Error::other)? {
+            library.set_origin(file_name);
+            libraries.insert(library.name.clone(), library);
+        }
+        Ok(Config {
+            libraries,
+            imported_files: vec![file_name.to_owned()],
+            ..Default::default()
+        })
     }
 
     pub fn get_library(&self, name: &str) -> Option<&LibraryConfig> {
@@ -175,11 +558,211 @@ impl Config {
         self.libraries.values()
     }
 
+    /// Infer a library mapping for the VHDL files below `root` for workspaces that have no
+    /// configuration file.
+    ///
+    /// Libraries are inferred from directory names, library clauses and `lib.unit`
+    /// references. Files that cannot be attributed to any library are placed
+    /// in a library named `defaultlib`.
+    /// Files and libraries that are already part of this configuration are left out.
+    pub fn infer_from_directory(&self, root: &Path) -> Config {
+        let known_files: FnvHashSet<PathBuf> = self
+            .iter_libraries()
+            .flat_map(|library| library.file_names(&mut NullMessages))
+            .collect();
+        let known_libraries = self
+            .iter_libraries()
+            .map(|library| library.name.to_lowercase())
+            .collect();
+        let file_names: Vec<PathBuf> = infer::find_vhdl_files(root)
+            .into_iter()
+            .filter(|file_name| !known_files.contains(file_name))
+            .collect();
+
+        let libraries = infer::infer_libraries(root, &file_names, self.standard, &known_libraries)
+            .into_iter()
+            .map(|library| (library.name.clone(), library))
+            .collect();
+        Config {
+            libraries,
+            standard: self.standard,
+            ..Default::default()
+        }
+    }
+
+    /// Serialize the library mapping in the format of a `vhdl_ls.toml` file
+    /// that is located in `parent`.
+    /// File names below `parent` are written relative to `parent`.
+    pub fn to_toml_string(&self, parent: &Path) -> String {
+        let parent = glob::Pattern::escape(&parent.to_string_lossy());
+        let relative = |patterns: &[String]| -> Value {
+            Value::Array(
+                patterns
+                    .iter()
+                    .map(|pattern| {
+                        let pattern = pattern
+                            .strip_prefix(&parent)
+                            .and_then(|pattern| pattern.strip_prefix(std::path::MAIN_SEPARATOR))
+                            .unwrap_or(pattern);
+                        Value::String(pattern.to_owned())
+                    })
+                    .collect(),
+            )
+        };
+
+        let mut libraries = Table::new();
+        let mut names: Vec<&String> = self.libraries.keys().collect();
+        names.sort();
+        for name in names {
+            let library = &self.libraries[name];
+            let mut table = Table::new();
+            table.insert("files".to_owned(), relative(&library.patterns));
+            if !library.exclude_patterns.is_empty() {
+                table.insert("exclude".to_owned(), relative(&library.exclude_patterns));
+            }
+            if !library.stub_patterns.is_empty() {
+                table.insert("stubs".to_owned(), relative(&library.stub_patterns));
+            }
+            if library.is_third_party {
+                table.insert("is_third_party".to_owned(), Value::Boolean(true));
+            }
+            if library.black_box {
+                table.insert("black_box".to_owned(), Value::Boolean(true));
+            }
+            if let Some(standard) = library.standard {
+                table.insert(
+                    "standard".to_owned(),
+                    Value::String(standard.as_ref().to_owned()),
+                );
+            }
+            libraries.insert(name.clone(), Value::Table(table));
+        }
+
+        let mut config = Table::new();
+        config.insert(
+            "standard".to_owned(),
+            Value::String(self.standard.as_ref().to_owned()),
+        );
+        config.insert("libraries".to_owned(), Value::Table(libraries));
+        if !self.defines.is_empty() {
+            config.insert(
+                "defines".to_owned(),
+                Value::Table(
+                    self.defines
+                        .iter()
+                        .map(|(name, value)| (name.clone(), Value::String(value.clone())))
+                        .collect(),
+                ),
+            );
+        }
+        config.to_string()
+    }
+
+    /// Describe the resolved configuration in the format of a `vhdl_ls.toml` file.
+    /// Each entry is annotated with the file that it was read from.
+    pub fn to_resolved_string(&self) -> String {
+        let origin = |origin: Option<&Path>| match origin {
+            Some(origin) => origin.to_string_lossy().into_owned(),
+            None => "default".to_owned(),
+        };
+
+        let mut result = format!(
+            "standard = {} # {}\n\n[libraries]\n",
+            Value::String(self.standard.as_ref().to_owned()),
+            origin(self.standard_origin.as_deref())
+        );
+
+        let mut names: Vec<&String> = self.libraries.keys().collect();
+        names.sort();
+        for (idx, name) in names.into_iter().enumerate() {
+            let library = &self.libraries[name];
+            if idx > 0 {
+                result.push('\n');
+            }
+            result.push_str(&format!("{name}.files = [\n"));
+            for pattern in library.patterns.iter() {
+                result.push_str(&format!(
+                    "  {}, # {}\n",
+                    Value::String(pattern.clone()),
+                    origin(library.origin(pattern))
+                ));
+            }
+            for source in library.sources.iter() {
+                result.push_str(&format!(
+                    "  {}, # embedded\n",
+                    Value::String(source.file_name().to_string_lossy().into_owned())
+                ));
+            }
+            result.push_str("]\n");
+            if !library.exclude_patterns.is_empty() {
+                result.push_str(&format!("{name}.exclude = [\n"));
+                for pattern in library.exclude_patterns.iter() {
+                    result.push_str(&format!(
+                        "  {}, # {}\n",
+                        Value::String(pattern.clone()),
+                        origin(library.origin(pattern))
+                    ));
+                }
+                result.push_str("]\n");
+            }
+            if !library.stub_patterns.is_empty() {
+                result.push_str(&format!("{name}.stubs = [\n"));
+                for pattern in library.stub_patterns.iter() {
+                    result.push_str(&format!(
+                        "  {}, # {}\n",
+                        Value::String(pattern.clone()),
+                        origin(library.origin(pattern))
+                    ));
+                }
+                result.push_str("]\n");
+            }
+            if library.is_third_party {
+                result.push_str(&format!("{name}.is_third_party = true\n"));
+            }
+            if library.black_box {
+                result.push_str(&format!("{name}.black_box = true\n"));
+            }
+            if let Some(standard) = library.standard {
+                result.push_str(&format!(
+                    "{name}.standard = {}\n",
+                    Value::String(standard.as_ref().to_owned())
+                ));
+            }
+        }
+        if !self.defines.is_empty() {
+            result.push_str("\n[defines]\n");
+            let mut names: Vec<&String> = self.defines.keys().collect();
+            names.sort();
+            for name in names {
+                result.push_str(&format!(
+                    "{name} = {}\n",
+                    Value::String(self.defines[name].clone())
+                ));
+            }
+        }
+        result
+    }
+
+    /// The files that libraries were imported from, i.e., simulator setup files
+    /// or VUnit export files.
+    /// A change to any of these files changes the configuration.
+    pub fn imported_files(&self) -> &[PathBuf] {
+        &self.imported_files
+    }
+
     /// Append another config to self
     ///
-    /// In case of conflict the appended config takes precedence
+    /// In case of conflict the appended config takes precedence.
+    /// Unlike the libraries of included files, which are merged, a library of the appended
+    /// config replaces the library of the same name. This allows a project to replace
+    /// the installed `std` and `ieee` libraries.
     pub fn append(&mut self, config: &Config, messages: &mut dyn MessageHandler) {
-        self.standard = config.standard;
+        config.report_ignored_included_keys(messages);
+        self.standard_origin.clone_from(&config.standard_origin);
+        if self.standard != config.standard {
+            self.standard = config.standard;
+            self.select_embedded_libraries();
+        }
         for library in config.iter_libraries() {
             if let Some(parent_library) = self.libraries.get_mut(&library.name) {
                 *parent_library = library.clone();
@@ -193,9 +776,98 @@ impl Config {
             }
         }
         self.severities = config.severities;
+        self.defines.extend(config.defines.clone());
+        if config.cache_directory.is_some() {
+            self.cache_directory.clone_from(&config.cache_directory);
+        }
+        self.lazy_analysis |= config.lazy_analysis;
+        for file_name in &config.imported_files {
+            if !self.imported_files.contains(file_name) {
+                self.imported_files.push(file_name.clone());
+            }
+        }
+    }
+
+    /// Append the config of another workspace folder to self
+    ///
+    /// Unlike `append`, a library that several folders define is merged such that no folder
+    /// loses its files. The standard of the folder only applies to the libraries of the folder
+    /// and the lint settings of the first folder apply to all folders.
+    pub fn append_workspace_folder(&mut self, folder: &Config, messages: &mut dyn MessageHandler) {
+        folder.report_ignored_included_keys(messages);
+        for library in folder.iter_libraries() {
+            let mut library = library.clone();
+            let standard = folder.library_standard(&library);
+            library.standard = (standard != self.standard).then_some(standard);
+            match self.libraries.get_mut(&library.name) {
+                Some(existing) if !Self::is_embedded(existing) => {
+                    if existing.standard.unwrap_or(self.standard) != standard {
+                        for pattern in library.patterns.iter() {
+                            library.file_standards.push((pattern.clone(), standard));
+                        }
+                        library.standard = None;
+                    }
+                    existing.merge(&library);
+                }
+                _ => {
+                    self.libraries.insert(library.name.clone(), library);
+                }
+            }
+        }
+        if self.severities != folder.severities {
+            messages.push(Message::warning(
+                "Ignoring the lint settings of a workspace folder that differ from the first folder",
+            ));
+        }
+        self.defines.extend(folder.defines.clone());
+        if self.cache_directory.is_none() {
+            self.cache_directory.clone_from(&folder.cache_directory);
+        }
+        self.lazy_analysis |= folder.lazy_analysis;
+        for file_name in &folder.imported_files {
+            if !self.imported_files.contains(file_name) {
+                self.imported_files.push(file_name.clone());
+            }
+        }
+    }
+
+    fn report_ignored_included_keys(&self, messages: &mut dyn MessageHandler) {
+        for (file_name, key) in &self.ignored_included_keys {
+            messages.push(Message::warning(format!(
+                "Ignoring '{key}' of included file {}, only libraries and defines are included",
+                file_name.to_string_lossy()
+            )));
+        }
+    }
+
+    #[cfg(feature = "embedded-libraries")]
+    fn is_embedded(library: &LibraryConfig) -> bool {
+        embedded::is_embedded(library)
     }
 
-    /// Load configuration file from installation folder
+    #[cfg(not(feature = "embedded-libraries"))]
+    fn is_embedded(_library: &LibraryConfig) -> bool {
+        false
+    }
+
+    /// Replace the embedded standard libraries with the ones of the current standard.
+    /// Libraries that were re-defined by a configuration file are kept.
+    fn select_embedded_libraries(&mut self) {
+        #[cfg(feature = "embedded-libraries")]
+        for library in embedded::standard_libraries(self.standard) {
+            if let Some(existing) = self.libraries.get_mut(&library.name) {
+                if embedded::is_embedded(existing) {
+                    *existing = library;
+                }
+            }
+        }
+    }
+
+    /// Load the standard libraries.
+    ///
+    /// The libraries are read from the `vhdl_ls.toml` file in `location` if given.
+    /// Otherwise, the libraries that are compiled into the binary are used if the
+    /// `embedded-libraries` feature is enabled, or a set of installation folders is searched.
     fn load_installed_config(
         &mut self,
         messages: &mut dyn MessageHandler,
@@ -207,34 +879,56 @@ impl Config {
             self.load_config(&path, "Installation", messages);
             return;
         }
-        let search_paths = [
-            "../vhdl_libraries",
-            "../../vhdl_libraries",
-            "/usr/lib/rust_hdl/vhdl_libraries",
-            "/usr/local/lib/rust_hdl/vhdl_libraries",
-            "../share/vhdl_libraries",
-        ];
-
-        for dir in search_paths.into_iter() {
-            let mut file_name = PathBuf::from(dir);
-            // Expand a relative path
-            if !file_name.is_absolute() {
-                let exe_path = env::current_exe().expect("Executable path needed");
-                let exe_folder = exe_path.parent().expect("Executable folder must exist");
-                file_name = exe_folder.join(file_name)
-            }
-            file_name.push("vhdl_ls.toml");
-            if file_name.exists() {
-                self.load_config(&file_name, "Installation", messages);
-                return;
+
+        #[cfg(feature = "embedded-libraries")]
+        {
+            let mut libraries = FnvHashMap::default();
+            for library in embedded::standard_libraries(self.standard) {
+                libraries.insert(library.name.clone(), library);
             }
+            let config = Config {
+                libraries,
+                ..Default::default()
+            };
+            messages.push(Message::log("Loaded embedded standard libraries"));
+            self.append(&config, messages);
         }
 
-        // Panic if we did not yet find the installed libraries
-        panic!(
-            "Couldn't find installed libraries at {}.",
-            search_paths.join(", ")
-        );
+        #[cfg(not(feature = "embedded-libraries"))]
+        {
+            let search_paths = [
+                "../vhdl_libraries",
+                "../../vhdl_libraries",
+                "../../vhdl_lang/vhdl_libraries",
+                "/usr/lib/rust_hdl/vhdl_libraries",
+                "/usr/local/lib/rust_hdl/vhdl_libraries",
+                "../share/vhdl_libraries",
+            ];
+
+            let exe_folder = std::env::current_exe()
+                .ok()
+                .and_then(|exe_path| Some(exe_path.parent()?.to_owned()));
+            for dir in search_paths.into_iter() {
+                let mut file_name = PathBuf::from(dir);
+                // Expand a relative path
+                if !file_name.is_absolute() {
+                    let Some(exe_folder) = &exe_folder else {
+                        continue;
+                    };
+                    file_name = exe_folder.join(file_name)
+                }
+                file_name.push("vhdl_ls.toml");
+                if file_name.exists() {
+                    self.load_config(&file_name, "Installation", messages);
+                    return;
+                }
+            }
+
+            messages.push(Message::error(format!(
+                "Couldn't find installed libraries at {}. The std and ieee libraries are missing.",
+                search_paths.join(", ")
+            )));
+        }
     }
 
     /// Load configuration file from home folder
@@ -281,8 +975,9 @@ impl Config {
     /// If the `standard_libraries_path` is given, it must point to a valid
     /// `vhdl_ls.toml` file, which will be used as source for the standard libraries
     /// i.e., `std` or `ieee`.
-    /// If this path is `None`, a set of standard search paths will be queried for the location
-    /// of this file.
+    /// If this path is `None`, the embedded standard libraries are used. Without the
+    /// `embedded-libraries` feature, a set of standard search paths will be queried for the
+    /// location of this file instead.
     pub fn load_external_config(
         &mut self,
         messages: &mut dyn MessageHandler,
@@ -302,6 +997,46 @@ impl Config {
     pub fn standard(&self) -> VHDLStandard {
         self.standard
     }
+
+    /// User defined identifiers for conditional analysis
+    pub fn defines(&self) -> &Defines {
+        &self.defines
+    }
+
+    /// The directory where the parsed files of third-party libraries are cached, if any
+    pub fn cache_directory(&self) -> Option<&Path> {
+        self.cache_directory.as_deref()
+    }
+
+    /// Units of third-party libraries are only analyzed when they are used by units of
+    /// other libraries or when their file is open
+    pub fn lazy_analysis(&self) -> bool {
+        self.lazy_analysis
+    }
+
+    /// The VHDL standard of the files of a library
+    pub fn library_standard(&self, library: &LibraryConfig) -> VHDLStandard {
+        library.standard.unwrap_or(self.standard)
+    }
+
+    /// The VHDL standard of a file of a library
+    pub fn file_standard(&self, library: &LibraryConfig, file_name: &Path) -> VHDLStandard {
+        library
+            .file_standard(file_name)
+            .unwrap_or_else(|| self.library_standard(library))
+    }
+}
+
+/// The `work` library is an alias for the library of the current design unit
+/// and can therefore not be defined.
+fn check_library_name(name: &str) -> Result<(), String> {
+    if name.to_lowercase() == "work" {
+        return Err(format!(
+            "The '{}' library is not a valid library.\nHint: To use a library that contains all files, use a common name for all libraries, i.e., 'defaultlib'",
+            name
+        ));
+    }
+    Ok(())
 }
 
 fn match_file_patterns(
@@ -310,11 +1045,7 @@ fn match_file_patterns(
 ) -> BTreeSet<PathBuf> {
     let mut result = BTreeSet::new();
     for pattern in patterns.iter() {
-        let stripped_pattern = if cfg!(windows) {
-            pattern.strip_prefix("\\\\?\\").unwrap_or(pattern.as_str())
-        } else {
-            pattern.as_str()
-        };
+        let stripped_pattern = strip_verbatim_prefix(pattern);
 
         if is_literal(stripped_pattern) {
             let file_path = PathBuf::from(pattern);
@@ -426,6 +1157,32 @@ where
     }
 }
 
+fn strip_verbatim_prefix(pattern: &str) -> &str {
+    if cfg!(windows) {
+        pattern.strip_prefix("\\\\?\\").unwrap_or(pattern)
+    } else {
+        pattern
+    }
+}
+
+/// Returns true if the file name matches any of the patterns.
+/// Unlike `match_file_patterns` the file does not have to exist.
+fn matches_file_patterns(patterns: &[String], file_name: &Path) -> bool {
+    let options = glob::MatchOptions {
+        require_literal_separator: true,
+        ..glob::MatchOptions::new()
+    };
+    patterns.iter().any(|pattern| {
+        let stripped_pattern = strip_verbatim_prefix(pattern);
+        if is_literal(stripped_pattern) {
+            Path::new(pattern) == file_name || Path::new(stripped_pattern) == file_name
+        } else {
+            glob::Pattern::new(stripped_pattern)
+                .is_ok_and(|pattern| pattern.matches_path_with(file_name, options))
+        }
+    })
+}
+
 /// Returns true if the pattern is a plain file name and not a glob pattern
 fn is_literal(pattern: &str) -> bool {
     !pattern.chars().any(|chr| matches!(&chr, '?' | '*' | '['))
@@ -742,18 +1499,616 @@ lib.exclude = [
     }
 
     #[test]
-    fn the_work_library_is_an_illegal_library() {
-        let parent = Path::new("parent_folder");
+    fn files_that_do_not_exist_yet_match_the_library_patterns() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
         let config = Config::from_str(
             "
 [libraries]
-work.files = [
-  'a.vhd', 'b.vhd'
+lib.files = [
+  'src/*.vhd',
+  'pkg.vhd',
+]
+lib.exclude = [
+  'src/tb_*.vhd'
 ]
 ",
             parent,
-        );
-        assert_eq!(config.expect_err("Expected erroneous config"), "The 'work' library is not a valid library.\nHint: To use a library that contains all files, use a common name for all libraries, i.e., 'defaultlib'")
+        )
+        .unwrap();
+
+        let library = config.get_library("lib").unwrap();
+        assert!(library.matches_file(&parent.join("src").join("new.vhd")));
+        assert!(library.matches_file(&parent.join("pkg.vhd")));
+        assert!(!library.matches_file(&parent.join("src").join("tb_new.vhd")));
+        assert!(!library.matches_file(&parent.join("src").join("sub").join("new.vhd")));
+        assert!(!library.matches_file(&parent.join("other.vhd")));
+    }
+
+    #[test]
+    fn test_library_with_only_stubs() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        let config = Config::from_str(
+            "
+[libraries]
+vendor.stubs = [
+  'stubs/*.vhd'
+]
+",
+            parent,
+        )
+        .unwrap();
+
+        std::fs::create_dir(parent.join("stubs")).unwrap();
+        let stub = touch(parent, "stubs/ip.vhd");
+
+        let mut messages = vec![];
+        let library = config.get_library("vendor").unwrap();
+        assert_files_eq(&library.file_names(&mut messages), &[]);
+        assert_files_eq(&library.stub_file_names(&mut messages), &[stub]);
+        assert_eq!(messages, vec![]);
+    }
+
+    #[test]
+    fn the_work_library_is_an_illegal_library() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+[libraries]
+work.files = [
+  'a.vhd', 'b.vhd'
+]
+",
+            parent,
+        );
+        assert_eq!(config.expect_err("Expected erroneous config"), "The 'work' library is not a valid library.\nHint: To use a library that contains all files, use a common name for all libraries, i.e., 'defaultlib'")
+    }
+
+    #[test]
+    fn library_standard() {
+        let parent = Path::new("parent_folder");
+        let config = Config::from_str(
+            "
+standard = '2019'
+[libraries]
+legacy.files = ['legacy/*.vhd']
+legacy.standard = '93'
+rtl.files = ['rtl/*.vhd']
+",
+            parent,
+        )
+        .unwrap();
+        let legacy = config.get_library("legacy").unwrap();
+        let rtl = config.get_library("rtl").unwrap();
+        assert_eq!(legacy.standard(), Some(VHDLStandard::VHDL1993));
+        assert_eq!(config.library_standard(legacy), VHDLStandard::VHDL1993);
+        assert_eq!(rtl.standard(), None);
+        assert_eq!(config.library_standard(rtl), VHDLStandard::VHDL2019);
+
+        let config = Config::from_str(
+            "
+[libraries]
+legacy.files = ['legacy/*.vhd']
+legacy.standard = '1987'
+",
+            parent,
+        );
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "Unsupported standard '1987' for library legacy"
+        );
+    }
+
+    #[test]
+    fn cache_directory() {
+        let parent = Path::new("parent_folder");
+        let mut config = Config::from_str(
+            "
+cache = '.vhdl_ls_cache'
+[libraries]
+",
+            parent,
+        )
+        .unwrap();
+        assert_eq!(
+            config.cache_directory(),
+            Some(parent.join(".vhdl_ls_cache").as_path())
+        );
+
+        // A config without a cache directory keeps the existing one
+        config.append(
+            &Config::from_str("[libraries]", parent).unwrap(),
+            &mut NullMessages,
+        );
+        assert_eq!(
+            config.cache_directory(),
+            Some(parent.join(".vhdl_ls_cache").as_path())
+        );
+
+        assert_eq!(
+            Config::from_str(
+                "cache = 1
+[libraries]",
+                parent
+            ),
+            Err("cache must be a string".to_owned())
+        );
+    }
+
+    #[test]
+    fn workspace_folders_defining_the_same_library_are_merged() {
+        let folder_a = Path::new("folder_a");
+        let folder_b = Path::new("folder_b");
+        let mut config = Config::default();
+        config.append(
+            &Config::from_str("[libraries]\nlib.files = ['*.vhd']", folder_a).unwrap(),
+            &mut NullMessages,
+        );
+        let mut messages = vec![];
+        config.append_workspace_folder(
+            &Config::from_str(
+                "
+standard = '1993'
+[libraries]
+lib.files = ['*.vhd']
+other.files = ['other/*.vhd']
+
+[lint]
+unused = 'error'
+",
+                folder_b,
+            )
+            .unwrap(),
+            &mut messages,
+        );
+        assert_eq!(
+            messages,
+            vec![Message::warning(
+                "Ignoring the lint settings of a workspace folder that differ from the first folder"
+            )]
+        );
+        assert_eq!(config.standard(), VHDLStandard::VHDL2008);
+        assert_eq!(config.severities(), &SeverityMap::default());
+
+        // Neither folder loses the files of the library
+        let library = config.get_library("lib").unwrap();
+        assert!(library.matches_file(&folder_a.join("a.vhd")));
+        assert!(library.matches_file(&folder_b.join("b.vhd")));
+        // The standard of a folder only applies to its own files
+        assert_eq!(
+            config.file_standard(library, &folder_a.join("a.vhd")),
+            VHDLStandard::VHDL2008
+        );
+        assert_eq!(
+            config.file_standard(library, &folder_b.join("b.vhd")),
+            VHDLStandard::VHDL1993
+        );
+        let other = config.get_library("other").unwrap();
+        assert_eq!(config.library_standard(other), VHDLStandard::VHDL1993);
+    }
+
+    #[test]
+    fn lazy_analysis() {
+        let parent = Path::new("parent_folder");
+        let mut config = Config::from_str("[libraries]", parent).unwrap();
+        assert!(!config.lazy_analysis());
+        config.append(
+            &Config::from_str("lazy_analysis = true\n[libraries]", parent).unwrap(),
+            &mut NullMessages,
+        );
+        assert!(config.lazy_analysis());
+        assert_eq!(
+            Config::from_str("lazy_analysis = 'yes'\n[libraries]", parent),
+            Err("lazy_analysis must be a boolean".to_owned())
+        );
+    }
+
+    #[test]
+    fn defines() {
+        let parent = Path::new("parent_folder");
+        let mut config = Config::from_str(
+            "
+[libraries]
+lib.files = ['*.vhd']
+
+[defines]
+TOOL_TYPE = 'SYNTHESIS'
+TARGET = 'fpga'
+",
+            parent,
+        )
+        .unwrap();
+        config.append(
+            &Config::from_str(
+                "
+[libraries]
+
+[defines]
+TARGET = 'asic'
+",
+                parent,
+            )
+            .unwrap(),
+            &mut NullMessages,
+        );
+        assert_eq!(
+            config.defines(),
+            &Defines::from_iter([
+                ("TOOL_TYPE".to_owned(), "SYNTHESIS".to_owned()),
+                ("TARGET".to_owned(), "asic".to_owned()),
+            ])
+        );
+
+        let config = Config::from_str(
+            "
+[libraries]
+[defines]
+VERSION = 3
+",
+            parent,
+        );
+        assert_eq!(
+            config.expect_err("Expected erroneous config"),
+            "Value of define VERSION must be a string"
+        );
+    }
+
+    #[test]
+    fn import_library_mapping() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        std::fs::write(
+            parent.join("lib1-obj08.cf"),
+            "v 4\nfile . \"pkg1.vhd\" \"20240101120000.000\" \"3a9b\" \"20240101120000.123\":\n",
+        )
+        .unwrap();
+        std::fs::write(
+            parent.join("lib2-obj08.cf"),
+            "v 4\nfile . \"pkg2.vhd\" \"20240101120000.000\" \"3a9b\" \"20240101120000.123\":\n",
+        )
+        .unwrap();
+
+        let config = Config::from_str(
+            "
+import = ['lib1-obj08.cf', 'lib2-obj08.cf']
+
+[libraries]
+lib2.files = [
+  'ent.vhd'
+]
+",
+            parent,
+        )
+        .unwrap();
+
+        let pkg1_path = touch(parent, "pkg1.vhd");
+        let ent_path = touch(parent, "ent.vhd");
+        let mut messages = vec![];
+        let lib1 = config.get_library("lib1").unwrap();
+        let lib2 = config.get_library("lib2").unwrap();
+        assert_files_eq(&lib1.file_names(&mut messages), &[pkg1_path]);
+        assert_files_eq(&lib2.file_names(&mut messages), &[ent_path]);
+        assert_eq!(messages, vec![]);
+    }
+
+    #[test]
+    fn import_without_libraries() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        std::fs::write(parent.join("lib-obj08.cf"), "v 4\n").unwrap();
+
+        let config = Config::from_str("import = ['lib-obj08.cf']", parent).unwrap();
+        assert!(config.get_library("lib").is_some());
+        assert_eq!(
+            Config::from_str("", parent).expect_err("Expected erroneous config"),
+            "missing field libraries"
+        );
+    }
+
+    #[test]
+    fn vunit_export() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        std::fs::write(
+            parent.join("vunit.json"),
+            r#"{"files": [
+                {"file_name": "pkg.vhd", "library_name": "lib1"},
+                {"file_name": "ent.vhd", "library_name": "lib2"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let config = Config::from_str(
+            "
+vunit_export = 'vunit.json'
+
+[libraries]
+lib2.files = [
+  'tb_ent.vhd'
+]
+",
+            parent,
+        )
+        .unwrap();
+
+        let pkg_path = touch(parent, "pkg.vhd");
+        let tb_ent_path = touch(parent, "tb_ent.vhd");
+        let mut messages = vec![];
+        let lib1 = config.get_library("lib1").unwrap();
+        let lib2 = config.get_library("lib2").unwrap();
+        assert_files_eq(&lib1.file_names(&mut messages), &[pkg_path]);
+        assert_files_eq(&lib2.file_names(&mut messages), &[tb_ent_path]);
+        assert_eq!(messages, vec![]);
+        assert_eq!(config.imported_files(), &[parent.join("vunit.json")]);
+    }
+
+    #[test]
+    fn include_merges_libraries() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        let ip_a = parent.join("ip").join("a");
+        let ip_b = parent.join("ip").join("b");
+        std::fs::create_dir_all(&ip_a).unwrap();
+        std::fs::create_dir_all(&ip_b).unwrap();
+        let config_a = ip_a.join("vhdl_ls.toml");
+        let config_b = ip_b.join("vhdl_ls.toml");
+        std::fs::write(
+            &config_a,
+            "
+[libraries]
+ip_lib.files = ['*.vhd']
+a_lib.files = ['a_pkg.vhd']
+",
+        )
+        .unwrap();
+        std::fs::write(
+            &config_b,
+            "
+[libraries]
+ip_lib.files = ['*.vhd']
+",
+        )
+        .unwrap();
+        let root_config = parent.join("vhdl_ls.toml");
+        std::fs::write(
+            &root_config,
+            "
+include = ['ip/*/vhdl_ls.toml']
+
+[libraries]
+ip_lib.exclude = ['ip/b/tb.vhd']
+",
+        )
+        .unwrap();
+
+        let a = touch(&ip_a, "a.vhd");
+        let b = touch(&ip_b, "b.vhd");
+        touch(&ip_b, "tb.vhd");
+        let a_pkg = touch(&ip_a, "a_pkg.vhd");
+
+        let config = Config::read_file_path(&root_config).unwrap();
+        let mut messages = vec![];
+        assert_files_eq(
+            &config
+                .get_library("ip_lib")
+                .unwrap()
+                .file_names(&mut messages),
+            &[a, a_pkg.clone(), b],
+        );
+        assert_files_eq(
+            &config
+                .get_library("a_lib")
+                .unwrap()
+                .file_names(&mut messages),
+            &[a_pkg],
+        );
+        assert_eq!(messages, vec![]);
+        assert_eq!(config.imported_files(), &[config_a.clone(), config_b]);
+
+        let resolved = config.to_resolved_string();
+        assert!(resolved.contains(&format!(
+            "\"{}\", # {}",
+            ip_a.join("*.vhd").to_string_lossy(),
+            config_a.to_string_lossy()
+        )));
+        assert!(resolved.contains(&format!(
+            "\"{}\", # {}",
+            ip_b.join("tb.vhd").to_string_lossy(),
+            root_config.to_string_lossy()
+        )));
+    }
+
+    #[test]
+    fn ignored_keys_of_included_files_are_reported() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        let root_config = parent.join("vhdl_ls.toml");
+        let included = parent.join("ip.toml");
+        std::fs::write(&root_config, "include = ['ip.toml']").unwrap();
+        std::fs::write(
+            &included,
+            "
+standard = '1993'
+
+[libraries]
+ip_lib.files = ['*.vhd']
+
+[lint]
+unused = 'error'
+",
+        )
+        .unwrap();
+
+        let config = Config::read_file_path(&root_config).unwrap();
+        assert_eq!(config.standard, VHDLStandard::default());
+
+        let mut messages = vec![];
+        let mut appended = Config::default();
+        appended.append(&config, &mut messages);
+        assert_eq!(
+            messages,
+            ["standard", "lint"]
+                .iter()
+                .map(|key| Message::warning(format!(
+                    "Ignoring '{key}' of included file {}, only libraries and defines are included",
+                    included.to_string_lossy()
+                )))
+                .collect::<Vec<_>>()
+        );
+        assert!(appended.get_library("ip_lib").is_some());
+    }
+
+    #[test]
+    fn circular_include() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        let config_a = parent.join("a.toml");
+        std::fs::write(&config_a, "include = ['b.toml']").unwrap();
+        std::fs::write(parent.join("b.toml"), "include = ['a.toml']").unwrap();
+
+        let err = Config::read_file_path(&config_a).unwrap_err();
+        assert!(
+            err.to_string().contains("Circular include"),
+            "{}",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn circular_include_through_parent_directories() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        std::fs::create_dir(parent.join("a")).unwrap();
+        std::fs::create_dir(parent.join("b")).unwrap();
+        let config_a = parent.join("a").join("vhdl_ls.toml");
+        std::fs::write(&config_a, "include = ['../b/vhdl_ls.toml']").unwrap();
+        std::fs::write(
+            parent.join("b").join("vhdl_ls.toml"),
+            "include = ['../a/vhdl_ls.toml']",
+        )
+        .unwrap();
+
+        let err = Config::read_file_path(&config_a).unwrap_err();
+        assert!(
+            err.to_string().contains("Circular include"),
+            "{}",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "embedded-libraries")]
+    fn embedded_libraries_follow_standard() {
+        let file_names = |config: &Config, name: &str| -> Vec<String> {
+            config
+                .get_library(name)
+                .unwrap()
+                .sources()
+                .iter()
+                .map(|source| source.file_name().to_string_lossy().into_owned())
+                .collect()
+        };
+
+        let mut config = Config::default();
+        config.load_installed_config(&mut NullMessages, None);
+        let embedded = |file_name: &str| {
+            embedded::embedded_root()
+                .join(file_name)
+                .to_string_lossy()
+                .into_owned()
+        };
+        assert!(file_names(&config, "std").contains(&embedded("std/env.vhd")));
+
+        let project_config = Config::from_str(
+            "
+standard = \"1993\"
+[libraries]
+ieee.files = ['ieee/*.vhd']
+",
+            Path::new("root"),
+        )
+        .unwrap();
+        config.append(&project_config, &mut NullMessages);
+        assert_eq!(
+            file_names(&config, "std"),
+            vec![
+                embedded("std1993/standard.vhd"),
+                embedded("std1993/textio.vhd")
+            ]
+        );
+        // Libraries of the project take precedence
+        assert!(config.get_library("ieee").unwrap().sources().is_empty());
+
+        let project_config =
+            Config::from_str("standard = \"2019\"\n[libraries]", Path::new("root")).unwrap();
+        config.append(&project_config, &mut NullMessages);
+        assert!(file_names(&config, "std").contains(&embedded("std2019/reflection.vhd")));
+    }
+
+    #[test]
+    fn infer_and_write_config() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        std::fs::create_dir(parent.join("mylib")).unwrap();
+        std::fs::write(parent.join("mylib/pkg.vhd"), "package pkg is end package;").unwrap();
+        std::fs::write(
+            parent.join("ent.vhd"),
+            "library mylib;\nuse mylib.pkg.all;\nentity ent is end entity;",
+        )
+        .unwrap();
+
+        let config = Config::default().infer_from_directory(parent);
+        assert_eq!(
+            config.to_toml_string(parent),
+            "\
+standard = \"2008\"
+
+[libraries.defaultlib]
+files = [\"ent.vhd\"]
+
+[libraries.mylib]
+files = [\"mylib/pkg.vhd\"]
+"
+        );
+
+        let written = Config::from_str(&config.to_toml_string(parent), parent).unwrap();
+        assert_eq!(written.libraries, config.libraries);
+    }
+
+    #[test]
+    fn fusesoc_cores() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let parent = tempdir.path();
+        std::fs::create_dir(parent.join("ip")).unwrap();
+        std::fs::write(
+            parent.join("ip/fifo.core"),
+            "CAPI=2:
+name: acme:ip:fifo:1.0
+filesets:
+  rtl:
+    files: [fifo.vhd]
+    file_type: vhdlSource-2008
+    logical_name: fifo_lib
+",
+        )
+        .unwrap();
+
+        let config = Config::from_str(
+            "
+[fusesoc]
+cores = ['ip']
+",
+            parent,
+        )
+        .unwrap();
+
+        let fifo_path = touch(&parent.join("ip"), "fifo.vhd");
+        let mut messages = vec![];
+        let library = config.get_library("fifo_lib").unwrap();
+        assert!(!library.is_third_party);
+        assert_files_eq(&library.file_names(&mut messages), &[fifo_path]);
+        assert_eq!(messages, vec![]);
     }
 
     #[test]
@@ -0,0 +1,234 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! The standard libraries `std` and `ieee` compiled into the binary
+
+use std::fs;
+use std::path::PathBuf;
+
+use super::LibraryConfig;
+use crate::data::{Latin1String, Source};
+use crate::standard::VHDLStandard;
+
+/// Directory that the embedded sources are written to, such that editors can open them
+/// when navigating to a declaration of the standard libraries.
+/// The directory is versioned since the sources may change between releases.
+pub(super) fn embedded_root() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("vhdl_ls")
+        .join(format!("vhdl_libraries-{}", env!("CARGO_PKG_VERSION")))
+}
+
+/// Write an embedded source to the embedded root unless it has been written before.
+/// Analysis uses the in-memory contents, so a failure only affects navigation.
+fn write_to_disk(file_name: &PathBuf, contents: &[u8]) {
+    if file_name.exists() {
+        return;
+    }
+    if let Some(parent) = file_name.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(file_name, contents);
+}
+
+macro_rules! embed {
+    ($($file_name:literal),* $(,)?) => {
+        &[$(
+            (
+                $file_name,
+                include_bytes!(concat!("../../vhdl_libraries/", $file_name)) as &[u8],
+            ),
+        )*]
+    };
+}
+
+static STD_1993: &[(&str, &[u8])] = embed!["std1993/standard.vhd", "std1993/textio.vhd"];
+
+static STD_2008: &[(&str, &[u8])] = embed!["std/standard.vhd", "std/textio.vhd", "std/env.vhd",];
+
+static STD_2019: &[(&str, &[u8])] = embed![
+    "std/standard.vhd",
+    "std2019/textio.vhd",
+    "std2019/env.vhd",
+    "std2019/reflection.vhd",
+];
+
+static IEEE_1993: &[(&str, &[u8])] = embed![
+    "ieee1993/math_complex.vhdl",
+    "ieee1993/math_real.vhdl",
+    "ieee1993/numeric_bit.vhdl",
+    "ieee1993/numeric_std.vhdl",
+    "ieee1993/std_logic_1164.vhdl",
+    "synopsys/std_logic_arith.vhdl",
+    "synopsys/std_logic_misc.vhdl",
+    "synopsys/std_logic_signed.vhdl",
+    "synopsys/std_logic_unsigned.vhdl",
+    "vital2000/memory_b.vhdl",
+    "vital2000/memory_p.vhdl",
+    "vital2000/prmtvs_b.vhdl",
+    "vital2000/prmtvs_p.vhdl",
+    "vital2000/timing_b.vhdl",
+    "vital2000/timing_p.vhdl",
+];
+
+static IEEE_2008: &[(&str, &[u8])] = embed![
+    "ieee2008/fixed_float_types.vhdl",
+    "ieee2008/fixed_generic_pkg-body.vhdl",
+    "ieee2008/fixed_generic_pkg.vhdl",
+    "ieee2008/fixed_pkg.vhdl",
+    "ieee2008/float_generic_pkg-body.vhdl",
+    "ieee2008/float_generic_pkg.vhdl",
+    "ieee2008/float_pkg.vhdl",
+    "ieee2008/ieee_bit_context.vhdl",
+    "ieee2008/ieee_std_context.vhdl",
+    "ieee2008/math_complex-body.vhdl",
+    "ieee2008/math_complex.vhdl",
+    "ieee2008/math_real-body.vhdl",
+    "ieee2008/math_real.vhdl",
+    "ieee2008/numeric_bit-body.vhdl",
+    "ieee2008/numeric_bit.vhdl",
+    "ieee2008/numeric_bit_unsigned-body.vhdl",
+    "ieee2008/numeric_bit_unsigned.vhdl",
+    "ieee2008/numeric_std-body.vhdl",
+    "ieee2008/numeric_std.vhdl",
+    "ieee2008/numeric_std_unsigned-body.vhdl",
+    "ieee2008/numeric_std_unsigned.vhdl",
+    "ieee2008/std_logic_1164-body.vhdl",
+    "ieee2008/std_logic_1164.vhdl",
+    "ieee2008/std_logic_textio.vhdl",
+    "synopsys/std_logic_arith.vhdl",
+    "synopsys/std_logic_misc.vhdl",
+    "synopsys/std_logic_signed.vhdl",
+    "synopsys/std_logic_unsigned.vhdl",
+    "vital2000/memory_b.vhdl",
+    "vital2000/memory_p.vhdl",
+    "vital2000/prmtvs_b.vhdl",
+    "vital2000/prmtvs_p.vhdl",
+    "vital2000/timing_b.vhdl",
+    "vital2000/timing_p.vhdl",
+];
+
+fn library(name: &str, files: &[(&str, &[u8])]) -> LibraryConfig {
+    let root = embedded_root();
+    LibraryConfig {
+        name: name.to_owned(),
+        sources: files
+            .iter()
+            .map(|(file_name, contents)| {
+                let file_name = root.join(file_name);
+                write_to_disk(&file_name, contents);
+                Source::inline(&file_name, &Latin1String::new(contents).to_string())
+            })
+            .collect(),
+        is_third_party: true,
+        ..Default::default()
+    }
+}
+
+/// The `std` and `ieee` libraries of the given standard as in-memory sources.
+/// For VHDL 2008, this is equivalent to the `vhdl_libraries/vhdl_ls.toml` configuration.
+///
+/// VHDL 2019 did not change the `ieee` library, so the VHDL 2008 version is used.
+pub(super) fn standard_libraries(standard: VHDLStandard) -> Vec<LibraryConfig> {
+    let (std, ieee) = match standard {
+        VHDLStandard::VHDL1993 => (STD_1993, IEEE_1993),
+        VHDLStandard::VHDL2008 => (STD_2008, IEEE_2008),
+        VHDLStandard::VHDL2019 => (STD_2019, IEEE_2008),
+    };
+    vec![library("std", std), library("ieee", ieee)]
+}
+
+/// Returns true if all sources of the library are embedded sources
+pub(super) fn is_embedded(library: &LibraryConfig) -> bool {
+    let root = embedded_root();
+    !library.sources.is_empty()
+        && library
+            .sources
+            .iter()
+            .all(|source| source.file_name().starts_with(&root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Contents;
+    use crate::{Config, NullMessages, Project};
+
+    fn analyse(standard: VHDLStandard) -> Project {
+        let mut config = Config {
+            standard,
+            ..Default::default()
+        };
+        for library in standard_libraries(standard) {
+            config.libraries.insert(library.name.clone(), library);
+        }
+        let mut project = Project::from_config(config, &mut NullMessages);
+        let diagnostics = project.analyse();
+        assert!(
+            diagnostics.is_empty(),
+            "Unexpected diagnostics for {standard:?}: {diagnostics:?}"
+        );
+        project
+    }
+
+    #[test]
+    fn embedded_libraries_analyse_without_errors() {
+        let project = analyse(VHDLStandard::VHDL2008);
+        assert!(project
+            .get_source(&embedded_root().join("ieee2008/numeric_std.vhdl"))
+            .is_some());
+    }
+
+    #[test]
+    fn embedded_sources_exist_on_disk() {
+        for library in standard_libraries(VHDLStandard::VHDL2008) {
+            for source in library.sources() {
+                let on_disk = Contents::from_latin1_file(source.file_name()).unwrap();
+                assert_eq!(on_disk.num_lines(), source.contents().num_lines());
+            }
+        }
+    }
+
+    #[test]
+    fn embedded_libraries_of_each_standard_analyse_without_errors() {
+        let project = analyse(VHDLStandard::VHDL1993);
+        assert!(project
+            .get_source(&embedded_root().join("ieee1993/numeric_std.vhdl"))
+            .is_some());
+        assert!(project
+            .get_source(&embedded_root().join("std/env.vhd"))
+            .is_none());
+
+        let project = analyse(VHDLStandard::VHDL2019);
+        assert!(project
+            .get_source(&embedded_root().join("std2019/reflection.vhd"))
+            .is_some());
+    }
+
+    #[test]
+    fn vhdl2008_declarations_are_not_visible_in_vhdl1993() {
+        let mut project = analyse(VHDLStandard::VHDL1993);
+        project.update_source(&Source::inline(
+            &PathBuf::from("pkg.vhd"),
+            "
+package pkg is
+  constant c0 : boolean_vector(0 to 1) := (others => false);
+  constant c1 : string := to_string(1);
+  constant c2 : bit_vector(0 to 1) := \"01\";
+end package;
+",
+        ));
+        let diagnostics = project.analyse();
+        let messages: Vec<String> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.clone())
+            .collect();
+        assert_eq!(diagnostics.len(), 2, "{messages:?}");
+        assert!(messages[0].contains("boolean_vector"), "{messages:?}");
+        assert!(messages[1].contains("to_string"), "{messages:?}");
+    }
+}
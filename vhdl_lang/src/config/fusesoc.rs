@@ -0,0 +1,471 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Import of libraries from FuseSoC `.core` files (CAPI2 format)
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use fnv::FnvHashMap;
+use yaml_rust2::{Yaml, YamlLoader};
+
+use super::{check_library_name, LibraryConfig};
+use crate::standard::VHDLStandard;
+
+/// A FuseSoC core with its VHDL files grouped by logical library name.
+/// Each file has the standard of its file type, if the file type names one.
+#[derive(Debug)]
+struct Core {
+    vlnv: Vlnv,
+    files: FnvHashMap<String, BTreeMap<PathBuf, Option<VHDLStandard>>>,
+    depend: Vec<Vlnv>,
+}
+
+/// A vendor:library:name:version identifier.
+/// Empty fields of a dependency match any core.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Vlnv {
+    vendor: String,
+    library: String,
+    name: String,
+}
+
+impl Vlnv {
+    fn parse(vlnv: &str) -> Vlnv {
+        // Dependencies may be prefixed by version operators such as '>=' or '^'
+        let vlnv = vlnv.trim_start_matches(['>', '<', '=', '^', '~']);
+        let mut parts = vlnv.split(':');
+        let (Some(vendor), Some(library), Some(name)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return Vlnv {
+                name: vlnv.to_owned(),
+                ..Default::default()
+            };
+        };
+        Vlnv {
+            vendor: vendor.to_owned(),
+            library: library.to_owned(),
+            name: name.to_owned(),
+        }
+    }
+
+    fn matches(&self, core: &Vlnv) -> bool {
+        let matches_field = |dep: &str, core: &str| dep.is_empty() || dep == core;
+        self.name == core.name
+            && matches_field(&self.vendor, &core.vendor)
+            && matches_field(&self.library, &core.library)
+    }
+
+    /// The library that files without a `logical_name` are placed in.
+    /// FuseSoC uses `work`, which is not a valid library name here,
+    /// so the name of the core is used instead.
+    fn default_library_name(&self) -> String {
+        self.name
+            .chars()
+            .map(|chr| {
+                if chr.is_ascii_alphanumeric() {
+                    chr
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+}
+
+/// Returns true for the FuseSoC file types of VHDL files,
+/// i.e., `vhdlSource`, `vhdlSource-93` or `vhdlSource-2008`.
+fn is_vhdl_file_type(file_type: &str) -> bool {
+    file_type == "vhdlSource" || file_type.starts_with("vhdlSource-")
+}
+
+/// The standard of a VHDL file type such as `vhdlSource-93`.
+/// Plain `vhdlSource` files use the standard of the project.
+fn standard_of_file_type(file_type: &str) -> Option<VHDLStandard> {
+    VHDLStandard::try_from(file_type.strip_prefix("vhdlSource-")?).ok()
+}
+
+/// Set the standard of a library if all of its files have the same file type standard.
+/// Otherwise, the files whose file type names a standard keep it individually.
+fn set_standards(library: &mut LibraryConfig, files: &BTreeMap<String, Option<VHDLStandard>>) {
+    let first = files.values().next().copied().flatten();
+    if first.is_some() && files.values().all(|standard| *standard == first) {
+        library.standard = first;
+        return;
+    }
+    library.file_standards = files
+        .iter()
+        .filter_map(|(pattern, standard)| Some((pattern.clone(), (*standard)?)))
+        .collect();
+}
+
+/// Import the VHDL files of FuseSoC cores.
+///
+/// All `.core` files found below `core_dirs` are imported.
+/// Cores found below `library_dirs` (FuseSoC core libraries) are only imported
+/// when they are a direct or indirect dependency of an imported core.
+/// Their libraries are marked as third-party.
+pub(super) fn import_cores(
+    core_dirs: &[String],
+    library_dirs: &[String],
+) -> Result<Vec<LibraryConfig>, String> {
+    let library_files = find_core_files(library_dirs)?;
+    // Core libraries are commonly located within the workspace
+    let workspace_files = find_core_files(core_dirs)?
+        .difference(&library_files)
+        .cloned()
+        .collect();
+    let workspace_cores = read_cores(workspace_files)?;
+    let library_cores = read_cores(library_files)?;
+
+    // Resolve the dependencies of the workspace cores within the core libraries
+    let mut used_library_cores = vec![false; library_cores.len()];
+    let mut pending: Vec<&Vlnv> = workspace_cores
+        .iter()
+        .flat_map(|core| core.depend.iter())
+        .collect();
+    while let Some(dependency) = pending.pop() {
+        for (idx, core) in library_cores.iter().enumerate() {
+            if !used_library_cores[idx] && dependency.matches(&core.vlnv) {
+                used_library_cores[idx] = true;
+                pending.extend(core.depend.iter());
+            }
+        }
+    }
+
+    let mut libraries: FnvHashMap<String, LibraryConfig> = FnvHashMap::default();
+    let mut standards: FnvHashMap<String, BTreeMap<String, Option<VHDLStandard>>> =
+        FnvHashMap::default();
+    let used_cores = workspace_cores.iter().map(|core| (core, false)).chain(
+        library_cores
+            .iter()
+            .zip(used_library_cores)
+            .filter(|(_, used)| *used)
+            .map(|(core, _)| (core, true)),
+    );
+    for (core, is_third_party) in used_cores {
+        for (name, files) in core.files.iter() {
+            let library = libraries
+                .entry(name.clone())
+                .or_insert_with(|| LibraryConfig {
+                    name: name.clone(),
+                    is_third_party,
+                    ..Default::default()
+                });
+            // A library is only third-party if all of its files are
+            library.is_third_party &= is_third_party;
+            for (file, standard) in files {
+                let pattern = glob::Pattern::escape(&file.to_string_lossy());
+                library.patterns.push(pattern.clone());
+                standards
+                    .entry(name.clone())
+                    .or_default()
+                    .insert(pattern, *standard);
+            }
+        }
+    }
+    for (name, files) in standards {
+        if let Some(library) = libraries.get_mut(&name) {
+            set_standards(library, &files);
+        }
+    }
+
+    let mut libraries: Vec<_> = libraries.into_values().collect();
+    libraries.sort_by(|lib_a, lib_b| lib_a.name.cmp(&lib_b.name));
+    Ok(libraries)
+}
+
+/// Finds all `.core` files below the given directories
+fn find_core_files(dirs: &[String]) -> Result<BTreeSet<PathBuf>, String> {
+    let mut core_files = BTreeSet::new();
+    for dir in dirs {
+        let pattern = Path::new(&glob::Pattern::escape(dir)).join("**/*.core");
+        let paths = glob::glob(&pattern.to_string_lossy())
+            .map_err(|err| format!("Invalid FuseSoC directory '{dir}' {err}"))?;
+        core_files.extend(paths.flatten());
+    }
+    Ok(core_files)
+}
+
+fn read_cores(core_files: BTreeSet<PathBuf>) -> Result<Vec<Core>, String> {
+    let mut cores = Vec::new();
+    for core_file in core_files {
+        if let Some(core) = read_core(&core_file)? {
+            cores.push(core);
+        }
+    }
+    Ok(cores)
+}
+
+/// Reads a single `.core` file.
+/// Returns `None` for files that are not in the CAPI2 format.
+fn read_core(file_name: &Path) -> Result<Option<Core>, String> {
+    let contents = fs::read_to_string(file_name)
+        .map_err(|err| format!("Cannot read {}: {err}", file_name.to_string_lossy()))?;
+    // CAPI2 core files must start with this line, older formats are not YAML
+    if !contents.trim_start().starts_with("CAPI=2:") {
+        return Ok(None);
+    }
+    let docs = YamlLoader::load_from_str(&contents)
+        .map_err(|err| format!("Error while parsing {}: {err}", file_name.to_string_lossy()))?;
+    let Some(doc) = docs.first() else {
+        return Ok(None);
+    };
+
+    let error = |msg: &str| format!("{}: {msg}", file_name.to_string_lossy());
+    let vlnv = Vlnv::parse(doc["name"].as_str().ok_or_else(|| error("missing name"))?);
+    let parent = file_name.parent().unwrap_or(Path::new(""));
+
+    let mut core = Core {
+        files: FnvHashMap::default(),
+        depend: Vec::new(),
+        vlnv,
+    };
+
+    let Some(filesets) = doc["filesets"].as_hash() else {
+        return Ok(Some(core));
+    };
+    for fileset in filesets.values() {
+        let fileset_type = fileset["file_type"].as_str();
+        let fileset_library = fileset["logical_name"].as_str();
+
+        if let Some(depend) = fileset["depend"].as_vec() {
+            core.depend.extend(
+                depend
+                    .iter()
+                    .filter_map(|dep| dep.as_str())
+                    .map(Vlnv::parse),
+            );
+        }
+
+        let Some(files) = fileset["files"].as_vec() else {
+            continue;
+        };
+        for file in files {
+            // A file is either a plain name or a map from the name to its attributes
+            let (name, attributes) = match file {
+                Yaml::String(name) => (name.as_str(), &Yaml::BadValue),
+                Yaml::Hash(hash) => match hash.iter().next() {
+                    Some((Yaml::String(name), attributes)) => (name.as_str(), attributes),
+                    _ => return Err(error("invalid file entry")),
+                },
+                _ => return Err(error("invalid file entry")),
+            };
+
+            let Some(file_type) = attributes["file_type"].as_str().or(fileset_type) else {
+                continue;
+            };
+            if !is_vhdl_file_type(file_type) {
+                continue;
+            }
+
+            let library = match attributes["logical_name"].as_str().or(fileset_library) {
+                Some(library) => library.to_owned(),
+                None => core.vlnv.default_library_name(),
+            };
+            check_library_name(&library)?;
+            core.files
+                .entry(library)
+                .or_default()
+                .insert(parent.join(name), standard_of_file_type(file_type));
+        }
+    }
+
+    Ok(Some(core))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn to_string(path: &Path) -> String {
+        path.to_string_lossy().into_owned()
+    }
+
+    fn patterns(paths: &[PathBuf]) -> Vec<String> {
+        paths
+            .iter()
+            .map(|path| glob::Pattern::escape(&path.to_string_lossy()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_vlnv() {
+        assert_eq!(
+            Vlnv::parse(">=acme:ip:fifo:1.2"),
+            Vlnv {
+                vendor: "acme".to_owned(),
+                library: "ip".to_owned(),
+                name: "fifo".to_owned(),
+            }
+        );
+        assert!(Vlnv::parse("::fifo").matches(&Vlnv::parse("acme:ip:fifo:1.0")));
+        assert!(!Vlnv::parse("other:ip:fifo").matches(&Vlnv::parse("acme:ip:fifo:1.0")));
+    }
+
+    #[test]
+    fn imports_vhdl_filesets() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write(
+            &root.join("ip/uart/uart.core"),
+            "CAPI=2:
+name: acme:ip:uart-core:1.0
+filesets:
+  rtl:
+    files:
+      - rtl/uart_pkg.vhd
+      - rtl/uart.vhd
+      - rtl/legacy.vhd: {file_type: vhdlSource-93, logical_name: legacy}
+      - rtl/wrapper.v: {file_type: verilogSource}
+    file_type: vhdlSource-2008
+    logical_name: uart
+  tb:
+    files:
+      - tb/tb_uart.vhd
+    file_type: vhdlSource-2008
+  constraints:
+    files: [uart.xdc]
+    file_type: xdc
+",
+        );
+
+        let libraries = import_cores(&[to_string(root)], &[]).unwrap();
+        let names: Vec<_> = libraries.iter().map(|lib| lib.name.as_str()).collect();
+        assert_eq!(names, vec!["legacy", "uart", "uart_core"]);
+        assert_eq!(
+            libraries[0].patterns,
+            patterns(&[root.join("ip/uart/rtl/legacy.vhd")])
+        );
+        assert_eq!(
+            libraries[1].patterns,
+            patterns(&[
+                root.join("ip/uart/rtl/uart.vhd"),
+                root.join("ip/uart/rtl/uart_pkg.vhd")
+            ])
+        );
+        assert_eq!(
+            libraries[2].patterns,
+            patterns(&[root.join("ip/uart/tb/tb_uart.vhd")])
+        );
+        assert!(libraries.iter().all(|lib| !lib.is_third_party));
+        assert_eq!(libraries[0].standard, Some(VHDLStandard::VHDL1993));
+        assert_eq!(libraries[1].standard, Some(VHDLStandard::VHDL2008));
+        assert_eq!(libraries[2].standard, Some(VHDLStandard::VHDL2008));
+    }
+
+    #[test]
+    fn libraries_with_mixed_standards_set_the_standard_per_file() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write(
+            &root.join("mixed.core"),
+            "CAPI=2:
+name: acme:ip:mixed:1.0
+filesets:
+  rtl:
+    files:
+      - legacy.vhd: {file_type: vhdlSource-93}
+      - plain.vhd: {file_type: vhdlSource}
+      - new.vhd
+    file_type: vhdlSource-2008
+    logical_name: mixed
+",
+        );
+
+        let libraries = import_cores(&[to_string(root)], &[]).unwrap();
+        assert_eq!(libraries.len(), 1);
+        let library = &libraries[0];
+        assert_eq!(library.standard, None);
+        assert_eq!(
+            library.file_standard(&root.join("legacy.vhd")),
+            Some(VHDLStandard::VHDL1993)
+        );
+        assert_eq!(
+            library.file_standard(&root.join("new.vhd")),
+            Some(VHDLStandard::VHDL2008)
+        );
+        assert_eq!(library.file_standard(&root.join("plain.vhd")), None);
+    }
+
+    #[test]
+    fn dependencies_from_core_libraries_are_third_party() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write(
+            &root.join("src/top.core"),
+            "CAPI=2:
+name: acme:soc:top
+filesets:
+  rtl:
+    files: [top.vhd]
+    file_type: vhdlSource-2008
+    logical_name: soc
+    depend: ['>=acme:ip:fifo:1.0']
+",
+        );
+        write(
+            &root.join("cores/fifo/fifo.core"),
+            "CAPI=2:
+name: acme:ip:fifo:1.1
+filesets:
+  rtl:
+    files: [fifo.vhd]
+    file_type: vhdlSource
+    logical_name: fifo_lib
+    depend: ['::ram']
+",
+        );
+        write(
+            &root.join("cores/ram/ram.core"),
+            "CAPI=2:
+name: acme:ip:ram:1.0
+filesets:
+  rtl:
+    files: [ram.vhd]
+    file_type: vhdlSource
+    logical_name: ram_lib
+",
+        );
+        write(
+            &root.join("cores/unused/unused.core"),
+            "CAPI=2:
+name: acme:ip:unused:1.0
+filesets:
+  rtl:
+    files: [unused.vhd]
+    file_type: vhdlSource
+    logical_name: unused_lib
+",
+        );
+
+        let libraries =
+            import_cores(&[to_string(root)], &[to_string(&root.join("cores"))]).unwrap();
+        let names: Vec<_> = libraries
+            .iter()
+            .map(|lib| (lib.name.as_str(), lib.is_third_party))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("fifo_lib", true), ("ram_lib", true), ("soc", false)]
+        );
+    }
+
+    #[test]
+    fn ignores_files_that_are_not_capi2() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write(&root.join("old.core"), "[main]\nname = old\n");
+        assert_eq!(import_cores(&[to_string(root)], &[]).unwrap(), vec![]);
+    }
+}
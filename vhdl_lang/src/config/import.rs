@@ -0,0 +1,402 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Import of library mappings from the setup files of simulators
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{check_library_name, substitute_environment_variables, LibraryConfig};
+
+/// Import the libraries recorded in a simulator setup file.
+///
+/// Supported are
+/// - ModelSim / Questa `modelsim.ini` files. The source files of each library are read from
+///   the `_info` file within the library directory.
+/// - GHDL library index files such as `lib-obj08.cf`.
+/// - NVC library directories.
+pub(super) fn import_libraries(file_name: &Path) -> Result<Vec<LibraryConfig>, String> {
+    if file_name.is_dir() {
+        return Ok(vec![import_nvc_library(file_name)?]);
+    }
+
+    match file_name.extension().and_then(|ext| ext.to_str()) {
+        Some("ini") => import_modelsim_ini(file_name),
+        Some("cf") => Ok(vec![import_ghdl_cf(file_name)?]),
+        _ => Err(format!(
+            "Cannot import {}, expected a modelsim.ini, a GHDL .cf file or a NVC library directory",
+            file_name.to_string_lossy()
+        )),
+    }
+}
+
+fn read_to_string(file_name: &Path) -> Result<String, String> {
+    fs::read_to_string(file_name)
+        .map_err(|err| format!("Cannot read {}: {err}", file_name.to_string_lossy()))
+}
+
+fn parent_of(file_name: &Path) -> &Path {
+    file_name.parent().unwrap_or(Path::new(""))
+}
+
+fn is_vhdl_file(file_name: &Path) -> bool {
+    file_name
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("vhd") || ext.eq_ignore_ascii_case("vhdl"))
+}
+
+fn new_library(name: &str, files: BTreeSet<PathBuf>) -> Result<LibraryConfig, String> {
+    check_library_name(name)?;
+    Ok(LibraryConfig {
+        name: name.to_owned(),
+        patterns: files
+            .iter()
+            .map(|file| glob::Pattern::escape(&file.to_string_lossy()))
+            .collect(),
+        ..Default::default()
+    })
+}
+
+/// Reads the `[Library]` section of a `modelsim.ini` file.
+/// Libraries of the ModelSim installation (located in `$MODEL_TECH`) are skipped.
+fn import_modelsim_ini(file_name: &Path) -> Result<Vec<LibraryConfig>, String> {
+    let contents = read_to_string(file_name)?;
+    let parent = parent_of(file_name);
+
+    let mut libraries = Vec::new();
+    let mut in_library_section = false;
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+        if let Some(section) = line.strip_prefix('[') {
+            in_library_section = section
+                .trim_end_matches(']')
+                .eq_ignore_ascii_case("library");
+            continue;
+        }
+        if !in_library_section {
+            continue;
+        }
+        let Some((name, path)) = line.split_once('=') else {
+            continue;
+        };
+        let (name, path) = (name.trim(), path.trim());
+        if name.eq_ignore_ascii_case("others") || path.contains("$MODEL_TECH") {
+            continue;
+        }
+
+        let path = substitute_environment_variables(path, &subst::Env)?;
+        let library_dir = parent.join(path);
+        let files = read_modelsim_info(&library_dir.join("_info"))?;
+        libraries.push(new_library(name, files)?);
+    }
+    Ok(libraries)
+}
+
+/// Reads the source file names from the `_info` file of a ModelSim library.
+///
+/// Source files are recorded as lines starting with `F`, relative to the working directory
+/// recorded in the preceding `d` line. Either may be prefixed by a `Z<n> ` macro definition.
+/// A library that has not been compiled yet has no `_info` file and therefore no files.
+fn read_modelsim_info(file_name: &Path) -> Result<BTreeSet<PathBuf>, String> {
+    let mut files = BTreeSet::new();
+    if !file_name.exists() {
+        return Ok(files);
+    }
+
+    let contents = fs::read(file_name)
+        .map_err(|err| format!("Cannot read {}: {err}", file_name.to_string_lossy()))?;
+    let contents = String::from_utf8_lossy(&contents);
+
+    let mut work_dir = parent_of(parent_of(file_name)).to_owned();
+    for line in contents.lines() {
+        let line = match line.strip_prefix('Z') {
+            Some(macro_def) => match macro_def.split_once(' ') {
+                Some((num, line)) if num.chars().all(|chr| chr.is_ascii_digit()) => line,
+                _ => line,
+            },
+            None => line,
+        };
+
+        if let Some(dir) = line.strip_prefix('d') {
+            work_dir = PathBuf::from(dir);
+        } else if let Some(file) = line.strip_prefix('F') {
+            let file = work_dir.join(file);
+            if is_vhdl_file(&file) {
+                files.insert(file);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Reads a GHDL library index file such as `lib-obj08.cf`.
+/// The library name is the part of the file name before the last `-obj`.
+fn import_ghdl_cf(file_name: &Path) -> Result<LibraryConfig, String> {
+    let name = file_name
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.rsplit_once("-obj"))
+        .map(|(name, _)| name)
+        .ok_or_else(|| {
+            format!(
+                "Cannot determine the library name of {}, expected <library>-obj<standard>.cf",
+                file_name.to_string_lossy()
+            )
+        })?;
+
+    let contents = read_to_string(file_name)?;
+    let parent = parent_of(file_name);
+
+    let mut files = BTreeSet::new();
+    for line in contents.lines() {
+        let Some(line) = line.strip_prefix("file ") else {
+            continue;
+        };
+        let mut tokens = ghdl_tokens(line).into_iter();
+        let (Some(dir), Some(file)) = (tokens.next(), tokens.next()) else {
+            return Err(format!(
+                "Malformed file entry in {}: {line}",
+                file_name.to_string_lossy()
+            ));
+        };
+
+        // Relative directories are relative to the directory of the index file
+        let file = if dir == "." {
+            parent.join(file)
+        } else {
+            parent.join(dir).join(file)
+        };
+        files.insert(file);
+    }
+
+    new_library(name, files)
+}
+
+/// Split a line of a GHDL library index into whitespace separated tokens
+/// where double quoted strings form a single token.
+fn ghdl_tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+    while let Some(&chr) = chars.peek() {
+        if chr.is_whitespace() {
+            chars.next();
+        } else if chr == '"' {
+            chars.next();
+            let mut token = String::new();
+            for chr in chars.by_ref() {
+                if chr == '"' {
+                    break;
+                }
+                token.push(chr);
+            }
+            tokens.push(token);
+        } else {
+            let mut token = String::new();
+            while let Some(chr) = chars.next_if(|chr| !chr.is_whitespace()) {
+                token.push(chr);
+            }
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+/// Reads a NVC library directory.
+///
+/// NVC stores analyzed units in a binary format, so this is a best effort that collects the
+/// names of existing VHDL files referenced by the units.
+/// The library name is the name of the directory.
+fn import_nvc_library(library_dir: &Path) -> Result<LibraryConfig, String> {
+    if !library_dir.join("_NVC_LIB").exists() {
+        return Err(format!(
+            "{} is not a NVC library directory",
+            library_dir.to_string_lossy()
+        ));
+    }
+
+    let name = library_dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| {
+            format!(
+                "Invalid library directory {}",
+                library_dir.to_string_lossy()
+            )
+        })?
+        .to_lowercase();
+
+    let entries = fs::read_dir(library_dir)
+        .map_err(|err| format!("Cannot read {}: {err}", library_dir.to_string_lossy()))?;
+
+    let mut files = BTreeSet::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.file_name().is_some_and(|name| name == "_NVC_LIB") {
+            continue;
+        }
+        let Ok(contents) = fs::read(&path) else {
+            continue;
+        };
+        for string in contents.split(|byte| !(byte.is_ascii_graphic() || *byte == b' ')) {
+            let file = parent_of(library_dir).join(String::from_utf8_lossy(string).as_ref());
+            if is_vhdl_file(&file) && file.is_file() {
+                files.insert(file);
+            }
+        }
+    }
+
+    new_library(&name, files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn patterns(paths: &[PathBuf]) -> Vec<String> {
+        paths
+            .iter()
+            .map(|path| glob::Pattern::escape(&path.to_string_lossy()))
+            .collect()
+    }
+
+    #[test]
+    fn imports_modelsim_ini() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write(
+            &root.join("modelsim.ini"),
+            "
+[Library]
+std = $MODEL_TECH/../std
+; A comment
+lib1 = libs/lib1
+lib2 = libs/lib2
+others = $MODEL_TECH/../modelsim.ini
+
+[vcom]
+VHDL93 = 2008
+",
+        );
+        write(
+            &root.join("libs/lib1/_info"),
+            &format!(
+                "m255
+K4
+cModel Technology
+d{root}
+Epkg
+Z0 d{root}
+Z1 8src/pkg.vhd
+Z2 Fsrc/pkg.vhd
+Eent
+R0
+Fsrc/ent.vhd
+vmod
+F/abs/mod.v
+",
+                root = root.to_string_lossy()
+            ),
+        );
+
+        let libraries = import_libraries(&root.join("modelsim.ini")).unwrap();
+        assert_eq!(libraries.len(), 2);
+        assert_eq!(libraries[0].name, "lib1");
+        assert_eq!(
+            libraries[0].patterns,
+            patterns(&[root.join("src/ent.vhd"), root.join("src/pkg.vhd")])
+        );
+        assert_eq!(libraries[1].name, "lib2");
+        assert_eq!(libraries[1].patterns, Vec::<String>::new());
+    }
+
+    #[test]
+    fn imports_ghdl_cf() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write(
+            &root.join("mylib-obj08.cf"),
+            "v 4
+file . \"src/pkg.vhd\" \"20240101120000.000\" \"3a9b\" \"20240101120000.123\":
+  package pkg at 1( 0) + 0 on 4 body;
+file \"/abs/dir/\" \"ent.vhd\" \"20240101120000.000\" \"6f0c\" \"20240101120000.123\":
+  entity ent at 1( 0) + 0 on 5;
+",
+        );
+
+        let libraries = import_libraries(&root.join("mylib-obj08.cf")).unwrap();
+        assert_eq!(libraries.len(), 1);
+        assert_eq!(libraries[0].name, "mylib");
+        assert_eq!(
+            libraries[0].patterns,
+            patterns(&[PathBuf::from("/abs/dir/ent.vhd"), root.join("src/pkg.vhd")])
+        );
+    }
+
+    #[test]
+    fn imports_ghdl_cf_relative_to_its_directory() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write(
+            &root.join("build/my-obj-lib-obj93.cf"),
+            "v 4
+file \"../rtl/\" \"ent.vhd\" \"20240101120000.000\" \"6f0c\" \"20240101120000.123\":
+  entity ent at 1( 0) + 0 on 5;
+",
+        );
+
+        let libraries = import_libraries(&root.join("build/my-obj-lib-obj93.cf")).unwrap();
+        assert_eq!(libraries.len(), 1);
+        assert_eq!(libraries[0].name, "my-obj-lib");
+        assert_eq!(
+            libraries[0].patterns,
+            patterns(&[root.join("build/../rtl/ent.vhd")])
+        );
+    }
+
+    #[test]
+    fn the_work_library_cannot_be_imported() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write(&root.join("work-obj93.cf"), "v 4\n");
+        assert!(import_libraries(&root.join("work-obj93.cf"))
+            .expect_err("Expected error")
+            .starts_with("The 'work' library is not a valid library."));
+    }
+
+    #[test]
+    fn imports_nvc_library() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write(&root.join("src/pkg.vhd"), "");
+        write(&root.join("mylib/_NVC_LIB"), "");
+        fs::write(
+            root.join("mylib/MYLIB.PKG"),
+            b"\x01\x02src/pkg.vhd\x00\x07missing.vhd\x00\xff",
+        )
+        .unwrap();
+
+        let libraries = import_libraries(&root.join("mylib")).unwrap();
+        assert_eq!(libraries.len(), 1);
+        assert_eq!(libraries[0].name, "mylib");
+        assert_eq!(libraries[0].patterns, patterns(&[root.join("src/pkg.vhd")]));
+    }
+
+    #[test]
+    fn error_on_unknown_file_type() {
+        assert!(import_libraries(Path::new("libraries.txt")).is_err());
+    }
+}
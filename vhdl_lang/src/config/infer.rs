@@ -0,0 +1,314 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Inference of a library mapping for workspaces without a configuration file
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use fnv::{FnvHashMap, FnvHashSet};
+use rayon::prelude::*;
+
+use super::LibraryConfig;
+use crate::ast::{AnyDesignUnit, HasIdent, HasPrimaryIdent};
+use crate::data::NullDiagnostics;
+use crate::standard::VHDLStandard;
+use crate::syntax::{Kind, Token, VHDLParser, Value};
+
+/// The library of files where no library could be inferred
+pub(super) const DEFAULT_LIBRARY: &str = "defaultlib";
+
+/// The names that can be found in a single file
+#[derive(Default)]
+struct FileSummary {
+    primary_units: Vec<String>,
+    // The primary units of secondary units, i.e., the entity of an architecture
+    secondary_units: Vec<String>,
+    library_clauses: BTreeSet<String>,
+    // Selected names `lib.unit` where `lib` is a library of a library clause
+    references: Vec<(String, String)>,
+}
+
+/// Find all VHDL files below `root`, regardless of the case of their extension.
+/// Hidden directories are skipped.
+pub(super) fn find_vhdl_files(root: &Path) -> Vec<PathBuf> {
+    let options = glob::MatchOptions {
+        case_sensitive: false,
+        require_literal_leading_dot: true,
+        ..Default::default()
+    };
+    let root = glob::Pattern::escape(&root.to_string_lossy());
+    let mut file_names = BTreeSet::new();
+    for ext in ["vhd", "vhdl"] {
+        let Ok(paths) = glob::glob_with(&format!("{root}/**/*.{ext}"), options) else {
+            continue;
+        };
+        file_names.extend(paths.flatten().filter(|path| path.is_file()));
+    }
+    file_names.into_iter().collect()
+}
+
+/// Infer the library of each file.
+///
+/// A file is placed in
+/// 1. the library that its primary units are referenced from, i.e., `lib.pkg` together
+///    with a `library lib` clause in another file
+/// 2. the library named like one of the directories between `root` and the file,
+///    if any file declares a library of that name
+/// 3. the library of the primary unit for files that only contain secondary units
+/// 4. the default library otherwise
+///
+/// Libraries in `known_libraries` (i.e., `ieee`) are never inferred.
+pub(super) fn infer_libraries(
+    root: &Path,
+    file_names: &[PathBuf],
+    standard: VHDLStandard,
+    known_libraries: &FnvHashSet<String>,
+) -> Vec<LibraryConfig> {
+    let parser = VHDLParser::new(standard);
+    let summaries: Vec<(&PathBuf, FileSummary)> = file_names
+        .par_iter()
+        .filter_map(|file_name| {
+            let (_, design_file) = parser
+                .parse_design_file(file_name, &mut NullDiagnostics)
+                .ok()?;
+            Some((file_name, summarize(&design_file.design_units)))
+        })
+        .collect();
+
+    let library_names: BTreeSet<&String> = summaries
+        .iter()
+        .flat_map(|(_, summary)| summary.library_clauses.iter())
+        .filter(|name| name.as_str() != "work" && !known_libraries.contains(name.as_str()))
+        .collect();
+
+    // The number of references of each unit through each library
+    let mut references: FnvHashMap<&String, BTreeMap<&String, usize>> = FnvHashMap::default();
+    for (_, summary) in summaries.iter() {
+        for (library_name, unit_name) in summary.references.iter() {
+            if library_names.contains(library_name) {
+                *references
+                    .entry(unit_name)
+                    .or_default()
+                    .entry(library_name)
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    let mut file_libraries: Vec<Option<String>> = summaries
+        .iter()
+        .map(|(file_name, summary)| {
+            let mut votes: BTreeMap<&String, usize> = BTreeMap::new();
+            for unit_name in summary.primary_units.iter() {
+                for (library_name, count) in references.get(unit_name).into_iter().flatten() {
+                    *votes.entry(library_name).or_default() += count;
+                }
+            }
+            // The most referenced library, the first one in alphabetical order on a tie
+            if let Some((library_name, _)) = votes.into_iter().rev().max_by_key(|(_, count)| *count)
+            {
+                return Some(library_name.clone());
+            }
+
+            file_name
+                .parent()?
+                .ancestors()
+                .take_while(|dir| dir.starts_with(root) && *dir != root)
+                .filter_map(|dir| Some(dir.file_name()?.to_string_lossy().to_lowercase()))
+                .find(|dir_name| library_names.contains(dir_name))
+        })
+        .collect();
+
+    let mut primary_unit_libraries: FnvHashMap<&String, &String> = FnvHashMap::default();
+    for ((_, summary), library_name) in summaries.iter().zip(file_libraries.iter()) {
+        if let Some(library_name) = library_name {
+            for unit_name in summary.primary_units.iter() {
+                primary_unit_libraries.insert(unit_name, library_name);
+            }
+        }
+    }
+    let secondary_unit_libraries: Vec<Option<String>> = summaries
+        .iter()
+        .map(|(_, summary)| {
+            summary
+                .secondary_units
+                .iter()
+                .find_map(|unit_name| primary_unit_libraries.get(unit_name))
+                .map(|library_name| (*library_name).clone())
+        })
+        .collect();
+    for ((library_name, secondary_library_name), (_, summary)) in file_libraries
+        .iter_mut()
+        .zip(secondary_unit_libraries)
+        .zip(summaries.iter())
+    {
+        if library_name.is_none() && summary.primary_units.is_empty() {
+            *library_name = secondary_library_name;
+        }
+    }
+
+    let mut libraries: BTreeMap<String, LibraryConfig> = BTreeMap::new();
+    for ((file_name, _), library_name) in summaries.iter().zip(file_libraries) {
+        let library_name = library_name.unwrap_or_else(|| DEFAULT_LIBRARY.to_owned());
+        libraries
+            .entry(library_name.clone())
+            .or_insert_with(|| LibraryConfig {
+                name: library_name,
+                ..Default::default()
+            })
+            .patterns
+            .push(glob::Pattern::escape(&file_name.to_string_lossy()));
+    }
+    libraries.into_values().collect()
+}
+
+fn summarize(design_units: &[(Vec<Token>, AnyDesignUnit)]) -> FileSummary {
+    let mut summary = FileSummary::default();
+    for (tokens, unit) in design_units {
+        match unit {
+            AnyDesignUnit::Primary(unit) => summary
+                .primary_units
+                .push(unit.ident().item.name_utf8().to_lowercase()),
+            AnyDesignUnit::Secondary(unit) => summary
+                .secondary_units
+                .push(unit.primary_name().name_utf8().to_lowercase()),
+        }
+
+        let mut in_library_clause = false;
+        for (idx, token) in tokens.iter().enumerate() {
+            match token.kind {
+                Kind::Library => in_library_clause = true,
+                Kind::SemiColon => in_library_clause = false,
+                Kind::Identifier if in_library_clause => {
+                    if let Some(name) = identifier(token) {
+                        summary.library_clauses.insert(name);
+                    }
+                }
+                Kind::Identifier => {
+                    let (Some(dot), Some(suffix)) = (tokens.get(idx + 1), tokens.get(idx + 2))
+                    else {
+                        continue;
+                    };
+                    if dot.kind != Kind::Dot {
+                        continue;
+                    }
+                    if let (Some(prefix), Some(suffix)) = (identifier(token), identifier(suffix)) {
+                        if summary.library_clauses.contains(&prefix) {
+                            summary.references.push((prefix, suffix));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    summary
+}
+
+fn identifier(token: &Token) -> Option<String> {
+    match token.value {
+        Value::Identifier(ref symbol) => Some(symbol.name_utf8().to_lowercase()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+
+    fn write(root: &Path, file_name: &str, contents: &str) -> PathBuf {
+        let path = root.join(file_name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn mapping(libraries: &[LibraryConfig], root: &Path) -> Vec<(String, Vec<String>)> {
+        let root = glob::Pattern::escape(&root.to_string_lossy());
+        libraries
+            .iter()
+            .map(|library| {
+                (
+                    library.name.clone(),
+                    library
+                        .patterns
+                        .iter()
+                        .map(|pattern| pattern.strip_prefix(&root).unwrap()[1..].to_owned())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn infers_libraries() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        write(root, ".git/ignored.vhd", "package ignored is end package;");
+        write(
+            root,
+            "common/pkg.vhd",
+            "package pkg is end package;\npackage body pkg is end package body;",
+        );
+        write(
+            root,
+            "util/util_lib/fifo.VHDL",
+            "library ieee;\nuse ieee.std_logic_1164.all;\nentity fifo is end entity;",
+        );
+        write(
+            root,
+            "util/util_lib/fifo_rtl.vhd",
+            "architecture rtl of fifo is begin end architecture;",
+        );
+        write(
+            root,
+            "src/top.vhd",
+            "\
+library common_lib, util_lib;
+use common_lib.pkg.all;
+
+entity top is end entity;
+
+architecture rtl of top is
+begin
+  inst: entity util_lib.fifo;
+end architecture;
+",
+        );
+        write(
+            root,
+            "src/top_arch.VHD",
+            "architecture other of top is begin end;",
+        );
+
+        let file_names = find_vhdl_files(root);
+        assert_eq!(file_names.len(), 5);
+        let known_libraries = FnvHashSet::from_iter(["ieee".to_owned()]);
+        let libraries =
+            infer_libraries(root, &file_names, VHDLStandard::default(), &known_libraries);
+
+        assert_eq!(
+            mapping(&libraries, root),
+            vec![
+                ("common_lib".to_owned(), vec!["common/pkg.vhd".to_owned()]),
+                (
+                    "defaultlib".to_owned(),
+                    vec!["src/top.vhd".to_owned(), "src/top_arch.VHD".to_owned()]
+                ),
+                (
+                    "util_lib".to_owned(),
+                    vec![
+                        "util/util_lib/fifo.VHDL".to_owned(),
+                        "util/util_lib/fifo_rtl.vhd".to_owned()
+                    ]
+                ),
+            ]
+        );
+    }
+}
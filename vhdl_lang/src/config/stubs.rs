@@ -0,0 +1,221 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Lightweight JSON stubs that declare the interface of units whose sources are not available
+
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::data::Source;
+
+/// Returns true if the stub file uses the JSON format instead of VHDL
+pub(crate) fn is_json_stub(file_name: &Path) -> bool {
+    file_name
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Read a JSON stub file and convert it into an in-memory VHDL source.
+///
+/// The file contains an optional `use` array of selected names that are used by the
+/// declarations, an `entities` array and a `packages` array where each package has
+/// a `components` array. Entities and components have a `name` as well as optional
+/// `generics` and `ports` arrays. Each interface element has a `name`, a `type`,
+/// an optional `default` and, for ports, an optional `mode` that defaults to `in`.
+pub(crate) fn read_json_stub(file_name: &Path) -> Result<Source, String> {
+    let contents = fs::read_to_string(file_name)
+        .map_err(|err| format!("Cannot read {}: {err}", file_name.to_string_lossy()))?;
+    let stub: Value = serde_json::from_str(&contents)
+        .map_err(|err| format!("Cannot parse {}: {err}", file_name.to_string_lossy()))?;
+    let code = json_stub_to_vhdl(&stub)
+        .map_err(|err| format!("Malformed stub file {}: {err}", file_name.to_string_lossy()))?;
+    Ok(Source::inline(file_name, &code))
+}
+
+fn json_stub_to_vhdl(stub: &Value) -> Result<String, String> {
+    if !stub.is_object() {
+        return Err("expected an object".to_owned());
+    }
+
+    let mut context = String::new();
+    let uses = strings(stub, "use")?;
+    let mut libraries: Vec<&str> = Vec::new();
+    for name in uses.iter() {
+        let library = name.split('.').next().unwrap_or_default();
+        if !["work", "std"].contains(&library.to_ascii_lowercase().as_str())
+            && !libraries.contains(&library)
+        {
+            libraries.push(library);
+        }
+    }
+    for library in libraries {
+        writeln!(context, "library {library};").unwrap();
+    }
+    for name in uses.iter() {
+        writeln!(context, "use {name};").unwrap();
+    }
+
+    let mut code = String::new();
+    for entity in array(stub, "entities")? {
+        let name = string(entity, "name")?;
+        code.push_str(&context);
+        writeln!(code, "entity {name} is").unwrap();
+        write_interface(&mut code, entity, "  ")?;
+        code.push_str("end entity;\n\n");
+    }
+    for package in array(stub, "packages")? {
+        let name = string(package, "name")?;
+        code.push_str(&context);
+        writeln!(code, "package {name} is").unwrap();
+        for component in array(package, "components")? {
+            let component_name = string(component, "name")?;
+            writeln!(code, "  component {component_name} is").unwrap();
+            write_interface(&mut code, component, "    ")?;
+            code.push_str("  end component;\n");
+        }
+        code.push_str("end package;\n\n");
+    }
+    Ok(code)
+}
+
+/// Write the generic and port clauses of an entity or component
+fn write_interface(code: &mut String, unit: &Value, indent: &str) -> Result<(), String> {
+    for (key, clause) in [("generics", "generic"), ("ports", "port")] {
+        let elements = array(unit, key)?;
+        if elements.is_empty() {
+            continue;
+        }
+        writeln!(code, "{indent}{clause} (").unwrap();
+        for (idx, element) in elements.iter().enumerate() {
+            let name = string(element, "name")?;
+            let typ = string(element, "type")?;
+            write!(code, "{indent}  {name} : ").unwrap();
+            if key == "ports" {
+                let mode = match element.get("mode") {
+                    Some(mode) => mode
+                        .as_str()
+                        .ok_or_else(|| format!("mode of port {name} is not a string"))?,
+                    None => "in",
+                };
+                write!(code, "{mode} ").unwrap();
+            }
+            code.push_str(typ);
+            if let Some(default) = element.get("default") {
+                let default = default
+                    .as_str()
+                    .ok_or_else(|| format!("default of {name} is not a string"))?;
+                write!(code, " := {default}").unwrap();
+            }
+            code.push_str(if idx + 1 < elements.len() {
+                ";\n"
+            } else {
+                "\n"
+            });
+        }
+        writeln!(code, "{indent});").unwrap();
+    }
+    Ok(())
+}
+
+fn array<'v>(value: &'v Value, key: &str) -> Result<&'v [Value], String> {
+    match value.get(key) {
+        Some(array) => array
+            .as_array()
+            .map(Vec::as_slice)
+            .ok_or_else(|| format!("{key} is not an array")),
+        None => Ok(&[]),
+    }
+}
+
+fn string<'v>(value: &'v Value, key: &str) -> Result<&'v str, String> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("missing string {key} in {value}"))
+}
+
+fn strings<'v>(value: &'v Value, key: &str) -> Result<Vec<&'v str>, String> {
+    array(value, key)?
+        .iter()
+        .map(|item| {
+            item.as_str()
+                .ok_or_else(|| format!("{key} must only contain strings"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn converts_json_stub_to_vhdl() {
+        let stub = serde_json::json!({
+            "use": ["ieee.std_logic_1164.all"],
+            "entities": [{
+                "name": "bufg",
+                "ports": [
+                    {"name": "o", "mode": "out", "type": "std_ulogic"},
+                    {"name": "i", "type": "std_ulogic"}
+                ]
+            }],
+            "packages": [{
+                "name": "vcomponents",
+                "components": [{
+                    "name": "ibuf",
+                    "generics": [{"name": "iostandard", "type": "string", "default": "\"DEFAULT\""}],
+                    "ports": [
+                        {"name": "o", "mode": "out", "type": "std_ulogic"},
+                        {"name": "i", "type": "std_ulogic"}
+                    ]
+                }]
+            }]
+        });
+
+        assert_eq!(
+            json_stub_to_vhdl(&stub).unwrap(),
+            "\
+library ieee;
+use ieee.std_logic_1164.all;
+entity bufg is
+  port (
+    o : out std_ulogic;
+    i : in std_ulogic
+  );
+end entity;
+
+library ieee;
+use ieee.std_logic_1164.all;
+package vcomponents is
+  component ibuf is
+    generic (
+      iostandard : string := \"DEFAULT\"
+    );
+    port (
+      o : out std_ulogic;
+      i : in std_ulogic
+    );
+  end component;
+end package;
+
+"
+        );
+    }
+
+    #[test]
+    fn malformed_json_stub() {
+        let stub = serde_json::json!({"entities": [{"ports": []}]});
+        assert_eq!(
+            json_stub_to_vhdl(&stub),
+            Err("missing string name in {\"ports\":[]}".to_owned())
+        );
+    }
+}
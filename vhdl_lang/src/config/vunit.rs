@@ -0,0 +1,123 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Import of the library mapping from a VUnit `--export-json` file
+
+use std::fs;
+use std::path::Path;
+
+use fnv::FnvHashMap;
+use serde_json::Value;
+
+use super::{check_library_name, LibraryConfig};
+use crate::syntax::is_verilog_file;
+
+/// Import the libraries of a VUnit export file.
+///
+/// The file is created by running a VUnit script with `--export-json <file>` and contains
+/// a `files` array where each entry has a `file_name` and a `library_name`.
+/// Relative file names are relative to the folder of the export file.
+/// Verilog files that are part of the export are ignored.
+pub(super) fn import_vunit_export(file_name: &Path) -> Result<Vec<LibraryConfig>, String> {
+    let contents = fs::read_to_string(file_name)
+        .map_err(|err| format!("Cannot read {}: {err}", file_name.to_string_lossy()))?;
+    let export: Value = serde_json::from_str(&contents)
+        .map_err(|err| format!("Cannot parse {}: {err}", file_name.to_string_lossy()))?;
+    let parent = file_name.parent().unwrap_or(Path::new(""));
+
+    let files = export
+        .get("files")
+        .and_then(Value::as_array)
+        .ok_or_else(|| {
+            format!(
+                "{} is not a VUnit export file, missing the files array",
+                file_name.to_string_lossy()
+            )
+        })?;
+
+    let mut libraries: FnvHashMap<String, LibraryConfig> = FnvHashMap::default();
+    for file in files {
+        let (Some(source_name), Some(library_name)) = (
+            file.get("file_name").and_then(Value::as_str),
+            file.get("library_name").and_then(Value::as_str),
+        ) else {
+            return Err(format!(
+                "Malformed file entry in {}: {file}",
+                file_name.to_string_lossy()
+            ));
+        };
+
+        let source_name = parent.join(source_name);
+        if is_verilog_file(&source_name) {
+            continue;
+        }
+
+        check_library_name(library_name)?;
+        let library = libraries
+            .entry(library_name.to_owned())
+            .or_insert_with(|| LibraryConfig {
+                name: library_name.to_owned(),
+                ..Default::default()
+            });
+        let pattern = glob::Pattern::escape(&source_name.to_string_lossy());
+        if !library.patterns.contains(&pattern) {
+            library.patterns.push(pattern);
+        }
+    }
+
+    let mut libraries: Vec<_> = libraries.into_values().collect();
+    libraries.sort_by(|lhs, rhs| lhs.name.cmp(&rhs.name));
+    Ok(libraries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn imports_vunit_export() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        fs::write(
+            root.join("vunit.json"),
+            r#"{
+  "export_format_version": {"major": 1, "minor": 0, "patch": 0},
+  "files": [
+    {"file_name": "/abs/lib1/pkg.vhd", "library_name": "lib1"},
+    {"file_name": "src/ent.vhd", "library_name": "lib2"},
+    {"file_name": "src/mod.sv", "library_name": "lib2"},
+    {"file_name": "src/tb_ent.vhd", "library_name": "lib2"}
+  ],
+  "tests": []
+}"#,
+        )
+        .unwrap();
+
+        let libraries = import_vunit_export(&root.join("vunit.json")).unwrap();
+        assert_eq!(libraries.len(), 2);
+        assert_eq!(libraries[0].name, "lib1");
+        assert_eq!(libraries[0].patterns, vec!["/abs/lib1/pkg.vhd".to_owned()]);
+        assert_eq!(libraries[1].name, "lib2");
+        assert_eq!(
+            libraries[1].patterns,
+            vec![
+                glob::Pattern::escape(&root.join("src/ent.vhd").to_string_lossy()),
+                glob::Pattern::escape(&root.join("src/tb_ent.vhd").to_string_lossy()),
+            ]
+        );
+    }
+
+    #[test]
+    fn error_on_missing_files_array() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = tempdir.path();
+        fs::write(root.join("vunit.json"), "{}").unwrap();
+        assert!(import_vunit_export(&root.join("vunit.json"))
+            .expect_err("Expected error")
+            .ends_with("is not a VUnit export file, missing the files array"));
+    }
+}
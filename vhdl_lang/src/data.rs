@@ -10,6 +10,7 @@ mod diagnostic;
 pub mod error_codes;
 mod latin_1;
 mod message;
+mod progress;
 mod source;
 mod symbol_table;
 
@@ -18,5 +19,6 @@ pub use diagnostic::*;
 pub use error_codes::*;
 pub use latin_1::*;
 pub use message::*;
+pub use progress::*;
 pub use source::*;
 pub use symbol_table::*;
@@ -13,6 +13,48 @@ use std::path::Path;
 
 pub struct Contents {
     lines: Vec<String>,
+    changed_lines: ChangedLines,
+}
+
+/// The lines that changed since they were last taken with `Contents::take_changed_lines`
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub(crate) enum ChangedLines {
+    /// The contents were replaced as a whole
+    Unknown,
+    Unchanged,
+    /// Lines `start..old_end` of the previous contents were replaced by lines `start..new_end`
+    Lines {
+        start: u32,
+        old_end: u32,
+        new_end: u32,
+    },
+}
+
+impl ChangedLines {
+    /// Lines `start..end` of the current contents are replaced by `num_lines` lines
+    fn replace(self, start: u32, end: u32, num_lines: u32) -> ChangedLines {
+        match self {
+            ChangedLines::Unknown => ChangedLines::Unknown,
+            ChangedLines::Unchanged => ChangedLines::Lines {
+                start,
+                old_end: end,
+                new_end: start + num_lines,
+            },
+            ChangedLines::Lines {
+                start: prev_start,
+                old_end: prev_old_end,
+                new_end: prev_new_end,
+            } => {
+                // Both changes together, in lines of the current contents
+                let union_end = u32::max(prev_new_end, end);
+                ChangedLines::Lines {
+                    start: u32::min(prev_start, start),
+                    old_end: union_end - prev_new_end + prev_old_end,
+                    new_end: union_end - end + start + num_lines,
+                }
+            }
+        }
+    }
 }
 
 impl Contents {
@@ -28,6 +70,7 @@ impl Contents {
     pub fn from_str(code: &str) -> Contents {
         Contents {
             lines: split_lines(code),
+            changed_lines: ChangedLines::Unknown,
         }
     }
 
@@ -67,6 +110,7 @@ impl Contents {
 
         Contents {
             lines: split_lines(&result),
+            changed_lines: ChangedLines::Unknown,
         }
     }
 
@@ -78,9 +122,15 @@ impl Contents {
         self.lines.get(lineno).map(|string| string.as_str())
     }
 
+    /// The lines that changed since this function was last called
+    pub(crate) fn take_changed_lines(&mut self) -> ChangedLines {
+        std::mem::replace(&mut self.changed_lines, ChangedLines::Unchanged)
+    }
+
     pub fn change(&mut self, range: &Range, content: &str) {
         if self.lines.is_empty() {
             self.lines = split_lines(content);
+            self.changed_lines = ChangedLines::Unknown;
             return;
         }
 
@@ -123,9 +173,13 @@ impl Contents {
         }
 
         let end_line = std::cmp::min(self.lines.len().saturating_sub(1), end_line);
-        self.lines
-            .splice(start_line..=end_line, split_lines(&merged_content))
-            .count();
+        let new_lines = split_lines(&merged_content);
+        self.changed_lines = self.changed_lines.replace(
+            start_line as u32,
+            end_line as u32 + 1,
+            new_lines.len() as u32,
+        );
+        self.lines.splice(start_line..=end_line, new_lines).count();
     }
 }
 
@@ -277,7 +331,7 @@ impl<'a> ContentReader<'a> {
     /// Returns whether the chars that follow are the given substring.
     ///
     /// # Examples
-    /// ```
+    /// ```ignore
     /// use super::{ContentReader, Contents};
     /// let reader = ContentReader::new(Contents::from_str("foo bar"));
     /// assert!(reader.matches("foo"));
@@ -288,7 +342,6 @@ impl<'a> ContentReader<'a> {
     /// assert!(reader.matches("bar"));
     /// assert!(!reader.matches("foo"));
     /// ```
-    #[cfg(test)]
     pub fn matches(&mut self, substr: &str) -> bool {
         let mut lookahead = self.clone();
         for exp in substr.chars() {
@@ -324,7 +377,6 @@ impl<'a> ContentReader<'a> {
         self.state.pos()
     }
 
-    #[cfg(test)]
     pub fn seek_pos(&mut self, pos: Position) {
         self.state = ReaderState {
             pos: Position {
@@ -334,9 +386,11 @@ impl<'a> ContentReader<'a> {
             idx: 0,
         };
         while self.pos() < pos {
-            self.skip();
+            if self.pop_char().is_none() {
+                break;
+            }
         }
-        assert_eq!(self.pos(), pos);
+        debug_assert_eq!(self.pos(), pos);
     }
 
     pub fn state(&self) -> ReaderState {
@@ -629,4 +683,36 @@ mod tests {
         assert_eq!(contents.num_lines(), 1);
         assert_eq!(contents.get_line(0).unwrap().to_string(), "a\n");
     }
+
+    #[test]
+    fn changed_lines_are_tracked() {
+        let mut contents = new("a\nb\nc\nd\n");
+        assert_eq!(contents.take_changed_lines(), ChangedLines::Unknown);
+        assert_eq!(contents.take_changed_lines(), ChangedLines::Unchanged);
+
+        // Insert a line after b
+        contents.change(&Range::new(Position::new(1, 1), Position::new(1, 1)), "\nx");
+        assert_eq!(flatten(&contents), "a\nb\nx\nc\nd\n");
+        // Remove d
+        contents.change(&Range::new(Position::new(4, 0), Position::new(5, 0)), "");
+        assert_eq!(flatten(&contents), "a\nb\nx\nc\n");
+        assert_eq!(
+            contents.take_changed_lines(),
+            ChangedLines::Lines {
+                start: 1,
+                old_end: 4,
+                new_end: 4
+            }
+        );
+
+        contents.change(&Range::new(Position::new(0, 0), Position::new(0, 1)), "y");
+        assert_eq!(
+            contents.take_changed_lines(),
+            ChangedLines::Lines {
+                start: 0,
+                old_end: 1,
+                new_end: 1
+            }
+        );
+    }
 }
@@ -46,6 +46,17 @@ impl Diagnostic {
         }
     }
 
+    /// The diagnostic moved up or down by a number of lines within its source
+    pub(crate) fn shift_lines(mut self, lines: i64) -> Diagnostic {
+        self.pos.range = self.pos.range.shift_lines(lines);
+        for (pos, _) in self.related.iter_mut() {
+            if pos.source == self.pos.source {
+                pos.range = pos.range.shift_lines(lines);
+            }
+        }
+        self
+    }
+
     pub fn related(self, item: impl AsRef<SrcPos>, message: impl Into<String>) -> Diagnostic {
         let mut diagnostic = self;
         diagnostic.add_related(item, message);
@@ -4,7 +4,7 @@
 //
 // Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
 
-use crate::{Diagnostic, Severity, SrcPos};
+use crate::{Diagnostic, Severity, SrcPos, VHDLStandard};
 use enum_map::{enum_map, Enum, EnumMap};
 use std::fmt::{Display, Formatter};
 use std::ops::{Index, IndexMut};
@@ -26,6 +26,48 @@ pub enum ErrorCode {
     /// ```
     SyntaxError,
 
+    /// A `` `warning `` tool directive
+    ///
+    /// # Example
+    /// ```vhdl
+    /// `if TOOL_TYPE = "SYNTHESIS" then
+    /// `warning "Not supported for synthesis"
+    /// `end if
+    /// ```
+    DirectiveWarning,
+
+    /// An `` `error `` tool directive
+    ///
+    /// # Example
+    /// ```vhdl
+    /// `if VHDL_VERSION < "2008" then
+    /// `error "Requires VHDL 2008 or newer"
+    /// `end if
+    /// ```
+    DirectiveError,
+
+    /// A region of code that is excluded by conditional analysis.
+    /// This is not an error, but allows editors to display the region as inactive.
+    ///
+    /// # Example
+    /// ```vhdl
+    /// `if VHDL_VERSION = "1993" then
+    /// -- This region is inactive unless the standard is VHDL 1993
+    /// `end if
+    /// ```
+    InactiveRegion,
+
+    /// A construct that is not part of the VHDL standard that the file is analyzed with
+    ///
+    /// # Example
+    /// ```vhdl
+    /// -- With standard = "1993"
+    /// process (all) is
+    /// begin
+    /// end process;
+    /// ```
+    RequiresNewerStandard,
+
     // Analysis
     /// A circular dependency was found where one module depends on another module which
     /// (directly or indirectly) again depends on the first module.
@@ -279,6 +321,17 @@ pub enum ErrorCode {
     /// Instantiating a function as procedure or vice-versa
     MismatchedSubprogramInstantiation,
 
+    /// A component declaration does not match the Verilog module that it is bound to
+    ///
+    /// # Example
+    /// ```vhdl
+    /// -- module fifo(input clk, output [7:0] dout);
+    /// component fifo is
+    ///     port (clk : in std_logic; din : in std_logic_vector(7 downto 0));
+    /// end component;
+    /// ```
+    ComponentMismatch,
+
     /// Function returns without a value
     VoidReturn,
 
@@ -469,6 +522,8 @@ impl Default for SeverityMap {
         use Severity::*;
         let map = enum_map! {
             SyntaxError
+            | DirectiveError
+            | RequiresNewerStandard
             | CircularDependency
             | InvalidFormal
             | InvalidFormalConversion
@@ -494,6 +549,7 @@ impl Default for SeverityMap {
             | SignatureMismatch
             | AmbiguousInstantiation
             | MismatchedSubprogramInstantiation
+            | ComponentMismatch
             | VoidReturn
             | NonVoidReturn
             | IllegalReturn
@@ -523,9 +579,10 @@ impl Default for SeverityMap {
             | UnnecessaryWorkLibrary
             | UnassociatedContext
             | MissingInSensitivityList
-            | SuperfluousInSensitivityList => Some(Warning),
+            | SuperfluousInSensitivityList
+            | DirectiveWarning => Some(Warning),
             Internal => Some(Error),
-            Related => Some(Hint)
+            Related | InactiveRegion => Some(Hint)
         };
         SeverityMap { inner: map }
     }
@@ -587,6 +644,22 @@ impl Diagnostic {
         Self::new(item, msg, ErrorCode::SyntaxError)
     }
 
+    pub fn requires_newer_standard(
+        item: impl AsRef<SrcPos>,
+        construct: impl AsRef<str>,
+        required: VHDLStandard,
+    ) -> Diagnostic {
+        Self::new(
+            item,
+            format!(
+                "{} requires VHDL {} or newer",
+                construct.as_ref(),
+                required.as_ref()
+            ),
+            ErrorCode::RequiresNewerStandard,
+        )
+    }
+
     pub fn circular_dependency(item: impl AsRef<SrcPos>) -> Diagnostic {
         Self::new(
             item,
@@ -5,6 +5,8 @@
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
 use super::Position;
+#[cfg(feature = "analysis-cache")]
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str;
 
@@ -51,6 +53,7 @@ pub fn char_to_latin1(chr: char) -> Option<u8> {
 }
 
 #[derive(PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct Latin1String {
     pub bytes: Vec<u8>,
 }
@@ -0,0 +1,166 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+use super::Symbol;
+use fnv::FnvHashMap;
+use std::sync::mpsc::{channel, Sender};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressStage {
+    /// Files are parsed
+    Parsing,
+    /// Design units are analyzed
+    Analyzing,
+}
+
+impl std::fmt::Display for ProgressStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProgressStage::Parsing => f.pad("Parsing"),
+            ProgressStage::Analyzing => f.pad("Analyzing"),
+        }
+    }
+}
+
+/// Reported each time a file has been parsed or a design unit has been analyzed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Progress {
+    pub stage: ProgressStage,
+    /// The library of the file or design unit
+    pub library: String,
+    /// Number of files or design units of the library that are done
+    pub library_done: usize,
+    /// Number of files or design units of the library
+    pub library_total: usize,
+    /// Number of files or design units of all libraries that are done
+    pub done: usize,
+    /// Number of files or design units of all libraries
+    pub total: usize,
+}
+
+/// Receives the progress of parsing and analysis.
+/// Progress is always reported on the thread that parses or analyzes the project.
+pub trait ProgressHandler {
+    fn progress(&mut self, progress: &Progress);
+}
+
+impl<F: FnMut(&Progress)> ProgressHandler for F {
+    fn progress(&mut self, progress: &Progress) {
+        self(progress)
+    }
+}
+
+/// Used by the worker threads to report that a file or a design unit is done
+pub(crate) struct ProgressReporter {
+    sender: Option<Sender<(Symbol, bool)>>,
+}
+
+impl ProgressReporter {
+    /// A file or design unit that belongs to the given libraries is done
+    pub fn done<'a>(&self, libraries: impl IntoIterator<Item = &'a Symbol>) {
+        if let Some(sender) = self.sender.as_ref() {
+            for (idx, library) in libraries.into_iter().enumerate() {
+                // The receiver only disconnects once the work is done
+                let _ = sender.send((library.clone(), idx == 0));
+            }
+        }
+    }
+}
+
+/// Run `work` and forward the progress it reports to `handler`.
+///
+/// When there is a handler, `work` runs on a separate thread while the progress is
+/// forwarded on the current thread. `total` is the number of items and `totals`
+/// the number of items per library, where an item may belong to several libraries.
+pub(crate) fn with_progress<T: Send>(
+    handler: Option<&mut dyn ProgressHandler>,
+    stage: ProgressStage,
+    total: usize,
+    totals: FnvHashMap<Symbol, usize>,
+    work: impl FnOnce(&ProgressReporter) -> T + Send,
+) -> T {
+    let Some(handler) = handler else {
+        return work(&ProgressReporter { sender: None });
+    };
+
+    let mut done = 0;
+    let mut library_done: FnvHashMap<Symbol, usize> = FnvHashMap::default();
+    let (sender, receiver) = channel();
+
+    std::thread::scope(|scope| {
+        let worker = scope.spawn(move || {
+            work(&ProgressReporter {
+                sender: Some(sender),
+            })
+        });
+
+        for (library, first) in receiver {
+            let count = library_done.entry(library.clone()).or_default();
+            *count += 1;
+            if first {
+                done += 1;
+            }
+            handler.progress(&Progress {
+                stage,
+                library: library.name_utf8(),
+                library_done: *count,
+                library_total: totals.get(&library).copied().unwrap_or_default(),
+                done,
+                total,
+            });
+        }
+
+        match worker.join() {
+            Ok(result) => result,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::SymbolTable;
+
+    #[test]
+    fn progress_is_counted_per_library() {
+        let symbols = SymbolTable::default();
+        let lib1 = symbols.insert_utf8("lib1");
+        let lib2 = symbols.insert_utf8("lib2");
+        let totals = FnvHashMap::from_iter([(lib1.clone(), 2), (lib2.clone(), 1)]);
+
+        let mut reports = Vec::new();
+        let result = with_progress(
+            Some(&mut |progress: &Progress| {
+                reports.push((
+                    progress.library.clone(),
+                    progress.library_done,
+                    progress.library_total,
+                    progress.done,
+                    progress.total,
+                ))
+            }),
+            ProgressStage::Parsing,
+            2,
+            totals,
+            |reporter| {
+                reporter.done([&lib1]);
+                reporter.done([&lib1, &lib2]);
+                42
+            },
+        );
+
+        assert_eq!(result, 42);
+        assert_eq!(
+            reports,
+            vec![
+                ("lib1".to_owned(), 1, 2, 1, 2),
+                ("lib1".to_owned(), 2, 2, 2, 2),
+                ("lib2".to_owned(), 1, 1, 2, 2),
+            ]
+        );
+    }
+}
@@ -4,8 +4,10 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::contents::Contents;
+use super::contents::{ChangedLines, Contents};
 use parking_lot::{RwLock, RwLockReadGuard};
+#[cfg(feature = "analysis-cache")]
+use serde::{Deserialize, Serialize};
 use std::cmp::{max, min};
 use std::collections::hash_map::DefaultHasher;
 use std::convert::AsRef;
@@ -154,6 +156,11 @@ impl Source {
         self.0.contents()
     }
 
+    /// The lines that changed since this function was last called
+    pub(crate) fn take_changed_lines(&self) -> ChangedLines {
+        self.0.contents.write().take_changed_lines()
+    }
+
     pub fn file_name(&self) -> &Path {
         self.0.file_name()
     }
@@ -177,10 +184,19 @@ impl Source {
             *contents = Contents::from_str(content);
         }
     }
+
+    /// Replace the contents with the contents of the file on disk,
+    /// e.g., when the unsaved changes of an editor are discarded
+    pub fn reload(&self) -> io::Result<()> {
+        let contents = Contents::from_latin1_file(self.file_name())?;
+        *self.0.contents.write() = contents;
+        Ok(())
+    }
 }
 
 /// A lexical position (line, column) in a source.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug, Default)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct Position {
     /// Line (zero-based).
     pub line: u32,
@@ -228,6 +244,7 @@ impl Position {
 
 /// A lexical range in a source.
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct Range {
     /// Start of the range (inclusive).
     pub start: Position,
@@ -243,6 +260,13 @@ impl Range {
     pub fn contains(&self, position: Position) -> bool {
         self.start <= position && self.end >= position
     }
+
+    /// The range moved up or down by a number of lines
+    pub(crate) fn shift_lines(self, lines: i64) -> Range {
+        let shift =
+            |pos: Position| Position::new((i64::from(pos.line) + lines) as u32, pos.character);
+        Range::new(shift(self.start), shift(self.end))
+    }
 }
 
 /// A lexical range within a specific source file.
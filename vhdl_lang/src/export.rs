@@ -0,0 +1,244 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Generation of compile scripts for simulators and synthesis tools
+
+use std::fmt::Write;
+use std::path::Path;
+
+use itertools::Itertools;
+use strum::{EnumString, IntoStaticStr};
+
+use crate::standard::VHDLStandard;
+
+/// The tools that compile scripts can be generated for.
+#[derive(PartialEq, Debug, Clone, Copy, Eq, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "kebab-case")]
+pub enum ExportTool {
+    /// A shell script invoking `ghdl -a`
+    Ghdl,
+    /// A shell script invoking `nvc -a`
+    Nvc,
+    /// A do-file invoking `vlib` and `vcom`
+    Modelsim,
+    /// A Tcl script invoking `read_vhdl`
+    VivadoTcl,
+}
+
+/// Generates a compile script for `tool`.
+///
+/// `files` contains the library name, the file name and the VHDL standard of every file that
+/// should be compiled. Files are compiled in the order they are given, which is usually the order
+/// returned by [Project::compile_order](crate::Project::compile_order).
+pub fn compile_script(tool: ExportTool, files: &[(String, &Path, VHDLStandard)]) -> String {
+    let mut script = String::new();
+    match tool {
+        ExportTool::Ghdl => {
+            script.push_str("#!/bin/sh\nset -e\n");
+            for (library, file_name, standard) in files {
+                let std = match standard {
+                    VHDLStandard::VHDL1993 => "93",
+                    VHDLStandard::VHDL2008 => "08",
+                    VHDLStandard::VHDL2019 => "19",
+                };
+                writeln!(
+                    script,
+                    "ghdl -a --std={std} --work={library} {}",
+                    shell_quote(file_name)
+                )
+                .unwrap();
+            }
+        }
+        ExportTool::Nvc => {
+            script.push_str("#!/bin/sh\nset -e\n");
+            for (library, file_name, standard) in files {
+                writeln!(
+                    script,
+                    "nvc --std={} --work={library} -a {}",
+                    standard.as_ref(),
+                    shell_quote(file_name)
+                )
+                .unwrap();
+            }
+        }
+        ExportTool::Modelsim => {
+            for library in files.iter().map(|(library, _, _)| library).unique() {
+                writeln!(script, "vlib {library}").unwrap();
+                writeln!(script, "vmap {library} {library}").unwrap();
+            }
+            for (library, file_name, standard) in files {
+                let std = match standard {
+                    VHDLStandard::VHDL1993 => "-93",
+                    VHDLStandard::VHDL2008 => "-2008",
+                    VHDLStandard::VHDL2019 => "-2019",
+                };
+                writeln!(
+                    script,
+                    "vcom {std} -work {library} {}",
+                    tcl_quote(file_name)
+                )
+                .unwrap();
+            }
+        }
+        ExportTool::VivadoTcl => {
+            for (library, file_name, standard) in files {
+                let std = match standard {
+                    VHDLStandard::VHDL1993 => "",
+                    VHDLStandard::VHDL2008 => " -vhdl2008",
+                    VHDLStandard::VHDL2019 => " -vhdl2019",
+                };
+                writeln!(
+                    script,
+                    "read_vhdl -library {library}{std} {}",
+                    tcl_quote(file_name)
+                )
+                .unwrap();
+            }
+        }
+    }
+    script
+}
+
+/// Quote a file name for a POSIX shell
+fn shell_quote(file_name: &Path) -> String {
+    format!("'{}'", file_name.to_string_lossy().replace('\'', "'\\''"))
+}
+
+/// Quote a file name for Tcl.
+/// Braces prevent substitution unless the file name itself contains braces or backslashes,
+/// in which case double quotes and escapes are used instead.
+fn tcl_quote(file_name: &Path) -> String {
+    let file_name = file_name.to_string_lossy();
+    if file_name.contains(['{', '}', '\\']) {
+        let mut quoted = String::from("\"");
+        for chr in file_name.chars() {
+            if matches!(chr, '"' | '\\' | '$' | '[' | ']' | '{' | '}') {
+                quoted.push('\\');
+            }
+            quoted.push(chr);
+        }
+        quoted.push('"');
+        quoted
+    } else {
+        format!("{{{file_name}}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn files(standard: VHDLStandard) -> Vec<(String, &'static Path, VHDLStandard)> {
+        vec![
+            ("lib1".to_owned(), Path::new("/src/pkg.vhd"), standard),
+            ("lib2".to_owned(), Path::new("/src/my ent.vhd"), standard),
+        ]
+    }
+
+    #[test]
+    fn parses_tool_names() {
+        assert_eq!(ExportTool::from_str("ghdl"), Ok(ExportTool::Ghdl));
+        assert_eq!(ExportTool::from_str("nvc"), Ok(ExportTool::Nvc));
+        assert_eq!(ExportTool::from_str("modelsim"), Ok(ExportTool::Modelsim));
+        assert_eq!(
+            ExportTool::from_str("vivado-tcl"),
+            Ok(ExportTool::VivadoTcl)
+        );
+        assert!(ExportTool::from_str("vivado").is_err());
+    }
+
+    #[test]
+    fn ghdl_script() {
+        assert_eq!(
+            compile_script(ExportTool::Ghdl, &files(VHDLStandard::VHDL2008)),
+            "\
+#!/bin/sh
+set -e
+ghdl -a --std=08 --work=lib1 '/src/pkg.vhd'
+ghdl -a --std=08 --work=lib2 '/src/my ent.vhd'
+"
+        );
+    }
+
+    #[test]
+    fn nvc_script() {
+        assert_eq!(
+            compile_script(ExportTool::Nvc, &files(VHDLStandard::VHDL2019)),
+            "\
+#!/bin/sh
+set -e
+nvc --std=2019 --work=lib1 -a '/src/pkg.vhd'
+nvc --std=2019 --work=lib2 -a '/src/my ent.vhd'
+"
+        );
+    }
+
+    #[test]
+    fn modelsim_script() {
+        assert_eq!(
+            compile_script(ExportTool::Modelsim, &files(VHDLStandard::VHDL1993)),
+            "\
+vlib lib1
+vmap lib1 lib1
+vlib lib2
+vmap lib2 lib2
+vcom -93 -work lib1 {/src/pkg.vhd}
+vcom -93 -work lib2 {/src/my ent.vhd}
+"
+        );
+    }
+
+    #[test]
+    fn vivado_script() {
+        assert_eq!(
+            compile_script(ExportTool::VivadoTcl, &files(VHDLStandard::VHDL2008)),
+            "\
+read_vhdl -library lib1 -vhdl2008 {/src/pkg.vhd}
+read_vhdl -library lib2 -vhdl2008 {/src/my ent.vhd}
+"
+        );
+    }
+
+    #[test]
+    fn files_are_compiled_with_their_own_standard() {
+        let files = vec![
+            (
+                "legacy".to_owned(),
+                Path::new("/src/old.vhd"),
+                VHDLStandard::VHDL1993,
+            ),
+            (
+                "lib".to_owned(),
+                Path::new("/src/new.vhd"),
+                VHDLStandard::VHDL2008,
+            ),
+        ];
+        assert_eq!(
+            compile_script(ExportTool::Ghdl, &files),
+            "\
+#!/bin/sh
+set -e
+ghdl -a --std=93 --work=legacy '/src/old.vhd'
+ghdl -a --std=08 --work=lib '/src/new.vhd'
+"
+        );
+        assert_eq!(
+            compile_script(ExportTool::VivadoTcl, &files),
+            "\
+read_vhdl -library legacy {/src/old.vhd}
+read_vhdl -library lib -vhdl2008 {/src/new.vhd}
+"
+        );
+    }
+
+    #[test]
+    fn quotes_special_characters() {
+        assert_eq!(shell_quote(Path::new("it's.vhd")), "'it'\\''s.vhd'");
+        assert_eq!(tcl_quote(Path::new("a{b.vhd")), "\"a\\{b.vhd\"");
+    }
+}
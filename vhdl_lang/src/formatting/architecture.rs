@@ -34,6 +34,7 @@ impl VHDLFormatter<'_> {
 
 #[cfg(test)]
 mod test {
+    use crate::formatting::VHDLFormatter;
     use crate::syntax::test::Code;
     use vhdl_lang::formatting::test_utils::check_formatted;
 
@@ -114,6 +115,44 @@ end foo;",
         );
     }
 
+    #[test]
+    fn format_architecture_with_psl_comments() {
+        check_architecture_formatted(
+            "\
+architecture foo of bar is
+    -- psl default clock is rising_edge(clk);
+begin
+    -- psl assert hs;
+    -- psl cover {req; ack};
+    -- psl check: assert always {req}  |=> ack;
+    -- psl cover {req;
+    --            ack};
+end foo;",
+        );
+    }
+
+    #[test]
+    fn format_architecture_with_psl_comments_that_are_comments() {
+        // Comments that are not parsed as PSL are part of the tokens of the design unit
+        let input = "\
+architecture foo of bar is
+    -- psl default clock is the rising edge of clk, see below
+    signal x: bit;
+begin
+    bar: process(clk) is
+    begin
+        -- psl assert always a -> next b;
+        x <= y;
+    end process bar;
+    -- psl assert that the fifo can't overflow; see below
+    -- psl cover the case where
+    --   both requests arrive
+    y <= x;
+end foo;";
+        let file = Code::new(input).design_file();
+        assert_eq!(VHDLFormatter::format_design_file(&file), input);
+    }
+
     #[test]
     fn format_architecture_preserve_whitespace() {
         check_architecture_formatted(
@@ -113,18 +113,7 @@ impl Buffer {
             self.line_break();
         }
         self.insert_extra_newline = false;
-        if let Some(comments) = &token.comments {
-            // This is for example the case for situations like
-            // some_token /* comment in between */ some_other token
-            if comments.leading.len() == 1
-                && leading_comment_is_on_token_line(&comments.leading[0], token)
-            {
-                self.format_comment(&comments.leading[0]);
-                self.push_ch(' ');
-            } else if !comments.leading.is_empty() {
-                self.format_leading_comments(comments.leading.as_slice());
-            }
-        }
+        self.push_leading_comments(token);
         match &token.value {
             Value::Identifier(ident) => self.push_str(&ident.to_string()),
             Value::String(string) => {
@@ -149,6 +138,25 @@ impl Buffer {
             Value::Text(text) => self.push_str(&text.to_string()),
             Value::None => self.push_str(kind_str(token.kind)),
         }
+        self.push_trailing_comment(token);
+    }
+
+    fn push_leading_comments(&mut self, token: &Token) {
+        if let Some(comments) = &token.comments {
+            // This is for example the case for situations like
+            // some_token /* comment in between */ some_other token
+            if comments.leading.len() == 1
+                && leading_comment_is_on_token_line(&comments.leading[0], token)
+            {
+                self.format_comment(&comments.leading[0]);
+                self.push_ch(' ');
+            } else if !comments.leading.is_empty() {
+                self.format_leading_comments(comments.leading.as_slice());
+            }
+        }
+    }
+
+    fn push_trailing_comment(&mut self, token: &Token) {
         if let Some(comments) = &token.comments {
             if let Some(trailing_comment) = &comments.trailing {
                 self.push_ch(' ');
@@ -158,6 +166,25 @@ impl Buffer {
         }
     }
 
+    /// Push source text that spans from the start of the `first` token to the end
+    /// of the `last` token unchanged except for the indentation of the first line.
+    /// Comments before `first` and after `last` are handled like they are for
+    /// [`Buffer::push_token`].
+    pub fn push_verbatim(&mut self, first: &Token, text: &str, last: &Token) {
+        if self.insert_extra_newline {
+            self.line_break();
+        }
+        self.insert_extra_newline = false;
+        self.push_leading_comments(first);
+        for (i, line) in text.lines().enumerate() {
+            if i > 0 {
+                self.line_break();
+            }
+            self.push_str(line.trim_end());
+        }
+        self.push_trailing_comment(last);
+    }
+
     fn push_str(&mut self, value: &str) {
         self.inner.push_str(value);
     }
@@ -64,6 +64,10 @@ impl VHDLFormatter<'_> {
         statement: &LabeledConcurrentStatement,
         buffer: &mut Buffer,
     ) {
+        if let ConcurrentStatement::PslDirective(_) = statement.statement.item {
+            self.format_token_span_verbatim(statement.span(), buffer);
+            return;
+        }
         self.format_optional_label(statement.label.tree.as_ref(), buffer);
         self.format_concurrent_statement(&statement.statement, buffer);
     }
@@ -91,6 +95,7 @@ impl VHDLFormatter<'_> {
             CaseGenerate(case_generate) => {
                 self.format_case_generate_statement(case_generate, span, buffer)
             }
+            PslDirective(_) => self.format_token_span_verbatim(span, buffer),
         }
     }
 
@@ -741,6 +746,17 @@ end process;",
         check_statement("assert false report \"message\" severity error;");
     }
 
+    #[test]
+    fn check_psl_directive() {
+        check_statement("assert always req -> next ack;");
+        check_statement("check: cover {req; busy[*1 to 3]; ack} report \"done\";");
+        check_statement(
+            "\
+assume always req ->
+    next_e[1 to 4](ack);",
+        );
+    }
+
     #[test]
     fn check_signal_assignment() {
         check_statement("foo <= bar(2 to 3);");
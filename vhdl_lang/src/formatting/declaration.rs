@@ -69,6 +69,7 @@ impl VHDLFormatter<'_> {
                 self.format_configuration_specification(configuration, buffer)
             }
             View(view_declaration) => self.format_view(view_declaration, declaration.span, buffer),
+            Psl(_) => self.format_token_span_verbatim(declaration.span, buffer),
         }
     }
 
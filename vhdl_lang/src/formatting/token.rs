@@ -27,6 +27,46 @@ impl VHDLFormatter<'_> {
         }
     }
 
+    /// Formats the tokens of a span as they appear in the source.
+    /// This is used for PSL statements whose layout is not formatted
+    /// and that may be embedded in comments.
+    pub(crate) fn format_token_span_verbatim(&self, span: TokenSpan, buffer: &mut Buffer) {
+        let first = self.tokens.index(span.start_token);
+        let last = self.tokens.index(span.end_token);
+        let start = first.pos.start();
+        let end = last.pos.end();
+        let contents = first.pos.source.contents();
+        let mut text = String::new();
+        let mut indentation = 0;
+        for lineno in start.line..=end.line {
+            let Some(line) = contents.get_line(lineno as usize) else {
+                break;
+            };
+            let mut line: Vec<char> = line.trim_end_matches(['\r', '\n']).chars().collect();
+            if lineno == end.line {
+                line.truncate(end.character as usize);
+            }
+            let mut line: String = line.into_iter().collect();
+            if lineno == start.line {
+                let prefix: String = line.chars().take(start.character as usize).collect();
+                // Statements within a PSL comment keep the comment prefix
+                indentation = prefix.find("--").unwrap_or(prefix.len());
+                line = line.split_off(indentation);
+            } else {
+                // Continuation lines keep their indentation relative to the first line
+                let whitespace = line
+                    .chars()
+                    .take(indentation)
+                    .take_while(|chr| chr.is_whitespace())
+                    .count();
+                line = line.split_off(whitespace);
+                text.push('\n');
+            }
+            text.push_str(&line);
+        }
+        buffer.push_verbatim(first, &text, last);
+    }
+
     pub(crate) fn join_token_span(&self, span: TokenSpan, buffer: &mut Buffer) {
         for id in span.iter() {
             self.format_token_id(id, buffer);
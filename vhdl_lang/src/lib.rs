@@ -14,8 +14,11 @@ extern crate self as vhdl_lang;
 pub mod ast;
 #[macro_use]
 mod analysis;
+#[cfg(feature = "analysis-cache")]
+mod cache;
 mod config;
 mod data;
+mod export;
 mod lint;
 mod named_entity;
 mod project;
@@ -27,15 +30,17 @@ mod standard;
 
 pub use crate::config::Config;
 pub use crate::data::{
-    Diagnostic, Latin1String, Message, MessageHandler, MessagePrinter, MessageType,
-    NullDiagnostics, NullMessages, Position, Range, Severity, SeverityMap, Source, SrcPos,
+    Diagnostic, ErrorCode, Latin1String, Message, MessageHandler, MessagePrinter, MessageType,
+    NullDiagnostics, NullMessages, Position, Progress, ProgressHandler, ProgressStage, Range,
+    Severity, SeverityMap, Source, SrcPos,
 };
+pub use crate::export::{compile_script, ExportTool};
 pub use formatting::VHDLFormatter;
 
-pub use crate::analysis::EntHierarchy;
+pub use crate::analysis::{DeclarationDetails, EntHierarchy, InterfaceDetails};
 pub use crate::named_entity::{
     AnyEnt, AnyEntKind, Concurrent, Design, EntRef, EntityId, HasEntityId, InterfaceEnt, Object,
-    Overloaded, Reference, Related, Sequential, Type,
+    Overloaded, Psl, Reference, Related, Sequential, Type,
 };
 
 pub use crate::project::{Project, SourceFile};
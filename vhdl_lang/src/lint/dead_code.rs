@@ -320,6 +320,37 @@ end architecture;",
         )
     }
 
+    #[test]
+    fn signals_used_in_psl_directives_are_used() {
+        let mut builder = LibraryBuilder::new();
+
+        let code = builder.code(
+            "libname",
+            "
+entity ent is
+end entity;
+
+architecture a of ent is
+  signal unused : bit;
+  signal req, ack : bit;
+  property handshake is always req -> next ack;
+begin
+  assert handshake;
+end architecture;",
+        );
+
+        let (root, diagnostics) = builder.get_analyzed_root();
+        check_no_diagnostics(&diagnostics);
+
+        let lib = root.get_lib(&root.symbol_utf8("libname")).unwrap();
+        let ent = get_ent(&root, code.s1("unused"));
+
+        check_unused(
+            find_unused_declarations(&root, lib, &root.symbol_utf8("ent")),
+            FnvHashSet::from_iter(vec![ent]),
+        )
+    }
+
     #[test]
     fn unused_ports() {
         let mut builder = LibraryBuilder::new();
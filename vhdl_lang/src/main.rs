@@ -10,47 +10,102 @@ use std::iter::zip;
 use std::path::{Path, PathBuf};
 use vhdl_lang::ast::DesignFile;
 use vhdl_lang::{
-    Config, Diagnostic, MessagePrinter, Project, Severity, SeverityMap, Source, VHDLFormatter,
+    compile_script, Config, Diagnostic, ErrorCode, ExportTool, Message, MessagePrinter, Progress,
+    ProgressHandler, ProgressStage, Project, Severity, SeverityMap, Source, VHDLFormatter,
     VHDLParser, VHDLStandard,
 };
 
 #[derive(Debug, clap::Args)]
-#[group(required = true, multiple = false)]
+#[group(required = true, multiple = true)]
 pub struct Group {
     /// Config file in TOML format containing libraries and settings
     #[arg(short, long)]
     config: Option<String>,
 
+    /// Import the library mapping of a simulator.
+    /// Either a modelsim.ini file, a GHDL library file such as lib-obj08.cf or a NVC library directory.
+    /// Can be given multiple times and is combined with the config file, if any.
+    #[arg(short, long)]
+    import: Vec<String>,
+
+    /// Read the library mapping from a VUnit export file created with `--export-json`.
+    /// Is combined with the config file, if any.
+    #[arg(long)]
+    vunit_export: Option<String>,
+
     /// Format the passed file and write the contents to stdout.
     ///
     /// This is experimental and the formatting behavior will change in the future.
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with_all = ["config", "import", "vunit_export"])]
     format: Option<String>,
 }
 
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Write a script to stdout that compiles all files of the project in dependency order
+    Export {
+        /// Config file in TOML format containing libraries and settings
+        #[arg(short, long)]
+        config: String,
+
+        /// The tool to generate the script for: ghdl, nvc, modelsim or vivado-tcl
+        #[arg(short, long)]
+        tool: ExportTool,
+    },
+    /// Inspect the configuration
+    Config {
+        /// Config file in TOML format containing libraries and settings
+        #[arg(short, long)]
+        config: Option<String>,
+
+        /// Print the resolved configuration including the standard libraries and all
+        /// included files. Each entry is annotated with the file that it was read from.
+        #[arg(long, required = true)]
+        show: bool,
+    },
+}
+
 /// Run vhdl analysis
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 struct Args {
     /// The number of threads to use. By default, the maximum is selected based on process cores
-    #[arg(short = 'p', long)]
+    #[arg(short = 'p', long, global = true)]
     num_threads: Option<usize>,
 
     /// Path to the config file for the VHDL standard libraries (i.e., IEEE std_logic_1164).
-    /// If omitted, will search for these libraries in a set of standard paths
-    #[arg(short = 'l', long)]
+    /// If omitted, the libraries that are embedded in the binary are used
+    #[arg(short = 'l', long, global = true)]
     libraries: Option<String>,
 
+    /// Show a progress bar on stderr while the project is parsed and analyzed
+    #[arg(long)]
+    progress: bool,
+
     #[clap(flatten)]
     group: Group,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
 fn main() {
     let args = Args::parse();
-    if let Some(config_path) = args.group.config {
-        parse_and_analyze_project(config_path, args.num_threads, args.libraries);
+    if let Some(Command::Export { config, tool }) = args.command {
+        export_compile_script(config, tool, args.num_threads, args.libraries);
+    } else if let Some(Command::Config { config, .. }) = args.command {
+        show_config(config, args.libraries);
     } else if let Some(format) = args.group.format {
         format_file(format);
+    } else {
+        parse_and_analyze_project(
+            args.group.config,
+            args.group.import,
+            args.group.vunit_export,
+            args.num_threads,
+            args.libraries,
+            args.progress,
+        );
     }
 }
 
@@ -103,9 +158,12 @@ fn check_formatted_file(path: &Path, parser: VHDLParser, design_file: DesignFile
 }
 
 fn parse_and_analyze_project(
-    config_path: String,
+    config_path: Option<String>,
+    imports: Vec<String>,
+    vunit_export: Option<String>,
     num_threads: Option<usize>,
     libraries: Option<String>,
+    show_progress: bool,
 ) {
     rayon::ThreadPoolBuilder::new()
         .num_threads(num_threads.unwrap_or(0))
@@ -115,15 +173,34 @@ fn parse_and_analyze_project(
     let mut config = Config::default();
     let mut msg_printer = MessagePrinter::default();
     config.load_external_config(&mut msg_printer, libraries.clone());
-    config.append(
-        &Config::read_file_path(Path::new(&config_path)).expect("Failed to read config file"),
-        &mut msg_printer,
-    );
+    for import in imports {
+        config.append(
+            &Config::import_file_path(Path::new(&import))
+                .expect("Failed to import library mapping"),
+            &mut msg_printer,
+        );
+    }
+    if let Some(vunit_export) = vunit_export {
+        config.append(
+            &Config::read_vunit_export(Path::new(&vunit_export))
+                .expect("Failed to read VUnit export file"),
+            &mut msg_printer,
+        );
+    }
+    if let Some(config_path) = config_path {
+        config.append(
+            &Config::read_file_path(Path::new(&config_path)).expect("Failed to read config file"),
+            &mut msg_printer,
+        );
+    }
 
     let severity_map = *config.severities();
-    let mut project = Project::from_config(config, &mut msg_printer);
+    let mut project = Project::new(config.standard());
+    let mut progress_bar = ProgressBar::new(show_progress);
+    project.update_config_with_progress(config, &mut msg_printer, &mut progress_bar);
     project.enable_all_linters();
-    let diagnostics = project.analyse();
+    let diagnostics = project.analyse_with_progress(&mut progress_bar);
+    progress_bar.finish();
 
     show_diagnostics(&diagnostics, &severity_map);
 
@@ -137,9 +214,127 @@ fn parse_and_analyze_project(
     }
 }
 
+/// Shows the progress of parsing and analysis on a single line of stderr
+struct ProgressBar {
+    enabled: bool,
+    shown: Option<(ProgressStage, usize)>,
+}
+
+impl ProgressBar {
+    const WIDTH: usize = 30;
+
+    fn new(enabled: bool) -> ProgressBar {
+        ProgressBar {
+            enabled,
+            shown: None,
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.shown.take().is_some() {
+            eprintln!();
+        }
+    }
+}
+
+impl ProgressHandler for ProgressBar {
+    fn progress(&mut self, progress: &Progress) {
+        if !self.enabled {
+            return;
+        }
+        // Only redraw when the bar changes
+        let filled = progress.done * Self::WIDTH / progress.total.max(1);
+        if self.shown == Some((progress.stage, filled)) && progress.done < progress.total {
+            return;
+        }
+        self.shown = Some((progress.stage, filled));
+        eprint!(
+            "\r{:<9} [{}{}] {}/{} {} {}/{}\x1b[K",
+            progress.stage,
+            "#".repeat(filled),
+            " ".repeat(Self::WIDTH - filled),
+            progress.done,
+            progress.total,
+            progress.library,
+            progress.library_done,
+            progress.library_total
+        );
+    }
+}
+
+fn export_compile_script(
+    config_path: String,
+    tool: ExportTool,
+    num_threads: Option<usize>,
+    libraries: Option<String>,
+) {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads.unwrap_or(0))
+        .build_global()
+        .unwrap();
+
+    // The script is written to stdout, so messages go to stderr
+    let mut messages: Vec<Message> = Vec::new();
+    let mut config = Config::default();
+    config.load_external_config(&mut messages, libraries);
+    let project_config =
+        Config::read_file_path(Path::new(&config_path)).expect("Failed to read config file");
+    config.append(&project_config, &mut messages);
+
+    let mut project = Project::from_config(config, &mut messages);
+    for message in messages {
+        eprintln!("{message}");
+    }
+    project.analyse();
+
+    // Only compile the libraries of the project, not the standard libraries of the tool
+    let project_libraries = project_config
+        .iter_libraries()
+        .map(|library| library.name().to_lowercase())
+        .collect_vec();
+    let files = project
+        .compile_order()
+        .into_iter()
+        .map(|(library, source)| (library.name_utf8(), source))
+        .filter(|(library, _)| project_libraries.contains(&library.to_lowercase()))
+        .collect_vec();
+    // Files are compiled with the standard of their library, if it differs from the project
+    let files = files
+        .iter()
+        .map(|(library, source)| {
+            (
+                library.clone(),
+                source.file_name(),
+                project.standard_of(source),
+            )
+        })
+        .collect_vec();
+
+    print!("{}", compile_script(tool, &files));
+}
+
+fn show_config(config_path: Option<String>, libraries: Option<String>) {
+    // The configuration is written to stdout, so messages go to stderr
+    let mut messages: Vec<Message> = Vec::new();
+    let mut config = Config::default();
+    config.load_external_config(&mut messages, libraries);
+    if let Some(config_path) = config_path {
+        config.append(
+            &Config::read_file_path(Path::new(&config_path)).expect("Failed to read config file"),
+            &mut messages,
+        );
+    }
+    for message in messages {
+        eprintln!("{message}");
+    }
+    print!("{}", config.to_resolved_string());
+}
+
 fn show_diagnostics(diagnostics: &[Diagnostic], severity_map: &SeverityMap) {
     let diagnostics = diagnostics
         .iter()
+        // Inactive regions are only of interest to editors
+        .filter(|diag| diag.code != ErrorCode::InactiveRegion)
         .filter_map(|diag| diag.show(severity_map))
         .collect_vec();
     for str in &diagnostics {
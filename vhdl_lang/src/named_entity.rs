@@ -28,6 +28,8 @@ pub use arena::{Arena, ArenaId, EntityId, FinalArena, Reference};
 mod visibility;
 pub(crate) use visibility::IntoUnambiguousError;
 pub use visibility::{Visibility, Visible};
+#[cfg(feature = "analysis-cache")]
+pub(crate) use visibility::{VisibleEntity, VisibleRegion};
 mod region;
 pub(crate) use region::RegionKind;
 pub use region::{AsUnique, NamedEntities, OverloadedName, Region, SetReference};
@@ -124,6 +126,8 @@ pub enum AnyEntKind<'a> {
     /// A VHDL 2019 View.
     /// The [Subtype] data is the type of the associated record.
     View(Subtype<'a>),
+    /// A PSL property or sequence declaration or a formal parameter thereof.
+    Psl(Psl),
 }
 
 impl<'a> AnyEntKind<'a> {
@@ -200,6 +204,7 @@ impl<'a> AnyEntKind<'a> {
             Design(design) => design.describe(),
             Type(typ) => typ.describe(),
             View(..) => "view",
+            Psl(psl) => psl.describe(),
         }
     }
 }
@@ -638,6 +643,27 @@ impl<'a> AnyEnt<'a> {
             self.unsafe_ref_mut().related = Related::DeclaredBy(ent);
         }
     }
+
+    // Used to fill in an entity that is restored from the cache once all entities
+    // that it refers to have been allocated
+    #[cfg(feature = "analysis-cache")]
+    pub(crate) unsafe fn restore(
+        &self,
+        parent: Option<EntRef<'a>>,
+        related: Related<'a>,
+        implicits: Vec<EntRef<'a>>,
+        kind: AnyEntKind<'a>,
+        attrs: FnvHashMap<Symbol, (SrcPos, AttributeEnt<'a>)>,
+    ) {
+        unsafe {
+            let ent = self.unsafe_ref_mut();
+            ent.parent = parent;
+            ent.related = related;
+            ent.implicits = implicits;
+            ent.kind = kind;
+            ent.attrs = attrs;
+        }
+    }
 }
 
 impl std::cmp::PartialEq for AnyEnt<'_> {
@@ -826,6 +852,10 @@ impl SubprogramSpecification {
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(
+    feature = "analysis-cache",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Concurrent {
     Block,
     Process,
@@ -845,6 +875,31 @@ impl Concurrent {
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(
+    feature = "analysis-cache",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+pub enum Psl {
+    Property,
+    Sequence,
+    Parameter,
+}
+
+impl Psl {
+    fn describe(&self) -> &'static str {
+        match self {
+            Psl::Property => "property",
+            Psl::Sequence => "sequence",
+            Psl::Parameter => "PSL parameter",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(
+    feature = "analysis-cache",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum Sequential {
     Loop,
     If,
@@ -25,12 +25,19 @@ use super::EntRef;
 use super::Related;
 use super::TypeEnt;
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub struct ArenaId(u32);
 
 // Reserve 0 for standard package
 static ACOUNTER: AtomicU32 = AtomicU32::new(1);
 
+impl ArenaId {
+    /// The arena of the standard package
+    pub(crate) fn std() -> ArenaId {
+        ArenaId(0)
+    }
+}
+
 impl Default for ArenaId {
     fn default() -> Self {
         ArenaId(ACOUNTER.fetch_add(1, Ordering::Relaxed))
@@ -128,6 +135,23 @@ impl<'a> FinalArena {
             .is_some_and(|local_arena| local_arena.contains(id.local_id()))
     }
 
+    /// The ids of all arenas that entities may be taken from
+    #[cfg(feature = "analysis-cache")]
+    pub(crate) fn arena_ids(&self) -> impl Iterator<Item = ArenaId> + '_ {
+        self.refs.keys().map(|id| ArenaId(*id))
+    }
+
+    /// The entities of one of the arenas in the order they were allocated
+    #[cfg(feature = "analysis-cache")]
+    pub(crate) fn entities_of(&'a self, id: ArenaId) -> impl Iterator<Item = EntRef<'a>> + 'a {
+        let local_arena = self.refs.get(&id.0);
+        let len = local_arena.map_or(0, |local_arena| local_arena.items.len());
+        (0..len).map(move |idx| unsafe {
+            let ent = local_arena.unwrap().get(LocalId(idx as u32));
+            &*ent as EntRef<'a>
+        })
+    }
+
     pub fn link(&mut self, referenced: &FinalArena) {
         for (id, arena) in referenced.refs.iter() {
             self.refs.entry(*id).or_insert_with(|| arena.clone());
@@ -157,7 +181,7 @@ impl Arena {
     // Pre-defined id to store standard package
     pub fn new_std() -> Self {
         Self {
-            local: RefCell::new(LocalArena::new(ArenaId(0))),
+            local: RefCell::new(LocalArena::new(ArenaId::std())),
             refs: Default::default(),
         }
     }
@@ -280,6 +304,17 @@ impl Arena {
         }
     }
 
+    /// Returns whether the id refers to an entity of the local arena or of a linked arena
+    #[cfg(feature = "analysis-cache")]
+    pub(crate) fn is_valid_id(&self, id: EntityId) -> bool {
+        let local = self.local.borrow();
+        if local.id == id.arena_id() {
+            local.contains(id.local_id())
+        } else {
+            self.refs.borrow().is_valid_id(id)
+        }
+    }
+
     pub fn get_type(&self, id: EntityId) -> TypeEnt<'_> {
         TypeEnt::from_any(self.get(id)).unwrap()
     }
@@ -320,6 +355,18 @@ impl EntityId {
         LocalId((self.id & (u32::MAX as usize)) as u32)
     }
 
+    /// The position of the entity within its arena
+    #[cfg(feature = "analysis-cache")]
+    pub(crate) fn local_index(&self) -> u32 {
+        self.local_id().0
+    }
+
+    /// The entity at a position within an arena
+    #[cfg(feature = "analysis-cache")]
+    pub(crate) fn from_local_index(arena_id: ArenaId, index: u32) -> EntityId {
+        EntityId::new_arena(arena_id, LocalId(index))
+    }
+
     /// Returns an `EntityId` from a raw `usize` value
     /// for deserialization purposes.
     pub(crate) fn from_raw(id: usize) -> EntityId {
@@ -376,6 +423,13 @@ impl Reference {
         self.id.store(UNDEFINED_ID, Ordering::Relaxed);
     }
 
+    /// Sets the reference of a design unit whose analysis is restored from the cache
+    #[cfg(feature = "analysis-cache")]
+    pub(crate) fn restore(&self, id: Option<EntityId>) {
+        let id = id.map_or(UNDEFINED_ID, |id| id.to_raw());
+        self.id.store(id, Ordering::Relaxed);
+    }
+
     pub(crate) fn set(&mut self, id: EntityId) {
         // We only clear in a single thread so relaxed ordering should be fine
         self.id.store(id.to_raw(), Ordering::Relaxed);
@@ -41,6 +41,12 @@ impl<'a> InterfaceEnt<'a> {
         self.ent
     }
 
+    /// An interface element whose kind has not been restored from the cache yet
+    #[cfg(feature = "analysis-cache")]
+    pub(crate) fn from_any_unchecked(ent: EntRef<'a>) -> Self {
+        InterfaceEnt { ent }
+    }
+
     pub fn from_any(ent: EntRef<'a>) -> Option<Self> {
         match ent.kind() {
             AnyEntKind::Object(Object { iface: Some(_), .. })
@@ -223,7 +223,7 @@ impl<'a> Region<'a> {
 #[derive(Clone, Debug, PartialEq, Eq)]
 /// A non-empty collection of overloaded entities
 pub struct OverloadedName<'a> {
-    entities: FnvHashMap<SubprogramKey<'a>, OverloadedEnt<'a>>,
+    pub(crate) entities: FnvHashMap<SubprogramKey<'a>, OverloadedEnt<'a>>,
 }
 
 impl<'a> OverloadedName<'a> {
@@ -372,6 +372,10 @@ impl<'a> NamedEntities<'a> {
 }
 
 #[derive(Copy, Clone, PartialEq, Default)]
+#[cfg_attr(
+    feature = "analysis-cache",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub(crate) enum RegionKind {
     PackageDeclaration,
     PackageBody,
@@ -41,6 +41,10 @@ pub enum Type<'a> {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "analysis-cache",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum UniversalType {
     Real,
     Integer,
@@ -151,6 +155,12 @@ impl<'a> TypeEnt<'a> {
         TypeEnt(ent)
     }
 
+    /// A type whose kind has not been restored from the cache yet
+    #[cfg(feature = "analysis-cache")]
+    pub(crate) fn from_any_unchecked(ent: EntRef<'a>) -> TypeEnt<'a> {
+        TypeEnt(ent)
+    }
+
     pub fn from_any(ent: EntRef<'a>) -> Option<TypeEnt<'a>> {
         if matches!(ent.kind(), AnyEntKind::Type(..)) {
             Some(TypeEnt(ent))
@@ -314,6 +324,12 @@ impl<'a> Deref for TypeEnt<'a> {
 pub struct BaseType<'a>(EntRef<'a>);
 
 impl<'a> BaseType<'a> {
+    /// A base type whose kind has not been restored from the cache yet
+    #[cfg(feature = "analysis-cache")]
+    pub(crate) fn from_any_unchecked(ent: EntRef<'a>) -> BaseType<'a> {
+        BaseType(ent)
+    }
+
     pub fn kind(&self) -> &'a Type<'a> {
         if let AnyEntKind::Type(typ) = self.0.kind() {
             typ
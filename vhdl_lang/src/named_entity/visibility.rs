@@ -10,10 +10,10 @@ use fnv::FnvHashMap;
 use std::collections::hash_map::Entry;
 
 #[derive(Clone, Debug)]
-struct VisibleEntity<'a> {
+pub(crate) struct VisibleEntity<'a> {
     // The position where the entity was made visible
-    visible_pos: Vec<Option<SrcPos>>,
-    entity: EntRef<'a>,
+    pub(crate) visible_pos: Vec<Option<SrcPos>>,
+    pub(crate) entity: EntRef<'a>,
 }
 
 #[derive(Debug)]
@@ -75,8 +75,8 @@ impl<'a> VisibleEntity<'a> {
 #[derive(Clone)]
 pub struct VisibleRegion<'a> {
     // The position where the entity was made visible
-    visible_pos: Vec<Option<SrcPos>>,
-    region: &'a Region<'a>,
+    pub(crate) visible_pos: Vec<Option<SrcPos>>,
+    pub(crate) region: &'a Region<'a>,
 }
 
 impl<'a> VisibleRegion<'a> {
@@ -95,8 +95,11 @@ impl<'a> VisibleRegion<'a> {
 #[derive(Clone, Default)]
 pub struct Visibility<'a> {
     // TODO store unique regions
-    all_in_regions: Vec<VisibleRegion<'a>>,
-    visible: FnvHashMap<Designator, FnvHashMap<EntityId, VisibleEntity<'a>>>,
+    pub(crate) all_in_regions: Vec<VisibleRegion<'a>>,
+    pub(crate) visible: FnvHashMap<Designator, FnvHashMap<EntityId, VisibleEntity<'a>>>,
+    // All names of a unit of a black-box library were made visible.
+    // Since the unit is opaque, any name might be declared by it.
+    pub(crate) black_box: bool,
 }
 
 impl<'a> Visibility<'a> {
@@ -111,6 +114,14 @@ impl<'a> Visibility<'a> {
         });
     }
 
+    pub fn make_black_box_visible(&mut self) {
+        self.black_box = true;
+    }
+
+    pub fn has_black_box_visibility(&self) -> bool {
+        self.black_box
+    }
+
     pub fn all_in_region(&self) -> impl Iterator<Item = &VisibleRegion<'a>> {
         self.all_in_regions.iter()
     }
@@ -124,6 +135,7 @@ impl<'a> Visibility<'a> {
         visible_pos: Option<&SrcPos>,
         visibility: &Visibility<'a>,
     ) {
+        self.black_box |= visibility.black_box;
         for visible_region in visibility.all_in_regions.iter() {
             self.all_in_regions
                 .push(visible_region.clone_with_more_visiblity(visible_pos));
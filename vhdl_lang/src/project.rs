@@ -4,11 +4,13 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use crate::analysis::DesignRoot;
+use crate::analysis::{DeclarationDetails, DesignRoot};
 use crate::ast::search::Searcher;
 use crate::ast::DesignFile;
+#[cfg(feature = "analysis-cache")]
+use crate::cache::DesignFileCache;
 use crate::completion::{list_completion_options, CompletionItem};
-use crate::config::Config;
+use crate::config::{is_json_stub, read_json_stub, Config};
 use crate::lint::dead_code::UnusedDeclarationsLinter;
 use crate::lint::sensitivity_list::SensitivityListLinter;
 use crate::named_entity::EntRef;
@@ -56,6 +58,14 @@ impl Project {
         self.lint.sensitivity_list = Some(SensitivityListLinter::default());
     }
 
+    pub fn disable_unused_declaration_detection(&mut self) {
+        self.lint.unused_declarations = None;
+    }
+
+    pub fn disable_sensitivity_list_linting(&mut self) {
+        self.lint.sensitivity_list = None;
+    }
+
     pub fn enable_all_linters(&mut self) {
         self.enable_unused_declaration_detection();
         self.enable_sensitivity_list_linting();
@@ -65,9 +75,7 @@ impl Project {
     /// Files referred by configuration are parsed into corresponding libraries.
     pub fn from_config(config: Config, messages: &mut dyn MessageHandler) -> Project {
         let mut project = Project::new(config.standard());
-        let files = project.load_files_from_config(&config, messages);
-        project.parse_and_add_files(files, messages);
-        project.config = config;
+        project.update_config(config, messages);
         project
     }
 
@@ -75,8 +83,40 @@ impl Project {
     /// The design state is reset, new files are added and parsed. Existing source files will be
     /// kept and parsed from in-memory source (required for incremental document updates).
     pub fn update_config(&mut self, config: Config, messages: &mut dyn MessageHandler) {
-        self.parser = VHDLParser::new(config.standard());
+        self.update_config_and_report(config, messages, None);
+    }
+
+    /// Like `update_config` but reports each parsed file to the progress handler
+    pub fn update_config_with_progress(
+        &mut self,
+        config: Config,
+        messages: &mut dyn MessageHandler,
+        progress: &mut dyn ProgressHandler,
+    ) {
+        self.update_config_and_report(config, messages, Some(progress));
+    }
+
+    fn update_config_and_report(
+        &mut self,
+        config: Config,
+        messages: &mut dyn MessageHandler,
+        progress: Option<&mut dyn ProgressHandler>,
+    ) {
+        self.parser = VHDLParser::new(config.standard()).with_defines(config.defines().clone());
+        let open_sources = self.root.take_open_sources();
         self.root = DesignRoot::new(self.parser.symbols.clone());
+        self.root.set_open_sources(open_sources);
+
+        #[cfg(feature = "analysis-cache")]
+        if let Some(directory) = config.cache_directory() {
+            self.root.set_cache_directory(directory);
+        }
+        #[cfg(not(feature = "analysis-cache"))]
+        if config.cache_directory().is_some() {
+            messages.push(Message::warning(
+                "Ignoring the cache directory since vhdl_lang was built without the analysis-cache feature",
+            ));
+        }
 
         // Reset library associations for known files,
         // all project files are added to the corresponding libraries later on.
@@ -84,52 +124,122 @@ impl Project {
             .values_mut()
             .for_each(|source_file| source_file.library_names.clear());
 
-        // Files might already be part of self.files, these have to be parsed
-        // from in-memory source. New files can be parsed as usual.
-        let (known_files, new_files) = self
-            .load_files_from_config(&config, messages)
-            .into_iter()
-            .partition(|(file_name, _library_names)| self.files.contains_key(file_name));
-
-        for (file_name, library_names) in known_files {
-            if let Some(source_file) = self.files.get_mut(&file_name) {
-                source_file.parser_diagnostics.clear();
-                source_file.library_names = library_names;
-                source_file.design_file = self
-                    .parser
-                    .parse_design_source(&source_file.source, &mut source_file.parser_diagnostics);
-            }
-        }
-
+        let files_to_parse = self.load_files_from_config(&config, messages);
         self.config = config;
-        self.parse_and_add_files(new_files, messages);
+        self.parse_and_add_files(files_to_parse, messages, progress);
+    }
+
+    /// The active project configuration
+    pub fn config(&self) -> &Config {
+        &self.config
     }
 
+    /// Returns the libraries and the standard of each file of the configuration.
+    /// A file that is part of libraries with different standards uses the newest standard.
     fn load_files_from_config(
         &mut self,
         config: &Config,
         messages: &mut dyn MessageHandler,
-    ) -> FnvHashMap<FilePath, FnvHashSet<Symbol>> {
-        let mut files: FnvHashMap<FilePath, FnvHashSet<Symbol>> = FnvHashMap::default();
+    ) -> FnvHashMap<FilePath, (FnvHashSet<Symbol>, VHDLStandard)> {
+        let mut files: FnvHashMap<FilePath, (FnvHashSet<Symbol>, VHDLStandard)> =
+            FnvHashMap::default();
         self.empty_libraries.clear();
 
         for library in config.iter_libraries() {
             let library_name =
                 Latin1String::from_utf8(library.name()).expect("Library name not latin-1 encoded");
             let library_name = self.parser.symbol(&library_name);
+            let standard = config.library_standard(library);
+            if standard != config.standard() {
+                self.root
+                    .set_library_standard(library_name.clone(), standard);
+            }
+
+            // In-memory sources are added to the known files
+            // such that they are parsed like files that have been opened
+            for source in library.sources() {
+                self.files
+                    .entry(source.file_path().to_owned())
+                    .or_insert_with(|| SourceFile {
+                        source: source.clone(),
+                        library_names: FnvHashSet::default(),
+                        standard,
+                        parser_diagnostics: vec![],
+                        design_file: DesignFile::default(),
+                    });
+            }
+
+            if library.black_box {
+                self.root.set_library_black_box(library_name.clone());
+            }
+            if library.is_third_party && config.lazy_analysis() {
+                self.root.set_library_lazy(library_name.clone());
+            }
+            #[cfg(feature = "analysis-cache")]
+            if library.is_third_party && config.cache_directory().is_some() {
+                self.root.set_library_cached(library_name.clone());
+            }
+
+            let mut stub_file_names = library.stub_file_names(messages);
+            // JSON stubs are converted into VHDL and parsed like in-memory sources
+            stub_file_names.retain(|file_name| {
+                if !is_json_stub(file_name) {
+                    return true;
+                }
+                match read_json_stub(file_name) {
+                    Ok(source) => {
+                        self.files.insert(
+                            source.file_path().to_owned(),
+                            SourceFile {
+                                source,
+                                library_names: FnvHashSet::default(),
+                                standard,
+                                parser_diagnostics: vec![],
+                                design_file: DesignFile::default(),
+                            },
+                        );
+                        true
+                    }
+                    Err(err) => {
+                        messages.push(Message::error(err));
+                        false
+                    }
+                }
+            });
+            if !stub_file_names.is_empty() {
+                self.root.set_library_stubs(
+                    library_name.clone(),
+                    stub_file_names
+                        .iter()
+                        .map(|file_name| FilePath::new(file_name)),
+                );
+            }
 
             let mut empty_library = true;
-            for file_name in library.file_names(messages) {
+            for file_name in library
+                .file_names(messages)
+                .into_iter()
+                .chain(stub_file_names)
+                .chain(
+                    library
+                        .sources()
+                        .iter()
+                        .map(|source| source.file_name().to_owned()),
+                )
+            {
                 empty_library = false;
+                let standard = config.file_standard(library, &file_name);
 
                 match files.entry(FilePath::new(&file_name)) {
                     Entry::Occupied(mut entry) => {
-                        entry.get_mut().insert(library_name.clone());
+                        let (library_names, file_standard) = entry.get_mut();
+                        library_names.insert(library_name.clone());
+                        *file_standard = (*file_standard).max(standard);
                     }
                     Entry::Vacant(entry) => {
                         let mut set = FnvHashSet::default();
                         set.insert(library_name.clone());
-                        entry.insert(set);
+                        entry.insert((set, standard));
                     }
                 }
             }
@@ -141,26 +251,75 @@ impl Project {
         files
     }
 
+    /// Files might already be part of the project, these are parsed from the in-memory source.
+    /// New files are read from disk.
     fn parse_and_add_files(
         &mut self,
-        files_to_parse: FnvHashMap<FilePath, FnvHashSet<Symbol>>,
+        files_to_parse: FnvHashMap<FilePath, (FnvHashSet<Symbol>, VHDLStandard)>,
         messages: &mut dyn MessageHandler,
+        progress: Option<&mut dyn ProgressHandler>,
     ) {
         use rayon::prelude::*;
 
-        let parsed: Vec<_> = files_to_parse
-            .into_par_iter()
-            .map_init(
-                || &self.parser,
-                |parser, (file_name, library_names)| {
-                    let mut diagnostics = Vec::new();
-                    let result = parser.parse_design_file(&file_name, &mut diagnostics);
-                    (file_name, library_names, diagnostics, result)
-                },
-            )
-            .collect();
+        let mut totals: FnvHashMap<Symbol, usize> = FnvHashMap::default();
+        for (library_names, _) in files_to_parse.values() {
+            for library_name in library_names.iter() {
+                *totals.entry(library_name.clone()).or_default() += 1;
+            }
+        }
+        let total = files_to_parse.len();
+        let (known_files, new_files): (FnvHashMap<_, _>, FnvHashMap<_, _>) = files_to_parse
+            .into_iter()
+            .partition(|(file_name, _mapping)| self.files.contains_key(file_name));
+
+        let source_parser = SourceParser::new(&self.config, &self.parser);
+        let parser = &self.parser;
+        let files = &mut self.files;
+        let parsed: Vec<_> = with_progress(
+            progress,
+            ProgressStage::Parsing,
+            total,
+            totals,
+            |reporter| {
+                for (file_name, (library_names, standard)) in known_files {
+                    if let Some(source_file) = files.get_mut(&file_name) {
+                        reporter.done(&library_names);
+                        source_file.parser_diagnostics.clear();
+                        source_file.design_file = source_parser.parse(
+                            &parser.with_standard(standard),
+                            &library_names,
+                            &source_file.source,
+                            &mut source_file.parser_diagnostics,
+                        );
+                        source_file.library_names = library_names;
+                        source_file.standard = standard;
+                    }
+                }
 
-        for (file_name, library_names, parser_diagnostics, result) in parsed.into_iter() {
+                new_files
+                    .into_par_iter()
+                    .map_init(
+                        || parser,
+                        |parser, (file_name, (library_names, standard))| {
+                            let mut diagnostics = Vec::new();
+                            let result = Source::from_latin1_file(&file_name).map(|source| {
+                                let design_file = source_parser.parse(
+                                    &parser.with_standard(standard),
+                                    &library_names,
+                                    &source,
+                                    &mut diagnostics,
+                                );
+                                (source, design_file)
+                            });
+                            reporter.done(&library_names);
+                            (file_name, library_names, standard, diagnostics, result)
+                        },
+                    )
+                    .collect()
+            },
+        );
+
+        for (file_name, library_names, standard, parser_diagnostics, result) in parsed.into_iter() {
             let (source, design_file) = match result {
                 Ok(result) => result,
                 Err(err) => {
@@ -174,11 +333,19 @@ impl Project {
                 SourceFile {
                     source,
                     library_names,
+                    standard,
                     parser_diagnostics,
                     design_file,
                 },
             );
         }
+
+        // Report a cache that cannot be written once rather than for every file
+        if let Some(err) = source_parser.write_error() {
+            messages.push(Message::warning(format!(
+                "Cannot write to the cache directory: {err}"
+            )));
+        }
     }
 
     pub fn library_mapping_of(&self, source: &Source) -> Vec<Symbol> {
@@ -192,47 +359,202 @@ impl Project {
         libs
     }
 
+    /// The VHDL standard that a source is parsed and analyzed with,
+    /// i.e., the standard of its library or of the project
+    pub fn standard_of(&self, source: &Source) -> VHDLStandard {
+        self.files
+            .get(source.file_path())
+            .map_or(self.parser.standard, |file| file.standard)
+    }
+
     pub fn get_source(&self, file_name: &Path) -> Option<Source> {
         self.files
             .get(&FilePath::new(file_name))
             .map(|file| file.source.clone())
     }
 
-    pub fn update_source(&mut self, source: &Source) {
-        let mut source_file = {
-            if let Some(mut source_file) = self.files.remove(source.file_path()) {
-                // File is already part of the project
-                for library_name in source_file.library_names.iter() {
-                    self.root.remove_source(library_name.clone(), source);
-                }
-                source_file.source = source.clone();
-                source_file
-            } else {
-                // File is not part of the project
-                // @TODO use config wildcards to map to library
+    /// Mark a source as open or closed in an editor.
+    /// The units of open files are analyzed even if their library is analyzed lazily.
+    pub fn set_source_open(&mut self, source: &Source, is_open: bool) {
+        self.root.set_source_open(source, is_open);
+    }
 
-                // Add unmapped files to an anonymous library work
-                // To still get some semantic analysis for unmapped files
-                let mut library_names = FnvHashSet::default();
-                library_names.insert(self.root.symbol_utf8("work"));
+    /// Add a file that was created after the configuration was loaded
+    /// to the libraries whose file patterns match it.
+    /// Returns the source of the file or `None` if it does not belong to any library.
+    pub fn add_file(
+        &mut self,
+        file_name: &Path,
+        messages: &mut dyn MessageHandler,
+    ) -> Option<Source> {
+        let mut library_names = FnvHashSet::default();
+        let mut file_standard = None;
+        for library in self.config.iter_libraries() {
+            if !library.matches_file(file_name) {
+                continue;
+            }
+            let library_name =
+                Latin1String::from_utf8(library.name()).expect("Library name not latin-1 encoded");
+            let library_name = self.parser.symbol(&library_name);
+            self.empty_libraries.remove(&library_name);
+            library_names.insert(library_name);
+            // Like in the configuration the newest standard of the libraries is used
+            file_standard = file_standard.max(Some(self.config.file_standard(library, file_name)));
+        }
+        let standard = file_standard?;
 
-                SourceFile {
-                    source: source.clone(),
-                    library_names,
-                    parser_diagnostics: vec![],
-                    design_file: DesignFile::default(),
-                }
+        // A file that was opened before it matched a library is moved to its libraries
+        let file_path = FilePath::new(file_name);
+        if let Some(source_file) = self.files.get_mut(&file_path) {
+            for library_name in source_file.library_names.drain() {
+                self.root.remove_source(library_name, &source_file.source);
             }
+        }
+
+        let mut files_to_parse = FnvHashMap::default();
+        files_to_parse.insert(file_path, (library_names, standard));
+        self.parse_and_add_files(files_to_parse, messages, None);
+        self.get_source(file_name)
+    }
+
+    /// Remove a file that was deleted or closed from the project.
+    /// Returns false if the file was not part of the project.
+    pub fn remove_file(&mut self, file_name: &Path) -> bool {
+        let Some(source_file) = self.files.remove(&FilePath::new(file_name)) else {
+            return false;
+        };
+        for library_name in source_file.library_names {
+            self.root.remove_source(library_name, &source_file.source);
+        }
+        self.root.set_source_open(&source_file.source, false);
+        true
+    }
+
+    pub fn update_source(&mut self, source: &Source) {
+        if let Some(mut source_file) = self.files.remove(source.file_path()) {
+            // File is already part of the project
+            source_file.source = source.clone();
+            self.reparse_source_file(&mut source_file);
+            self.files
+                .insert(source.file_path().to_owned(), source_file);
+            return;
+        }
+
+        // File is not part of the project
+        // @TODO use config wildcards to map to library
+
+        // Add unmapped files to an anonymous library work
+        // To still get some semantic analysis for unmapped files
+        let mut library_names = FnvHashSet::default();
+        library_names.insert(self.root.symbol_utf8("work"));
+
+        let mut source_file = SourceFile {
+            source: source.clone(),
+            library_names,
+            standard: self.parser.standard,
+            parser_diagnostics: vec![],
+            design_file: DesignFile::default(),
         };
-        source_file.parser_diagnostics.clear();
         source_file.design_file = self
             .parser
+            .with_standard(source_file.standard)
             .parse_design_source(source, &mut source_file.parser_diagnostics);
         self.files
             .insert(source.file_path().to_owned(), source_file);
     }
 
+    /// Re-parse a file of the project after an edit.
+    /// Design units whose tokens did not change are kept together with their analysis results.
+    fn reparse_source_file(&mut self, source_file: &mut SourceFile) {
+        let source = source_file.source.clone();
+
+        // Design units that have not been analyzed yet are added first
+        // such that they can be kept as well
+        let design_file = source_file.take_design_file();
+        let mut design_files = multiply(design_file, source_file.library_names.len());
+        for library_name in source_file.library_names.iter() {
+            let design_file = design_files.pop().unwrap();
+            self.root.add_design_file(library_name.clone(), design_file);
+        }
+
+        let mut library_names = source_file.library_names.iter();
+        let previous_units = library_names
+            .next()
+            .map(|library_name| self.root.source_unit_tokens(library_name, &source))
+            .unwrap_or_default();
+
+        let mut diagnostics = Vec::new();
+        let (unchanged, design_file) = self
+            .parser
+            .with_standard(source_file.standard)
+            .reparse_design_source(&source, &previous_units, &mut diagnostics);
+
+        let mut kept = FnvHashMap::default();
+        let mut kept_ranges = Vec::with_capacity(unchanged.len());
+        for (idx, lines) in unchanged {
+            let tokens = previous_units[idx];
+            let start = tokens[0].pos.start();
+            kept.insert(start, lines);
+            kept_ranges.push((start, tokens[tokens.len() - 1].pos.end(), lines));
+        }
+
+        // Keep the diagnostics of the unchanged design units
+        let parser_diagnostics = std::mem::take(&mut source_file.parser_diagnostics);
+        for mut diagnostic in parser_diagnostics {
+            if diagnostic.pos.source() != &source {
+                continue;
+            }
+            let lines = kept_ranges.iter().find_map(|(start, end, lines)| {
+                (*start <= diagnostic.pos.start() && diagnostic.pos.end() <= *end).then_some(*lines)
+            });
+            if let Some(lines) = lines {
+                if lines != 0 {
+                    diagnostic = diagnostic.shift_lines(lines);
+                }
+                source_file.parser_diagnostics.push(diagnostic);
+            }
+        }
+        source_file.parser_diagnostics.extend(diagnostics);
+
+        for library_name in source_file.library_names.iter() {
+            self.root
+                .remove_source_units(library_name.clone(), &source, &kept);
+        }
+        source_file.design_file = design_file;
+    }
+
+    /// A read-only copy of the project as of its last analysis, e.g., to serve requests
+    /// while the project is analyzed on another thread.
+    /// Design units that have not been analyzed, such as those of lazy libraries, are left out.
+    /// The analysis results are shared with the project, such that the copy is cheap.
+    pub fn snapshot(&self) -> Project {
+        Project {
+            parser: self.parser.with_standard(self.parser.standard),
+            config: self.config.clone(),
+            root: self.root.snapshot(),
+            files: self
+                .files
+                .iter()
+                .map(|(file_path, source_file)| (file_path.clone(), source_file.snapshot()))
+                .collect(),
+            empty_libraries: self.empty_libraries.clone(),
+            lint: Linters::default(),
+        }
+    }
+
     pub fn analyse(&mut self) -> Vec<Diagnostic> {
+        self.analyse_and_report(None)
+    }
+
+    /// Like `analyse` but reports each analyzed design unit to the progress handler
+    pub fn analyse_with_progress(&mut self, progress: &mut dyn ProgressHandler) -> Vec<Diagnostic> {
+        self.analyse_and_report(Some(progress))
+    }
+
+    fn analyse_and_report(
+        &mut self,
+        progress: Option<&mut dyn ProgressHandler>,
+    ) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
 
         for source_file in self.files.values_mut() {
@@ -252,7 +574,7 @@ impl Project {
             self.root.ensure_library(library_name.clone());
         }
 
-        let analyzed_units = self.root.analyze(&mut diagnostics);
+        let analyzed_units = self.root.analyze_with_progress(&mut diagnostics, progress);
 
         if let Some(ref mut lint) = self.lint.unused_declarations {
             lint.lint(&self.root, &self.config, &analyzed_units, &mut diagnostics);
@@ -265,6 +587,13 @@ impl Project {
         diagnostics
     }
 
+    /// Returns all source files together with the library they are compiled into.
+    /// Files are ordered such that each file comes after the files it depends on.
+    /// The dependencies are only known after the project has been analyzed.
+    pub fn compile_order(&self) -> Vec<(Symbol, Source)> {
+        self.root.source_order()
+    }
+
     /// Search for reference at position
     /// Character offset on a line in a document (zero-based). Assuming that the line is
     /// represented as a string, the `character` value represents the gap between the
@@ -321,6 +650,11 @@ impl Project {
         self.root.format_declaration(ent)
     }
 
+    /// Get the details of a declaration such as its comments, see [DeclarationDetails]
+    pub fn declaration_details(&self, ent: EntRef<'_>) -> Option<DeclarationDetails> {
+        self.root.declaration_details(ent)
+    }
+
     pub fn format_entity(&self, id: EntityId) -> Option<String> {
         let ent = self.root.get_ent(id);
         self.format_declaration(ent)
@@ -375,9 +709,87 @@ fn multiply<T: Clone>(value: T, n: usize) -> Vec<T> {
     }
 }
 
+/// Parses sources, the files that only belong to third-party libraries are loaded from
+/// the cache if there is one. Files with syntax errors are not cached.
+struct SourceParser {
+    #[cfg(feature = "analysis-cache")]
+    cache: Option<DesignFileCache>,
+    #[cfg(feature = "analysis-cache")]
+    third_party: FnvHashSet<Symbol>,
+}
+
+impl SourceParser {
+    #[cfg(feature = "analysis-cache")]
+    fn new(config: &Config, parser: &VHDLParser) -> SourceParser {
+        SourceParser {
+            cache: config.cache_directory().map(DesignFileCache::new),
+            third_party: config
+                .iter_libraries()
+                .filter(|library| library.is_third_party)
+                .filter_map(|library| Latin1String::from_utf8(library.name()).ok())
+                .map(|name| parser.symbol(&name))
+                .collect(),
+        }
+    }
+
+    #[cfg(not(feature = "analysis-cache"))]
+    fn new(_config: &Config, _parser: &VHDLParser) -> SourceParser {
+        SourceParser {}
+    }
+
+    #[cfg(feature = "analysis-cache")]
+    fn parse(
+        &self,
+        parser: &VHDLParser,
+        library_names: &FnvHashSet<Symbol>,
+        source: &Source,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> DesignFile {
+        let Some(cache) = self
+            .cache
+            .as_ref()
+            .filter(|_| library_names.is_subset(&self.third_party))
+        else {
+            return parser.parse_design_source(source, diagnostics);
+        };
+        if let Some(design_file) = cache.load(parser, source) {
+            return design_file;
+        }
+
+        let design_file = parser.parse_design_source(source, diagnostics);
+        if diagnostics.is_empty() {
+            cache.store(parser, source, &design_file);
+        }
+        design_file
+    }
+
+    #[cfg(not(feature = "analysis-cache"))]
+    fn parse(
+        &self,
+        parser: &VHDLParser,
+        _library_names: &FnvHashSet<Symbol>,
+        source: &Source,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> DesignFile {
+        parser.parse_design_source(source, diagnostics)
+    }
+
+    /// The first error that occurred when storing a file in the cache, if any
+    #[cfg(feature = "analysis-cache")]
+    fn write_error(&self) -> Option<String> {
+        self.cache.as_ref().and_then(|cache| cache.write_error())
+    }
+
+    #[cfg(not(feature = "analysis-cache"))]
+    fn write_error(&self) -> Option<String> {
+        None
+    }
+}
+
 pub struct SourceFile {
     library_names: FnvHashSet<Symbol>,
     source: Source,
+    standard: VHDLStandard,
     design_file: DesignFile,
     parser_diagnostics: Vec<Diagnostic>,
 }
@@ -387,6 +799,17 @@ impl SourceFile {
         std::mem::take(&mut self.design_file)
     }
 
+    /// A copy without the design units that have not been added to the libraries yet
+    fn snapshot(&self) -> SourceFile {
+        SourceFile {
+            library_names: self.library_names.clone(),
+            source: self.source.clone(),
+            standard: self.standard,
+            design_file: DesignFile::default(),
+            parser_diagnostics: self.parser_diagnostics.clone(),
+        }
+    }
+
     pub fn num_lines(&self) -> usize {
         self.source.contents().num_lines()
     }
@@ -395,6 +818,7 @@ impl SourceFile {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::data::error_codes::ErrorCode;
     use crate::syntax::test::check_no_diagnostics;
 
     /// Test that an empty library is created
@@ -463,154 +887,1223 @@ end architecture;
         assert_eq!(diag.message, "Duplicate architecture 'rtl' of entity 'ent'")
     }
 
-    /// Test that the same file can be added to several libraries
     #[test]
-    fn test_same_file_in_multiple_libraries() {
+    fn libraries_are_parsed_and_analyzed_with_their_standard() {
         let root = tempfile::tempdir().unwrap();
-        let vhdl_file_path1 = root.path().join("file.vhd");
+        // 'force' is a keyword since VHDL 2008
         std::fs::write(
-            vhdl_file_path1,
+            root.path().join("legacy.vhd"),
             "
-package pkg is
-end package;
-        ",
+entity legacy is
+  port (force : out bit);
+end entity;
+
+architecture rtl of legacy is
+begin
+  process (force)
+  begin
+  end process;
+end architecture;
+",
         )
         .unwrap();
-
-        let vhdl_file_path2 = root.path().join("use_file.vhd");
         std::fs::write(
-            vhdl_file_path2,
+            root.path().join("rtl.vhd"),
             "
-library lib1;
-use lib1.pkg.all;
+library legacy_lib;
 
-package use_pkg1 is
-end package;
+entity rtl is
+  port (o : out bit);
+end entity;
 
-library lib2;
-use lib2.pkg.all;
+architecture rtl of rtl is
+begin
+  process (o)
+  begin
+  end process;
 
-package use_pkg2 is
-end package;
-        ",
+  inst: entity legacy_lib.legacy;
+end architecture;
+",
         )
         .unwrap();
 
-        let config_str = "
+        let config_str = format!(
+            "
 [libraries]
-lib1.files = ['file.vhd']
-lib2.files = ['file.vhd']
-use_lib.files = ['use_file.vhd']
-        ";
+std.files = ['{}/vhdl_libraries/std/standard.vhd']
+legacy_lib.files = ['legacy.vhd']
+legacy_lib.standard = '1993'
+rtl_lib.files = ['rtl.vhd']
+        ",
+            env!("CARGO_MANIFEST_DIR")
+        );
 
-        let config = Config::from_str(config_str, root.path()).unwrap();
+        let config = Config::from_str(&config_str, root.path()).unwrap();
         let mut messages = Vec::new();
         let mut project = Project::from_config(config, &mut messages);
         assert_eq!(messages, vec![]);
-        check_no_diagnostics(&project.analyse());
-    }
-
-    fn update(project: &mut Project, source: &mut Source, contents: &str) {
-        std::fs::write(Path::new(source.file_name()), contents).unwrap();
-        *source = Source::from_latin1_file(source.file_name()).unwrap();
-        project.update_source(source);
+        let diagnostics = project.analyse();
+        // Output ports cannot be read in VHDL 1993
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        assert_eq!(diagnostics[0].code, ErrorCode::RequiresNewerStandard);
+        assert_eq!(
+            diagnostics[0].pos.source.file_name(),
+            root.path().join("legacy.vhd")
+        );
     }
 
-    /// Test that the same file can be added to several libraries
     #[test]
-    fn test_re_analyze_after_update() {
-        let tempdir = tempfile::tempdir().unwrap();
-        let root = dunce::canonicalize(tempdir.path()).unwrap();
-
-        let path1 = root.join("file1.vhd");
-        let path2 = root.join("file2.vhd");
+    fn conditional_analysis_uses_defines_of_config() {
+        let root = tempfile::tempdir().unwrap();
         std::fs::write(
-            &path1,
+            root.path().join("pkg.vhd"),
             "
+`if TARGET = \"fpga\" then
 package pkg is
 end package;
-        ",
+`else
+package pkg is
+  syntax error
+end package;
+`end if
+",
         )
         .unwrap();
-        let mut source1 = Source::from_latin1_file(&path1).unwrap();
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = ['pkg.vhd']
+
+[defines]
+TARGET = 'fpga'
+",
+            root.path(),
+        )
+        .unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        let diagnostics = project.analyse();
+        assert!(
+            diagnostics
+                .iter()
+                .all(|diag| diag.code == ErrorCode::InactiveRegion),
+            "{diagnostics:?}"
+        );
+    }
 
+    #[test]
+    fn stubs_declare_interface_of_encrypted_units() {
+        let root = tempfile::tempdir().unwrap();
         std::fs::write(
-            &path2,
+            root.path().join("ip.vhd"),
             "
-library lib1;
-use lib1.pkg.all;
+`protect begin_protected
+`protect encoding = (enctype = \"base64\", line_length = 76, bytes = 64)
+`protect data_block
+aGVsbG8gd29ybGQ7IGVudGl0eSBpcCBpcyBlbmQgZW50aXR5Ow==
+`protect end_protected
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("ip_stub.vhd"),
+            "
+entity ip is
+  port (clk : in bit; data : out bit);
+end entity;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("top.vhd"),
+            "
+library vendor;
 
-package pkg is
-end package;
-        ",
+entity top is
+end entity;
+
+architecture rtl of top is
+  signal clk, data : bit;
+begin
+  inst: entity vendor.ip(rtl)
+    port map (clk => clk, data => data, missing => clk);
+end architecture;
+",
         )
         .unwrap();
-        let mut source2 = Source::from_latin1_file(&path2).unwrap();
 
-        let config_str = "
+        let config_str = format!(
+            "
 [libraries]
-lib1.files = ['file1.vhd']
-lib2.files = ['file2.vhd']
-        ";
+std.files = ['{}/vhdl_libraries/std/standard.vhd']
+vendor.files = ['ip.vhd']
+vendor.stubs = ['ip_stub.vhd']
+lib.files = ['top.vhd']
+        ",
+            env!("CARGO_MANIFEST_DIR")
+        );
 
-        let config = Config::from_str(config_str, &root).unwrap();
+        let config = Config::from_str(&config_str, root.path()).unwrap();
         let mut messages = Vec::new();
         let mut project = Project::from_config(config, &mut messages);
         assert_eq!(messages, vec![]);
-        check_no_diagnostics(&project.analyse());
+        let diagnostics: Vec<_> = project
+            .analyse()
+            .into_iter()
+            .filter(|diag| diag.code != ErrorCode::InactiveRegion)
+            .collect();
+        // The ports of the stub are checked while the missing architecture is not an error
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        assert_eq!(diagnostics[0].message, "No declaration of 'missing'");
+    }
 
-        // Add syntax error
-        update(
-            &mut project,
-            &mut source1,
+    #[test]
+    fn stubs_are_superseded_by_actual_units() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("ip.vhd"),
             "
-package is
-        ",
-        );
-        let diagnostics = project.analyse();
-        assert_eq!(diagnostics.len(), 3);
-        // Syntax error comes first
-        assert_eq!(diagnostics[0].pos.source, source1);
-        assert_eq!(diagnostics[1].pos.source, source1);
+entity ip is
+  port (clk : in bit);
+end entity;
+
+architecture rtl of ip is
+begin
+end architecture;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("ip_stub.vhd"),
+            "
+entity ip is
+  port (clk : in bit);
+end entity;
+",
+        )
+        .unwrap();
+
+        let config_str = format!(
+            "
+[libraries]
+std.files = ['{}/vhdl_libraries/std/standard.vhd']
+vendor.files = ['ip.vhd']
+vendor.stubs = ['ip_stub.vhd']
+        ",
+            env!("CARGO_MANIFEST_DIR")
+        );
+
+        let config = Config::from_str(&config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics, vec![]);
+
+        let entity = project
+            .root
+            .get_design_entity(
+                &project.parser.symbol(&Latin1String::new(b"vendor")),
+                &project.parser.symbol(&Latin1String::new(b"ip")),
+            )
+            .unwrap();
+        assert_eq!(
+            entity.decl_pos().unwrap().source.file_name(),
+            root.path().join("ip.vhd")
+        );
+    }
+
+    #[test]
+    fn units_of_black_box_libraries_are_opaque() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("top.vhd"),
+            "
+library unisim;
+use unisim.vcomponents.all;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  signal clk_in, clk : bit;
+begin
+  buf: bufg port map (i => clk_in, o => clk);
+  inst: entity unisim.ibuf(rtl) port map (i => clk_in, o => missing);
+  clk_in <= not clkk;
+end architecture;
+",
+        )
+        .unwrap();
+
+        let config_str = format!(
+            "
+[libraries]
+std.files = ['{}/vhdl_libraries/std/standard.vhd']
+unisim.black_box = true
+lib.files = ['top.vhd']
+        ",
+            env!("CARGO_MANIFEST_DIR")
+        );
+
+        let config = Config::from_str(&config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+        // Only the names of the design itself are checked
+        assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+        assert_eq!(diagnostics[0].message, "No declaration of 'missing'");
+        assert_eq!(diagnostics[1].message, "No declaration of 'clkk'");
+    }
+
+    #[test]
+    fn json_stubs_declare_units_of_black_box_libraries() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("vcomponents.json"),
+            r#"{
+  "packages": [{
+    "name": "vcomponents",
+    "components": [{
+      "name": "bufg",
+      "ports": [
+        {"name": "o", "mode": "out", "type": "bit"},
+        {"name": "i", "type": "bit"}
+      ]
+    }]
+  }]
+}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("top.vhd"),
+            "
+library unisim;
+use unisim.vcomponents.all;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  signal clk_in, clk : bit;
+  signal count : natural;
+begin
+  buf: bufg port map (i => clk_in, o => count);
+end architecture;
+",
+        )
+        .unwrap();
+
+        let config_str = format!(
+            "
+[libraries]
+std.files = ['{}/vhdl_libraries/std/standard.vhd']
+unisim.black_box = true
+unisim.stubs = ['vcomponents.json']
+lib.files = ['top.vhd']
+        ",
+            env!("CARGO_MANIFEST_DIR")
+        );
+
+        let config = Config::from_str(&config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+        // The component of the stub is type-checked
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        assert_eq!(diagnostics[0].code, ErrorCode::TypeMismatch);
+        assert_eq!(
+            diagnostics[0].pos.source.file_name(),
+            root.path().join("top.vhd")
+        );
+    }
+
+    #[test]
+    fn verilog_modules_are_entities() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("fifo.sv"),
+            "
+module fifo #(parameter WIDTH = 8) (
+  input logic clk,
+  input logic [WIDTH-1:0] din,
+  output logic [WIDTH-1:0] dout
+);
+endmodule
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("top.vhd"),
+            "
+library ieee;
+use ieee.std_logic_1164.all;
+
+entity top is
+end entity;
+
+architecture rtl of top is
+  component fifo is
+    generic (WIDTH : integer := 8);
+    port (clk : in std_logic; din : in std_logic_vector(7 downto 0); dout : in std_logic_vector(7 downto 0); full : out std_logic);
+  end component;
+  signal clk : std_logic;
+  signal din, dout : std_logic_vector(7 downto 0);
+begin
+  direct: entity work.fifo generic map (WIDTH => 8) port map (clk => clk, din => din, dout => dout, empty => open);
+  comp: fifo port map (clk => clk, din => din, dout => dout, full => open);
+end architecture;
+",
+        )
+        .unwrap();
+
+        let config_str = format!(
+            "
+[libraries]
+std.files = ['{0}/vhdl_libraries/std/*.vhd']
+ieee.files = ['{0}/vhdl_libraries/ieee2008/std_logic_1164*.vhdl']
+lib.files = ['fifo.sv', 'top.vhd']
+        ",
+            env!("CARGO_MANIFEST_DIR")
+        );
+
+        let config = Config::from_str(&config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let mut diagnostics = project.analyse();
+        diagnostics.sort_by_key(|diag| diag.pos.start());
+        let messages: Vec<_> = diagnostics
+            .iter()
+            .map(|diag| (diag.code, diag.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    ErrorCode::ComponentMismatch,
+                    "Port 'dout' has mode out in Verilog module 'fifo'"
+                ),
+                (
+                    ErrorCode::ComponentMismatch,
+                    "No port 'full' in Verilog module 'fifo'"
+                ),
+                (ErrorCode::Unresolved, "No declaration of 'empty'"),
+            ]
+        );
+
+        // Goto-definition jumps into the Verilog file
+        let top = project.get_source(&root.path().join("top.vhd")).unwrap();
+        let ent = project
+            .find_declaration(&top, Position::new(15, 25))
+            .unwrap();
+        let decl_pos = ent.decl_pos().unwrap();
+        assert_eq!(decl_pos.source.file_name(), root.path().join("fifo.sv"));
+        assert_eq!(
+            decl_pos.range(),
+            Range::new(Position::new(1, 7), Position::new(1, 11))
+        );
+    }
+
+    /// Test that the same file can be added to several libraries
+    #[test]
+    fn test_same_file_in_multiple_libraries() {
+        let root = tempfile::tempdir().unwrap();
+        let vhdl_file_path1 = root.path().join("file.vhd");
+        std::fs::write(
+            vhdl_file_path1,
+            "
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+
+        let vhdl_file_path2 = root.path().join("use_file.vhd");
+        std::fs::write(
+            vhdl_file_path2,
+            "
+library lib1;
+use lib1.pkg.all;
+
+package use_pkg1 is
+end package;
+
+library lib2;
+use lib2.pkg.all;
+
+package use_pkg2 is
+end package;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib1.files = ['file.vhd']
+lib2.files = ['file.vhd']
+use_lib.files = ['use_file.vhd']
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+    }
+
+    #[test]
+    fn parsing_and_analysis_report_progress() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("pkg.vhd"),
+            "
+package pkg is
+end package;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.path().join("ent.vhd"),
+            "
+entity ent is
+end entity;
+
+architecture rtl of ent is
+begin
+end architecture;
+",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib1.files = ['pkg.vhd']
+lib2.files = ['pkg.vhd', 'ent.vhd']
+        ";
+
+        let config = Config::from_str(config_str, root.path()).unwrap();
+        let mut messages = Vec::new();
+        let mut reports: Vec<Progress> = Vec::new();
+        let mut project = Project::new(config.standard());
+        project.update_config_with_progress(config, &mut messages, &mut |progress: &Progress| {
+            reports.push(progress.clone())
+        });
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(
+            &project
+                .analyse_with_progress(&mut |progress: &Progress| reports.push(progress.clone())),
+        );
+
+        let last_report = |stage: ProgressStage, library: &str| {
+            reports
+                .iter()
+                .rfind(|progress| progress.stage == stage && progress.library == library)
+                .map(|progress| (progress.library_done, progress.library_total))
+        };
+        assert_eq!(last_report(ProgressStage::Parsing, "lib1"), Some((1, 1)));
+        assert_eq!(last_report(ProgressStage::Parsing, "lib2"), Some((2, 2)));
+        assert_eq!(last_report(ProgressStage::Analyzing, "lib1"), Some((1, 1)));
+        assert_eq!(last_report(ProgressStage::Analyzing, "lib2"), Some((3, 3)));
+
+        let parsing = reports
+            .iter()
+            .filter(|progress| progress.stage == ProgressStage::Parsing)
+            .map(|progress| (progress.done, progress.total))
+            .max();
+        assert_eq!(parsing, Some((2, 2)));
+        let analyzing = reports
+            .iter()
+            .filter(|progress| progress.stage == ProgressStage::Analyzing)
+            .map(|progress| (progress.done, progress.total))
+            .max();
+        assert_eq!(analyzing, Some((4, 4)));
+    }
+
+    /// Test that files are ordered after the files they depend on
+    #[test]
+    fn test_compile_order() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        std::fs::write(
+            root.join("a_ent.vhd"),
+            "
+library lib2;
+use lib2.pkg.all;
+
+entity ent is
+end entity;
+        ",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("b_arch.vhd"),
+            "
+architecture rtl of ent is
+begin
+end architecture;
+        ",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("c_pkg.vhd"),
+            "
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+
+        let config_str = "
+[libraries]
+lib1.files = ['a_ent.vhd', 'b_arch.vhd']
+lib2.files = ['c_pkg.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+
+        let order: Vec<_> = project
+            .compile_order()
+            .into_iter()
+            .map(|(library, source)| (library.name_utf8(), source.file_name().to_owned()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("lib2".to_owned(), root.join("c_pkg.vhd")),
+                ("lib1".to_owned(), root.join("a_ent.vhd")),
+                ("lib1".to_owned(), root.join("b_arch.vhd")),
+            ]
+        );
+    }
+
+    /// Test that the files of a library are compiled with the standard of the library
+    #[test]
+    fn standard_of_files_follows_library_standard() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        std::fs::write(root.join("old.vhd"), "entity old is end entity;").unwrap();
+        std::fs::write(root.join("recent.vhd"), "entity recent is end entity;").unwrap();
+
+        let config_str = "
+standard = '2008'
+[libraries]
+old.files = ['old.vhd']
+old.standard = '1993'
+recent.files = ['recent.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+
+        let mut standards: Vec<_> = project
+            .compile_order()
+            .into_iter()
+            .map(|(library, source)| (library.name_utf8(), project.standard_of(&source)))
+            .collect();
+        standards.sort();
+        assert_eq!(
+            standards,
+            vec![
+                ("old".to_owned(), VHDLStandard::VHDL1993),
+                ("recent".to_owned(), VHDLStandard::VHDL2008),
+            ]
+        );
+    }
+
+    fn update(project: &mut Project, source: &mut Source, contents: &str) {
+        std::fs::write(Path::new(source.file_name()), contents).unwrap();
+        *source = Source::from_latin1_file(source.file_name()).unwrap();
+        project.update_source(source);
+    }
+
+    /// Test that the same file can be added to several libraries
+    #[test]
+    fn test_re_analyze_after_update() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path1 = root.join("file1.vhd");
+        let path2 = root.join("file2.vhd");
+        std::fs::write(
+            &path1,
+            "
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+        let mut source1 = Source::from_latin1_file(&path1).unwrap();
+
+        std::fs::write(
+            &path2,
+            "
+library lib1;
+use lib1.pkg.all;
+
+package pkg is
+end package;
+        ",
+        )
+        .unwrap();
+        let mut source2 = Source::from_latin1_file(&path2).unwrap();
+
+        let config_str = "
+[libraries]
+lib1.files = ['file1.vhd']
+lib2.files = ['file2.vhd']
+        ";
+
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+
+        // Add syntax error
+        update(
+            &mut project,
+            &mut source1,
+            "
+package is
+        ",
+        );
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 3);
+        // Syntax error comes first
+        assert_eq!(diagnostics[0].pos.source, source1);
+        assert_eq!(diagnostics[1].pos.source, source1);
         assert_eq!(diagnostics[2].pos.source, source2);
 
-        // Make it good again
-        update(
-            &mut project,
-            &mut source1,
-            "
+        // Make it good again
+        update(
+            &mut project,
+            &mut source1,
+            "
+package pkg is
+end package;
+        ",
+        );
+        check_no_diagnostics(&project.analyse());
+
+        // Add analysis error
+        update(
+            &mut project,
+            &mut source2,
+            "
+package pkg is
+end package;
+
+package pkg is
+end package;
+        ",
+        );
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pos.source, source2);
+
+        // Make it good again
+        update(
+            &mut project,
+            &mut source2,
+            "
+package pkg is
+end package;
+        ",
+        );
+        check_no_diagnostics(&project.analyse());
+    }
+
+    /// Analyze the project and return the number of analyzed design units
+    fn analyse_and_count(project: &mut Project) -> (Vec<Diagnostic>, usize) {
+        let mut analyzed = 0;
+        let diagnostics = project.analyse_with_progress(&mut |progress: &Progress| {
+            if progress.stage == ProgressStage::Analyzing {
+                analyzed = progress.done;
+            }
+        });
+        (diagnostics, analyzed)
+    }
+
+    /// Test that only the edited design unit of a file is parsed and analyzed again
+    #[test]
+    fn only_edited_design_unit_is_reparsed() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path = root.join("file.vhd");
+        let contents = "
+entity ent is
+end entity;
+
+architecture rtl of ent is
+  component foo is end component;
+begin
+end architecture;
+
 package pkg is
 end package;
-        ",
+";
+        std::fs::write(&path, contents).unwrap();
+        let mut source = Source::from_latin1_file(&path).unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+        ";
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let (diagnostics, analyzed) = analyse_and_count(&mut project);
+        check_no_diagnostics(&diagnostics);
+        assert_eq!(analyzed, 3);
+
+        // Introduce a syntax error in the architecture
+        update(
+            &mut project,
+            &mut source,
+            &contents.replace("end component;", "end component"),
         );
-        check_no_diagnostics(&project.analyse());
+        let (diagnostics, analyzed) = analyse_and_count(&mut project);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].pos.start().line, 5);
+        assert_eq!(analyzed, 1);
 
-        // Add analysis error
+        // Fix it again
         update(
             &mut project,
-            &mut source2,
-            "
+            &mut source,
+            &contents.replace("component foo", "component bar"),
+        );
+        let (diagnostics, analyzed) = analyse_and_count(&mut project);
+        check_no_diagnostics(&diagnostics);
+        assert_eq!(analyzed, 1);
+
+        // An edit of the entity affects the architecture as well
+        update(
+            &mut project,
+            &mut source,
+            &contents
+                .replace("component foo", "component bar")
+                .replace("entity ent is", "entity ent is begin"),
+        );
+        let (diagnostics, analyzed) = analyse_and_count(&mut project);
+        check_no_diagnostics(&diagnostics);
+        assert_eq!(analyzed, 2);
+    }
+
+    /// Test that design units below an edit that adds a line are moved instead of parsed again
+    #[test]
+    fn design_units_below_edit_are_moved() {
+        let parser = VHDLParser::new(VHDLStandard::default());
+        let source = Source::inline(
+            Path::new("file.vhd"),
+            "\
 package pkg is
 end package;
 
+entity ent is
+end entity;
+
+-- comment
+architecture rtl of ent is
+begin
+end architecture;
+",
+        );
+        let mut diagnostics = Vec::new();
+        let design_file = parser.parse_design_source(&source, &mut diagnostics);
+        check_no_diagnostics(&diagnostics);
+        let previous_units: Vec<Vec<Token>> = design_file
+            .design_units
+            .into_iter()
+            .map(|(tokens, _)| tokens)
+            .collect();
+        let previous_units: Vec<&[Token]> = previous_units.iter().map(Vec::as_slice).collect();
+
+        source.change(
+            Some(&Range::new(Position::new(1, 0), Position::new(1, 0))),
+            "  constant c : natural := 0;\n",
+        );
+        let (kept, design_file) =
+            parser.reparse_design_source(&source, &previous_units, &mut diagnostics);
+        check_no_diagnostics(&diagnostics);
+        assert_eq!(kept, vec![(1, 1), (2, 1)]);
+        assert_eq!(design_file.design_units.len(), 1);
+
+        // Removing the comment before the architecture moves nothing
+        let previous_units: Vec<Vec<Token>> = parser
+            .parse_design_source(&source, &mut diagnostics)
+            .design_units
+            .into_iter()
+            .map(|(tokens, _)| tokens)
+            .collect();
+        let previous_units: Vec<&[Token]> = previous_units.iter().map(Vec::as_slice).collect();
+        source.change(
+            Some(&Range::new(Position::new(7, 0), Position::new(8, 0))),
+            "",
+        );
+        let (kept, design_file) =
+            parser.reparse_design_source(&source, &previous_units, &mut diagnostics);
+        check_no_diagnostics(&diagnostics);
+        assert_eq!(kept, vec![(0, 0), (1, 0)]);
+        assert_eq!(design_file.design_units.len(), 1);
+    }
+
+    /// Test that the diagnostics of moved design units are moved as well
+    #[test]
+    fn diagnostics_of_moved_design_units_are_moved() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path = root.join("file.vhd");
+        let contents = "
 package pkg is
 end package;
-        ",
+
+entity ent is
+end entity;
+
+architecture rtl of ent is
+  component foo is end component
+  signal s : missing_t;
+begin
+end architecture;
+";
+        std::fs::write(&path, contents).unwrap();
+        let source = Source::from_latin1_file(&path).unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+        ";
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config.clone(), &mut messages);
+        assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+        assert_eq!(diagnostics.len(), 2);
+
+        // Add a line to the package
+        source.change(
+            Some(&Range::new(Position::new(2, 0), Position::new(2, 0))),
+            "  -- comment\n",
+        );
+        project.update_source(&source);
+        let mut diagnostics = project.analyse();
+
+        let contents = contents.replace("package pkg is\n", "package pkg is\n  -- comment\n");
+        std::fs::write(&path, &contents).unwrap();
+        let mut expected = Project::from_config(config.clone(), &mut messages);
+        let mut expected_diagnostics = expected.analyse();
+        diagnostics.sort_by(|a, b| a.pos.cmp(&b.pos));
+        expected_diagnostics.sort_by(|a, b| a.pos.cmp(&b.pos));
+        assert_eq!(diagnostics, expected_diagnostics);
+        assert_eq!(diagnostics[0].pos.start().line, 9);
+        assert_eq!(diagnostics[1].pos.start().line, 10);
+
+        // Remove the line again
+        source.change(
+            Some(&Range::new(Position::new(2, 0), Position::new(3, 0))),
+            "",
         );
+        project.update_source(&source);
         let diagnostics = project.analyse();
-        assert_eq!(diagnostics.len(), 1);
-        assert_eq!(diagnostics[0].pos.source, source2);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].pos.start().line, 8);
+        assert_eq!(diagnostics[1].pos.start().line, 9);
+        let ent = project
+            .find_declaration(&source, Position::new(7, 21))
+            .unwrap();
+        assert_eq!(ent.decl_pos().unwrap().start().line, 4);
+    }
 
-        // Make it good again
+    /// Test that the whole file is parsed again when an edit merges design units
+    #[test]
+    fn whole_file_is_reparsed_when_design_units_merge() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path = root.join("file.vhd");
+        let contents = "
+package pkg1 is
+end package;
+
+package pkg2 is
+end package;
+";
+        std::fs::write(&path, contents).unwrap();
+        let mut source = Source::from_latin1_file(&path).unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+        ";
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config.clone(), &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+
+        // The end of the first package is missing such that the second package is part of it
         update(
             &mut project,
-            &mut source2,
+            &mut source,
+            &contents.replacen("end package;", "", 1),
+        );
+        let diagnostics = project.analyse();
+        assert!(!diagnostics.is_empty());
+        let mut expected = Project::from_config(config, &mut messages);
+        assert_eq!(diagnostics, expected.analyse());
+
+        update(&mut project, &mut source, contents);
+        check_no_diagnostics(&project.analyse());
+    }
+
+    /// Test that a snapshot keeps the last analysis while the project is analyzed again
+    #[test]
+    fn snapshot_keeps_last_analysis() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path = root.join("file.vhd");
+        let contents = "
+entity ent is
+end entity;
+
+architecture rtl of ent is
+begin
+end architecture;
+";
+        std::fs::write(&path, contents).unwrap();
+        let mut source = Source::from_latin1_file(&path).unwrap();
+
+        let config_str = "
+[libraries]
+lib.files = ['file.vhd']
+        ";
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        check_no_diagnostics(&project.analyse());
+
+        let snapshot = project.snapshot();
+        let snapshot_source = snapshot.get_source(&path).unwrap();
+
+        // The architecture no longer refers to an existing entity
+        update(
+            &mut project,
+            &mut source,
+            &contents.replace("entity ent is", "entity ent2 is"),
+        );
+        assert!(!project.analyse().is_empty());
+        assert!(project
+            .find_declaration(&source, Position::new(4, 21))
+            .is_none());
+
+        let ent = snapshot
+            .find_declaration(&snapshot_source, Position::new(4, 21))
+            .unwrap();
+        assert_eq!(ent.decl_pos().unwrap().start().line, 1);
+    }
+
+    /// Test that the files and analyzed units of third-party libraries are cached
+    #[test]
+    #[cfg(feature = "analysis-cache")]
+    fn third_party_libraries_are_cached() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        std::fs::write(
+            root.join("vendor.vhd"),
             "
-package pkg is
+package vendor_pkg is
+  component vendor_comp is
+  end component;
+  constant vendor_const : natural := vendor_undefined;
 end package;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("broken.vhd"),
+            "
+package broken_pkg is
+end package
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("rtl.vhd"),
+            "
+library vendor;
+use vendor.vendor_pkg.all;
+
+entity ent is
+end entity;
+",
+        )
+        .unwrap();
+
+        let config_str = format!(
+            "
+cache = 'cache'
+[libraries]
+std.files = ['{}/vhdl_libraries/std/standard.vhd']
+std.is_third_party = true
+vendor.files = ['vendor.vhd', 'broken.vhd']
+vendor.is_third_party = true
+lib.files = ['rtl.vhd']
         ",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let config = Config::from_str(&config_str, &root).unwrap();
+        let cache_entries = |dir: &Path| {
+            std::fs::read_dir(dir)
+                .unwrap()
+                .filter(|entry| entry.as_ref().unwrap().path().is_file())
+                .count()
+        };
+        let units = root.join("cache").join("units");
+
+        // Files with syntax errors and files of other libraries are not cached
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config.clone(), &mut messages);
+        assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+        assert_eq!(cache_entries(&root.join("cache")), 2);
+        assert_eq!(cache_entries(&units), 3);
+
+        // Cached files are analyzed like parsed files
+        let mut project = Project::from_config(config.clone(), &mut messages);
+        assert_eq!(messages, vec![]);
+        assert_eq!(project.analyse(), diagnostics);
+
+        // Cached units are restored instead of analyzed again
+        for entry in std::fs::read_dir(&units).unwrap() {
+            let path = entry.unwrap().path();
+            let mut bytes = std::fs::read(&path).unwrap();
+            let needle = b"'vendor_undefined'";
+            for start in 0..=bytes.len().saturating_sub(needle.len()) {
+                if bytes[start..].starts_with(needle) {
+                    bytes[start..start + needle.len()].make_ascii_uppercase();
+                }
+            }
+            std::fs::write(&path, bytes).unwrap();
+        }
+        let mut project = Project::from_config(config.clone(), &mut messages);
+        assert_eq!(messages, vec![]);
+        assert!(project
+            .analyse()
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("'VENDOR_UNDEFINED'")));
+
+        // A stale entry is replaced
+        std::fs::write(
+            root.join("vendor.vhd"),
+            "
+package renamed_pkg is
+  constant vendor_const : natural := vendor_undefined;
+end package;
+",
+        )
+        .unwrap();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        let diagnostics = project.analyse();
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("vendor_pkg")));
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("'vendor_undefined'")));
+        assert_eq!(cache_entries(&root.join("cache")), 2);
+    }
+
+    /// Test that units of third-party libraries are only analyzed when they are used
+    /// or when their file is open
+    #[test]
+    fn third_party_libraries_are_analyzed_lazily() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+        std::fs::write(
+            root.join("used.vhd"),
+            "
+use work.missing_used.all;
+
+package used_pkg is
+end package;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("unused.vhd"),
+            "
+use work.missing_unused.all;
+
+package unused_pkg is
+end package;
+",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("rtl.vhd"),
+            "
+library vendor;
+use vendor.used_pkg.all;
+
+entity ent is
+end entity;
+",
+        )
+        .unwrap();
+
+        let config_str = "
+lazy_analysis = true
+[libraries]
+vendor.files = ['used.vhd', 'unused.vhd']
+vendor.is_third_party = true
+lib.files = ['rtl.vhd']
+        ";
+        let config = Config::from_str(config_str, &root).unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+
+        let messages_of = |diagnostics: Vec<Diagnostic>| -> Vec<String> {
+            diagnostics
+                .into_iter()
+                .map(|diagnostic| diagnostic.message)
+                .collect()
+        };
+        assert_eq!(
+            messages_of(project.analyse()),
+            vec!["No primary unit 'missing_used' within library 'vendor'".to_owned()]
+        );
+
+        let unused = project.get_source(&root.join("unused.vhd")).unwrap();
+        project.set_source_open(&unused, true);
+        let mut messages = messages_of(project.analyse());
+        messages.sort();
+        assert_eq!(
+            messages,
+            vec![
+                "No primary unit 'missing_unused' within library 'vendor'".to_owned(),
+                "No primary unit 'missing_used' within library 'vendor'".to_owned(),
+            ]
         );
-        check_no_diagnostics(&project.analyse());
     }
 
     /// Test that the configuration can be updated
@@ -679,4 +2172,56 @@ lib.files = ['file2.vhd']
         assert_eq!(diagnostics[0].pos.source, source2); // No such library
         assert_eq!(diagnostics[1].pos.source, source2); // No declaration
     }
+
+    #[test]
+    fn files_are_added_and_removed_after_loading() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let root = dunce::canonicalize(tempdir.path()).unwrap();
+
+        let path1 = root.join("file1.vhd");
+        std::fs::write(
+            &path1,
+            "
+use work.pkg.all;
+
+entity ent is
+end entity;
+        ",
+        )
+        .unwrap();
+
+        let config = Config::from_str(
+            "
+[libraries]
+lib.files = ['*.vhd']
+        ",
+            &root,
+        )
+        .unwrap();
+        let mut messages = Vec::new();
+        let mut project = Project::from_config(config, &mut messages);
+        assert_eq!(messages, vec![]);
+        assert_eq!(project.analyse().len(), 1);
+
+        // A file that is created later is added to the libraries that match it
+        let path2 = root.join("file2.vhd");
+        std::fs::write(&path2, "package pkg is end package;").unwrap();
+        let source2 = project.add_file(&path2, &mut messages).unwrap();
+        assert_eq!(messages, vec![]);
+        assert_eq!(
+            project.library_mapping_of(&source2),
+            vec![project.root.symbol_utf8("lib")]
+        );
+        check_no_diagnostics(&project.analyse());
+
+        let path3 = root.join("notes.txt");
+        std::fs::write(&path3, "").unwrap();
+        assert_eq!(project.add_file(&path3, &mut messages), None);
+
+        std::fs::remove_file(&path2).unwrap();
+        assert!(project.remove_file(&path2));
+        assert!(!project.remove_file(&path2));
+        assert_eq!(project.get_source(&path2), None);
+        assert_eq!(project.analyse().len(), 1);
+    }
 }
@@ -21,12 +21,14 @@ mod interface_declaration;
 mod names;
 mod object_declaration;
 mod parser;
+mod psl;
 mod range;
 mod separated_list;
 mod sequential_statement;
 mod subprogram;
 mod subtype_indication;
 mod type_declaration;
+mod verilog;
 mod waveform;
 
 mod recover;
@@ -36,3 +38,4 @@ mod view;
 
 pub use parser::{ParserResult, VHDLParser};
 pub use tokens::*;
+pub(crate) use verilog::is_verilog_file;
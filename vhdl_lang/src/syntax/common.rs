@@ -9,7 +9,7 @@ use crate::ast::token_range::WithToken;
 use crate::ast::Ident;
 use crate::data::Diagnostic;
 use crate::syntax::parser::ParsingContext;
-use crate::{SrcPos, TokenId};
+use crate::TokenId;
 
 /// Parse optional part followed by optional keyword
 pub fn parse_optional<F, R>(
@@ -53,11 +53,11 @@ pub fn check_label_identifier_mismatch(
     ctx: &mut ParsingContext<'_>,
     label: Option<&Ident>,
     end_ident: Option<Ident>,
-) -> Option<SrcPos> {
+) -> Option<TokenId> {
     if let Some(ident) = label {
         if let Some(end_ident) = end_ident {
             if ident.item == end_ident.item {
-                return Some(end_ident.pos(ctx).clone());
+                return Some(end_ident.token);
             } else {
                 ctx.diagnostics.push(Diagnostic::syntax_error(
                     end_ident.pos(ctx),
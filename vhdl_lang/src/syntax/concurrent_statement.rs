@@ -6,12 +6,15 @@
 
 use super::common::check_label_identifier_mismatch;
 use super::common::ParseResult;
-use super::declarative_part::{is_declarative_part, parse_declarative_part};
+use super::declarative_part::{
+    is_declarative_part, parse_block_declarative_part, parse_declarative_part,
+};
 use super::expression::parse_aggregate;
 use super::expression::{parse_choices, parse_expression};
 use super::interface_declaration::{parse_generic_interface_list, parse_port_interface_list};
 use super::names::parse_name;
 use super::names::{expression_to_ident, parse_association_list, parse_selected_name};
+use super::psl::{parse_psl_comments, parse_psl_directive};
 use super::range::parse_discrete_range;
 use super::sequential_statement::{
     parse_assert_statement, parse_labeled_sequential_statements, parse_selection,
@@ -25,6 +28,7 @@ use crate::data::*;
 use crate::syntax::recover::{expect_semicolon, expect_semicolon_or_last};
 use crate::syntax::{Kind, TokenAccess};
 use crate::TokenId;
+use crate::VHDLStandard::VHDL2008;
 use vhdl_lang::syntax::parser::ParsingContext;
 use vhdl_lang::TokenSpan;
 
@@ -48,7 +52,7 @@ pub fn parse_block_statement(
     };
     let is_token = ctx.stream.pop_if_kind(Is);
     let header = parse_block_header(ctx)?;
-    let decl = parse_declarative_part(ctx)?;
+    let decl = parse_block_declarative_part(ctx)?;
     let begin_token = ctx.stream.expect_kind(Begin)?;
     let statements = parse_labeled_concurrent_statements(ctx)?;
     let end_token = ctx.stream.expect_kind(End)?;
@@ -187,6 +191,7 @@ pub fn parse_process_statement(
     let sensitivity_list = if let Some(left_par) = ctx.stream.pop_if_kind(LeftPar) {
         peek_token!(ctx.stream, token,
         All => {
+            ctx.require_standard(token, "Sensitivity list 'all'", VHDL2008);
             ctx.stream.skip();
             let right_par = ctx.stream.expect_kind(RightPar)?;
             Some(WithTokenSpan::new(SensitivityList::All, TokenSpan::new(left_par, right_par)))
@@ -396,7 +401,7 @@ fn parse_optional_declarative_part(
     ctx: &mut ParsingContext<'_>,
 ) -> ParseResult<Option<(Vec<WithTokenSpan<Declaration>>, TokenId)>> {
     if is_declarative_part(ctx)? {
-        let decls = parse_declarative_part(ctx)?;
+        let decls = parse_block_declarative_part(ctx)?;
         let begin_token = ctx.stream.expect_kind(Begin)?;
         Ok(Some((decls, begin_token)))
     } else {
@@ -642,7 +647,14 @@ pub fn parse_concurrent_statement(
             For => ConcurrentStatement::ForGenerate(parse_for_generate_statement(ctx, label)?),
             If => ConcurrentStatement::IfGenerate(parse_if_generate_statement(ctx, label)?),
             Case => ConcurrentStatement::CaseGenerate(parse_case_generate_statement(ctx, label)?),
-            Assert => ConcurrentStatement::Assert(parse_concurrent_assert_statement(ctx, false)?),
+            Assert => {
+                if ctx.standard >= VHDL2008 {
+                    parse_psl_directive(ctx)?
+                } else {
+                    ConcurrentStatement::Assert(parse_concurrent_assert_statement(ctx, false)?)
+                }
+            },
+            Assume | AssumeGuarantee | Restrict | RestrictGuarantee | Cover => parse_psl_directive(ctx)?,
             Postponed => {
                 let tok = ctx.stream.get_current_token_id();
                 ctx.stream.skip();
@@ -692,6 +704,7 @@ pub fn parse_labeled_concurrent_statements(
 ) -> ParseResult<Vec<LabeledConcurrentStatement>> {
     let mut statements = Vec::new();
     loop {
+        statements.extend(parse_psl_comments(ctx, parse_labeled_concurrent_statement));
         let token = ctx.stream.peek_expect()?;
         match token.kind {
             End | Elsif | Else | When => {
@@ -892,7 +905,7 @@ end block name;",
             begin_token: code.s1("begin").token(),
             statements: vec![],
             end_token: code.s1("end").token(),
-            end_label_pos: Some(code.s("name", 2).pos()),
+            end_label_pos: Some(code.s("name", 2).token()),
             span: code.token_span().skip_to(code.s1("block").token()),
         };
         let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
@@ -1066,7 +1079,7 @@ end process name;",
             begin_token: code.s1("begin").token(),
             statements: vec![],
             end_token: code.s1("end").token(),
-            end_label_pos: Some(code.s("name", 2).pos()),
+            end_label_pos: Some(code.s("name", 2).token()),
             span: code.token_span().skip_to(code.s1("process").token()),
         };
         let stmt = code.with_stream_no_diagnostics(parse_labeled_concurrent_statement);
@@ -2205,7 +2218,7 @@ end generate gen1;",
                     },
                 ],
             },
-            end_label_pos: Some(code.s("gen1", 2).pos()),
+            end_label_pos: Some(code.s("gen1", 2).token()),
             end_token: code.s1("end").token(),
             span: code.token_span().skip_to(code.s1("case").token()),
         };
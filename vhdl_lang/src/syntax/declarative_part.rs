@@ -18,6 +18,7 @@ use super::type_declaration::parse_type_declaration;
 use crate::ast::token_range::WithTokenSpan;
 use crate::ast::{ContextClause, Declaration, PackageInstantiation};
 use crate::syntax::concurrent_statement::parse_map_aspect;
+use crate::syntax::psl::{parse_psl_comments, parse_psl_declaration};
 use crate::syntax::recover::expect_semicolon_or_last;
 use crate::syntax::view::parse_mode_view_declaration;
 use vhdl_lang::syntax::parser::ParsingContext;
@@ -62,12 +63,30 @@ pub fn is_declarative_part(ctx: &mut ParsingContext<'_>) -> ParseResult<bool> {
             | Package
             | For
             | View
+            | Property
+            | Sequence
+            | Default
             | Begin
     ))
 }
 
 pub fn parse_declarative_part(
     ctx: &mut ParsingContext<'_>,
+) -> ParseResult<Vec<WithTokenSpan<Declaration>>> {
+    parse_declarations(ctx, false)
+}
+
+/// The declarative part of an architecture, block or generate statement body.
+/// These may contain PSL declarations embedded in `-- psl` comments.
+pub fn parse_block_declarative_part(
+    ctx: &mut ParsingContext<'_>,
+) -> ParseResult<Vec<WithTokenSpan<Declaration>>> {
+    parse_declarations(ctx, true)
+}
+
+fn parse_declarations(
+    ctx: &mut ParsingContext<'_>,
+    psl_comments: bool,
 ) -> ParseResult<Vec<WithTokenSpan<Declaration>>> {
     let mut declarations: Vec<WithTokenSpan<Declaration>> = Vec::new();
 
@@ -89,6 +108,9 @@ pub fn parse_declarative_part(
                 | Variable
                 | Attribute
                 | View
+                | Property
+                | Sequence
+                | Default
                 | Use
                 | Alias
                 | Begin
@@ -96,7 +118,21 @@ pub fn parse_declarative_part(
         )
     }
 
-    while let Some(token) = ctx.stream.peek() {
+    loop {
+        if psl_comments {
+            declarations.extend(parse_psl_comments(ctx, |ctx| {
+                let start_token = ctx.stream.get_current_token_id();
+                let decl = parse_psl_declaration(ctx)?;
+                let end_token = ctx.stream.get_last_token_id();
+                Ok(WithTokenSpan::new(
+                    Declaration::Psl(decl),
+                    TokenSpan::new(start_token, end_token),
+                ))
+            }));
+        }
+        let Some(token) = ctx.stream.peek() else {
+            break;
+        };
         let start_token = ctx.stream.get_current_token_id();
         match token.kind {
             Begin | End => break,
@@ -168,6 +204,22 @@ pub fn parse_declarative_part(
                 }
             }
 
+            Property | Sequence | Default => {
+                match parse_psl_declaration(ctx).or_recover_until(ctx, is_recover_token) {
+                    Ok(decl) => {
+                        let end_token = ctx.stream.get_last_token_id();
+                        declarations.push(WithTokenSpan::new(
+                            Declaration::Psl(decl),
+                            TokenSpan::new(start_token, end_token),
+                        ))
+                    }
+                    Err(err) => {
+                        ctx.diagnostics.push(err);
+                        continue;
+                    }
+                }
+            }
+
             _ => {
                 use crate::VHDLStandard::*;
                 let expected: &[Kind] = match ctx.standard {
@@ -4,7 +4,8 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::tokens::{HasTokenSpan, Kind::*, TokenSpan};
+use super::tokens::{HasTokenSpan, Kind::*, Token, TokenSpan, Value};
+use crate::VHDLStandard::VHDL2008;
 use vhdl_lang::syntax::parser::ParsingContext;
 
 use super::common::check_end_identifier_mismatch;
@@ -15,7 +16,9 @@ use super::configuration::parse_configuration_declaration;
 use super::context::{
     parse_context, parse_library_clause, parse_use_clause, DeclarationOrReference,
 };
-use super::declarative_part::{parse_declarative_part, parse_package_instantiation};
+use super::declarative_part::{
+    parse_block_declarative_part, parse_declarative_part, parse_package_instantiation,
+};
 use crate::ast::*;
 use crate::data::error_codes::ErrorCode;
 use crate::data::*;
@@ -66,7 +69,7 @@ pub fn parse_architecture_body(ctx: &mut ParsingContext<'_>) -> ParseResult<Arch
     let entity_name = ctx.stream.expect_ident()?;
     ctx.stream.expect_kind(Is)?;
 
-    let decl = parse_declarative_part(ctx)?;
+    let decl = parse_block_declarative_part(ctx)?;
     let begin_token = ctx.stream.expect_kind(Begin)?;
 
     let statements = parse_labeled_concurrent_statements(ctx)?;
@@ -96,6 +99,10 @@ pub fn parse_package_declaration(ctx: &mut ParsingContext<'_>) -> ParseResult<Pa
 
     ctx.stream.expect_kind(Is)?;
     let generic_clause = parse_optional_generic_list(ctx)?;
+    if let Some(generic_clause) = &generic_clause {
+        let generic_token = generic_clause.span.start_token.pos(ctx).clone();
+        ctx.require_standard(generic_token, "Generic package", VHDL2008);
+    }
     let decl = parse_declarative_part(ctx)?;
     let end_token = ctx.stream.expect_kind(End)?;
     ctx.stream.pop_if_kind(Package);
@@ -151,11 +158,38 @@ fn context_item_message(context_item: &ContextItem, message: impl AsRef<str>) ->
     format!("{} {}", prefix, message.as_ref())
 }
 
+/// `context` is a reserved word since VHDL 2008 and an identifier before
+fn is_context_identifier(token: &Token) -> bool {
+    matches!(&token.value, Value::Identifier(sym) if sym.name_utf8().eq_ignore_ascii_case("context"))
+}
+
+/// Skip a context declaration or context reference when parsing a standard older than VHDL 2008.
+/// The construct is reported instead of failing to parse the rest of the file.
+fn skip_context_before_vhdl_2008(ctx: &mut ParsingContext<'_>) {
+    let context_token = ctx.stream.get_current_token_id();
+    let is_declaration = ctx.stream.next_kinds_are(&[Identifier, Identifier, Is]);
+    let construct = if is_declaration {
+        "Context declaration"
+    } else {
+        "Context reference"
+    };
+    ctx.require_standard(context_token.pos(ctx).clone(), construct, VHDL2008);
+    if is_declaration {
+        let _ = ctx.stream.skip_until(|kind| kind == End);
+    }
+    let _ = ctx.stream.skip_until(|kind| kind == SemiColon);
+    ctx.stream.skip();
+}
+
 pub fn parse_design_file(ctx: &mut ParsingContext<'_>) -> ParseResult<DesignFile> {
     let mut context_clause = vec![];
     let mut design_units = vec![];
 
     while let Some(token) = ctx.stream.peek() {
+        if ctx.standard < VHDL2008 && is_context_identifier(token) {
+            skip_context_before_vhdl_2008(ctx);
+            continue;
+        }
         try_init_token_kind!(
             token,
             Library => {
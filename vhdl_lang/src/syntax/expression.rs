@@ -12,6 +12,7 @@ use crate::ast::token_range::{WithToken, WithTokenSpan};
 use crate::ast::{Literal, *};
 use crate::data::Diagnostic;
 use crate::syntax::TokenAccess;
+use crate::VHDLStandard::VHDL2008;
 use crate::{ast, HasTokenSpan, TokenId, TokenSpan};
 use vhdl_lang::syntax::parser::ParsingContext;
 
@@ -481,6 +482,22 @@ fn parse_primary(ctx: &mut ParsingContext<'_>) -> ParseResult<WithTokenSpan<Expr
         kind => {
             // Prefix unary operation
             if let Some((unary_op, op_precedence)) = kind_to_prefix_unary_op(kind) {
+                match unary_op {
+                    Operator::QueQue => {
+                        ctx.require_standard(token, "Condition operator '??'", VHDL2008)
+                    }
+                    Operator::And
+                    | Operator::Or
+                    | Operator::Nand
+                    | Operator::Nor
+                    | Operator::Xor
+                    | Operator::Xnor => ctx.require_standard(
+                        token,
+                        format!("Unary logical operator '{}'", kind.as_str()),
+                        VHDL2008,
+                    ),
+                    _ => {}
+                }
                 ctx.stream.skip();
 
                 let expr = parse_expr(ctx, op_precedence)?;
@@ -517,6 +534,21 @@ fn parse_expr(
         if let Some((binary_op, op_precedence)) = kind_to_binary_op(token.kind) {
             // Binary operation
             if op_precedence > min_precedence {
+                if matches!(
+                    binary_op,
+                    Operator::QueEQ
+                        | Operator::QueNE
+                        | Operator::QueLT
+                        | Operator::QueLTE
+                        | Operator::QueGT
+                        | Operator::QueGTE
+                ) {
+                    ctx.require_standard(
+                        token,
+                        format!("Matching relational operator '{}'", token.kind.as_str()),
+                        VHDL2008,
+                    );
+                }
                 ctx.stream.skip();
                 let rhs = parse_expr(ctx, op_precedence)?;
                 let pos = lhs.span.combine(rhs.span);
@@ -444,6 +444,13 @@ fn _parse_name(ctx: &mut ParsingContext<'_>) -> ParseResult<WithTokenSpan<Name>>
                 }
             }
             LeftSquare => {
+                if !(ctx.stream.nth_kind_is(1, Identifier)
+                    || ctx.stream.nth_kind_is(1, Return)
+                    || ctx.stream.nth_kind_is(1, RightSquare))
+                {
+                    // Not a signature but i.e. a PSL repetition `a[*2]`
+                    break;
+                }
                 let state = ctx.stream.state();
                 let signature = Some(parse_signature(ctx)?);
                 if !ctx.stream.skip_if_kind(Tick) {
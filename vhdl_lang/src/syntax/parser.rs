@@ -4,11 +4,12 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use super::tokens::{Symbols, TokenStream, Tokenizer};
+use super::tokens::{Defines, Symbols, TokenStream, Tokenizer};
 use crate::ast::DesignFile;
 use crate::data::*;
 use crate::standard::VHDLStandard;
 use crate::syntax::design_unit::parse_design_file;
+use crate::syntax::verilog::{is_verilog_file, parse_verilog_source};
 use crate::{Token, TokenId};
 use std::io;
 use std::sync::Arc;
@@ -17,6 +18,8 @@ use vhdl_lang::TokenAccess;
 pub struct VHDLParser {
     pub symbols: Arc<Symbols>,
     pub standard: VHDLStandard,
+    /// User defined identifiers for conditional analysis
+    pub defines: Arc<Defines>,
 }
 
 pub(crate) struct ParsingContext<'a> {
@@ -39,6 +42,23 @@ impl TokenAccess for ParsingContext<'_> {
     }
 }
 
+impl ParsingContext<'_> {
+    /// Report a construct that is only part of the `required` and newer standards
+    /// when parsing an older standard
+    pub fn require_standard(
+        &mut self,
+        pos: impl AsRef<SrcPos>,
+        construct: impl AsRef<str>,
+        required: VHDLStandard,
+    ) {
+        if self.standard < required {
+            self.diagnostics.push(Diagnostic::requires_newer_standard(
+                pos, construct, required,
+            ));
+        }
+    }
+}
+
 pub type ParserResult = Result<(Source, DesignFile), io::Error>;
 
 impl VHDLParser {
@@ -46,6 +66,22 @@ impl VHDLParser {
         VHDLParser {
             symbols: Arc::new(Symbols::from_standard(vhdl_standard)),
             standard: vhdl_standard,
+            defines: Arc::default(),
+        }
+    }
+
+    /// Evaluate conditional analysis directives with the given user defined identifiers
+    pub fn with_defines(mut self, defines: Defines) -> VHDLParser {
+        self.defines = Arc::new(defines);
+        self
+    }
+
+    /// A parser for another standard that shares the symbols of this parser
+    pub fn with_standard(&self, standard: VHDLStandard) -> VHDLParser {
+        VHDLParser {
+            symbols: self.symbols.clone(),
+            standard,
+            defines: self.defines.clone(),
         }
     }
 
@@ -53,17 +89,57 @@ impl VHDLParser {
         self.symbols.symtab().insert(name)
     }
 
+    /// Parse a design file. The module headers of Verilog and SystemVerilog files
+    /// are parsed as entities.
     pub fn parse_design_source(
         &self,
         source: &Source,
         diagnostics: &mut dyn DiagnosticHandler,
+    ) -> DesignFile {
+        if is_verilog_file(source.file_name()) {
+            parse_verilog_source(self, source, diagnostics)
+        } else {
+            self.parse_vhdl_source(source, diagnostics)
+        }
+    }
+
+    pub(crate) fn parse_vhdl_source(
+        &self,
+        source: &Source,
+        diagnostics: &mut dyn DiagnosticHandler,
     ) -> DesignFile {
         let contents = source.contents();
-        let tokenizer = Tokenizer::new(&self.symbols, source, ContentReader::new(&contents));
-        let stream = TokenStream::new(tokenizer, diagnostics);
+        let stream = TokenStream::new(self.tokenizer(source, &contents), diagnostics);
+        self.parse_stream(&stream, diagnostics)
+    }
 
+    fn tokenizer<'a>(&'a self, source: &'a Source, contents: &'a Contents) -> Tokenizer<'a> {
+        Tokenizer::new(&self.symbols, source, ContentReader::new(contents))
+            .with_standard(self.standard)
+            .with_defines(&self.defines)
+    }
+
+    /// A tokenizer that starts at a position within the contents
+    fn tokenizer_at<'a>(
+        &'a self,
+        source: &'a Source,
+        contents: &'a Contents,
+        pos: Position,
+    ) -> Tokenizer<'a> {
+        let mut reader = ContentReader::new(contents);
+        reader.seek_pos(pos);
+        Tokenizer::new(&self.symbols, source, reader)
+            .with_standard(self.standard)
+            .with_defines(&self.defines)
+    }
+
+    fn parse_stream(
+        &self,
+        stream: &TokenStream<'_>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> DesignFile {
         let mut ctx = ParsingContext {
-            stream: &stream,
+            stream,
             diagnostics,
             standard: self.standard,
         };
@@ -77,6 +153,231 @@ impl VHDLParser {
         }
     }
 
+    /// Parse a design file after an edit, given the tokens of the design units of the
+    /// previous version in the order of the file.
+    ///
+    /// A design unit is kept if its tokens are unchanged relative to the start of the unit.
+    /// Design units above the changed lines keep their position and those below are moved by
+    /// the number of lines that were added or removed. Only the text between the kept design
+    /// units is tokenized and parsed. The whole file is tokenized if it is not known which
+    /// lines changed, such as after the contents were replaced as a whole.
+    /// The whole file is parsed if the parsed tokens do not end where a kept design unit
+    /// begins, such as when the end of a design unit has been removed.
+    ///
+    /// Returns the indices of the kept design units together with the number of lines that
+    /// they moved and a design file containing the other design units.
+    /// Diagnostics are only reported for the other design units.
+    pub(crate) fn reparse_design_source(
+        &self,
+        source: &Source,
+        previous_units: &[&[Token]],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> (Vec<(usize, i64)>, DesignFile) {
+        if is_verilog_file(source.file_name()) {
+            return (Vec::new(), parse_verilog_source(self, source, diagnostics));
+        }
+        let changed_lines = source.take_changed_lines();
+        match self.reparse_vhdl_source(source, changed_lines, previous_units, diagnostics) {
+            Some(result) => result,
+            None => (Vec::new(), self.parse_vhdl_source(source, diagnostics)),
+        }
+    }
+
+    /// Returns `None` if the whole file needs to be parsed
+    fn reparse_vhdl_source(
+        &self,
+        source: &Source,
+        changed_lines: ChangedLines,
+        previous_units: &[&[Token]],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> Option<(Vec<(usize, i64)>, DesignFile)> {
+        let contents = source.contents();
+        let mut token_diagnostics = Vec::new();
+        // Tool directives such as conditional analysis depend on the text before them
+        let (tokens, kept) = match changed_lines {
+            ChangedLines::Unchanged if !has_tool_directives(&contents) => self
+                .tokenize_changed_lines(
+                    source,
+                    &contents,
+                    previous_units,
+                    (u32::MAX, u32::MAX, u32::MAX),
+                    &mut token_diagnostics,
+                ),
+            ChangedLines::Lines {
+                start,
+                old_end,
+                new_end,
+            } if !has_tool_directives(&contents) => self.tokenize_changed_lines(
+                source,
+                &contents,
+                previous_units,
+                (start, old_end, new_end),
+                &mut token_diagnostics,
+            ),
+            _ => {
+                let tokens =
+                    TokenStream::new(self.tokenizer(source, &contents), &mut token_diagnostics)
+                        .into_tokens();
+                let kept = find_moved_units(&tokens, previous_units);
+                (tokens, kept)
+            }
+        };
+
+        // Parse the tokens between the kept design units
+        let mut design_units = Vec::new();
+        let mut gap_diagnostics = Vec::new();
+        let mut gap_start = 0;
+        let gaps = kept
+            .iter()
+            .map(|&(idx, start, _)| (start, Some(start + previous_units[idx].len())))
+            .chain(std::iter::once((tokens.len(), None)));
+        for (gap_end, unit_end) in gaps {
+            if gap_start < gap_end {
+                let stream = TokenStream::from_tokens(
+                    self.tokenizer(source, &contents),
+                    tokens[gap_start..gap_end].to_vec(),
+                );
+                let num_diagnostics = gap_diagnostics.len();
+                let design_file = self.parse_stream(&stream, &mut gap_diagnostics);
+
+                if unit_end.is_some() {
+                    let next_start = tokens[gap_end].pos.start();
+                    let num_parsed: usize = design_file
+                        .design_units
+                        .iter()
+                        .map(|(unit_tokens, _)| unit_tokens.len())
+                        .sum();
+                    if num_parsed != gap_end - gap_start
+                        || gap_diagnostics[num_diagnostics..]
+                            .iter()
+                            .any(|diagnostic| diagnostic.pos.end() > next_start)
+                    {
+                        return None;
+                    }
+                }
+                design_units.extend(design_file.design_units);
+            }
+            if let Some(unit_end) = unit_end {
+                gap_start = unit_end;
+            }
+        }
+
+        // The diagnostics of the kept design units are already known
+        let kept_ranges: Vec<_> = kept
+            .iter()
+            .map(|&(idx, start, _)| {
+                let end = start + previous_units[idx].len() - 1;
+                (tokens[start].pos.start(), tokens[end].pos.end())
+            })
+            .collect();
+        for diagnostic in token_diagnostics.into_iter().chain(gap_diagnostics) {
+            let is_kept = kept_ranges.iter().any(|(start, end)| {
+                *start <= diagnostic.pos.start() && diagnostic.pos.end() <= *end
+            });
+            if !is_kept {
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        Some((
+            kept.into_iter()
+                .map(|(idx, _, lines)| (idx, lines))
+                .collect(),
+            DesignFile { design_units },
+        ))
+    }
+
+    /// Tokenize the text between the design units that are above or below the changed lines
+    /// `start..old_end`, which are now lines `start..new_end`. The tokens of these
+    /// design units are moved instead, unless the tokenized text continues into them,
+    /// such as when a multi-line comment has been opened.
+    ///
+    /// Returns the tokens and the index, first token and number of moved lines of each kept
+    /// design unit.
+    fn tokenize_changed_lines(
+        &self,
+        source: &Source,
+        contents: &Contents,
+        previous_units: &[&[Token]],
+        (start, old_end, new_end): (u32, u32, u32),
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> (Vec<Token>, Vec<(usize, usize, i64)>) {
+        let mut tokens = Vec::new();
+        let mut kept = Vec::new();
+
+        // Where tokenizing continues after the last kept design unit
+        let mut cursor = Position::default();
+        // Whether the last kept design unit is below the changed lines
+        let mut last_is_below = false;
+        // Whether the text since the last kept design unit needs to be tokenized
+        let mut is_changed = false;
+        let mut tokenizer: Option<Tokenizer<'_>> = None;
+        let mut pending: Option<Token> = None;
+
+        for (idx, unit_tokens) in previous_units.iter().enumerate() {
+            let (Some(first), Some(last)) = (unit_tokens.first(), unit_tokens.last()) else {
+                continue;
+            };
+            let is_below = first.full_range().start.line >= old_end;
+            let lines = if last.full_range().end.line < start {
+                0
+            } else if is_below {
+                i64::from(new_end) - i64::from(old_end)
+            } else {
+                is_changed = true;
+                continue;
+            };
+            let mut first = first.clone();
+            first.shift_lines(lines);
+
+            if is_changed || is_below != last_is_below {
+                let tokenizer =
+                    tokenizer.get_or_insert_with(|| self.tokenizer_at(source, contents, cursor));
+                let is_unchanged = loop {
+                    match pending
+                        .take()
+                        .or_else(|| next_token(tokenizer, diagnostics))
+                    {
+                        Some(token) if token.pos.start() < first.pos.start() => tokens.push(token),
+                        Some(token) if token == first => break true,
+                        Some(token) if token.pos.start() == first.pos.start() => {
+                            tokens.push(token);
+                            break false;
+                        }
+                        token => {
+                            pending = token;
+                            break false;
+                        }
+                    }
+                };
+                if !is_unchanged {
+                    // The design unit is tokenized together with the text before it
+                    is_changed = true;
+                    continue;
+                }
+            }
+
+            kept.push((idx, tokens.len(), lines));
+            tokens.extend(unit_tokens.iter().map(|token| {
+                let mut token = token.clone();
+                token.shift_lines(lines);
+                token
+            }));
+            cursor = tokens[tokens.len() - 1].full_range().end;
+            last_is_below = is_below;
+            is_changed = false;
+            tokenizer = None;
+        }
+
+        // The text after the last kept design unit
+        let tokenizer =
+            tokenizer.get_or_insert_with(|| self.tokenizer_at(source, contents, cursor));
+        tokens.extend(pending);
+        while let Some(token) = next_token(tokenizer, diagnostics) {
+            tokens.push(token);
+        }
+        (tokens, kept)
+    }
     pub fn parse_design_file(
         &self,
         file_name: &Path,
@@ -87,3 +388,59 @@ impl VHDLParser {
         Ok((source, design_file))
     }
 }
+
+/// The next token, tokens that cannot be tokenized are reported
+fn next_token(
+    tokenizer: &mut Tokenizer<'_>,
+    diagnostics: &mut dyn DiagnosticHandler,
+) -> Option<Token> {
+    loop {
+        match tokenizer.pop() {
+            Ok(token) => return token,
+            Err(err) => diagnostics.push(err),
+        }
+    }
+}
+
+/// True if the contents contain a tool directive such as `` `if ``
+fn has_tool_directives(contents: &Contents) -> bool {
+    (0..contents.num_lines())
+        .filter_map(|lineno| contents.get_line(lineno))
+        .any(|line| line.contains('`'))
+}
+
+/// Find the previous design units among the tokens of the new version, in the same order.
+/// Returns the index, first token and number of moved lines of each design unit that was found.
+fn find_moved_units(tokens: &[Token], previous_units: &[&[Token]]) -> Vec<(usize, usize, i64)> {
+    let mut kept = Vec::new();
+    let mut next = 0;
+    for (idx, unit_tokens) in previous_units.iter().enumerate() {
+        let Some(first) = unit_tokens.first() else {
+            continue;
+        };
+        let found = (next..tokens.len()).find_map(|start| {
+            let token = &tokens[start];
+            if token.kind != first.kind
+                || token.pos.start().character != first.pos.start().character
+            {
+                return None;
+            }
+            let lines = i64::from(token.pos.start().line) - i64::from(first.pos.start().line);
+            let candidates = tokens.get(start..start + unit_tokens.len())?;
+            let is_moved = candidates
+                .iter()
+                .zip(unit_tokens.iter())
+                .all(|(token, previous)| {
+                    let mut previous = previous.clone();
+                    previous.shift_lines(lines);
+                    *token == previous
+                });
+            is_moved.then_some((start, lines))
+        });
+        if let Some((start, lines)) = found {
+            kept.push((idx, start, lines));
+            next = start + unit_tokens.len();
+        }
+    }
+    kept
+}
@@ -0,0 +1,693 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! PSL (IEEE 1850) declarations and directives embedded in VHDL-2008.
+//!
+//! Operands that are not PSL constructs are parsed as VHDL expressions such that the names
+//! within them can be resolved like any other name.
+//! Property and sequence instances, i.e. `handshake(req, ack)`, are parsed as function calls.
+
+use super::common::ParseResult;
+use super::expression::parse_expression;
+use super::tokens::{Kind::*, Token, TokenSpan, Value};
+use crate::ast::token_range::{WithToken, WithTokenSpan};
+use crate::ast::*;
+use crate::data::Diagnostic;
+use crate::syntax::recover::expect_semicolon;
+use crate::syntax::TokenAccess;
+use crate::TokenId;
+use vhdl_lang::syntax::parser::ParsingContext;
+
+/// The lower case name of an identifier token
+fn identifier_name(token: &Token) -> Option<String> {
+    match &token.value {
+        Value::Identifier(sym) if token.kind == Identifier => {
+            Some(sym.name_utf8().to_ascii_lowercase())
+        }
+        _ => None,
+    }
+}
+
+fn peek_identifier_name(ctx: &ParsingContext<'_>, offset: usize) -> Option<String> {
+    let id = ctx.stream.get_current_token_id() + offset;
+    if ctx.stream.nth_kind_is(offset, Identifier) {
+        identifier_name(ctx.stream.index(id))
+    } else {
+        None
+    }
+}
+
+/// PSL property or sequence declaration or `default clock` declaration
+pub fn parse_psl_declaration(ctx: &mut ParsingContext<'_>) -> ParseResult<PslDeclaration> {
+    let token = ctx.stream.peek_expect()?;
+    let kind = match token.kind {
+        Property => PslDeclarationKind::Property,
+        Sequence => PslDeclarationKind::Sequence,
+        Default => {
+            ctx.stream.skip();
+            if peek_identifier_name(ctx, 0).as_deref() != Some("clock") {
+                return Err(Diagnostic::syntax_error(
+                    ctx.stream.peek_expect()?,
+                    "Expected 'clock'",
+                ));
+            }
+            ctx.stream.skip();
+            ctx.stream.expect_kind(Is)?;
+            let clock = parse_psl_expression(ctx)?;
+            expect_semicolon(ctx);
+            return Ok(PslDeclaration::DefaultClock(clock));
+        }
+        _ => return Err(token.kinds_error(&[Property, Sequence, Default])),
+    };
+    ctx.stream.skip();
+    let ident = ctx.stream.expect_ident()?;
+    let mut parameters = Vec::new();
+    if ctx.stream.skip_if_kind(LeftPar) {
+        loop {
+            parameters.push(parse_psl_parameter(ctx)?);
+            expect_token!(
+                ctx.stream,
+                token,
+                SemiColon => continue,
+                RightPar => break
+            );
+        }
+    }
+    ctx.stream.expect_kind(Is)?;
+    let expression = parse_psl_expression(ctx)?;
+    expect_semicolon(ctx);
+
+    Ok(PslDeclaration::Property(PslPropertyDeclaration {
+        kind,
+        ident: ident.into(),
+        parameters,
+        expression,
+    }))
+}
+
+/// PSL formal parameters of the same kind, i.e. `boolean req, ack`
+fn parse_psl_parameter(ctx: &mut ParsingContext<'_>) -> ParseResult<PslParameter> {
+    let token = ctx.stream.peek_expect()?;
+    let kind = match token.kind {
+        Property => PslParameterKind::Property,
+        Sequence => PslParameterKind::Sequence,
+        _ => match identifier_name(token).as_deref() {
+            Some("const") => PslParameterKind::Const,
+            Some("boolean") => PslParameterKind::Boolean,
+            Some("bit") => PslParameterKind::Bit,
+            Some("bitvector") => PslParameterKind::Bitvector,
+            Some("numeric") => PslParameterKind::Numeric,
+            Some("string") => PslParameterKind::String,
+            _ => {
+                return Err(Diagnostic::syntax_error(
+                    token,
+                    "Expected 'const', 'boolean', 'bit', 'bitvector', 'numeric', 'string', 'property' or 'sequence'",
+                ))
+            }
+        },
+    };
+    let kind = WithToken::new(kind, ctx.stream.get_current_token_id());
+    ctx.stream.skip();
+    let mut idents = vec![ctx.stream.expect_ident()?.into()];
+    while ctx.stream.skip_if_kind(Comma) {
+        idents.push(ctx.stream.expect_ident()?.into());
+    }
+    Ok(PslParameter { kind, idents })
+}
+
+/// PSL statements embedded in `-- psl` comments before the current token,
+/// i.e. `-- psl assert always req -> next ack;`.
+/// Only called where PSL is allowed. A comment that does not contain a valid statement
+/// remains an ordinary comment and is not reported.
+pub fn parse_psl_comments<T>(
+    ctx: &mut ParsingContext<'_>,
+    parse: impl Fn(&mut ParsingContext<'_>) -> ParseResult<T>,
+) -> Vec<T> {
+    let mut items = Vec::new();
+    for comment in ctx.stream.take_psl_comments() {
+        let mut diagnostics = Vec::new();
+        let mut comment_ctx = ParsingContext {
+            stream: ctx.stream,
+            diagnostics: &mut diagnostics,
+            standard: ctx.standard,
+        };
+        let (result, is_complete) = ctx
+            .stream
+            .parse_psl_comment(comment, || parse(&mut comment_ctx));
+        if let (Ok(item), true, true) = (result, is_complete, diagnostics.is_empty()) {
+            ctx.stream.keep_psl_comment(comment);
+            items.push(item);
+        }
+    }
+    items
+}
+
+/// PSL verification directive, i.e. `assert always req -> next ack;`
+/// An assertion of a VHDL condition is a concurrent assertion statement,
+/// unless it is embedded in a comment where it must not become an ordinary statement.
+pub fn parse_psl_directive(ctx: &mut ParsingContext<'_>) -> ParseResult<ConcurrentStatement> {
+    let in_comment = ctx.stream.is_psl_comment();
+    let kind = expect_token!(
+        ctx.stream,
+        token,
+        Assert => PslDirectiveKind::Assert,
+        Assume => PslDirectiveKind::Assume,
+        AssumeGuarantee => PslDirectiveKind::AssumeGuarantee,
+        Restrict => PslDirectiveKind::Restrict,
+        RestrictGuarantee => PslDirectiveKind::RestrictGuarantee,
+        Cover => PslDirectiveKind::Cover
+    );
+    let property = parse_psl_expression(ctx)?;
+    let report = if ctx.stream.skip_if_kind(Report) {
+        Some(parse_expression(ctx)?)
+    } else {
+        None
+    };
+    let severity = if ctx.stream.skip_if_kind(Severity) {
+        Some(parse_expression(ctx)?)
+    } else {
+        None
+    };
+    expect_semicolon(ctx);
+
+    Ok(match property.item {
+        PslExpression::Hdl(condition) if kind == PslDirectiveKind::Assert && !in_comment => {
+            ConcurrentStatement::Assert(ConcurrentAssertStatement {
+                postponed: false,
+                statement: AssertStatement {
+                    condition: WithTokenSpan::new(condition, property.span),
+                    report,
+                    severity,
+                },
+            })
+        }
+        item => ConcurrentStatement::PslDirective(PslDirective {
+            kind,
+            property: WithTokenSpan::new(item, property.span),
+            report,
+            severity,
+        }),
+    })
+}
+
+/// PSL property, sequence or boolean
+pub fn parse_psl_expression(
+    ctx: &mut ParsingContext<'_>,
+) -> ParseResult<WithTokenSpan<PslExpression>> {
+    parse_binary(ctx, false, 0)
+}
+
+/// Binding strength of binary operators, higher binds stronger
+fn precedence(operator: PslOperator) -> usize {
+    use PslOperator::*;
+    match operator {
+        Implication | Equivalence => 1,
+        OverlappingImplication | NonOverlappingImplication => 2,
+        Until | UntilInclusive | Before | BeforeInclusive => 3,
+        Abort | AsyncAbort | SyncAbort => 4,
+        Concatenation => 5,
+        Fusion => 6,
+        SequenceOr => 7,
+        LengthMatchingAnd => 8,
+        Within => 9,
+        Clock => 10,
+        And | Or => 11,
+        _ => unreachable!("{operator:?} is not a binary operator"),
+    }
+}
+
+fn is_right_associative(operator: PslOperator) -> bool {
+    use PslOperator::*;
+    matches!(
+        operator,
+        Implication
+            | Equivalence
+            | OverlappingImplication
+            | NonOverlappingImplication
+            | Until
+            | UntilInclusive
+            | Before
+            | BeforeInclusive
+    )
+}
+
+/// The binary operator at the current position together with whether it is strong
+/// and the number of tokens it consists of.
+/// `;`, `:` and `|` are sequence operators within braces only.
+fn peek_binary_operator(
+    ctx: &ParsingContext<'_>,
+    sere: bool,
+) -> Option<(PslOperator, bool, usize)> {
+    let token = ctx.stream.peek()?;
+    let strong = ctx.stream.nth_kind_is(1, Exclamation);
+    let operator = match token.kind {
+        MinusGt => PslOperator::Implication,
+        LtMinusGt => PslOperator::Equivalence,
+        AmpAmp => PslOperator::LengthMatchingAnd,
+        CommAt => PslOperator::Clock,
+        And => PslOperator::And,
+        Or => PslOperator::Or,
+        Bar if ctx.stream.nth_kind_is(1, MinusGt) => {
+            return Some((PslOperator::OverlappingImplication, false, 2))
+        }
+        Bar if ctx.stream.nth_kind_is(1, RightArrow) => {
+            return Some((PslOperator::NonOverlappingImplication, false, 2))
+        }
+        Bar if sere => PslOperator::SequenceOr,
+        SemiColon if sere => PslOperator::Concatenation,
+        Colon if sere => PslOperator::Fusion,
+        Until => return Some((PslOperator::Until, strong, 1 + strong as usize)),
+        Identifier => {
+            let operator = match identifier_name(token)?.as_str() {
+                "until_" => PslOperator::UntilInclusive,
+                "before" => PslOperator::Before,
+                "before_" => PslOperator::BeforeInclusive,
+                "abort" => PslOperator::Abort,
+                "async_abort" => PslOperator::AsyncAbort,
+                "sync_abort" => PslOperator::SyncAbort,
+                "within" => PslOperator::Within,
+                _ => return None,
+            };
+            let strong =
+                strong && matches!(operator, PslOperator::Before | PslOperator::BeforeInclusive);
+            return Some((operator, strong, 1 + strong as usize));
+        }
+        _ => return None,
+    };
+    Some((operator, false, 1))
+}
+
+fn parse_binary(
+    ctx: &mut ParsingContext<'_>,
+    sere: bool,
+    min_precedence: usize,
+) -> ParseResult<WithTokenSpan<PslExpression>> {
+    let mut lhs = parse_unary(ctx, sere)?;
+    while let Some((operator, strong, num_tokens)) = peek_binary_operator(ctx, sere) {
+        let operator_precedence = precedence(operator);
+        if operator_precedence < min_precedence {
+            break;
+        }
+        // `and` and `or` of VHDL booleans are part of the VHDL expression
+        if matches!(operator, PslOperator::And | PslOperator::Or)
+            && matches!(lhs.item, PslExpression::Hdl(_))
+        {
+            break;
+        }
+        let operator_token = ctx.stream.get_current_token_id();
+        for _ in 0..num_tokens {
+            ctx.stream.skip();
+        }
+        let rhs = parse_binary(
+            ctx,
+            sere,
+            if is_right_associative(operator) {
+                operator_precedence
+            } else {
+                operator_precedence + 1
+            },
+        )?;
+        let span = lhs.span.combine(rhs.span);
+        lhs = WithTokenSpan::new(
+            PslExpression::Operation(PslOperation {
+                operator: WithToken::new(operator, operator_token),
+                strong,
+                arguments: Vec::new(),
+                operands: vec![lhs, rhs],
+            }),
+            span,
+        );
+    }
+    Ok(lhs)
+}
+
+/// A primary with any suffix operators, i.e. `{a; b}[*2]` or `{a; b}!`
+fn parse_unary(
+    ctx: &mut ParsingContext<'_>,
+    sere: bool,
+) -> ParseResult<WithTokenSpan<PslExpression>> {
+    let mut expr = parse_primary(ctx, sere)?;
+    while let Some((operator, operator_token)) = peek_suffix_operator(ctx) {
+        let (arguments, end_token) = if operator == PslOperator::Strong {
+            ctx.stream.skip();
+            (Vec::new(), operator_token)
+        } else {
+            parse_repetition(ctx, operator)?
+        };
+        let span = expr.span.end_with(end_token);
+        expr = WithTokenSpan::new(
+            PslExpression::Operation(PslOperation {
+                operator: WithToken::new(operator, operator_token),
+                strong: false,
+                arguments,
+                operands: vec![expr],
+            }),
+            span,
+        );
+    }
+    Ok(expr)
+}
+
+fn peek_suffix_operator(ctx: &ParsingContext<'_>) -> Option<(PslOperator, TokenId)> {
+    let token_id = ctx.stream.get_current_token_id();
+    let operator = match ctx.stream.peek_kind()? {
+        Exclamation => PslOperator::Strong,
+        LeftSquare => repetition_operator(ctx)?,
+        _ => return None,
+    };
+    Some((operator, token_id))
+}
+
+/// The kind of the repetition starting at the current `[`
+fn repetition_operator(ctx: &ParsingContext<'_>) -> Option<PslOperator> {
+    if ctx.stream.nth_kind_is(1, Times) {
+        Some(PslOperator::ConsecutiveRepetition)
+    } else if ctx.stream.nth_kind_is(1, Plus) {
+        Some(PslOperator::PlusRepetition)
+    } else if ctx.stream.nth_kind_is(1, EQ) {
+        Some(PslOperator::NonConsecutiveRepetition)
+    } else if ctx.stream.nth_kind_is(1, MinusGt) {
+        Some(PslOperator::GotoRepetition)
+    } else {
+        None
+    }
+}
+
+/// Repetitions such as `[*]`, `[*2]`, `[*1 to 3]`, `[+]`, `[=2]` or `[->1]`
+fn parse_repetition(
+    ctx: &mut ParsingContext<'_>,
+    operator: PslOperator,
+) -> ParseResult<(Vec<WithTokenSpan<Expression>>, TokenId)> {
+    ctx.stream.expect_kind(LeftSquare)?;
+    ctx.stream.skip();
+    if operator == PslOperator::PlusRepetition {
+        return Ok((Vec::new(), ctx.stream.expect_kind(RightSquare)?));
+    }
+    if let Some(right_square) = ctx.stream.pop_if_kind(RightSquare) {
+        return Ok((Vec::new(), right_square));
+    }
+    parse_bracket_arguments(ctx)
+}
+
+/// The count or range within brackets, i.e. `2]` or `1 to 3]` where `[` has been consumed
+fn parse_bracket_arguments(
+    ctx: &mut ParsingContext<'_>,
+) -> ParseResult<(Vec<WithTokenSpan<Expression>>, TokenId)> {
+    let mut arguments = vec![parse_expression(ctx)?];
+    if ctx.stream.skip_if_kind(To) {
+        arguments.push(parse_expression(ctx)?);
+    }
+    let right_square = ctx.stream.expect_kind(RightSquare)?;
+    Ok((arguments, right_square))
+}
+
+fn prefix_operator(ctx: &ParsingContext<'_>) -> Option<PslOperator> {
+    let token = ctx.stream.peek()?;
+    if token.kind == Next {
+        return Some(PslOperator::Next);
+    }
+    Some(match identifier_name(token)?.as_str() {
+        "always" => PslOperator::Always,
+        "never" => PslOperator::Never,
+        "eventually" => PslOperator::Eventually,
+        "next_a" => PslOperator::NextA,
+        "next_e" => PslOperator::NextE,
+        "next_event" => PslOperator::NextEvent,
+        "next_event_a" => PslOperator::NextEventA,
+        "next_event_e" => PslOperator::NextEventE,
+        _ => return None,
+    })
+}
+
+fn parse_primary(
+    ctx: &mut ParsingContext<'_>,
+    sere: bool,
+) -> ParseResult<WithTokenSpan<PslExpression>> {
+    let start_token = ctx.stream.get_current_token_id();
+    if let Some(operator) = prefix_operator(ctx) {
+        return parse_prefix_operation(ctx, operator);
+    }
+    let token = ctx.stream.peek_expect()?;
+    match token.kind {
+        LeftCurly => {
+            ctx.stream.skip();
+            let sere = parse_binary(ctx, true, 0)?;
+            let end_token = ctx.stream.expect_kind(RightCurly)?;
+            Ok(WithTokenSpan::new(
+                PslExpression::Braced(Box::new(sere)),
+                TokenSpan::new(start_token, end_token),
+            ))
+        }
+        // A repetition without operand within a sequence, i.e. `{a; [*2]; b}`
+        LeftSquare if sere && repetition_operator(ctx).is_some() => {
+            let operator = repetition_operator(ctx).unwrap();
+            let (arguments, end_token) = parse_repetition(ctx, operator)?;
+            Ok(WithTokenSpan::new(
+                PslExpression::Operation(PslOperation {
+                    operator: WithToken::new(operator, start_token),
+                    strong: false,
+                    arguments,
+                    operands: Vec::new(),
+                }),
+                TokenSpan::new(start_token, end_token),
+            ))
+        }
+        _ => {
+            let state = ctx.stream.state();
+            match parse_expression(ctx) {
+                Ok(expr) => Ok(expr.map_into(PslExpression::Hdl)),
+                // A property within parentheses, i.e. `(a -> b)`, or the negation thereof
+                Err(err) => {
+                    ctx.stream.set_state(state);
+                    match ctx.stream.peek_kind() {
+                        Some(LeftPar) => {
+                            ctx.stream.skip();
+                            let expr = parse_binary(ctx, false, 0)?;
+                            let end_token = ctx.stream.expect_kind(RightPar)?;
+                            Ok(WithTokenSpan::new(
+                                PslExpression::Parenthesized(Box::new(expr)),
+                                TokenSpan::new(start_token, end_token),
+                            ))
+                        }
+                        Some(Not) => {
+                            ctx.stream.skip();
+                            let operand = parse_unary(ctx, sere)?;
+                            let span = operand.span.start_with(start_token);
+                            Ok(WithTokenSpan::new(
+                                PslExpression::Operation(PslOperation {
+                                    operator: WithToken::new(PslOperator::Not, start_token),
+                                    strong: false,
+                                    arguments: Vec::new(),
+                                    operands: vec![operand],
+                                }),
+                                span,
+                            ))
+                        }
+                        _ => Err(err),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// FL occurrence operators such as `always p`, `eventually! s`, `next[2] p`, `next_e[1 to 3] (b)`
+/// or `next_event!(b)[2] (p)`
+fn parse_prefix_operation(
+    ctx: &mut ParsingContext<'_>,
+    operator: PslOperator,
+) -> ParseResult<WithTokenSpan<PslExpression>> {
+    use PslOperator::*;
+    let operator_token = ctx.stream.get_current_token_id();
+    ctx.stream.skip();
+    let strong = if operator == Eventually {
+        ctx.stream.expect_kind(Exclamation)?;
+        true
+    } else {
+        !matches!(operator, Always | Never) && ctx.stream.skip_if_kind(Exclamation)
+    };
+
+    let mut arguments = Vec::new();
+    if matches!(operator, NextEvent | NextEventA | NextEventE) {
+        ctx.stream.expect_kind(LeftPar)?;
+        arguments.push(parse_expression(ctx)?);
+        ctx.stream.expect_kind(RightPar)?;
+    }
+    if matches!(
+        operator,
+        Next | NextA | NextE | NextEvent | NextEventA | NextEventE
+    ) && ctx.stream.skip_if_kind(LeftSquare)
+    {
+        arguments.extend(parse_bracket_arguments(ctx)?.0);
+    }
+
+    let operand = parse_binary(ctx, false, 0)?;
+    let span = operand.span.start_with(operator_token);
+    Ok(WithTokenSpan::new(
+        PslExpression::Operation(PslOperation {
+            operator: WithToken::new(operator, operator_token),
+            strong,
+            arguments,
+            operands: vec![operand],
+        }),
+        span,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::syntax::test::Code;
+    use crate::VHDLStandard::VHDL2008;
+
+    /// Renders the structure of a PSL expression as nested operations
+    fn structure(expr: &PslExpression) -> String {
+        match expr {
+            PslExpression::Hdl(expr) => format!("{expr}"),
+            PslExpression::Operation(operation) => {
+                let mut parts = vec![format!("{:?}", operation.operator.item)];
+                if operation.strong {
+                    parts[0].push('!');
+                }
+                for argument in &operation.arguments {
+                    parts.push(format!("[{}]", argument.item));
+                }
+                for operand in &operation.operands {
+                    parts.push(structure(&operand.item));
+                }
+                format!("({})", parts.join(" "))
+            }
+            PslExpression::Braced(expr) => format!("{{{}}}", structure(&expr.item)),
+            PslExpression::Parenthesized(expr) => structure(&expr.item),
+        }
+    }
+
+    fn parse_structure(code: &str) -> String {
+        let code = Code::with_standard(code, VHDL2008);
+        structure(&code.parse_ok_no_diagnostics(parse_psl_expression).item)
+    }
+
+    #[test]
+    fn parses_vhdl_boolean() {
+        assert_eq!(parse_structure("a and b = '1'"), "a and b = '1'");
+    }
+
+    #[test]
+    fn parses_temporal_operators() {
+        assert_eq!(
+            parse_structure("always req -> next ack"),
+            "(Always (Implication req (Next ack)))"
+        );
+        assert_eq!(
+            parse_structure("always (req -> next![2] ack) until! done"),
+            "(Always (Until! (Implication req (Next! [2] ack)) done))"
+        );
+        assert_eq!(
+            parse_structure("next_event_a(clk)[1 to 3](busy)"),
+            "(NextEventA [clk] [1] [3] (busy))"
+        );
+        assert_eq!(
+            parse_structure("eventually! ack abort rst"),
+            "(Eventually! (Abort ack rst))"
+        );
+    }
+
+    #[test]
+    fn parses_sequences() {
+        assert_eq!(
+            parse_structure("{req; busy[*1 to 3]; ack} |=> {done[->]}"),
+            "(NonOverlappingImplication {(Concatenation (Concatenation req (ConsecutiveRepetition [1] [3] busy)) ack)} {(GotoRepetition done)})"
+        );
+        assert_eq!(
+            parse_structure("{a : b | c && d}!"),
+            "(Strong {(Fusion a (SequenceOr b (LengthMatchingAnd c d)))})"
+        );
+        assert_eq!(
+            parse_structure("{a; [*]; b[+]; c[=2]}"),
+            "{(Concatenation (Concatenation (Concatenation a (ConsecutiveRepetition)) (PlusRepetition b)) (NonConsecutiveRepetition [2] c))}"
+        );
+    }
+
+    #[test]
+    fn parses_properties_of_properties() {
+        assert_eq!(
+            parse_structure("not (always a) and never b"),
+            "(And (Not (Always a)) (Never b))"
+        );
+        assert_eq!(
+            parse_structure("(always a) @ rising_edge(clk)"),
+            "(Clock (Always a) rising_edge(clk))"
+        );
+    }
+
+    #[test]
+    fn parses_property_declaration() {
+        let code = Code::with_standard(
+            "property handshake(boolean req, ack; const n) is always req -> next[n] ack;",
+            VHDL2008,
+        );
+        let decl = code.parse_ok_no_diagnostics(parse_psl_declaration);
+        let PslDeclaration::Property(decl) = decl else {
+            panic!("Expected a property declaration");
+        };
+        assert_eq!(decl.kind, PslDeclarationKind::Property);
+        assert_eq!(decl.ident.tree, code.s1("handshake").ident());
+        assert_eq!(decl.parameters.len(), 2);
+        assert_eq!(decl.parameters[0].kind.item, PslParameterKind::Boolean);
+        assert_eq!(decl.parameters[0].idents.len(), 2);
+        assert_eq!(decl.parameters[1].kind.item, PslParameterKind::Const);
+        assert_eq!(
+            structure(&decl.expression.item),
+            "(Always (Implication req (Next [n] ack)))"
+        );
+    }
+
+    #[test]
+    fn parses_default_clock() {
+        let code = Code::with_standard("default clock is rising_edge(clk);", VHDL2008);
+        let decl = code.parse_ok_no_diagnostics(parse_psl_declaration);
+        let PslDeclaration::DefaultClock(clock) = decl else {
+            panic!("Expected a default clock declaration");
+        };
+        assert_eq!(structure(&clock.item), "rising_edge(clk)");
+    }
+
+    #[test]
+    fn assertion_of_a_vhdl_condition_is_a_concurrent_assertion() {
+        let code = Code::with_standard("assert a = b report \"msg\";", VHDL2008);
+        assert!(matches!(
+            code.parse_ok_no_diagnostics(parse_psl_directive),
+            ConcurrentStatement::Assert(_)
+        ));
+
+        let code = Code::with_standard("cover {a; b} report \"msg\" severity note;", VHDL2008);
+        let ConcurrentStatement::PslDirective(directive) =
+            code.parse_ok_no_diagnostics(parse_psl_directive)
+        else {
+            panic!("Expected a PSL directive");
+        };
+        assert_eq!(directive.kind, PslDirectiveKind::Cover);
+        assert!(directive.report.is_some());
+        assert!(directive.severity.is_some());
+    }
+
+    #[test]
+    fn assertion_of_a_vhdl_condition_in_a_comment_is_a_psl_directive() {
+        let code = Code::with_standard(
+            "\
+architecture a of ent is
+begin
+  -- psl assert a = b;
+end architecture;",
+            VHDL2008,
+        );
+        let arch = code.architecture_body();
+        assert!(matches!(
+            arch.statements[0].statement.item,
+            ConcurrentStatement::PslDirective(_)
+        ));
+    }
+}
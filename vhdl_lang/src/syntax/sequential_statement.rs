@@ -20,6 +20,7 @@ use crate::syntax::kinds_error;
 use crate::syntax::recover::{expect_semicolon, expect_semicolon_or_last};
 use crate::syntax::separated_list::parse_name_list;
 use crate::HasTokenSpan;
+use crate::VHDLStandard::VHDL2008;
 use vhdl_lang::syntax::parser::ParsingContext;
 use vhdl_lang::TokenSpan;
 
@@ -298,7 +299,17 @@ pub fn parse_signal_assignment_right_hand(
 fn parse_variable_assignment_right_hand(
     ctx: &mut ParsingContext<'_>,
 ) -> ParseResult<AssignmentRightHand<WithTokenSpan<Expression>>> {
-    parse_assignment_right_hand(ctx, parse_expression)
+    let rhs = parse_assignment_right_hand(ctx, parse_expression)?;
+    if let AssignmentRightHand::Conditional(conditionals) = &rhs {
+        if let Some(first) = conditionals.conditionals.first() {
+            ctx.require_standard(
+                first.condition.pos(ctx),
+                "Conditional variable assignment",
+                VHDL2008,
+            );
+        }
+    }
+    Ok(rhs)
 }
 
 fn parse_assignment_right_hand<T, F>(
@@ -1409,7 +1420,7 @@ end if mylabel;",
                             }],
                             else_item: None
                         },
-                        end_label_pos: Some(code.s("mylabel", 2).pos()),
+                        end_label_pos: Some(code.s("mylabel", 2).token()),
                     }),
                     code.pos_after("mylabel: ").token_span()
                 )
@@ -1477,7 +1488,7 @@ end if mylabel;",
                                 code.s1("else").token()
                             ))
                         },
-                        end_label_pos: Some(code.s("mylabel", 2).pos()),
+                        end_label_pos: Some(code.s("mylabel", 2).token()),
                     }),
                     code.pos_after("mylabel: ").token_span()
                 )
@@ -1559,7 +1570,7 @@ end if mylabel;",
                                 code.s1("else").token()
                             ))
                         },
-                        end_label_pos: Some(code.s("mylabel", 2).pos()),
+                        end_label_pos: Some(code.s("mylabel", 2).token()),
                     }),
                     code.pos_after("mylabel: ").token_span()
                 )
@@ -1674,7 +1685,7 @@ end loop lbl;",
                             code.s1("stmt2;").sequential_statement()
                         ],
                         end_token: code.s1("end").token(),
-                        end_label_pos: Some(code.s("lbl", 2).pos()),
+                        end_label_pos: Some(code.s("lbl", 2).token()),
                     }),
                     code.pos_after("lbl: ").token_span()
                 )
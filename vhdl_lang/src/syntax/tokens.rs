@@ -6,9 +6,12 @@
 
 #[macro_use]
 mod tokenizer;
+mod conditional_analysis;
 /// Contains constant keywords for different versions of VHDL.
 mod keywords;
 mod tokenstream;
 
+pub use conditional_analysis::Defines;
+
 pub use tokenizer::*;
 pub use tokenstream::*;
@@ -0,0 +1,321 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! Evaluation of the conditional analysis directives of VHDL-2019 (LRM 24.2), i.e.
+//! ```vhdl
+//! `if TOOL_TYPE = "SIMULATION" then
+//!     ...
+//! `elsif VHDL_VERSION >= "2019" then
+//!     ...
+//! `else
+//!     ...
+//! `end if
+//! ```
+
+use super::tokenizer::Kind::*;
+use super::tokenizer::*;
+use crate::data::{DiagnosticHandler, ErrorCode, Position, Source};
+use crate::standard::VHDLStandard;
+use crate::{Diagnostic, SrcPos};
+use fnv::FnvHashMap;
+use std::cmp::Ordering;
+
+/// User defined conditional analysis identifiers and their values.
+pub type Defines = FnvHashMap<String, String>;
+
+/// The value of `TOOL_TYPE` unless it is overwritten by a define
+const TOOL_TYPE: &str = "SIMULATION";
+/// The value of `TOOL_VENDOR` unless it is overwritten by a define
+const TOOL_VENDOR: &str = "VHDL-LS";
+/// The value of `TOOL_NAME` unless it is overwritten by a define
+const TOOL_NAME: &str = "vhdl_lang";
+
+/// A conditional analysis block that is not terminated yet
+struct Block {
+    /// The position of the `` `if `` directive
+    pos: SrcPos,
+    /// The region that contains the block is analyzed
+    enclosing_active: bool,
+    /// One of the branches of the block has been chosen
+    taken: bool,
+    /// The current branch of the block is analyzed
+    active: bool,
+    /// The `` `else `` directive has been seen
+    has_else: bool,
+}
+
+pub(crate) struct ConditionalAnalysis {
+    // Keys are lower case as identifiers are case-insensitive
+    identifiers: FnvHashMap<String, String>,
+    blocks: Vec<Block>,
+    // The start of the current inactive region
+    inactive_start: Option<Position>,
+}
+
+impl ConditionalAnalysis {
+    pub fn new(standard: VHDLStandard, defines: Option<&Defines>) -> ConditionalAnalysis {
+        let mut identifiers = FnvHashMap::default();
+        for (name, value) in [
+            ("vhdl_version", standard.as_ref()),
+            ("tool_type", TOOL_TYPE),
+            ("tool_vendor", TOOL_VENDOR),
+            ("tool_name", TOOL_NAME),
+            ("tool_edition", ""),
+            ("tool_version", env!("CARGO_PKG_VERSION")),
+        ] {
+            identifiers.insert(name.to_owned(), value.to_owned());
+        }
+        for (name, value) in defines.into_iter().flatten() {
+            identifiers.insert(name.to_lowercase(), value.clone());
+        }
+        ConditionalAnalysis {
+            identifiers,
+            blocks: Vec::new(),
+            inactive_start: None,
+        }
+    }
+
+    /// Whether the tokens at the current position shall be analyzed
+    pub fn is_active(&self) -> bool {
+        self.blocks.last().is_none_or(|block| block.active)
+    }
+
+    /// Handle an `` `if `` directive.
+    /// `end` is the end of the directive and `condition` are the tokens between `if` and `then`.
+    pub fn if_directive(
+        &mut self,
+        pos: SrcPos,
+        end: Position,
+        condition: &[Token],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let enclosing_active = self.is_active();
+        // Conditions within inactive regions are not evaluated
+        let taken = enclosing_active && self.evaluate(&pos, condition, diagnostics);
+        self.blocks.push(Block {
+            pos,
+            enclosing_active,
+            taken,
+            active: taken,
+            has_else: false,
+        });
+        self.update_region(enclosing_active, end, &mut |_| {});
+    }
+
+    /// Handle an `` `elsif `` directive or, when `condition` is `None`, an `` `else `` directive.
+    pub fn elsif_directive(
+        &mut self,
+        pos: SrcPos,
+        end: Position,
+        condition: Option<&[Token]>,
+        source: &Source,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let was_active = self.is_active();
+        let directive = if condition.is_some() {
+            "`elsif"
+        } else {
+            "`else"
+        };
+        let Some(block) = self.blocks.last() else {
+            diagnostics.push(Diagnostic::syntax_error(
+                &pos,
+                format!("{directive} without matching `if"),
+            ));
+            return;
+        };
+        if block.has_else {
+            diagnostics.push(Diagnostic::syntax_error(
+                &pos,
+                format!("{directive} after `else"),
+            ));
+        }
+
+        let (enclosing_active, taken) = (block.enclosing_active, block.taken);
+        let active = enclosing_active
+            && !taken
+            && condition.is_none_or(|condition| self.evaluate(&pos, condition, diagnostics));
+
+        let block = self.blocks.last_mut().unwrap();
+        block.active = active;
+        block.taken |= active;
+        block.has_else |= condition.is_none();
+
+        let start = pos.start();
+        self.update_region(was_active, end, &mut |region_start| {
+            push_inactive_region(source, region_start, start, diagnostics)
+        });
+    }
+
+    /// Handle an `` `end if `` directive.
+    pub fn end_directive(
+        &mut self,
+        pos: SrcPos,
+        end: Position,
+        source: &Source,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let was_active = self.is_active();
+        if self.blocks.pop().is_none() {
+            diagnostics.push(Diagnostic::syntax_error(&pos, "`end without matching `if"));
+            return;
+        }
+        let start = pos.start();
+        self.update_region(was_active, end, &mut |region_start| {
+            push_inactive_region(source, region_start, start, diagnostics)
+        });
+    }
+
+    /// Report blocks that are not terminated at the end of the source
+    pub fn finish(self, source: &Source, diagnostics: &mut dyn DiagnosticHandler) {
+        if let Some(start) = self.inactive_start {
+            push_inactive_region(source, start, source.contents().end(), diagnostics);
+        }
+        for block in self.blocks {
+            diagnostics.push(Diagnostic::syntax_error(
+                block.pos,
+                "Missing `end if for conditional analysis block",
+            ));
+        }
+    }
+
+    /// Start or end an inactive region after a directive has changed the state.
+    /// An inactive region starts at the line after the directive and ends at the start of
+    /// the directive that makes the code active again.
+    fn update_region(&mut self, was_active: bool, end: Position, on_end: &mut dyn FnMut(Position)) {
+        match (was_active, self.is_active()) {
+            (true, false) => self.inactive_start = Some(Position::new(end.line + 1, 0)),
+            (false, true) => {
+                if let Some(start) = self.inactive_start.take() {
+                    on_end(start);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Evaluate a conditional analysis expression.
+    /// Erroneous expressions are reported and evaluate to `false`.
+    fn evaluate(
+        &self,
+        pos: &SrcPos,
+        condition: &[Token],
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> bool {
+        let mut tokens = condition.iter().peekable();
+        let result = self
+            .expression(&mut tokens, pos)
+            .and_then(|value| match tokens.next() {
+                Some(token) => Err(Diagnostic::syntax_error(
+                    token,
+                    "Unexpected token in conditional analysis expression",
+                )),
+                None => Ok(value),
+            });
+        match result {
+            Ok(value) => value,
+            Err(diagnostic) => {
+                diagnostics.push(diagnostic);
+                false
+            }
+        }
+    }
+
+    /// conditional_analysis_expression ::=
+    ///     conditional_analysis_relation { logical_operator conditional_analysis_relation }
+    fn expression<'t>(
+        &self,
+        tokens: &mut std::iter::Peekable<impl Iterator<Item = &'t Token>>,
+        pos: &SrcPos,
+    ) -> Result<bool, Diagnostic> {
+        let mut value = self.relation(tokens, pos)?;
+        while let Some(kind) = tokens
+            .peek()
+            .map(|token| token.kind)
+            .filter(|kind| matches!(kind, And | Or | Xor | Xnor))
+        {
+            tokens.next();
+            let rhs = self.relation(tokens, pos)?;
+            value = match kind {
+                And => value && rhs,
+                Or => value || rhs,
+                Xor => value ^ rhs,
+                _ => value == rhs,
+            };
+        }
+        Ok(value)
+    }
+
+    /// conditional_analysis_relation ::=
+    ///     ( conditional_analysis_expression )
+    ///   | not ( conditional_analysis_expression )
+    ///   | conditional_analysis_identifier conditional_analysis_operator string_literal
+    fn relation<'t>(
+        &self,
+        tokens: &mut std::iter::Peekable<impl Iterator<Item = &'t Token>>,
+        pos: &SrcPos,
+    ) -> Result<bool, Diagnostic> {
+        let expected = |token: Option<&Token>, what: &str| {
+            Diagnostic::syntax_error(
+                token.map_or(pos, |token| &token.pos),
+                format!("Expected {what} in conditional analysis expression"),
+            )
+        };
+        let token = tokens.next();
+        match token.map(|token| (token.kind, &token.value)) {
+            Some((Not, _)) => Ok(!self.relation(tokens, pos)?),
+            Some((LeftPar, _)) => {
+                let value = self.expression(tokens, pos)?;
+                match tokens.next() {
+                    Some(token) if token.kind == RightPar => Ok(value),
+                    token => Err(expected(token, "')'")),
+                }
+            }
+            Some((Identifier, Value::Identifier(symbol))) => {
+                let name = symbol.name_utf8();
+                let Some(lhs) = self.identifiers.get(&name.to_lowercase()) else {
+                    return Err(Diagnostic::syntax_error(
+                        token.unwrap(),
+                        format!("Undefined conditional analysis identifier '{name}'"),
+                    ));
+                };
+                let operator = tokens.next();
+                let ordering = match tokens.next() {
+                    Some(Token {
+                        value: Value::String(rhs),
+                        ..
+                    }) => lhs.as_str().cmp(rhs.to_string().as_str()),
+                    token => return Err(expected(token, "string literal")),
+                };
+                match operator.map(|token| token.kind) {
+                    Some(EQ) => Ok(ordering == Ordering::Equal),
+                    Some(NE) => Ok(ordering != Ordering::Equal),
+                    Some(LT) => Ok(ordering == Ordering::Less),
+                    Some(LTE) => Ok(ordering != Ordering::Greater),
+                    Some(GT) => Ok(ordering == Ordering::Greater),
+                    Some(GTE) => Ok(ordering != Ordering::Less),
+                    _ => Err(expected(operator, "relational operator")),
+                }
+            }
+            _ => Err(expected(token, "identifier")),
+        }
+    }
+}
+
+fn push_inactive_region(
+    source: &Source,
+    start: Position,
+    end: Position,
+    diagnostics: &mut dyn DiagnosticHandler,
+) {
+    if start < end {
+        diagnostics.push(Diagnostic::new(
+            source.pos(start, end),
+            "Inactive region excluded by conditional analysis",
+            ErrorCode::InactiveRegion,
+        ));
+    }
+}
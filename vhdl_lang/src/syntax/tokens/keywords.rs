@@ -428,7 +428,7 @@ const ATTRIBUTES_2019: &[AttributeDesignator] = &[
     AttributeDesignator::LeftOf,
     AttributeDesignator::RightOf,
     // DesignatedSubtype
-    // Reflect
+    AttributeDesignator::Reflect,
     // Index
     AttributeDesignator::Value,
     AttributeDesignator::Val,
@@ -10,9 +10,12 @@ use crate::ast::token_range::WithToken;
 use crate::ast::{self, AttributeDesignator, Operator, WithRef};
 use crate::ast::{BaseSpecifier, Ident};
 use crate::data::*;
+#[cfg(feature = "analysis-cache")]
+use serde::{Deserialize, Serialize};
 
 /// The kind of a Token
 #[derive(PartialEq, Eq, Clone, Copy, Debug, IntoStaticStr)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 #[strum(serialize_all = "lowercase")]
 pub enum Kind {
     // Keywords
@@ -233,6 +236,20 @@ pub enum Kind {
     RightArrow,
     #[strum(serialize = "`")]
     GraveAccent,
+
+    // PSL delimiters
+    #[strum(serialize = "{")]
+    LeftCurly,
+    #[strum(serialize = "}")]
+    RightCurly,
+    #[strum(serialize = "->")]
+    MinusGt,
+    #[strum(serialize = "<->")]
+    LtMinusGt,
+    #[strum(serialize = "&&")]
+    AmpAmp,
+    #[strum(serialize = "!")]
+    Exclamation,
     #[strum(serialize = "{text}")]
     Text, // Raw text that is not processed (i.e. tokenized) further. Used in tool directives
 }
@@ -411,8 +428,11 @@ pub fn kinds_str(kinds: &[Kind]) -> String {
 
 /// The value of a Token
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub enum Value {
-    Identifier(Symbol),
+    Identifier(
+        #[cfg_attr(feature = "analysis-cache", serde(with = "crate::cache::cached"))] Symbol,
+    ),
     String(Latin1String),
     BitString(Latin1String, ast::BitString),
     AbstractLiteral(Latin1String, ast::AbstractLiteral),
@@ -424,9 +444,11 @@ pub enum Value {
 
 /// A Token
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct Token {
     pub kind: Kind,
     pub value: Value,
+    #[cfg_attr(feature = "analysis-cache", serde(with = "crate::cache::cached"))]
     pub pos: SrcPos,
     pub comments: Option<Box<TokenComments>>,
 }
@@ -435,6 +457,7 @@ pub struct Token {
 /// A token ID cannot be created directly by the user. Instead, the value must be taken
 /// from the AST.
 #[derive(PartialEq, Eq, Debug, Clone, Copy, Ord, PartialOrd)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct TokenId(usize);
 
 /// The TokenId represents an index into an array of tokens.
@@ -550,6 +573,7 @@ pub trait HasTokenSpan {
 /// Since the different pieces may be gathered in different locations,
 /// the fields are gated behind accessor functions which also check some invariants every time they are called.
 #[derive(PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct TokenSpan {
     pub start_token: TokenId,
     pub end_token: TokenId,
@@ -746,12 +770,14 @@ impl TokenAccess for [Token] {
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct TokenComments {
     pub leading: Vec<Comment>,
     pub trailing: Option<Comment>,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "analysis-cache", derive(Serialize, Deserialize))]
 pub struct Comment {
     pub value: String,
     pub range: crate::data::Range,
@@ -768,6 +794,7 @@ impl Comment {
     }
 }
 
+use super::conditional_analysis::Defines;
 use crate::standard::VHDLStandard;
 use itertools::Itertools;
 use std::convert::AsRef;
@@ -894,6 +921,16 @@ impl Token {
         range
     }
 
+    /// Move the token and its comments up or down by a number of lines
+    pub(crate) fn shift_lines(&mut self, lines: i64) {
+        self.pos.range = self.pos.range.shift_lines(lines);
+        if let Some(comments) = &mut self.comments {
+            for comment in comments.leading.iter_mut().chain(&mut comments.trailing) {
+                comment.range = comment.range.shift_lines(lines);
+            }
+        }
+    }
+
     /// return `true` when `self` is equal to `other` while ignoring all
     /// changes that are attributed to their position in the source file
     /// and all changes that only affect comments.
@@ -1142,6 +1179,16 @@ fn parse_exponent(reader: &mut ContentReader<'_>) -> Result<(i32, Latin1String),
 pub struct TokenState {
     last_token_kind: Option<Kind>,
     start: ReaderState,
+    psl_comment: PslComment,
+}
+
+/// The state of a PSL statement that is embedded in `-- psl` comments
+#[derive(Clone, Copy, Default)]
+struct PslComment {
+    // Inside of such a statement
+    active: bool,
+    // Nesting of braces within the statement, semicolons within braces do not end it
+    braces: usize,
 }
 
 impl TokenState {
@@ -1149,6 +1196,7 @@ impl TokenState {
         TokenState {
             last_token_kind: None,
             start,
+            psl_comment: PslComment::default(),
         }
     }
 }
@@ -1524,6 +1572,7 @@ fn parse_basic_identifier_or_keyword(
     buffer: &mut Latin1String,
     reader: &mut ContentReader<'_>,
     symbols: &Symbols,
+    standard: VHDLStandard,
 ) -> Result<(Kind, Value), TokenError> {
     buffer.bytes.clear();
     while let Some(b) = reader.peek()? {
@@ -1538,7 +1587,7 @@ fn parse_basic_identifier_or_keyword(
         }
     }
 
-    Ok(symbols.insert_or_keyword(buffer))
+    Ok(symbols.insert_or_keyword(buffer, standard))
 }
 
 /// Assumes leading ' has already been consumed
@@ -1579,12 +1628,79 @@ fn read_until_newline(
     Ok(())
 }
 
-fn get_leading_comments(reader: &mut ContentReader<'_>) -> Result<Vec<Comment>, TokenError> {
+/// The words that a PSL statement embedded in a `-- psl` comment can start with
+const PSL_COMMENT_STARTS: &[&str] = &[
+    "default",
+    "property",
+    "sequence",
+    "assert",
+    "assume",
+    "assume_guarantee",
+    "restrict",
+    "restrict_guarantee",
+    "cover",
+];
+
+fn read_word(reader: &mut ContentReader<'_>) -> String {
+    let mut word = String::new();
+    while let Ok(Some(byte)) = reader.peek() {
+        if byte.is_ascii_alphanumeric() || byte == b'_' {
+            word.push(byte.to_ascii_lowercase() as char);
+            reader.skip();
+        } else {
+            break;
+        }
+    }
+    word
+}
+
+/// Assume -- has already been consumed.
+/// Moves the reader to the start of the PSL statement of a comment such as
+/// `-- psl assert always req -> next ack;`. Ordinary comments that merely start with
+/// the word psl are left untouched.
+fn skip_psl_comment_prefix(reader: &mut ContentReader<'_>) -> bool {
+    let mut lookahead = reader.clone();
+    skip_whitespace_in_line(&mut lookahead);
+    if read_word(&mut lookahead) != "psl" || !matches!(lookahead.peek(), Ok(Some(b' ' | b'\t'))) {
+        return false;
+    }
+    skip_whitespace_in_line(&mut lookahead);
+    let start = lookahead.clone();
+    let mut word = read_word(&mut lookahead);
+    skip_whitespace_in_line(&mut lookahead);
+    if !word.is_empty() && matches!(lookahead.skip_if(b':'), Ok(true)) {
+        // A labeled directive such as `-- psl check_ack : assert ...`
+        skip_whitespace_in_line(&mut lookahead);
+        word = read_word(&mut lookahead);
+    }
+    if PSL_COMMENT_STARTS.contains(&word.as_str()) {
+        reader.set_to(&start);
+        true
+    } else {
+        false
+    }
+}
+
+/// Leading comments of the next token.
+/// `psl_comment` tracks PSL statements embedded in comments. It is `None` when such
+/// comments are not recognized, i.e. before VHDL-2008.
+/// Only comments that start a line can contain a PSL statement and the statement
+/// ends with the comment lines, even if it is not terminated.
+fn get_leading_comments(
+    reader: &mut ContentReader<'_>,
+    mut psl_comment: Option<&mut PslComment>,
+) -> Result<Vec<Comment>, TokenError> {
     let mut comments: Vec<Comment> = Vec::new();
+    let line = reader.pos().line;
 
     loop {
         skip_whitespace(reader);
         let state = reader.state();
+        if let Some(psl_comment) = psl_comment.as_deref_mut() {
+            if state.pos().line != line && !matches!(reader.peek()?, Some(b'-')) {
+                psl_comment.active = false;
+            }
+        }
 
         let byte = if let Some(byte) = reader.pop()? {
             byte
@@ -1603,6 +1719,27 @@ fn get_leading_comments(reader: &mut ContentReader<'_>) -> Result<Vec<Comment>,
             }
             b'-' => {
                 if reader.pop()? == Some(b'-') {
+                    // A comment that follows a multi-line comment on the same line
+                    // is not at the start of the line
+                    let starts_line = comments.last().is_none_or(|comment| {
+                        !comment.multi_line || comment.range.end.line != state.pos().line
+                    });
+                    if let Some(psl_comment) = psl_comment.as_deref_mut().filter(|_| starts_line) {
+                        if skip_psl_comment_prefix(reader) {
+                            *psl_comment = PslComment {
+                                active: true,
+                                braces: 0,
+                            };
+                            break;
+                        } else if psl_comment.active {
+                            // Continuation of a PSL statement on the next comment line
+                            skip_whitespace_in_line(reader);
+                            if !matches!(reader.peek()?, None | Some(b'\n')) {
+                                break;
+                            }
+                            continue;
+                        }
+                    }
                     comments.push(parse_comment(reader));
                 } else {
                     reader.set_state(state);
@@ -1619,6 +1756,16 @@ fn get_leading_comments(reader: &mut ContentReader<'_>) -> Result<Vec<Comment>,
     Ok(comments)
 }
 
+/// Skip until the end of the line
+fn skip_rest_of_line(reader: &mut ContentReader<'_>) {
+    while let Ok(Some(byte)) = reader.peek() {
+        if byte == b'\n' {
+            break;
+        }
+        reader.skip();
+    }
+}
+
 /// Skip whitespace but not newline
 fn skip_whitespace_in_line(reader: &mut ContentReader<'_>) {
     while let Ok(Some(byte)) = reader.peek() {
@@ -1668,48 +1815,83 @@ fn get_trailing_comment(reader: &mut ContentReader<'_>) -> Result<Option<Comment
 }
 
 /// Static tokenizer data
+///
+/// The keywords and attributes of all standards are known such that the symbols can be shared
+/// between files of different standards.
 pub struct Symbols {
     symtab: SymbolTable,
-    keywords: Vec<Kind>,
-    attributes: FnvHashMap<Symbol, AttributeDesignator>,
+    // The standard that is used when the tokenizer is not given a standard
+    standard: VHDLStandard,
+    // The keywords indexed by symbol id together with the standards that define them
+    keywords: Vec<(Kind, Vec<VHDLStandard>)>,
+    attributes: FnvHashMap<Symbol, (AttributeDesignator, Vec<VHDLStandard>)>,
 }
 
+const ALL_STANDARDS: [VHDLStandard; 3] = [
+    VHDLStandard::VHDL1993,
+    VHDLStandard::VHDL2008,
+    VHDLStandard::VHDL2019,
+];
+
 impl Symbols {
     pub fn symtab(&self) -> &SymbolTable {
         &self.symtab
     }
 
-    fn insert_or_keyword(&self, name: &Latin1String) -> (Kind, Value) {
+    /// The standard that these symbols were created for
+    pub fn standard(&self) -> VHDLStandard {
+        self.standard
+    }
+
+    fn insert_or_keyword(&self, name: &Latin1String, standard: VHDLStandard) -> (Kind, Value) {
         let symbol = self.symtab.insert(name);
-        if let Some(kind) = self.keywords.get(symbol.id) {
-            (*kind, Value::None)
-        } else {
-            (Identifier, Value::Identifier(symbol))
+        match self.keywords.get(symbol.id) {
+            Some((kind, standards)) if standards.contains(&standard) => (*kind, Value::None),
+            _ => (Identifier, Value::Identifier(symbol)),
+        }
+    }
+
+    fn attribute(&self, sym: &Symbol, standard: VHDLStandard) -> Option<&AttributeDesignator> {
+        match self.attributes.get(sym) {
+            Some((attr, standards)) if standards.contains(&standard) => Some(attr),
+            _ => None,
         }
     }
 
     pub fn from_standard(version: VHDLStandard) -> Symbols {
         let symtab = SymbolTable::default();
-        let kw = version.keywords();
-        let mut keywords = Vec::with_capacity(kw.len());
+        let mut keywords: Vec<(Kind, Vec<VHDLStandard>)> = Vec::new();
 
         let mut latin1 = Latin1String::empty();
-        for kind in kw {
-            latin1.bytes.clear();
-            latin1.bytes.extend_from_slice(kind.as_str().as_bytes());
-            let symbol = symtab.insert(&latin1);
-            assert_eq!(symbol.id, keywords.len());
-            keywords.push(*kind);
+        for standard in ALL_STANDARDS {
+            for kind in standard.keywords() {
+                latin1.bytes.clear();
+                latin1.bytes.extend_from_slice(kind.as_str().as_bytes());
+                let symbol = symtab.insert(&latin1);
+                if let Some((_, standards)) = keywords.get_mut(symbol.id) {
+                    standards.push(standard);
+                } else {
+                    assert_eq!(symbol.id, keywords.len());
+                    keywords.push((*kind, vec![standard]));
+                }
+            }
         }
 
-        let attributes = version
-            .builtin_attributes()
-            .iter()
-            .map(|attr| (symtab.insert_utf8(format!("{attr}").as_str()), attr.clone()))
-            .collect();
+        let mut attributes: FnvHashMap<Symbol, (AttributeDesignator, Vec<VHDLStandard>)> =
+            FnvHashMap::default();
+        for standard in ALL_STANDARDS {
+            for attr in standard.builtin_attributes() {
+                attributes
+                    .entry(symtab.insert_utf8(format!("{attr}").as_str()))
+                    .or_insert_with(|| (attr.clone(), Vec::new()))
+                    .1
+                    .push(standard);
+            }
+        }
 
         Symbols {
             symtab,
+            standard: version,
             keywords,
             attributes,
         }
@@ -1724,6 +1906,8 @@ impl std::default::Default for Symbols {
 
 pub struct Tokenizer<'a> {
     symbols: &'a Symbols,
+    standard: VHDLStandard,
+    defines: Option<&'a Defines>,
     buffer: Latin1String,
     state: TokenState,
     pub source: &'a Source,
@@ -1739,6 +1923,8 @@ impl<'a> Tokenizer<'a> {
     ) -> Tokenizer<'a> {
         Tokenizer {
             symbols,
+            standard: symbols.standard(),
+            defines: None,
             state: TokenState::new(reader.state()),
             buffer: Latin1String::empty(),
             source,
@@ -1747,10 +1933,29 @@ impl<'a> Tokenizer<'a> {
         }
     }
 
+    /// Tokenize according to `standard` instead of the standard of the symbols
+    pub fn with_standard(mut self, standard: VHDLStandard) -> Tokenizer<'a> {
+        self.standard = standard;
+        self
+    }
+
+    /// User defined identifiers that are available to conditional analysis directives
+    pub fn with_defines(mut self, defines: &'a Defines) -> Tokenizer<'a> {
+        self.defines = Some(defines);
+        self
+    }
+
+    pub fn standard(&self) -> VHDLStandard {
+        self.standard
+    }
+
+    pub fn defines(&self) -> Option<&'a Defines> {
+        self.defines
+    }
+
     pub fn attribute(&self, sym: Symbol) -> AttributeDesignator {
         self.symbols
-            .attributes
-            .get(&sym)
+            .attribute(&sym, self.standard)
             .cloned()
             .unwrap_or_else(|| AttributeDesignator::Ident(WithRef::new(sym)))
     }
@@ -1789,6 +1994,7 @@ impl<'a> Tokenizer<'a> {
                         &mut self.buffer,
                         &mut self.reader,
                         self.symbols,
+                        self.standard,
                     )?
                 }
             }
@@ -1815,7 +2021,11 @@ impl<'a> Tokenizer<'a> {
             }
             b'-' => {
                 self.reader.skip();
-                (Minus, Value::None)
+                if self.reader.skip_if(b'>')? {
+                    (MinusGt, Value::None)
+                } else {
+                    (Minus, Value::None)
+                }
             }
             b'"' => {
                 self.reader.skip();
@@ -1844,7 +2054,11 @@ impl<'a> Tokenizer<'a> {
             }
             b'&' => {
                 self.reader.skip();
-                (Concat, Value::None)
+                if self.reader.skip_if(b'&')? {
+                    (AmpAmp, Value::None)
+                } else {
+                    (Concat, Value::None)
+                }
             }
             b',' => {
                 self.reader.skip();
@@ -1873,6 +2087,17 @@ impl<'a> Tokenizer<'a> {
                         self.reader.skip();
                         (LtLt, Value::None)
                     }
+                    Some(b'-') => {
+                        let state = self.reader.state();
+                        self.reader.skip();
+                        if self.reader.skip_if(b'>')? {
+                            (LtMinusGt, Value::None)
+                        } else {
+                            // i.e. a<-1
+                            self.reader.set_state(state);
+                            (LT, Value::None)
+                        }
+                    }
                     _ => (LT, Value::None),
                 }
             }
@@ -1977,6 +2202,18 @@ impl<'a> Tokenizer<'a> {
                 self.reader.skip();
                 (GraveAccent, Value::None)
             }
+            b'{' => {
+                self.reader.skip();
+                (LeftCurly, Value::None)
+            }
+            b'}' => {
+                self.reader.skip();
+                (RightCurly, Value::None)
+            }
+            b'!' => {
+                self.reader.skip();
+                (Exclamation, Value::None)
+            }
             _ => {
                 self.reader.skip();
                 illegal_token!();
@@ -1986,14 +2223,49 @@ impl<'a> Tokenizer<'a> {
     }
 
     fn pop_raw(&mut self) -> Result<Option<Token>, TokenError> {
-        let leading_comments = get_leading_comments(&mut self.reader)?;
-        self.state.start = self.reader.state();
+        let mut leading_comments = Vec::new();
+        let parsed = loop {
+            let psl_comment =
+                (self.standard >= VHDLStandard::VHDL2008).then_some(&mut self.state.psl_comment);
+            leading_comments.append(&mut get_leading_comments(&mut self.reader, psl_comment)?);
+            self.state.start = self.reader.state();
 
-        match self.parse_token()? {
+            match self.parse_token() {
+                Err(_) if self.state.psl_comment.active => {
+                    // The text of a `-- psl` comment that cannot be tokenized remains a comment
+                    self.state.psl_comment.active = false;
+                    self.reader.set_state(self.state.start);
+                    skip_rest_of_line(&mut self.reader);
+                }
+                parsed => break parsed?,
+            }
+        };
+
+        match parsed {
             Some((kind, value)) => {
                 // Parsed a token.
                 let pos_start = self.state.start.pos();
                 let pos_end = self.reader.pos();
+                let psl_comment = &mut self.state.psl_comment;
+                if psl_comment.active {
+                    match kind {
+                        LeftCurly => psl_comment.braces += 1,
+                        RightCurly => psl_comment.braces = psl_comment.braces.saturating_sub(1),
+                        SemiColon if psl_comment.braces == 0 => {
+                            psl_comment.active = false;
+                            // Text that follows the statement remains a comment
+                            let mut lookahead = self.reader.clone();
+                            skip_whitespace_in_line(&mut lookahead);
+                            if !matches!(
+                                (lookahead.pop(), lookahead.pop()),
+                                (Ok(Some(b'-')), Ok(Some(b'-')))
+                            ) {
+                                skip_rest_of_line(&mut self.reader);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
                 let trailing_comment = get_trailing_comment(&mut self.reader)?;
                 let token_comments = if (!leading_comments.is_empty()) | trailing_comment.is_some()
                 {
@@ -2070,6 +2342,23 @@ impl<'a> Tokenizer<'a> {
         self.final_comments.clone()
     }
 
+    /// Skip the encoded lines of a protect envelope, i.e. the data that follows
+    /// `` `protect data_block ``. The block ends before the next line that starts
+    /// with a tool directive.
+    pub fn skip_protected_block(&mut self) {
+        let mut line_start = false;
+        while let Some(chr) = self.reader.get_char() {
+            match chr {
+                '`' if line_start => break,
+                '\n' => line_start = true,
+                ' ' | '\t' | '\r' => {}
+                _ => line_start = false,
+            }
+            self.reader.skip();
+        }
+        self.state.start = self.reader.state();
+    }
+
     pub fn text_until_newline(&mut self) -> DiagnosticResult<Token> {
         let start_pos = self.reader.pos();
         if let Err(err) = read_until_newline(&mut self.buffer, &mut self.reader) {
@@ -2766,6 +3055,57 @@ my_other_ident",
         assert_eq!(kinds_tokenize("=>"), vec![RightArrow]);
     }
 
+    #[test]
+    fn tokenize_psl_delimiters() {
+        assert_eq!(
+            kinds_tokenize("{ } -> <-> && ! |=> |->"),
+            vec![
+                LeftCurly,
+                RightCurly,
+                MinusGt,
+                LtMinusGt,
+                AmpAmp,
+                Exclamation,
+                Bar,
+                RightArrow,
+                Bar,
+                MinusGt
+            ]
+        );
+        assert_eq!(
+            kinds_tokenize("a<-1"),
+            vec![Identifier, LT, Minus, AbstractLiteral]
+        );
+    }
+
+    #[test]
+    fn tokenize_psl_comments() {
+        assert_eq!(
+            kinds_tokenize(
+                "\
+-- psl default clock is clk;
+-- psl check : assert always
+--   a;
+-- psl cover {a;
+--   b};
+-- psl is not a statement
+-- psl"
+            ),
+            vec![
+                Default, Identifier, Is, Identifier, SemiColon, Identifier, Colon, Assert,
+                Identifier, Identifier, SemiColon, Cover, LeftCurly, Identifier, SemiColon,
+                Identifier, RightCurly, SemiColon
+            ]
+        );
+    }
+
+    #[test]
+    fn psl_comments_are_comments_before_vhdl_2008() {
+        let code = Code::with_standard("-- psl assert a;", VHDLStandard::VHDL1993);
+        let (tokens, _) = code.tokenize_result();
+        assert!(tokens.is_empty());
+    }
+
     #[test]
     fn tokenize_cmp() {
         assert_eq!(kinds_tokenize("< <= > >="), vec![LT, LTE, GT, GTE]);
@@ -2946,7 +3286,7 @@ comment
 
     #[test]
     fn tokenize_illegal() {
-        let code = Code::new("begin!end");
+        let code = Code::new("begin$end");
         let (tokens, _) = code.tokenize_result();
         assert_eq!(
             tokens,
@@ -2957,7 +3297,7 @@ comment
                     pos: code.s1("begin").pos(),
                     comments: None,
                 }),
-                Err(Diagnostic::syntax_error(code.s1("!"), "Illegal token")),
+                Err(Diagnostic::syntax_error(code.s1("$"), "Illegal token")),
                 Ok(Token {
                     kind: End,
                     value: Value::None,
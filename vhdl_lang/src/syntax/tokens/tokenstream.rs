@@ -4,16 +4,36 @@
 //
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use vhdl_lang::syntax::parser::ParsingContext;
 
+use super::conditional_analysis::ConditionalAnalysis;
 use super::tokenizer::Kind::*;
 use super::tokenizer::*;
 use crate::ast::token_range::WithToken;
 use crate::ast::{AttributeDesignator, Ident, RangeAttribute, TypeAttribute};
-use crate::data::{DiagnosticHandler, DiagnosticResult};
+use crate::data::{DiagnosticHandler, DiagnosticResult, ErrorCode, Position, Range};
 use crate::{Diagnostic, SrcPos};
 
+/// The text that follows a directive without a trailing comment
+fn directive_text(text: &Token) -> String {
+    match &text.value {
+        Value::Text(text) => {
+            let text = text.to_string();
+            text.split("--")
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_owned()
+        }
+        _ => String::new(),
+    }
+}
+
+fn is_identifier(token: &Token, name: &str) -> bool {
+    matches!(&token.value, Value::Identifier(sym) if sym.name_utf8().eq_ignore_ascii_case(name))
+}
+
 pub struct TokenStream<'a> {
     tokenizer: Tokenizer<'a>,
     idx: Cell<usize>,
@@ -22,6 +42,122 @@ pub struct TokenStream<'a> {
     // when getting it via `TokenStream::get_current_token_id()`
     // It is updated in the `slice_tokens` method
     token_offset: Cell<usize>,
+    psl_comments: PslComments,
+}
+
+/// The PSL statements embedded in `-- psl` comments, as ranges of token indices.
+/// They are skipped like comments unless they are parsed where PSL is allowed.
+struct PslComments {
+    ranges: Vec<(usize, usize)>,
+    // The statement that is being parsed, its tokens are not skipped
+    current: Cell<Option<(usize, usize)>>,
+    // Statements that start before this index have already been considered
+    visited: Cell<usize>,
+    // The start of each statement that was parsed
+    parsed: RefCell<Vec<usize>>,
+}
+
+impl PslComments {
+    fn new(tokens: &[Token]) -> PslComments {
+        let mut ranges = Vec::new();
+        let mut start = None;
+        let mut braces = 0;
+        let mut in_comment = false;
+        for (idx, token) in tokens.iter().enumerate() {
+            let line = token.pos.start().line;
+            if idx == 0 || tokens[idx - 1].pos.end().line != line {
+                let prefix = psl_comment_prefix(token);
+                in_comment = prefix.is_some();
+                // Each `-- psl` comment starts a new statement
+                if prefix == Some(true) {
+                    if let Some(start) = start.take() {
+                        ranges.push((start, idx));
+                    }
+                }
+            }
+            if !in_comment {
+                if let Some(start) = start.take() {
+                    ranges.push((start, idx));
+                }
+                continue;
+            }
+            let range_start = *start.get_or_insert_with(|| {
+                braces = 0;
+                idx
+            });
+            match token.kind {
+                LeftCurly => braces += 1,
+                RightCurly => braces = usize::saturating_sub(braces, 1),
+                SemiColon if braces == 0 => {
+                    ranges.push((range_start, idx + 1));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(start) = start {
+            ranges.push((start, tokens.len()));
+        }
+        PslComments {
+            ranges,
+            current: Cell::new(None),
+            visited: Cell::new(0),
+            parsed: RefCell::new(Vec::new()),
+        }
+    }
+
+    fn starting_at(&self, idx: usize) -> Option<(usize, usize)> {
+        let i = self.ranges.partition_point(|(start, _)| *start < idx);
+        self.ranges
+            .get(i)
+            .filter(|(start, _)| *start == idx)
+            .copied()
+    }
+
+    fn ending_at(&self, idx: usize) -> Option<(usize, usize)> {
+        let i = self.ranges.partition_point(|(_, end)| *end < idx);
+        self.ranges.get(i).filter(|(_, end)| *end == idx).copied()
+    }
+}
+
+/// Whether a token that starts a line is within a comment, i.e. part of a PSL statement
+/// embedded in a `-- psl` comment. Returns `Some(true)` if the comment starts the statement.
+fn psl_comment_prefix(token: &Token) -> Option<bool> {
+    let start = token.pos.start();
+    let contents = token.pos.source.contents();
+    let line = contents.get_line(start.line as usize)?;
+    let prefix: String = line.chars().take(start.character as usize).collect();
+    let comment = prefix.trim_start().strip_prefix("--")?;
+    let comment = comment.trim_start();
+    Some(
+        comment
+            .strip_prefix("psl")
+            .is_some_and(|rest| rest.starts_with([' ', '\t']) && !rest.trim().contains(' ')),
+    )
+}
+
+/// The comment lines of a PSL statement embedded in `-- psl` comments
+fn psl_comment_lines(first: &Token, last_line: u32) -> Vec<Comment> {
+    let contents = first.pos.source.contents();
+    let mut comments = Vec::new();
+    for lineno in first.pos.start().line..=last_line {
+        let Some(line) = contents.get_line(lineno as usize) else {
+            break;
+        };
+        let line: Vec<char> = line.trim_end_matches('\n').chars().collect();
+        let Some(start) = line.windows(2).position(|chars| chars == ['-', '-']) else {
+            continue;
+        };
+        comments.push(Comment {
+            value: line[start + 2..].iter().collect(),
+            range: Range::new(
+                Position::new(lineno, start as u32),
+                Position::new(lineno, line.len() as u32),
+            ),
+            multi_line: false,
+        });
+    }
+    comments
 }
 
 impl<'a> TokenStream<'a> {
@@ -30,29 +166,185 @@ impl<'a> TokenStream<'a> {
     /// `identifier { any chars until newline }
     /// ```
     /// This needs special handling as the text that follows the identifier is arbitrary.
+    /// Conditional analysis directives as well as `` `warning `` and `` `error `` are evaluated.
+    /// IEEE 1735 `` `protect `` directives are tracked in `envelopes`.
+    ///
+    /// Returns the token that follows the directive.
     fn handle_tool_directive(
         grave_accent: Token,
         tokenizer: &mut Tokenizer<'_>,
+        conditional: &mut ConditionalAnalysis,
+        envelopes: &mut Vec<SrcPos>,
         diagnostics: &mut dyn DiagnosticHandler,
-    ) {
+    ) -> DiagnosticResult<Option<Token>> {
         let start_pos = grave_accent.pos.clone();
-        match tokenizer.pop() {
-            Ok(Some(tok)) => {
-                if tok.kind != Identifier {
-                    diagnostics.push(Diagnostic::syntax_error(tok, "Expecting identifier"));
-                    let _ = tokenizer.text_until_newline(); // skip potentially invalid tokens
-                    return;
+        let tok = match tokenizer.pop() {
+            Ok(Some(tok)) => tok,
+            Err(err) => {
+                diagnostics.push(err);
+                if let Err(err) = tokenizer.text_until_newline() {
+                    diagnostics.push(err);
                 }
+                return tokenizer.pop();
             }
-            Err(err) => diagnostics.push(err),
             Ok(None) => {
                 diagnostics.push(Diagnostic::syntax_error(start_pos, "Expecting identifier"));
-                return;
+                return Ok(None);
+            }
+        };
+        let pos = start_pos.combine(&tok);
+        match tok.kind {
+            If | Elsif => {
+                let (condition, end, next) =
+                    TokenStream::directive_condition(&pos, &tok, tokenizer, diagnostics);
+                if tok.kind == If {
+                    conditional.if_directive(pos, end, &condition, diagnostics);
+                } else {
+                    conditional.elsif_directive(
+                        pos,
+                        end,
+                        Some(&condition),
+                        tokenizer.source,
+                        diagnostics,
+                    );
+                }
+                return next.map_or_else(|| tokenizer.pop(), |next| Ok(Some(next)));
+            }
+            Else | End => {
+                let text = tokenizer.text_until_newline()?;
+                if tok.kind == End
+                    && !matches!(directive_text(&text).to_lowercase().as_str(), "" | "if")
+                {
+                    diagnostics.push(Diagnostic::syntax_error(&text, "Expected 'if'"));
+                }
+                let end = text.pos.end();
+                if tok.kind == Else {
+                    conditional.elsif_directive(pos, end, None, tokenizer.source, diagnostics);
+                } else {
+                    conditional.end_directive(pos, end, tokenizer.source, diagnostics);
+                }
+            }
+            Identifier if is_identifier(&tok, "protect") => {
+                let text = tokenizer.text_until_newline()?;
+                TokenStream::protect_directive(
+                    pos.combine(&text),
+                    &text,
+                    tokenizer,
+                    conditional,
+                    envelopes,
+                    diagnostics,
+                );
+            }
+            Identifier => {
+                let text = tokenizer.text_until_newline()?;
+                let code = match &tok.value {
+                    Value::Identifier(sym) if sym.name_utf8().eq_ignore_ascii_case("warning") => {
+                        Some(ErrorCode::DirectiveWarning)
+                    }
+                    Value::Identifier(sym) if sym.name_utf8().eq_ignore_ascii_case("error") => {
+                        Some(ErrorCode::DirectiveError)
+                    }
+                    _ => None,
+                };
+                if let Some(code) = code.filter(|_| conditional.is_active()) {
+                    let message = match &text.value {
+                        Value::Text(text) => text.to_string(),
+                        _ => String::new(),
+                    };
+                    // The message may be followed by a comment
+                    match message
+                        .trim_start()
+                        .strip_prefix('"')
+                        .and_then(|message| Some(&message[..message.rfind('"')?]))
+                    {
+                        Some(message) => diagnostics.push(Diagnostic::new(
+                            pos.combine(&text),
+                            message.replace("\"\"", "\""),
+                            code,
+                        )),
+                        None => diagnostics
+                            .push(Diagnostic::syntax_error(&text, "Expected string literal")),
+                    }
+                }
+            }
+            _ => {
+                diagnostics.push(Diagnostic::syntax_error(tok, "Expecting identifier"));
+                let _ = tokenizer.text_until_newline(); // skip potentially invalid tokens
             }
         }
-        match tokenizer.text_until_newline() {
-            Ok(_) => {}
-            Err(err) => diagnostics.push(err),
+        tokenizer.pop()
+    }
+
+    /// A `` `protect `` directive of an IEEE 1735 encryption envelope.
+    /// The encoded key and data blocks are skipped as a whole and the envelope
+    /// is reported as a region that is not analyzed.
+    fn protect_directive(
+        pos: SrcPos,
+        text: &Token,
+        tokenizer: &mut Tokenizer<'_>,
+        conditional: &ConditionalAnalysis,
+        envelopes: &mut Vec<SrcPos>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) {
+        let text = directive_text(text).to_lowercase();
+        let keyword = text
+            .split(|chr: char| chr.is_whitespace() || chr == '=')
+            .next()
+            .unwrap_or_default();
+        match keyword {
+            "begin_protected" => envelopes.push(pos),
+            "end_protected" => match envelopes.pop() {
+                Some(begin) => {
+                    if conditional.is_active() {
+                        diagnostics.push(Diagnostic::new(
+                            begin.combine(&pos),
+                            "Encrypted region of a protect envelope is not analyzed",
+                            ErrorCode::InactiveRegion,
+                        ));
+                    }
+                }
+                None => diagnostics.push(Diagnostic::syntax_error(
+                    pos,
+                    "`protect end_protected without matching `protect begin_protected",
+                )),
+            },
+            "key_block" | "data_block" | "digest_block" => tokenizer.skip_protected_block(),
+            _ => {}
+        }
+    }
+
+    /// Collect the condition of an `` `if `` or `` `elsif `` directive that is terminated by `then`.
+    /// The condition must be on the same line as the directive.
+    ///
+    /// Returns the condition, the end of the directive and a token that was popped after the
+    /// directive if the directive is not terminated by `then`.
+    fn directive_condition(
+        pos: &SrcPos,
+        directive: &Token,
+        tokenizer: &mut Tokenizer<'_>,
+        diagnostics: &mut dyn DiagnosticHandler,
+    ) -> (Vec<Token>, Position, Option<Token>) {
+        let line = pos.start().line;
+        let mut condition = Vec::new();
+        let mut end = directive.pos.end();
+        loop {
+            match tokenizer.pop() {
+                Ok(Some(token)) if token.kind == Then => {
+                    return (condition, token.pos.end(), None);
+                }
+                Ok(Some(token)) if token.kind != GraveAccent && token.pos.start().line == line => {
+                    end = token.pos.end();
+                    condition.push(token);
+                }
+                Ok(next) => {
+                    diagnostics.push(Diagnostic::syntax_error(
+                        tokenizer.source.pos(end, end),
+                        "Expected 'then'",
+                    ));
+                    return (condition, end, next);
+                }
+                Err(err) => diagnostics.push(err),
+            }
         }
     }
 
@@ -61,22 +353,56 @@ impl<'a> TokenStream<'a> {
         diagnostics: &mut dyn DiagnosticHandler,
     ) -> TokenStream<'a> {
         let mut tokens = Vec::new();
+        let mut conditional = ConditionalAnalysis::new(tokenizer.standard(), tokenizer.defines());
+        // Envelopes that are never closed are not reported as
+        // excerpts of encrypted code often only contain the header
+        let mut envelopes = Vec::new();
+        let mut next = tokenizer.pop();
         loop {
-            match tokenizer.pop() {
+            match next {
                 Ok(Some(token)) if token.kind == GraveAccent => {
-                    TokenStream::handle_tool_directive(token, &mut tokenizer, diagnostics)
+                    next = TokenStream::handle_tool_directive(
+                        token,
+                        &mut tokenizer,
+                        &mut conditional,
+                        &mut envelopes,
+                        diagnostics,
+                    );
+                    continue;
+                }
+                Ok(Some(token)) => {
+                    if conditional.is_active() {
+                        tokens.push(token)
+                    }
                 }
-                Ok(Some(token)) => tokens.push(token),
                 Ok(None) => break,
-                Err(err) => diagnostics.push(err),
+                Err(err) => {
+                    if conditional.is_active() {
+                        diagnostics.push(err)
+                    }
+                }
             }
+            next = tokenizer.pop();
         }
-        TokenStream {
+        conditional.finish(tokenizer.source, diagnostics);
+        TokenStream::from_tokens(tokenizer, tokens)
+    }
+
+    /// A stream of tokens that have already been tokenized from the source of the tokenizer
+    pub fn from_tokens(tokenizer: Tokenizer<'a>, tokens: Vec<Token>) -> TokenStream<'a> {
+        let stream = TokenStream {
             tokenizer,
             idx: Cell::new(0),
+            psl_comments: PslComments::new(&tokens),
             tokens,
             token_offset: Cell::new(0),
-        }
+        };
+        stream.set_idx(0);
+        stream
+    }
+
+    pub fn into_tokens(self) -> Vec<Token> {
+        self.tokens
     }
 
     pub fn state(&self) -> usize {
@@ -92,19 +418,60 @@ impl<'a> TokenStream<'a> {
     }
 
     pub fn back(&self) {
-        self.set_idx(self.get_idx() - 1)
+        self.set_idx(self.prev_idx(self.get_idx()))
     }
 
     fn get_idx(&self) -> usize {
         self.idx.get()
     }
 
-    fn set_idx(&self, idx: usize) {
+    /// Sets the index, skipping the PSL statements of `-- psl` comments
+    /// unless such a statement is being parsed
+    fn set_idx(&self, mut idx: usize) {
+        if self.psl_comments.current.get().is_none() {
+            while let Some((_, end)) = self.psl_comments.starting_at(idx) {
+                idx = end;
+            }
+        }
         self.idx.replace(idx);
     }
 
+    /// The index of the token before `idx`, skipping the PSL statements of `-- psl` comments
+    /// unless such a statement is being parsed
+    fn prev_idx(&self, mut idx: usize) -> usize {
+        if self.psl_comments.current.get().is_none() {
+            while let Some((start, _)) = self.psl_comments.ending_at(idx) {
+                idx = start;
+            }
+        }
+        idx.wrapping_sub(1)
+    }
+
+    /// The index of the `n`:th token after the current token
+    fn nth_idx(&self, n: usize) -> usize {
+        let mut idx = self.get_idx();
+        for _ in 0..n {
+            idx += 1;
+            if self.psl_comments.current.get().is_none() {
+                while let Some((_, end)) = self.psl_comments.starting_at(idx) {
+                    idx = end;
+                }
+            }
+        }
+        idx
+    }
+
+    /// The tokens that can be parsed, i.e. only the tokens of the PSL statement
+    /// if one is being parsed
+    fn available_tokens(&self) -> &[Token] {
+        match self.psl_comments.current.get() {
+            Some((_, end)) => &self.tokens[..end],
+            None => &self.tokens,
+        }
+    }
+
     pub fn peek(&self) -> Option<&Token> {
-        self.tokens.get(self.get_idx())
+        self.available_tokens().get(self.get_idx())
     }
 
     pub fn get_current_token_id(&self) -> TokenId {
@@ -112,7 +479,54 @@ impl<'a> TokenStream<'a> {
     }
 
     pub fn get_last_token_id(&self) -> TokenId {
-        TokenId::new(self.get_idx() - 1 - self.token_offset.get())
+        TokenId::new(self.prev_idx(self.get_idx()) - self.token_offset.get())
+    }
+
+    /// The PSL statements embedded in `-- psl` comments between the previous and the current
+    /// token that have not yet been considered, as pairs of the first token and the state
+    /// after the statement.
+    pub fn take_psl_comments(&self) -> Vec<(usize, usize)> {
+        let mut comments = Vec::new();
+        let mut idx = self.get_idx();
+        while let Some((start, end)) = self.psl_comments.ending_at(idx) {
+            if start < self.psl_comments.visited.get() {
+                break;
+            }
+            comments.push((start, end));
+            idx = start;
+        }
+        self.psl_comments.visited.set(self.get_idx());
+        comments.reverse();
+        comments
+    }
+
+    /// Parse the PSL statement of a `-- psl` comment, see `take_psl_comments`.
+    /// The parser only sees the tokens of the statement.
+    /// Returns the result and whether all tokens of the statement were consumed.
+    pub fn parse_psl_comment<T>(
+        &self,
+        (start, end): (usize, usize),
+        parse: impl FnOnce() -> T,
+    ) -> (T, bool) {
+        let state = self.get_idx();
+        self.psl_comments.current.set(Some((start, end)));
+        self.idx.replace(start);
+        let result = parse();
+        let is_complete = self.get_idx() == end;
+        self.psl_comments.current.set(None);
+        self.idx.replace(state);
+        (result, is_complete)
+    }
+
+    /// Keep the tokens of a PSL statement that was parsed from a `-- psl` comment,
+    /// the tokens of the other statements are turned back into comments
+    pub fn keep_psl_comment(&self, (start, _): (usize, usize)) {
+        self.psl_comments.parsed.borrow_mut().push(start);
+    }
+
+    /// True if a PSL statement of a `-- psl` comment is being parsed
+    pub fn is_psl_comment(&self) -> bool {
+        self.psl_comments.current.get().is_some()
     }
 
     fn eof_error(&self) -> Diagnostic {
@@ -137,7 +551,7 @@ impl<'a> TokenStream<'a> {
 
     fn token_before(&self, token: &Token) -> Option<&Token> {
         let idx = self.idx_of(token)?;
-        self.tokens.get(idx.wrapping_sub(1))
+        self.tokens.get(self.prev_idx(idx))
     }
 
     /// A position that aligns with the previous token
@@ -194,7 +608,7 @@ impl<'a> TokenStream<'a> {
     }
 
     pub fn nth_kind_is(&self, idx: usize, kind: Kind) -> bool {
-        if let Some(token) = self.tokens.get(self.get_idx() + idx) {
+        if let Some(token) = self.available_tokens().get(self.nth_idx(idx)) {
             token.kind == kind
         } else {
             false
@@ -284,9 +698,64 @@ impl<'a> TokenStream<'a> {
     ///                current position (EOF)
     /// ```
     /// After calling `slice_tokens` again, the returned vec is `[tok x]`
+    ///
+    /// PSL statements of `-- psl` comments that follow the last token belong to the next slice.
+    /// The statements that were not parsed become leading comments of the token that follows.
     pub fn slice_tokens(&self) -> Vec<Token> {
-        let vec = Vec::from(&self.tokens[self.token_offset.get()..self.state()]);
-        self.token_offset.replace(self.state());
+        let offset = self.token_offset.get();
+        let end = self.prev_idx(self.state()).wrapping_add(1).max(offset);
+        let mut vec = Vec::from(&self.tokens[offset..end]);
+        self.token_offset.replace(end);
+
+        let parsed = self.psl_comments.parsed.borrow();
+        let first = self
+            .psl_comments
+            .ranges
+            .partition_point(|(start, _)| *start < offset);
+        let mut comments: Vec<Comment> = Vec::new();
+        for &(start, psl_end) in &self.psl_comments.ranges[first..] {
+            if psl_end > end {
+                break;
+            }
+            if parsed.contains(&start) {
+                continue;
+            }
+            let first_token = &self.tokens[start];
+            // Lines of the comment that could not be tokenized precede the comments of the
+            // next token
+            let next_token = &self.tokens[psl_end];
+            let next_line = next_token
+                .comments
+                .as_ref()
+                .and_then(|comments| comments.leading.first())
+                .map_or(next_token.pos.start().line, |comment| {
+                    comment.range.start.line
+                });
+            let last_line = u32::max(self.tokens[psl_end - 1].pos.end().line, next_line - 1);
+            if let Some(leading) = first_token.comments.as_ref() {
+                comments.extend(leading.leading.iter().cloned());
+            }
+            comments.extend(psl_comment_lines(first_token, last_line));
+            for token in &mut vec[start - offset..psl_end - offset] {
+                token.comments = None;
+            }
+            // The comments precede the next token that is not part of a discarded statement
+            let next_is_discarded = self
+                .psl_comments
+                .starting_at(psl_end)
+                .is_some_and(|(next, _)| !parsed.contains(&next));
+            if !next_is_discarded {
+                let next = &mut vec[psl_end - offset];
+                let token_comments = next.comments.get_or_insert_with(|| {
+                    Box::new(TokenComments {
+                        leading: Vec::new(),
+                        trailing: None,
+                    })
+                });
+                comments.append(&mut token_comments.leading);
+                token_comments.leading = std::mem::take(&mut comments);
+            }
+        }
         vec
     }
 }
@@ -337,6 +806,7 @@ mod tests {
     use super::*;
     use crate::data::{ContentReader, Diagnostic, NoDiagnostics};
     use crate::syntax::test::Code;
+    use crate::syntax::tokens::Defines;
     use itertools::Itertools;
 
     macro_rules! new_stream {
@@ -522,6 +992,245 @@ mod tests {
         )
     }
 
+    #[test]
+    fn protect_envelope_skips_encoded_blocks() {
+        let code = Code::new(
+            "\
+before
+`protect begin_protected
+`protect version = 1
+`protect key_keyowner = \"Vendor\", key_keyname = \"key1\", key_method = \"rsa\"
+`protect encoding = (enctype = \"base64\", line_length = 76, bytes = 256)
+`protect key_block
+Zm9vIGJhciAnYmF6IiBxdXV4
+  YWJj\"ZGVm'Z2hp/+==
+`protect data_method = \"aes128-cbc\"
+`protect data_block
+$$ \" ' # end entity ;
+`protect end_protected
+following",
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, stream, diagnostics);
+        assert_eq!(identifiers(&stream), vec!["before", "following"]);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::new(
+                code.s1("`protect begin_protected")
+                    .pos()
+                    .combine(&code.s1("`protect end_protected").pos()),
+                "Encrypted region of a protect envelope is not analyzed",
+                ErrorCode::InactiveRegion,
+            )]
+        );
+    }
+
+    #[test]
+    fn unmatched_protect_envelope_directives() {
+        let code = Code::new(
+            "\
+`protect end_protected
+`protect begin_protected
+`protect data_block
+YWJj",
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, stream, diagnostics);
+        assert!(stream.tokens.is_empty());
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::syntax_error(
+                code.s1("`protect end_protected"),
+                "`protect end_protected without matching `protect begin_protected",
+            )]
+        );
+    }
+
+    fn identifiers(stream: &TokenStream<'_>) -> Vec<String> {
+        stream
+            .tokens
+            .iter()
+            .filter_map(|token| match &token.value {
+                Value::Identifier(sym) => Some(sym.name_utf8()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn conditional_analysis_selects_active_branch() {
+        let code = Code::new(
+            "\
+`if VHDL_VERSION = \"1993\" then
+old_code
+`elsif VHDL_VERSION >= \"2008\" and TOOL_NAME = \"vhdl_lang\" then
+new_code
+`else
+other_code
+`end if
+common_code",
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, stream, diagnostics);
+        assert_eq!(identifiers(&stream), vec!["new_code", "common_code"]);
+
+        let inactive = |start_line: u32, end_line: u32| {
+            Diagnostic::new(
+                code.source()
+                    .pos(Position::new(start_line, 0), Position::new(end_line, 0)),
+                "Inactive region excluded by conditional analysis",
+                ErrorCode::InactiveRegion,
+            )
+        };
+        assert_eq!(diagnostics, vec![inactive(1, 2), inactive(5, 6)]);
+    }
+
+    #[test]
+    fn conditional_analysis_nested_blocks() {
+        let code = Code::new(
+            "\
+`if (TOOL_TYPE /= \"SIMULATION\") then
+  `if VHDL_VERSION = \"2008\" then
+    a
+  `else
+    b
+  `end if
+`else
+  `if not (VHDL_VERSION < \"2008\") then
+    c
+  `else
+    d
+  `end if
+`end if",
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, stream, diagnostics);
+        assert_eq!(identifiers(&stream), vec!["c"]);
+        assert!(diagnostics
+            .iter()
+            .all(|diag| diag.code == ErrorCode::InactiveRegion));
+        assert_eq!(diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn conditional_analysis_with_defines() {
+        let code = Code::new(
+            "\
+`if my_define = \"yes\" then
+defined
+`end if
+`if tool_type = \"FORMAL\" then
+formal
+`end if",
+        );
+        let defines = Defines::from_iter([
+            ("MY_DEFINE".to_owned(), "yes".to_owned()),
+            ("TOOL_TYPE".to_owned(), "FORMAL".to_owned()),
+        ]);
+        let source = code.source();
+        let contents = source.contents();
+        let tokenizer = Tokenizer::new(&code.symbols, source, ContentReader::new(&contents))
+            .with_defines(&defines);
+        let stream = TokenStream::new(tokenizer, &mut NoDiagnostics);
+        assert_eq!(identifiers(&stream), vec!["defined", "formal"]);
+    }
+
+    #[test]
+    fn conditional_analysis_undefined_identifier() {
+        let code = Code::new(
+            "\
+`if FOO = \"bar\" then
+`end if",
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, _stream, diagnostics);
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic::syntax_error(
+                code.s1("FOO"),
+                "Undefined conditional analysis identifier 'FOO'"
+            )]
+        );
+    }
+
+    #[test]
+    fn conditional_analysis_missing_then_and_end() {
+        let code = Code::new(
+            "\
+`if VHDL_VERSION = \"2008\"
+code",
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, stream, diagnostics);
+        assert_eq!(identifiers(&stream), vec!["code"]);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::syntax_error(
+                    code.source()
+                        .pos(code.s1("\"2008\"").end(), code.s1("\"2008\"").end()),
+                    "Expected 'then'"
+                ),
+                Diagnostic::syntax_error(
+                    code.s1("`if"),
+                    "Missing `end if for conditional analysis block"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn conditional_analysis_unmatched_directives() {
+        let code = Code::new(
+            "\
+`else
+`end if",
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, _stream, diagnostics);
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::syntax_error(code.s1("`else"), "`else without matching `if"),
+                Diagnostic::syntax_error(code.s1("`end"), "`end without matching `if"),
+            ]
+        );
+    }
+
+    #[test]
+    fn warning_and_error_directives() {
+        let code = Code::new(
+            "\
+`warning \"Say \"\"hello\"\"\" -- comment
+`if VHDL_VERSION = \"2008\" then
+`error \"Unsupported\"
+`else
+`error \"Not reported\"
+`end if",
+        );
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        new_stream!(code, _stream, diagnostics);
+        let diagnostics = diagnostics
+            .into_iter()
+            .filter(|diag| diag.code != ErrorCode::InactiveRegion)
+            .collect_vec();
+        assert_eq!(
+            diagnostics,
+            vec![
+                Diagnostic::new(
+                    code.s1("`warning \"Say \"\"hello\"\"\" -- comment"),
+                    "Say \"hello\"",
+                    ErrorCode::DirectiveWarning
+                ),
+                Diagnostic::new(
+                    code.s1("`error \"Unsupported\""),
+                    "Unsupported",
+                    ErrorCode::DirectiveError
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn pop_tokens() {
         let code = Code::new(
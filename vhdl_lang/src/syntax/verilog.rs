@@ -0,0 +1,1010 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this file,
+// You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) 2024, Olof Kraigher olof.kraigher@gmail.com
+
+//! A lightweight parser for the headers of Verilog and SystemVerilog modules.
+//!
+//! Only the name, the parameters and the ports of a module are extracted.
+//! Each module is translated into a VHDL entity such that it can be instantiated
+//! and bound to component declarations from VHDL. The tokens of the entity refer
+//! to the Verilog file so that diagnostics and goto-definition point into the module.
+
+use std::path::Path;
+
+use super::parser::VHDLParser;
+use super::tokens::kind_str;
+use crate::ast::DesignFile;
+use crate::data::*;
+use crate::standard::VHDLStandard;
+
+/// Returns true if the file is a Verilog or SystemVerilog file
+pub(crate) fn is_verilog_file(file_name: &Path) -> bool {
+    file_name
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            ["v", "vh", "sv", "svh"]
+                .iter()
+                .any(|verilog_ext| ext.eq_ignore_ascii_case(verilog_ext))
+        })
+}
+
+/// Parse the module headers of a Verilog source into VHDL entities
+pub(crate) fn parse_verilog_source(
+    parser: &VHDLParser,
+    source: &Source,
+    diagnostics: &mut dyn DiagnosticHandler,
+) -> DesignFile {
+    let tokens = tokenize(source);
+    let modules = Modules::new(&tokens).parse();
+
+    let mut writer = EntityWriter::new(parser.standard);
+    for module in modules.iter() {
+        writer.write_module(module);
+    }
+
+    let generated = Source::inline(source.file_name(), &writer.code);
+    let mut generated_diagnostics = Vec::new();
+    let mut design_file = parser.parse_vhdl_source(&generated, &mut generated_diagnostics);
+
+    // Let all positions refer to the Verilog source
+    for (tokens, _) in design_file.design_units.iter_mut() {
+        for token in tokens.iter_mut() {
+            token.pos = writer.verilog_pos(&token.pos);
+        }
+    }
+    for mut diagnostic in generated_diagnostics {
+        diagnostic.pos = writer.verilog_pos(&diagnostic.pos);
+        diagnostics.push(diagnostic);
+    }
+    design_file
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum VerilogKind {
+    Identifier,
+    Number,
+    String,
+    Punctuation(char),
+}
+
+#[derive(Clone, Debug)]
+struct VerilogToken {
+    kind: VerilogKind,
+    text: String,
+    pos: SrcPos,
+}
+
+impl VerilogToken {
+    fn is_identifier(&self, name: &str) -> bool {
+        self.kind == VerilogKind::Identifier && self.text == name
+    }
+
+    fn is_punctuation(&self, chr: char) -> bool {
+        self.kind == VerilogKind::Punctuation(chr)
+    }
+}
+
+/// Split the source into tokens. Comments, attributes and compiler directives are skipped.
+fn tokenize(source: &Source) -> Vec<VerilogToken> {
+    let contents = source.contents();
+    let mut reader = ContentReader::new(&contents);
+    let mut tokens = Vec::new();
+
+    while let Some(chr) = reader.get_char() {
+        let start = reader.pos();
+        let mut text = String::new();
+        let kind = match chr {
+            chr if chr.is_whitespace() => {
+                reader.skip();
+                continue;
+            }
+            '/' if reader.matches("//") => {
+                skip_line(&mut reader);
+                continue;
+            }
+            '/' if reader.matches("/*") => {
+                skip_until(&mut reader, "*/");
+                continue;
+            }
+            '(' if reader.matches("(*") && !reader.matches("(*)") => {
+                skip_until(&mut reader, "*)");
+                continue;
+            }
+            '`' => {
+                // Compiler directives such as `timescale or `define are skipped,
+                // a backslash at the end of a line continues a macro definition
+                loop {
+                    let mut last = ' ';
+                    while let Some(chr) = reader.get_char() {
+                        if chr == '\n' {
+                            break;
+                        }
+                        if !chr.is_whitespace() {
+                            last = chr;
+                        }
+                        reader.skip();
+                    }
+                    reader.skip();
+                    if last != '\\' {
+                        break;
+                    }
+                }
+                continue;
+            }
+            '"' => {
+                reader.skip();
+                text.push('"');
+                while let Some(chr) = reader.pop_char() {
+                    text.push(chr);
+                    match chr {
+                        '\\' => {
+                            if let Some(chr) = reader.pop_char() {
+                                text.push(chr);
+                            }
+                        }
+                        '"' | '\n' => break,
+                        _ => {}
+                    }
+                }
+                VerilogKind::String
+            }
+            '\\' => {
+                // Escaped identifier
+                reader.skip();
+                while let Some(chr) = reader.get_char() {
+                    if chr.is_whitespace() {
+                        break;
+                    }
+                    text.push(chr);
+                    reader.skip();
+                }
+                VerilogKind::Identifier
+            }
+            chr if chr.is_ascii_alphabetic() || chr == '_' || chr == '$' => {
+                while let Some(chr) = reader.get_char() {
+                    if !(chr.is_ascii_alphanumeric() || chr == '_' || chr == '$') {
+                        break;
+                    }
+                    text.push(chr);
+                    reader.skip();
+                }
+                VerilogKind::Identifier
+            }
+            chr if chr.is_ascii_digit() || chr == '\'' => {
+                // Sized and based numbers such as 8'hFF or 'b1 form a single token
+                let mut based = false;
+                while let Some(chr) = reader.get_char() {
+                    if chr == '\'' {
+                        based = true;
+                    } else if !(chr.is_ascii_alphanumeric()
+                        || chr == '_'
+                        || chr == '.'
+                        || (based && chr == '?'))
+                    {
+                        break;
+                    }
+                    text.push(chr);
+                    reader.skip();
+                }
+                VerilogKind::Number
+            }
+            chr => {
+                reader.skip();
+                text.push(chr);
+                VerilogKind::Punctuation(chr)
+            }
+        };
+
+        tokens.push(VerilogToken {
+            kind,
+            text,
+            pos: SrcPos::new(source.clone(), Range::new(start, reader.pos())),
+        });
+    }
+    tokens
+}
+
+fn skip_line(reader: &mut ContentReader<'_>) {
+    while let Some(chr) = reader.pop_char() {
+        if chr == '\n' {
+            break;
+        }
+    }
+}
+
+fn skip_until(reader: &mut ContentReader<'_>, end: &str) {
+    while reader.get_char().is_some() {
+        if reader.matches(end) {
+            for _ in end.chars() {
+                reader.skip();
+            }
+            break;
+        }
+        reader.skip();
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum ParameterType {
+    Integer,
+    Real,
+    String,
+}
+
+#[derive(Debug)]
+struct Parameter<'t> {
+    name: &'t VerilogToken,
+    typ: ParameterType,
+    default: &'t [VerilogToken],
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Direction {
+    Input,
+    Output,
+    Inout,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum PortType<'t> {
+    Logic,
+    // The tokens of the left and right bound of a single packed dimension
+    Vector(&'t [VerilogToken], &'t [VerilogToken]),
+    // A multi-dimensional or user defined type
+    UnconstrainedVector,
+    Integer,
+    Real,
+}
+
+#[derive(Debug)]
+struct Port<'t> {
+    name: &'t VerilogToken,
+    direction: Direction,
+    typ: PortType<'t>,
+}
+
+#[derive(Debug)]
+struct Module<'t> {
+    name: &'t VerilogToken,
+    parameters: Vec<Parameter<'t>>,
+    ports: Vec<Port<'t>>,
+}
+
+struct Modules<'t> {
+    tokens: &'t [VerilogToken],
+    idx: usize,
+}
+
+impl<'t> Modules<'t> {
+    fn new(tokens: &'t [VerilogToken]) -> Self {
+        Modules { tokens, idx: 0 }
+    }
+
+    fn parse(mut self) -> Vec<Module<'t>> {
+        let mut modules = Vec::new();
+        while let Some(token) = self.tokens.get(self.idx) {
+            self.idx += 1;
+            if token.is_identifier("module") || token.is_identifier("macromodule") {
+                if let Some(module) = self.parse_module() {
+                    modules.push(module);
+                }
+            }
+        }
+        modules
+    }
+
+    fn peek(&self) -> Option<&'t VerilogToken> {
+        self.tokens.get(self.idx)
+    }
+
+    fn skip_if_punctuation(&mut self, chr: char) -> bool {
+        if self.peek().is_some_and(|token| token.is_punctuation(chr)) {
+            self.idx += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the tokens until the closing parenthesis of the current list
+    /// split at the commas that are not nested within parentheses, brackets or braces
+    fn list_items(&mut self) -> Vec<&'t [VerilogToken]> {
+        let mut items = Vec::new();
+        let mut depth = 0;
+        let mut start = self.idx;
+        while let Some(token) = self.tokens.get(self.idx) {
+            match token.kind {
+                VerilogKind::Punctuation('(' | '[' | '{') => depth += 1,
+                VerilogKind::Punctuation(')') if depth == 0 => {
+                    if start < self.idx {
+                        items.push(&self.tokens[start..self.idx]);
+                    }
+                    self.idx += 1;
+                    break;
+                }
+                VerilogKind::Punctuation(')' | ']' | '}') => depth -= 1,
+                VerilogKind::Punctuation(',') if depth == 0 => {
+                    items.push(&self.tokens[start..self.idx]);
+                    start = self.idx + 1;
+                }
+                _ => {}
+            }
+            self.idx += 1;
+        }
+        items
+    }
+
+    /// Returns the tokens of the statement until the next semicolon
+    fn statement(&mut self) -> &'t [VerilogToken] {
+        let start = self.idx;
+        while let Some(token) = self.tokens.get(self.idx) {
+            self.idx += 1;
+            if token.is_punctuation(';') {
+                return &self.tokens[start..self.idx - 1];
+            }
+        }
+        &self.tokens[start..]
+    }
+
+    fn parse_module(&mut self) -> Option<Module<'t>> {
+        if self
+            .peek()
+            .is_some_and(|token| token.is_identifier("automatic") || token.is_identifier("static"))
+        {
+            self.idx += 1;
+        }
+        let name = self
+            .peek()
+            .filter(|token| token.kind == VerilogKind::Identifier)?;
+        self.idx += 1;
+
+        let mut module = Module {
+            name,
+            parameters: Vec::new(),
+            ports: Vec::new(),
+        };
+
+        // Package imports within the header
+        while self
+            .peek()
+            .is_some_and(|token| token.is_identifier("import"))
+        {
+            self.statement();
+        }
+
+        let has_parameter_list = self.skip_if_punctuation('#');
+        if has_parameter_list && self.skip_if_punctuation('(') {
+            let mut typ = None;
+            for item in self.list_items() {
+                if let Some((parameter, item_typ)) = parse_parameter(item, typ) {
+                    typ = Some(item_typ);
+                    module.parameters.push(parameter);
+                }
+            }
+        }
+
+        // Names of the ports of a non-ANSI header that are declared in the body
+        let mut port_names = Vec::new();
+        if self.skip_if_punctuation('(') {
+            let mut previous: Option<(Direction, PortType<'t>)> = None;
+            for item in self.list_items() {
+                match parse_ansi_port(item, previous) {
+                    Some(port) => {
+                        previous = Some((port.direction, port.typ));
+                        module.ports.push(port);
+                    }
+                    None => {
+                        if let [name] = item {
+                            if name.kind == VerilogKind::Identifier {
+                                port_names.push(name);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        self.statement();
+
+        // The body is searched for declarations of parameters and non-ANSI ports
+        while let Some(token) = self.peek() {
+            if token.is_identifier("endmodule") {
+                self.idx += 1;
+                break;
+            } else if token.is_identifier("parameter") && !has_parameter_list {
+                let statement = self.statement();
+                let mut typ = None;
+                for item in statement.split(|token| token.is_punctuation(',')) {
+                    if let Some((parameter, item_typ)) = parse_parameter(item, typ) {
+                        typ = Some(item_typ);
+                        module.parameters.push(parameter);
+                    }
+                }
+            } else if parse_direction(token).is_some() && !port_names.is_empty() {
+                let statement = self.statement();
+                let mut previous = None;
+                for item in split_declaration(statement) {
+                    if let Some(port) = parse_ansi_port(item, previous) {
+                        previous = Some((port.direction, port.typ));
+                        if port_names.iter().any(|name| name.text == port.name.text) {
+                            module.ports.push(port);
+                        }
+                    }
+                }
+            } else {
+                self.statement();
+            }
+        }
+
+        // Ports of a non-ANSI header are declared in the order of the header
+        if !port_names.is_empty() {
+            module.ports.sort_by_key(|port| {
+                port_names
+                    .iter()
+                    .position(|name| name.text == port.name.text)
+            });
+        }
+        Some(module)
+    }
+}
+
+/// Split a declaration such as `input [7:0] a, b` into items where
+/// all but the first item consist of a name only
+fn split_declaration(statement: &[VerilogToken]) -> Vec<&[VerilogToken]> {
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, token) in statement.iter().enumerate() {
+        match token.kind {
+            VerilogKind::Punctuation('(' | '[' | '{') => depth += 1,
+            VerilogKind::Punctuation(')' | ']' | '}') => depth -= 1,
+            VerilogKind::Punctuation(',') if depth == 0 => {
+                items.push(&statement[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&statement[start..]);
+    items
+}
+
+fn parse_direction(token: &VerilogToken) -> Option<Direction> {
+    match token.text.as_str() {
+        "input" if token.kind == VerilogKind::Identifier => Some(Direction::Input),
+        "output" if token.kind == VerilogKind::Identifier => Some(Direction::Output),
+        "inout" if token.kind == VerilogKind::Identifier => Some(Direction::Inout),
+        _ => None,
+    }
+}
+
+/// Returns the index of the last identifier that is not within brackets or parentheses
+/// and that is not part of a default value
+fn last_name(tokens: &[VerilogToken]) -> Option<usize> {
+    let mut depth = 0;
+    let mut name = None;
+    for (idx, token) in tokens.iter().enumerate() {
+        match token.kind {
+            VerilogKind::Punctuation('(' | '[' | '{') => depth += 1,
+            VerilogKind::Punctuation(')' | ']' | '}') => depth -= 1,
+            VerilogKind::Punctuation('=') if depth == 0 => break,
+            VerilogKind::Identifier if depth == 0 => name = Some(idx),
+            _ => {}
+        }
+    }
+    name
+}
+
+/// Returns the ranges within brackets
+fn packed_ranges(tokens: &[VerilogToken]) -> Vec<&[VerilogToken]> {
+    let mut ranges = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, token) in tokens.iter().enumerate() {
+        if token.is_punctuation('[') {
+            if depth == 0 {
+                start = idx + 1;
+            }
+            depth += 1;
+        } else if token.is_punctuation(']') {
+            depth -= 1;
+            if depth == 0 {
+                ranges.push(&tokens[start..idx]);
+            }
+        }
+    }
+    ranges
+}
+
+/// Parse a port of an ANSI header or of a port declaration.
+/// An item without direction has the direction and type of the previous port.
+fn parse_ansi_port<'t>(
+    item: &'t [VerilogToken],
+    previous: Option<(Direction, PortType<'t>)>,
+) -> Option<Port<'t>> {
+    let (direction, rest) = match item.split_first() {
+        Some((first, rest)) => match parse_direction(first) {
+            Some(direction) => (Some(direction), rest),
+            None => (None, item),
+        },
+        None => return None,
+    };
+
+    // Unpacked dimensions and default values follow the name
+    let name_idx = last_name(rest)?;
+    let name = &rest[name_idx];
+    let type_tokens = &rest[..name_idx];
+
+    let (direction, typ) = match direction {
+        Some(direction) => (direction, parse_port_type(type_tokens)),
+        None if type_tokens.is_empty() => previous?,
+        None => (previous?.0, parse_port_type(type_tokens)),
+    };
+
+    Some(Port {
+        name,
+        direction,
+        typ,
+    })
+}
+
+fn parse_port_type(tokens: &[VerilogToken]) -> PortType<'_> {
+    const NET_TYPES: &[&str] = &[
+        "wire", "reg", "logic", "bit", "var", "tri", "wand", "wor", "tri0", "tri1", "uwire",
+        "signed", "unsigned", "supply0", "supply1",
+    ];
+
+    let ranges = packed_ranges(tokens);
+    let mut depth = 0;
+    for token in tokens.iter() {
+        if token.is_punctuation('[') {
+            depth += 1;
+        } else if token.is_punctuation(']') {
+            depth -= 1;
+        } else if depth == 0 && token.kind == VerilogKind::Identifier {
+            match token.text.as_str() {
+                "integer" | "int" | "shortint" | "longint" | "byte" => return PortType::Integer,
+                "real" | "shortreal" | "realtime" => return PortType::Real,
+                name if NET_TYPES.contains(&name) => {}
+                // A user defined type such as a struct
+                _ => return PortType::UnconstrainedVector,
+            }
+        }
+    }
+
+    match ranges.as_slice() {
+        [] => PortType::Logic,
+        [range] => {
+            let mut parts = range.split(|token| token.is_punctuation(':'));
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(left), Some(right), None) if !left.is_empty() && !right.is_empty() => {
+                    PortType::Vector(left, right)
+                }
+                _ => PortType::UnconstrainedVector,
+            }
+        }
+        _ => PortType::UnconstrainedVector,
+    }
+}
+
+/// Parse a parameter declaration such as `parameter integer WIDTH = 8`.
+/// A parameter without type has the type of the previous parameter.
+fn parse_parameter(
+    item: &[VerilogToken],
+    previous: Option<ParameterType>,
+) -> Option<(Parameter<'_>, ParameterType)> {
+    let mut item = item;
+    let mut explicit = false;
+    if let Some((first, rest)) = item.split_first() {
+        if first.is_identifier("localparam") {
+            return None;
+        } else if first.is_identifier("parameter") {
+            item = rest;
+            explicit = true;
+        }
+    }
+    if item
+        .first()
+        .is_some_and(|token| token.is_identifier("type"))
+    {
+        // Type parameters are not supported
+        return None;
+    }
+
+    let name_idx = last_name(item)?;
+    let name = &item[name_idx];
+    let type_tokens = &item[..name_idx];
+    let default = match item[name_idx + 1..]
+        .iter()
+        .position(|token| token.is_punctuation('='))
+    {
+        Some(idx) => &item[name_idx + 2 + idx..],
+        None => &[],
+    };
+
+    let mut typ = None;
+    for token in type_tokens.iter() {
+        match token.text.as_str() {
+            "string" => typ = Some(ParameterType::String),
+            "real" | "shortreal" | "realtime" => typ = Some(ParameterType::Real),
+            _ => {}
+        }
+    }
+    let typ = match typ {
+        Some(typ) => typ,
+        None if !type_tokens.is_empty() || explicit => match default {
+            [token] if token.kind == VerilogKind::String => ParameterType::String,
+            _ => ParameterType::Integer,
+        },
+        None => previous.unwrap_or(ParameterType::Integer),
+    };
+
+    Some((Parameter { name, typ, default }, typ))
+}
+
+/// Returns true if the expression only consists of names, numbers and arithmetic operators
+/// such that it has the same meaning in VHDL
+fn is_simple_expression(tokens: &[VerilogToken]) -> bool {
+    !tokens.is_empty()
+        && tokens.iter().all(|token| match token.kind {
+            VerilogKind::Identifier => !token.text.starts_with('$'),
+            VerilogKind::Number => number_literal(&token.text, ParameterType::Integer).is_some(),
+            VerilogKind::Punctuation(chr) => "+-*/()".contains(chr),
+            VerilogKind::String => false,
+        })
+}
+
+/// The value of a Verilog number as a VHDL literal
+fn number_literal(text: &str, typ: ParameterType) -> Option<String> {
+    let text = text.replace('_', "");
+    if let Some((_, value)) = text.split_once('\'') {
+        let value = value.trim_start_matches(['s', 'S']);
+        let mut chars = value.chars();
+        let radix = match chars.next()?.to_ascii_lowercase() {
+            'b' => 2,
+            'o' => 8,
+            'd' => 10,
+            'h' => 16,
+            _ => return None,
+        };
+        let value = u64::from_str_radix(chars.as_str(), radix).ok()?;
+        Some(value.to_string())
+    } else if typ == ParameterType::Real && !text.contains('.') {
+        Some(format!("{text}.0"))
+    } else {
+        Some(text)
+    }
+}
+
+/// Writes the VHDL entities of Verilog modules and maps the positions
+/// within the VHDL code to positions within the Verilog source
+struct EntityWriter {
+    standard: VHDLStandard,
+    code: String,
+    line: u32,
+    character: u32,
+    // A position within the VHDL code and the position of the Verilog code it was written from
+    mapping: Vec<(Position, SrcPos)>,
+}
+
+impl EntityWriter {
+    fn new(standard: VHDLStandard) -> Self {
+        EntityWriter {
+            standard,
+            code: String::new(),
+            line: 0,
+            character: 0,
+            mapping: Vec::new(),
+        }
+    }
+
+    fn position(&self) -> Position {
+        Position::new(self.line, self.character)
+    }
+
+    /// Write VHDL code that originates from the Verilog code at `pos`
+    fn write(&mut self, text: &str, pos: &SrcPos) {
+        self.mapping.push((self.position(), pos.clone()));
+        for chr in text.chars() {
+            if chr == '\n' {
+                self.line += 1;
+                self.character = 0;
+            } else {
+                self.character += chr.len_utf16() as u32;
+            }
+        }
+        self.code.push_str(text);
+    }
+
+    /// The position within the Verilog source of a position within the VHDL code
+    fn verilog_pos(&self, pos: &SrcPos) -> SrcPos {
+        let start = pos.start();
+        let idx = self
+            .mapping
+            .partition_point(|(position, _)| *position <= start);
+        match idx.checked_sub(1).and_then(|idx| self.mapping.get(idx)) {
+            Some((_, verilog_pos)) => verilog_pos.clone(),
+            None => pos.clone(),
+        }
+    }
+
+    /// Write a Verilog identifier as VHDL identifier.
+    /// Identifiers that are not valid basic identifiers in VHDL are written as extended identifiers.
+    fn identifier(&mut self, token: &VerilogToken) {
+        let name = token.text.as_str();
+        let is_basic = name.starts_with(|chr: char| chr.is_ascii_alphabetic())
+            && name
+                .chars()
+                .all(|chr| chr.is_ascii_alphanumeric() || chr == '_')
+            && !name.contains("__")
+            && !name.ends_with('_')
+            && !self
+                .standard
+                .keywords()
+                .iter()
+                .any(|kind| kind_str(*kind).eq_ignore_ascii_case(name));
+        if is_basic {
+            self.write(name, &token.pos);
+        } else {
+            self.write(&format!("\\{}\\", name.replace('\\', "\\\\")), &token.pos);
+        }
+    }
+
+    /// Write a Verilog expression, see [`is_simple_expression`]
+    fn expression(&mut self, tokens: &[VerilogToken], anchor: &SrcPos) {
+        for (idx, token) in tokens.iter().enumerate() {
+            if idx > 0 {
+                self.write(" ", anchor);
+            }
+            match token.kind {
+                VerilogKind::Identifier => self.identifier(token),
+                VerilogKind::Number => {
+                    let literal = number_literal(&token.text, ParameterType::Integer)
+                        .unwrap_or_else(|| token.text.clone());
+                    self.write(&literal, &token.pos)
+                }
+                _ => self.write(&token.text, &token.pos),
+            }
+        }
+    }
+
+    fn write_module(&mut self, module: &Module<'_>) {
+        let anchor = &module.name.pos;
+        let uses_std_logic = module
+            .ports
+            .iter()
+            .any(|port| !matches!(port.typ, PortType::Integer | PortType::Real));
+        if uses_std_logic {
+            self.write("library ieee;\nuse ieee.std_logic_1164.all;\n", anchor);
+        }
+        self.write("entity ", anchor);
+        self.identifier(module.name);
+        self.write(" is\n", anchor);
+
+        if !module.parameters.is_empty() {
+            self.write("  generic (\n", anchor);
+            for (idx, parameter) in module.parameters.iter().enumerate() {
+                self.write("    ", anchor);
+                self.identifier(parameter.name);
+                let (typ, neutral) = match parameter.typ {
+                    ParameterType::Integer => ("integer", "0"),
+                    ParameterType::Real => ("real", "0.0"),
+                    ParameterType::String => ("string", "\"\""),
+                };
+                self.write(&format!(" : {typ}"), &parameter.name.pos);
+                self.write(" := ", &parameter.name.pos);
+                // A default is always given as it is optional to override a parameter
+                let translated = match parameter.default {
+                    [token] if token.kind == VerilogKind::String => {
+                        self.write(&token.text, &token.pos);
+                        true
+                    }
+                    [token] if token.kind == VerilogKind::Number => {
+                        match number_literal(&token.text, parameter.typ) {
+                            Some(literal) => {
+                                self.write(&literal, &token.pos);
+                                true
+                            }
+                            None => false,
+                        }
+                    }
+                    default
+                        if parameter.typ == ParameterType::Integer
+                            && is_simple_expression(default) =>
+                    {
+                        self.expression(default, &parameter.name.pos);
+                        true
+                    }
+                    _ => false,
+                };
+                if !translated {
+                    self.write(neutral, &parameter.name.pos);
+                }
+                let separator = if idx + 1 < module.parameters.len() {
+                    ";\n"
+                } else {
+                    "\n"
+                };
+                self.write(separator, anchor);
+            }
+            self.write("  );\n", anchor);
+        }
+
+        if !module.ports.is_empty() {
+            self.write("  port (\n", anchor);
+            for (idx, port) in module.ports.iter().enumerate() {
+                let pos = &port.name.pos;
+                self.write("    ", anchor);
+                self.identifier(port.name);
+                let mode = match port.direction {
+                    Direction::Input => " : in ",
+                    Direction::Output => " : out ",
+                    Direction::Inout => " : inout ",
+                };
+                self.write(mode, pos);
+                match port.typ {
+                    PortType::Logic => self.write("std_logic", pos),
+                    PortType::Integer => self.write("integer", pos),
+                    PortType::Real => self.write("real", pos),
+                    PortType::Vector(left, right)
+                        if is_simple_expression(left) && is_simple_expression(right) =>
+                    {
+                        let direction = match (left, right) {
+                            ([left], [right])
+                                if left.kind == VerilogKind::Number
+                                    && right.kind == VerilogKind::Number =>
+                            {
+                                let value = |token: &VerilogToken| {
+                                    number_literal(&token.text, ParameterType::Integer)
+                                        .and_then(|value| value.parse::<u64>().ok())
+                                };
+                                if value(left) < value(right) {
+                                    " to "
+                                } else {
+                                    " downto "
+                                }
+                            }
+                            _ => " downto ",
+                        };
+                        self.write("std_logic_vector(", pos);
+                        self.expression(left, pos);
+                        self.write(direction, pos);
+                        self.expression(right, pos);
+                        self.write(")", pos);
+                    }
+                    PortType::Vector(..) | PortType::UnconstrainedVector => {
+                        self.write("std_logic_vector", pos)
+                    }
+                }
+                let separator = if idx + 1 < module.ports.len() {
+                    ";\n"
+                } else {
+                    "\n"
+                };
+                self.write(separator, anchor);
+            }
+            self.write("  );\n", anchor);
+        }
+        self.write("end entity;\n\n", anchor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::HasIdent;
+    use crate::syntax::test::Code;
+    use pretty_assertions::assert_eq;
+
+    fn entities(code: &str) -> String {
+        let source = Source::inline(Path::new("module.sv"), code);
+        let tokens = tokenize(&source);
+        let mut writer = EntityWriter::new(VHDLStandard::default());
+        for module in Modules::new(&tokens).parse() {
+            writer.write_module(&module);
+        }
+        writer.code
+    }
+
+    #[test]
+    fn ansi_module_header() {
+        assert_eq!(
+            entities(
+                "\
+`timescale 1ns / 1ps
+// A comment
+module fifo #(
+  parameter WIDTH = 8,
+  parameter integer DEPTH = 'h10,
+  parameter string NAME = \"fifo\",
+  localparam ADDR = $clog2(DEPTH)
+) (
+  input wire clk, rst,
+  input [WIDTH-1:0] din, /* another comment */
+  output logic [0:3] level,
+  output reg [7:0] dout [4],
+  inout [$clog2(DEPTH)-1:0] addr
+);
+  assign dout = din;
+endmodule
+"
+            ),
+            "\
+library ieee;
+use ieee.std_logic_1164.all;
+entity fifo is
+  generic (
+    WIDTH : integer := 8;
+    DEPTH : integer := 16;
+    NAME : string := \"fifo\"
+  );
+  port (
+    clk : in std_logic;
+    rst : in std_logic;
+    din : in std_logic_vector(WIDTH - 1 downto 0);
+    level : out std_logic_vector(0 to 3);
+    dout : out std_logic_vector(7 downto 0);
+    addr : inout std_logic_vector
+  );
+end entity;
+
+"
+        );
+    }
+
+    #[test]
+    fn non_ansi_module_header() {
+        assert_eq!(
+            entities(
+                "\
+module counter (clk, count, \\rst$n );
+  parameter MAX = 10, STEP = 1;
+  input clk;
+  input \\rst$n ;
+  output [3:0] count;
+  reg [3:0] count;
+endmodule
+"
+            ),
+            "\
+library ieee;
+use ieee.std_logic_1164.all;
+entity counter is
+  generic (
+    MAX : integer := 10;
+    STEP : integer := 1
+  );
+  port (
+    clk : in std_logic;
+    count : out std_logic_vector(3 downto 0);
+    \\rst$n\\ : in std_logic
+  );
+end entity;
+
+"
+        );
+    }
+
+    #[test]
+    fn positions_refer_to_verilog_source() {
+        let code = Code::new(
+            "\
+module adder (
+  input [7:0] a,
+  output [8:0] sum
+);
+endmodule
+",
+        );
+        let parser = VHDLParser::new(VHDLStandard::default());
+        let mut diagnostics = Vec::new();
+        let design_file = parse_verilog_source(&parser, code.source(), &mut diagnostics);
+        assert_eq!(diagnostics, vec![]);
+        assert_eq!(design_file.design_units.len(), 1);
+        let (tokens, unit) = &design_file.design_units[0];
+        assert_eq!(unit.ident().pos(tokens), &code.s1("adder").pos());
+        assert!(tokens.iter().any(|token| token.pos == code.s1("sum").pos()));
+    }
+}
@@ -13,7 +13,7 @@ pub fn parses_example_project_without_errors() {
 
     let mut vhdl_libraries_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
     // Load the VHDL standard libraries
-    vhdl_libraries_path.push("../vhdl_libraries/vhdl_ls.toml");
+    vhdl_libraries_path.push("vhdl_libraries/vhdl_ls.toml");
     config.append(
         &Config::read_file_path(&vhdl_libraries_path).expect("Failed to read config file"),
         &mut msg_printer,
@@ -51,7 +51,7 @@ fn unused_function_gets_detected() -> Result<(), Box<dyn Error>> {
     cmd.arg("--config")
         .arg("tests/unused_declarations/vhdl_ls.toml")
         .arg("--libraries")
-        .arg("../vhdl_libraries");
+        .arg("vhdl_libraries");
     cmd.assert().failure().stdout(predicate::str::contains(
         "error: Unused declaration of port 'baz' : inout",
     ));
@@ -0,0 +1,83 @@
+-- Package math_complex as defined by IEEE 1076.2-1996
+-- Only the package declaration is provided since it is sufficient for analysis.
+
+use work.math_real.all;
+
+package math_complex is
+  type complex is record
+    re : real;
+    im : real;
+  end record;
+
+  subtype positive_real is real range 0.0 to real'high;
+  subtype principal_value is real range -math_pi to math_pi;
+
+  type complex_polar is record
+    mag : positive_real;
+    arg : principal_value;
+  end record;
+
+  constant math_cbase_1 : complex := complex'(1.0, 0.0);
+  constant math_cbase_j : complex := complex'(0.0, 1.0);
+  constant math_czero : complex := complex'(0.0, 0.0);
+
+  function "=" (l : in complex_polar; r : in complex_polar) return boolean;
+  function "/=" (l : in complex_polar; r : in complex_polar) return boolean;
+  function cmplx (x : in real; y : in real := 0.0) return complex;
+  function get_principal_value (x : in real) return principal_value;
+  function complex_to_polar (z : in complex) return complex_polar;
+  function polar_to_complex (z : in complex_polar) return complex;
+  function "abs" (z : in complex) return positive_real;
+  function "abs" (z : in complex_polar) return positive_real;
+  function arg (z : in complex) return principal_value;
+  function arg (z : in complex_polar) return principal_value;
+  function "-" (z : in complex) return complex;
+  function "-" (z : in complex_polar) return complex_polar;
+  function conj (z : in complex) return complex;
+  function conj (z : in complex_polar) return complex_polar;
+  function sqrt (z : in complex) return complex;
+  function sqrt (z : in complex_polar) return complex_polar;
+  function exp (z : in complex) return complex;
+  function exp (z : in complex_polar) return complex_polar;
+  function log (z : in complex) return complex;
+  function log2 (z : in complex) return complex;
+  function log10 (z : in complex) return complex;
+  function log (z : in complex_polar) return complex_polar;
+  function log2 (z : in complex_polar) return complex_polar;
+  function log10 (z : in complex_polar) return complex_polar;
+  function log (z : in complex; base : in real) return complex;
+  function log (z : in complex_polar; base : in real) return complex_polar;
+  function sin (z : in complex) return complex;
+  function sin (z : in complex_polar) return complex_polar;
+  function cos (z : in complex) return complex;
+  function cos (z : in complex_polar) return complex_polar;
+  function sinh (z : in complex) return complex;
+  function sinh (z : in complex_polar) return complex_polar;
+  function cosh (z : in complex) return complex;
+  function cosh (z : in complex_polar) return complex_polar;
+
+  function "+" (l : in complex; r : in complex) return complex;
+  function "+" (l : in real; r : in complex) return complex;
+  function "+" (l : in complex; r : in real) return complex;
+  function "+" (l : in complex_polar; r : in complex_polar) return complex_polar;
+  function "+" (l : in real; r : in complex_polar) return complex_polar;
+  function "+" (l : in complex_polar; r : in real) return complex_polar;
+  function "-" (l : in complex; r : in complex) return complex;
+  function "-" (l : in real; r : in complex) return complex;
+  function "-" (l : in complex; r : in real) return complex;
+  function "-" (l : in complex_polar; r : in complex_polar) return complex_polar;
+  function "-" (l : in real; r : in complex_polar) return complex_polar;
+  function "-" (l : in complex_polar; r : in real) return complex_polar;
+  function "*" (l : in complex; r : in complex) return complex;
+  function "*" (l : in real; r : in complex) return complex;
+  function "*" (l : in complex; r : in real) return complex;
+  function "*" (l : in complex_polar; r : in complex_polar) return complex_polar;
+  function "*" (l : in real; r : in complex_polar) return complex_polar;
+  function "*" (l : in complex_polar; r : in real) return complex_polar;
+  function "/" (l : in complex; r : in complex) return complex;
+  function "/" (l : in real; r : in complex) return complex;
+  function "/" (l : in complex; r : in real) return complex;
+  function "/" (l : in complex_polar; r : in complex_polar) return complex_polar;
+  function "/" (l : in real; r : in complex_polar) return complex_polar;
+  function "/" (l : in complex_polar; r : in real) return complex_polar;
+end package;
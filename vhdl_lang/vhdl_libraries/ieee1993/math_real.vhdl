@@ -0,0 +1,55 @@
+-- Package math_real as defined by IEEE 1076.2-1996
+-- Only the package declaration is provided since it is sufficient for analysis.
+
+package math_real is
+  constant math_e : real := 2.71828_18284_59045_23536;
+  constant math_1_over_e : real := 0.36787_94411_71442_32160;
+  constant math_pi : real := 3.14159_26535_89793_23846;
+  constant math_2_pi : real := 6.28318_53071_79586_47693;
+  constant math_1_over_pi : real := 0.31830_98861_83790_67154;
+  constant math_pi_over_2 : real := 1.57079_63267_94896_61923;
+  constant math_pi_over_3 : real := 1.04719_75511_96597_74615;
+  constant math_pi_over_4 : real := 0.78539_81633_97448_30962;
+  constant math_3_pi_over_2 : real := 4.71238_89803_84689_85769;
+  constant math_log_of_2 : real := 0.69314_71805_59945_30942;
+  constant math_log_of_10 : real := 2.30258_50929_94045_68402;
+  constant math_log2_of_e : real := 1.44269_50408_88963_4074;
+  constant math_log10_of_e : real := 0.43429_44819_03251_82765;
+  constant math_sqrt_2 : real := 1.41421_35623_73095_04880;
+  constant math_1_over_sqrt_2 : real := 0.70710_67811_86547_52440;
+  constant math_sqrt_pi : real := 1.77245_38509_05516_02730;
+  constant math_deg_to_rad : real := 0.01745_32925_19943_29577;
+  constant math_rad_to_deg : real := 57.29577_95130_82320_87680;
+
+  function sign (x : in real) return real;
+  function ceil (x : in real) return real;
+  function floor (x : in real) return real;
+  function round (x : in real) return real;
+  function trunc (x : in real) return real;
+  function "mod" (x, y : in real) return real;
+  function realmax (x, y : in real) return real;
+  function realmin (x, y : in real) return real;
+  procedure uniform (variable seed1, seed2 : inout positive; variable x : out real);
+  function sqrt (x : in real) return real;
+  function cbrt (x : in real) return real;
+  function "**" (x : in integer; y : in real) return real;
+  function "**" (x : in real; y : in real) return real;
+  function exp (x : in real) return real;
+  function log (x : in real) return real;
+  function log2 (x : in real) return real;
+  function log10 (x : in real) return real;
+  function log (x : in real; base : in real) return real;
+  function sin (x : in real) return real;
+  function cos (x : in real) return real;
+  function tan (x : in real) return real;
+  function arcsin (x : in real) return real;
+  function arccos (x : in real) return real;
+  function arctan (y : in real) return real;
+  function arctan (y : in real; x : in real) return real;
+  function sinh (x : in real) return real;
+  function cosh (x : in real) return real;
+  function tanh (x : in real) return real;
+  function arcsinh (x : in real) return real;
+  function arccosh (x : in real) return real;
+  function arctanh (x : in real) return real;
+end package;
@@ -0,0 +1,136 @@
+-- Package numeric_std as defined by IEEE 1076.3-1997
+-- Only the package declaration is provided since it is sufficient for analysis.
+
+library ieee;
+use ieee.std_logic_1164.all;
+
+package numeric_std is
+  type UNSIGNED is array (natural range <>) of std_logic;
+  type SIGNED is array (natural range <>) of std_logic;
+
+  function "abs" (arg : SIGNED) return SIGNED;
+  function "-" (arg : SIGNED) return SIGNED;
+
+  function "+" (l, r : UNSIGNED) return UNSIGNED;
+  function "+" (l, r : SIGNED) return SIGNED;
+  function "+" (l : UNSIGNED; r : natural) return UNSIGNED;
+  function "+" (l : natural; r : UNSIGNED) return UNSIGNED;
+  function "+" (l : SIGNED; r : integer) return SIGNED;
+  function "+" (l : integer; r : SIGNED) return SIGNED;
+  function "-" (l, r : UNSIGNED) return UNSIGNED;
+  function "-" (l, r : SIGNED) return SIGNED;
+  function "-" (l : UNSIGNED; r : natural) return UNSIGNED;
+  function "-" (l : natural; r : UNSIGNED) return UNSIGNED;
+  function "-" (l : SIGNED; r : integer) return SIGNED;
+  function "-" (l : integer; r : SIGNED) return SIGNED;
+  function "*" (l, r : UNSIGNED) return UNSIGNED;
+  function "*" (l, r : SIGNED) return SIGNED;
+  function "*" (l : UNSIGNED; r : natural) return UNSIGNED;
+  function "*" (l : natural; r : UNSIGNED) return UNSIGNED;
+  function "*" (l : SIGNED; r : integer) return SIGNED;
+  function "*" (l : integer; r : SIGNED) return SIGNED;
+  function "/" (l, r : UNSIGNED) return UNSIGNED;
+  function "/" (l, r : SIGNED) return SIGNED;
+  function "/" (l : UNSIGNED; r : natural) return UNSIGNED;
+  function "/" (l : natural; r : UNSIGNED) return UNSIGNED;
+  function "/" (l : SIGNED; r : integer) return SIGNED;
+  function "/" (l : integer; r : SIGNED) return SIGNED;
+  function "rem" (l, r : UNSIGNED) return UNSIGNED;
+  function "rem" (l, r : SIGNED) return SIGNED;
+  function "rem" (l : UNSIGNED; r : natural) return UNSIGNED;
+  function "rem" (l : natural; r : UNSIGNED) return UNSIGNED;
+  function "rem" (l : SIGNED; r : integer) return SIGNED;
+  function "rem" (l : integer; r : SIGNED) return SIGNED;
+  function "mod" (l, r : UNSIGNED) return UNSIGNED;
+  function "mod" (l, r : SIGNED) return SIGNED;
+  function "mod" (l : UNSIGNED; r : natural) return UNSIGNED;
+  function "mod" (l : natural; r : UNSIGNED) return UNSIGNED;
+  function "mod" (l : SIGNED; r : integer) return SIGNED;
+  function "mod" (l : integer; r : SIGNED) return SIGNED;
+
+  function ">" (l, r : UNSIGNED) return boolean;
+  function ">" (l, r : SIGNED) return boolean;
+  function ">" (l : natural; r : UNSIGNED) return boolean;
+  function ">" (l : integer; r : SIGNED) return boolean;
+  function ">" (l : UNSIGNED; r : natural) return boolean;
+  function ">" (l : SIGNED; r : integer) return boolean;
+  function "<" (l, r : UNSIGNED) return boolean;
+  function "<" (l, r : SIGNED) return boolean;
+  function "<" (l : natural; r : UNSIGNED) return boolean;
+  function "<" (l : integer; r : SIGNED) return boolean;
+  function "<" (l : UNSIGNED; r : natural) return boolean;
+  function "<" (l : SIGNED; r : integer) return boolean;
+  function "<=" (l, r : UNSIGNED) return boolean;
+  function "<=" (l, r : SIGNED) return boolean;
+  function "<=" (l : natural; r : UNSIGNED) return boolean;
+  function "<=" (l : integer; r : SIGNED) return boolean;
+  function "<=" (l : UNSIGNED; r : natural) return boolean;
+  function "<=" (l : SIGNED; r : integer) return boolean;
+  function ">=" (l, r : UNSIGNED) return boolean;
+  function ">=" (l, r : SIGNED) return boolean;
+  function ">=" (l : natural; r : UNSIGNED) return boolean;
+  function ">=" (l : integer; r : SIGNED) return boolean;
+  function ">=" (l : UNSIGNED; r : natural) return boolean;
+  function ">=" (l : SIGNED; r : integer) return boolean;
+  function "=" (l, r : UNSIGNED) return boolean;
+  function "=" (l, r : SIGNED) return boolean;
+  function "=" (l : natural; r : UNSIGNED) return boolean;
+  function "=" (l : integer; r : SIGNED) return boolean;
+  function "=" (l : UNSIGNED; r : natural) return boolean;
+  function "=" (l : SIGNED; r : integer) return boolean;
+  function "/=" (l, r : UNSIGNED) return boolean;
+  function "/=" (l, r : SIGNED) return boolean;
+  function "/=" (l : natural; r : UNSIGNED) return boolean;
+  function "/=" (l : integer; r : SIGNED) return boolean;
+  function "/=" (l : UNSIGNED; r : natural) return boolean;
+  function "/=" (l : SIGNED; r : integer) return boolean;
+
+  function shift_left (arg : UNSIGNED; count : natural) return UNSIGNED;
+  function shift_left (arg : SIGNED; count : natural) return SIGNED;
+  function shift_right (arg : UNSIGNED; count : natural) return UNSIGNED;
+  function shift_right (arg : SIGNED; count : natural) return SIGNED;
+  function rotate_left (arg : UNSIGNED; count : natural) return UNSIGNED;
+  function rotate_left (arg : SIGNED; count : natural) return SIGNED;
+  function rotate_right (arg : UNSIGNED; count : natural) return UNSIGNED;
+  function rotate_right (arg : SIGNED; count : natural) return SIGNED;
+  function "sll" (arg : UNSIGNED; count : integer) return UNSIGNED;
+  function "sll" (arg : SIGNED; count : integer) return SIGNED;
+  function "srl" (arg : UNSIGNED; count : integer) return UNSIGNED;
+  function "srl" (arg : SIGNED; count : integer) return SIGNED;
+  function "rol" (arg : UNSIGNED; count : integer) return UNSIGNED;
+  function "rol" (arg : SIGNED; count : integer) return SIGNED;
+  function "ror" (arg : UNSIGNED; count : integer) return UNSIGNED;
+  function "ror" (arg : SIGNED; count : integer) return SIGNED;
+
+  function resize (arg : SIGNED; new_size : natural) return SIGNED;
+  function resize (arg : UNSIGNED; new_size : natural) return UNSIGNED;
+
+  function to_integer (arg : UNSIGNED) return natural;
+  function to_integer (arg : SIGNED) return integer;
+  function to_unsigned (arg, size : natural) return UNSIGNED;
+  function to_signed (arg : integer; size : natural) return SIGNED;
+
+  function "not" (l : UNSIGNED) return UNSIGNED;
+  function "not" (l : SIGNED) return SIGNED;
+  function "and" (l, r : UNSIGNED) return UNSIGNED;
+  function "and" (l, r : SIGNED) return SIGNED;
+  function "or" (l, r : UNSIGNED) return UNSIGNED;
+  function "or" (l, r : SIGNED) return SIGNED;
+  function "nand" (l, r : UNSIGNED) return UNSIGNED;
+  function "nand" (l, r : SIGNED) return SIGNED;
+  function "nor" (l, r : UNSIGNED) return UNSIGNED;
+  function "nor" (l, r : SIGNED) return SIGNED;
+  function "xor" (l, r : UNSIGNED) return UNSIGNED;
+  function "xor" (l, r : SIGNED) return SIGNED;
+  function "xnor" (l, r : UNSIGNED) return UNSIGNED;
+  function "xnor" (l, r : SIGNED) return SIGNED;
+
+  function std_match (l, r : std_ulogic) return boolean;
+  function std_match (l, r : UNSIGNED) return boolean;
+  function std_match (l, r : SIGNED) return boolean;
+  function std_match (l, r : std_logic_vector) return boolean;
+  function std_match (l, r : std_ulogic_vector) return boolean;
+
+  function to_01 (s : UNSIGNED; xmap : std_logic := '0') return UNSIGNED;
+  function to_01 (s : SIGNED; xmap : std_logic := '0') return SIGNED;
+end package;
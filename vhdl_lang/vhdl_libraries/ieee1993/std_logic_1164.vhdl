@@ -0,0 +1,74 @@
+-- Package std_logic_1164 as defined by IEEE 1164-1993
+-- Only the package declaration is provided since it is sufficient for analysis.
+
+package std_logic_1164 is
+  type std_ulogic is ('U', 'X', '0', '1', 'Z', 'W', 'L', 'H', '-');
+  type std_ulogic_vector is array (natural range <>) of std_ulogic;
+
+  function resolved (s : std_ulogic_vector) return std_ulogic;
+  subtype std_logic is resolved std_ulogic;
+  type std_logic_vector is array (natural range <>) of std_logic;
+
+  subtype X01 is resolved std_ulogic range 'X' to '1';
+  subtype X01Z is resolved std_ulogic range 'X' to 'Z';
+  subtype UX01 is resolved std_ulogic range 'U' to '1';
+  subtype UX01Z is resolved std_ulogic range 'U' to 'Z';
+
+  function "and" (l : std_ulogic; r : std_ulogic) return UX01;
+  function "nand" (l : std_ulogic; r : std_ulogic) return UX01;
+  function "or" (l : std_ulogic; r : std_ulogic) return UX01;
+  function "nor" (l : std_ulogic; r : std_ulogic) return UX01;
+  function "xor" (l : std_ulogic; r : std_ulogic) return UX01;
+  function "xnor" (l : std_ulogic; r : std_ulogic) return UX01;
+  function "not" (l : std_ulogic) return UX01;
+
+  function "and" (l, r : std_logic_vector) return std_logic_vector;
+  function "and" (l, r : std_ulogic_vector) return std_ulogic_vector;
+  function "nand" (l, r : std_logic_vector) return std_logic_vector;
+  function "nand" (l, r : std_ulogic_vector) return std_ulogic_vector;
+  function "or" (l, r : std_logic_vector) return std_logic_vector;
+  function "or" (l, r : std_ulogic_vector) return std_ulogic_vector;
+  function "nor" (l, r : std_logic_vector) return std_logic_vector;
+  function "nor" (l, r : std_ulogic_vector) return std_ulogic_vector;
+  function "xor" (l, r : std_logic_vector) return std_logic_vector;
+  function "xor" (l, r : std_ulogic_vector) return std_ulogic_vector;
+  function "xnor" (l, r : std_logic_vector) return std_logic_vector;
+  function "xnor" (l, r : std_ulogic_vector) return std_ulogic_vector;
+  function "not" (l : std_logic_vector) return std_logic_vector;
+  function "not" (l : std_ulogic_vector) return std_ulogic_vector;
+
+  function To_bit (s : std_ulogic; xmap : bit := '0') return bit;
+  function To_bitvector (s : std_logic_vector; xmap : bit := '0') return bit_vector;
+  function To_bitvector (s : std_ulogic_vector; xmap : bit := '0') return bit_vector;
+  function To_StdULogic (b : bit) return std_ulogic;
+  function To_StdLogicVector (b : bit_vector) return std_logic_vector;
+  function To_StdLogicVector (s : std_ulogic_vector) return std_logic_vector;
+  function To_StdULogicVector (b : bit_vector) return std_ulogic_vector;
+  function To_StdULogicVector (s : std_logic_vector) return std_ulogic_vector;
+
+  function To_X01 (s : std_logic_vector) return std_logic_vector;
+  function To_X01 (s : std_ulogic_vector) return std_ulogic_vector;
+  function To_X01 (s : std_ulogic) return X01;
+  function To_X01 (b : bit_vector) return std_logic_vector;
+  function To_X01 (b : bit_vector) return std_ulogic_vector;
+  function To_X01 (b : bit) return X01;
+  function To_X01Z (s : std_logic_vector) return std_logic_vector;
+  function To_X01Z (s : std_ulogic_vector) return std_ulogic_vector;
+  function To_X01Z (s : std_ulogic) return X01Z;
+  function To_X01Z (b : bit_vector) return std_logic_vector;
+  function To_X01Z (b : bit_vector) return std_ulogic_vector;
+  function To_X01Z (b : bit) return X01Z;
+  function To_UX01 (s : std_logic_vector) return std_logic_vector;
+  function To_UX01 (s : std_ulogic_vector) return std_ulogic_vector;
+  function To_UX01 (s : std_ulogic) return UX01;
+  function To_UX01 (b : bit_vector) return std_logic_vector;
+  function To_UX01 (b : bit_vector) return std_ulogic_vector;
+  function To_UX01 (b : bit) return UX01;
+
+  function rising_edge (signal s : std_ulogic) return boolean;
+  function falling_edge (signal s : std_ulogic) return boolean;
+
+  function Is_X (s : std_ulogic_vector) return boolean;
+  function Is_X (s : std_logic_vector) return boolean;
+  function Is_X (s : std_ulogic) return boolean;
+end package;
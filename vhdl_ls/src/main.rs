@@ -5,6 +5,7 @@
 // Copyright (c) 2018, Olof Kraigher olof.kraigher@gmail.com
 
 use clap::Parser;
+use std::time::Duration;
 use vhdl_ls::VHDLServerSettings;
 
 #[derive(Parser)]
@@ -15,7 +16,7 @@ struct Args {
     no_lint: bool,
 
     /// Path to the config file for the VHDL standard libraries (i.e., IEEE std_logic_1164).
-    /// If omitted, will search for these libraries in a set of standard paths
+    /// If omitted, the libraries that are embedded in the binary are used
     #[arg(short = 'l', long)]
     libraries: Option<String>,
 
@@ -23,6 +24,15 @@ struct Args {
     /// This will silence all window/showMessage and only use window/logMessage
     #[arg(long, default_value_t = false)]
     silent: bool,
+
+    /// Do not infer the library mapping from the workspace contents
+    /// when the workspace root contains no vhdl_ls.toml file
+    #[arg(long, default_value_t = false)]
+    no_library_inference: bool,
+
+    /// Time in milliseconds without further edits before the project is analyzed
+    #[arg(long, default_value_t = 200)]
+    analysis_debounce: u64,
 }
 
 fn main() {
@@ -33,7 +43,9 @@ fn main() {
     vhdl_ls::start(VHDLServerSettings {
         no_lint: args.no_lint,
         silent: args.silent,
+        no_library_inference: args.no_library_inference,
         libraries_path: args.libraries,
+        analysis_debounce: Duration::from_millis(args.analysis_debounce),
         ..Default::default()
     });
 }
@@ -8,10 +8,17 @@
 //! It also contains the main event loop for handling incoming messages from the LSP client and
 //! dispatching them to the appropriate server methods.
 
-use lsp_server::{Connection, ExtractError, Request, RequestId};
-use lsp_types::{notification, request, InitializeParams};
+use lsp_server::{Connection, ExtractError, Message, Request, RequestId};
+use lsp_types::notification::Notification;
+use lsp_types::{
+    notification, request, CancelParams, InitializeParams, MessageActionItem, NumberOrString,
+};
 use serde_json::Value;
 
+use std::any::Any;
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
 use std::{cell::RefCell, rc::Rc};
 
 use crate::rpc_channel::{RpcChannel, SharedRpcChannel};
@@ -23,13 +30,43 @@ pub fn start(settings: VHDLServerSettings) {
     let (connection, io_threads) = Connection::stdio();
     let connection_rpc = Rc::new(ConnectionRpcChannel::new(connection));
     let rpc = SharedRpcChannel::new(connection_rpc.clone());
+    let analysis_debounce = settings.analysis_debounce;
     let mut server = VHDLServer::new_settings(rpc, settings);
     connection_rpc.handle_initialization(&mut server);
-    connection_rpc.main_event_loop(server);
+    connection_rpc.main_event_loop(server, analysis_debounce);
 
     io_threads.join().unwrap();
 }
 
+/// Run a handler of the server, catching any panic such that a bug in a single handler
+/// does not take down the whole language server.
+/// Returns the panic message if the handler panicked.
+fn isolate<T>(
+    server: &mut VHDLServer,
+    handler: impl FnOnce(&mut VHDLServer) -> T,
+) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| handler(server))).map_err(panic_message)
+}
+
+/// The message of a caught panic
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Describe a request or notification by its method and, if any, the document it concerns
+fn incident(method: &str, params: &Value) -> String {
+    match params.pointer("/textDocument/uri").and_then(Value::as_str) {
+        Some(uri) => format!("{method} of {uri}"),
+        None => method.to_string(),
+    }
+}
+
 /// Wrapper for Connection implementing RpcChannel + Clone
 /// and keeping track of outgoing request IDs.
 #[derive(Clone)]
@@ -81,31 +118,172 @@ impl ConnectionRpcChannel {
     }
 
     /// Main event loop handling incoming messages from the client.
-    fn main_event_loop(&self, mut server: VHDLServer) {
+    ///
+    /// Edited sources are analyzed once no message has arrived for the `analysis_debounce`
+    /// duration, such that requests are not queued behind an analysis for every keystroke.
+    /// Until then, requests are served from the last analysis.
+    ///
+    /// The analysis runs on a worker thread, see `analyse_in_background`.
+    fn main_event_loop(&self, mut server: VHDLServer, analysis_debounce: Duration) {
         info!("Language server initialized, waiting for messages ...");
-        while let Ok(message) = self.connection.receiver.recv() {
+        loop {
+            let message = if server.has_pending_sources() {
+                match self.connection.receiver.recv_timeout(analysis_debounce) {
+                    Ok(message) => message,
+                    Err(err) if err.is_timeout() => {
+                        let deferred = self.analyse_in_background(&mut server);
+                        if self.handle_messages(&mut server, deferred).is_break() {
+                            break;
+                        }
+                        continue;
+                    }
+                    Err(_) => break,
+                }
+            } else {
+                match self.connection.receiver.recv() {
+                    Ok(message) => message,
+                    Err(_) => break,
+                }
+            };
+
+            // Handle all messages that have arrived so far together,
+            // such that requests that were cancelled in the meantime can be skipped
+            let mut messages = vec![message];
+            messages.extend(self.connection.receiver.try_iter());
+            if self.handle_messages(&mut server, messages).is_break() {
+                break;
+            }
+        }
+    }
+
+    /// Analyze the edited sources on a worker thread.
+    ///
+    /// The project is moved to the worker for the duration of the analysis and the server
+    /// keeps a snapshot of the last analysis. Requests and edits that arrive in the meantime
+    /// are served from the snapshot, see `is_served_during_analysis`. Other messages are
+    /// deferred and returned once the analysis has finished, except for `$/cancelRequest`,
+    /// which cancels a deferred request right away.
+    fn analyse_in_background(&self, server: &mut VHDLServer) -> Vec<Message> {
+        let files = server.pending_files();
+        let mut project = server.take_project_for_analysis();
+        let (done_sender, done) = crossbeam_channel::bounded(1);
+        let worker = std::thread::spawn(move || {
+            let diagnostics = project.analyse();
+            // The receiver is gone when the server is shutting down
+            let _ = done_sender.send(());
+            (project, diagnostics)
+        });
+
+        let mut deferred = Vec::new();
+        loop {
+            crossbeam_channel::select! {
+                // A panic of the worker disconnects the channel
+                recv(done) -> _ => break,
+                recv(self.connection.receiver) -> message => match message {
+                    Ok(message) if is_served_during_analysis(&message, &deferred) => {
+                        // Does not break since the message is not a shutdown request
+                        let _ = self.handle_messages(server, vec![message]);
+                    }
+                    Ok(message) => self.defer(message, &mut deferred),
+                    Err(_) => break,
+                },
+            }
+        }
+
+        let result = match worker.join() {
+            Ok((project, diagnostics)) => isolate(server, |server| {
+                server.finish_analysis(project, diagnostics)
+            }),
+            Err(payload) => Err(panic_message(payload)),
+        };
+        if let Err(panic_message) = result {
+            let files: Vec<_> = files.iter().map(|f| f.display().to_string()).collect();
+            let incident = format!("the analysis of {}", files.join(", "));
+            error!("Panic during {incident}: {panic_message}");
+            server.recover_from_panic(&incident, &panic_message);
+        }
+        deferred
+    }
+
+    /// Defer a message that arrived during an analysis.
+    /// A `$/cancelRequest` notification cancels the deferred request immediately.
+    fn defer(&self, message: Message, deferred: &mut Vec<Message>) {
+        let cancelled = cancelled_requests(std::slice::from_ref(&message));
+        deferred.retain(|message| match message {
+            Message::Request(request) if cancelled.contains(&request.id) => {
+                self.cancel_request(request.id.clone());
+                false
+            }
+            _ => true,
+        });
+        deferred.push(message);
+    }
+
+    /// Handle messages that arrived together.
+    /// Breaks if the connection can no longer be used.
+    fn handle_messages(&self, server: &mut VHDLServer, messages: Vec<Message>) -> ControlFlow<()> {
+        let cancelled = cancelled_requests(&messages);
+
+        for message in messages {
             trace!("Received message: {:?}", message);
             match message {
-                lsp_server::Message::Request(request) => {
-                    match self.connection.handle_shutdown(&request) {
-                        Ok(shutdown) => {
-                            if shutdown {
-                                server.shutdown_server();
-                            } else {
-                                self.handle_request(&mut server, request)
+                Message::Request(request) if cancelled.contains(&request.id) => {
+                    self.cancel_request(request.id);
+                }
+                Message::Request(request) => match self.connection.handle_shutdown(&request) {
+                    Ok(shutdown) => {
+                        if shutdown {
+                            server.shutdown_server();
+                        } else {
+                            let id = request.id.clone();
+                            let incident = incident(&request.method, &request.params);
+                            if let Err(panic_message) =
+                                isolate(server, |server| self.handle_request(server, request))
+                            {
+                                error!("Panic while handling {incident}: {panic_message}");
+                                self.send_response(lsp_server::Response::new_err(
+                                    id,
+                                    lsp_server::ErrorCode::InternalError as i32,
+                                    panic_message.clone(),
+                                ));
+                                server.recover_from_panic(&incident, &panic_message);
                             }
                         }
-                        Err(err) => panic!("{err:?}"),
+                    }
+                    Err(err) => {
+                        error!("Error while handling shutdown: {err:?}");
+                        return ControlFlow::Break(());
+                    }
+                },
+                Message::Notification(notification) => {
+                    let incident = incident(&notification.method, &notification.params);
+                    if let Err(panic_message) = isolate(server, |server| {
+                        self.handle_notification(server, notification)
+                    }) {
+                        error!("Panic while handling {incident}: {panic_message}");
+                        server.recover_from_panic(&incident, &panic_message);
                     }
                 }
-                lsp_server::Message::Notification(notification) => {
-                    self.handle_notification(&mut server, notification);
-                }
-                lsp_server::Message::Response(response) => {
-                    self.handle_response(&mut server, response)
+                Message::Response(response) => {
+                    if let Err(panic_message) =
+                        isolate(server, |server| self.handle_response(server, response))
+                    {
+                        error!("Panic while handling a response: {panic_message}");
+                        server.recover_from_panic("a response", &panic_message);
+                    }
                 }
             };
         }
+        ControlFlow::Continue(())
+    }
+
+    /// Answer a request that the client has cancelled
+    fn cancel_request(&self, id: RequestId) {
+        self.send_response(lsp_server::Response::new_err(
+            id,
+            lsp_server::ErrorCode::RequestCanceled as i32,
+            "Request was cancelled".to_string(),
+        ));
     }
 
     /// Send responses (to requests sent by the client) back to the client.
@@ -115,118 +293,138 @@ impl ConnectionRpcChannel {
     }
 
     /// Handle incoming requests from the client.
+    /// Requests with malformed params are answered with an `InvalidParams` error.
     fn handle_request(&self, server: &mut VHDLServer, request: lsp_server::Request) {
+        let id = request.id.clone();
+        if let Err(err) = self.dispatch_request(server, request) {
+            self.send_response(lsp_server::Response::new_err(
+                id,
+                lsp_server::ErrorCode::InvalidParams as i32,
+                err.to_string(),
+            ));
+        }
+    }
+
+    fn dispatch_request(
+        &self,
+        server: &mut VHDLServer,
+        request: lsp_server::Request,
+    ) -> Result<(), serde_json::Error> {
+        /// Returns the request if it has another method and an error if its params are malformed
+        #[allow(clippy::type_complexity)]
         fn extract<R>(
             request: lsp_server::Request,
-        ) -> Result<(lsp_server::RequestId, R::Params), lsp_server::Request>
+        ) -> Result<
+            Result<(lsp_server::RequestId, R::Params), lsp_server::Request>,
+            serde_json::Error,
+        >
         where
             R: request::Request,
             R::Params: serde::de::DeserializeOwned,
         {
-            request.extract(R::METHOD).map_err(|e| match e {
-                ExtractError::MethodMismatch(r) => r,
-                err @ ExtractError::JsonError { .. } => {
-                    panic!("{err:?}");
-                }
-            })
+            match request.extract(R::METHOD) {
+                Ok(extracted) => Ok(Ok(extracted)),
+                Err(ExtractError::MethodMismatch(request)) => Ok(Err(request)),
+                Err(ExtractError::JsonError { error, .. }) => Err(error),
+            }
         }
 
         trace!("Handling request: {:?}", request);
-        let request = match extract::<request::GotoDeclaration>(request) {
+        let request = match extract::<request::GotoDeclaration>(request)? {
             Ok((id, params)) => {
                 let result =
                     server.text_document_declaration(&params.text_document_position_params);
                 self.send_response(lsp_server::Response::new_ok(id, result));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::GotoDefinition>(request) {
+        let request = match extract::<request::GotoDefinition>(request)? {
             Ok((id, params)) => {
                 let result = server.text_document_definition(&params.text_document_position_params);
                 self.send_response(lsp_server::Response::new_ok(id, result));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::GotoImplementation>(request) {
+        let request = match extract::<request::GotoImplementation>(request)? {
             Ok((id, params)) => {
                 let result =
                     server.text_document_implementation(&params.text_document_position_params);
                 self.send_response(lsp_server::Response::new_ok(id, result));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::Rename>(request) {
+        let request = match extract::<request::Rename>(request)? {
             Ok((id, params)) => {
                 let result = server.rename(&params);
                 self.send_response(lsp_server::Response::new_ok(id, result));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::PrepareRenameRequest>(request) {
+        let request = match extract::<request::PrepareRenameRequest>(request)? {
             Ok((id, params)) => {
                 let result = server.prepare_rename(&params);
                 self.send_response(lsp_server::Response::new_ok(id, result));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::WorkspaceSymbolRequest>(request) {
+        let request = match extract::<request::WorkspaceSymbolRequest>(request)? {
             Ok((id, params)) => {
                 let result = server.workspace_symbol(&params);
                 self.send_response(lsp_server::Response::new_ok(id, result));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::DocumentSymbolRequest>(request) {
+        let request = match extract::<request::DocumentSymbolRequest>(request)? {
             Ok((id, params)) => {
                 let result = server.document_symbol(&params);
                 self.send_response(lsp_server::Response::new_ok(id, result));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::DocumentHighlightRequest>(request) {
+        let request = match extract::<request::DocumentHighlightRequest>(request)? {
             Ok((id, params)) => {
                 let result = server.document_highlight(&params.text_document_position_params);
                 self.send_response(lsp_server::Response::new_ok(id, result));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::HoverRequest>(request) {
+        let request = match extract::<request::HoverRequest>(request)? {
             Ok((id, params)) => {
                 let result = server.text_document_hover(&params.text_document_position_params);
                 self.send_response(lsp_server::Response::new_ok(id, result));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::References>(request) {
+        let request = match extract::<request::References>(request)? {
             Ok((id, params)) => {
                 let result = server.text_document_references(&params);
                 self.send_response(lsp_server::Response::new_ok(id, result));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::Completion>(request) {
+        let request = match extract::<request::Completion>(request)? {
             Ok((id, params)) => {
                 let res = server.request_completion(&params);
                 self.send_response(lsp_server::Response::new_ok(id, res));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
-        let request = match extract::<request::ResolveCompletionItem>(request) {
+        let request = match extract::<request::ResolveCompletionItem>(request)? {
             Ok((id, params)) => {
                 let res = server.resolve_completion_item(&params);
                 self.send_response(lsp_server::Response::new_ok(id, res));
-                return;
+                return Ok(());
             }
             Err(request) => request,
         };
@@ -237,56 +435,398 @@ impl ConnectionRpcChannel {
             lsp_server::ErrorCode::MethodNotFound as i32,
             "Unknown request".to_string(),
         ));
+        Ok(())
     }
 
     /// Handle incoming notifications from the client.
+    /// Notifications with malformed params are logged and ignored.
     fn handle_notification(&self, server: &mut VHDLServer, notification: lsp_server::Notification) {
+        let method = notification.method.clone();
+        if let Err(err) = self.dispatch_notification(server, notification) {
+            error!("Ignoring notification {method} with invalid params: {err}");
+        }
+    }
+
+    fn dispatch_notification(
+        &self,
+        server: &mut VHDLServer,
+        notification: lsp_server::Notification,
+    ) -> Result<(), serde_json::Error> {
+        /// Returns the notification if it has another method and an error if its params are
+        /// malformed
         fn extract<N>(
             notification: lsp_server::Notification,
-        ) -> Result<N::Params, lsp_server::Notification>
+        ) -> Result<Result<N::Params, lsp_server::Notification>, serde_json::Error>
         where
             N: notification::Notification,
             N::Params: serde::de::DeserializeOwned,
         {
-            notification.extract(N::METHOD).map_err(|e| match e {
-                ExtractError::MethodMismatch(n) => n,
-                err @ ExtractError::JsonError { .. } => {
-                    panic!("{err:?}");
-                }
-            })
+            match notification.extract(N::METHOD) {
+                Ok(params) => Ok(Ok(params)),
+                Err(ExtractError::MethodMismatch(notification)) => Ok(Err(notification)),
+                Err(ExtractError::JsonError { error, .. }) => Err(error),
+            }
         }
 
         trace!("Handling notification: {:?}", notification);
         // textDocument/didChange
-        let notification = match extract::<notification::DidChangeTextDocument>(notification) {
-            Ok(params) => return server.text_document_did_change_notification(&params),
+        let notification = match extract::<notification::DidChangeTextDocument>(notification)? {
+            Ok(params) => {
+                server.text_document_did_change_notification(&params);
+                return Ok(());
+            }
             Err(notification) => notification,
         };
         // textDocument/didOpen
-        let notification = match extract::<notification::DidOpenTextDocument>(notification) {
-            Ok(params) => return server.text_document_did_open_notification(&params),
+        let notification = match extract::<notification::DidOpenTextDocument>(notification)? {
+            Ok(params) => {
+                server.text_document_did_open_notification(&params);
+                return Ok(());
+            }
+            Err(notification) => notification,
+        };
+        // textDocument/didClose
+        let notification = match extract::<notification::DidCloseTextDocument>(notification)? {
+            Ok(params) => {
+                server.text_document_did_close_notification(&params);
+                return Ok(());
+            }
+            Err(notification) => notification,
+        };
+        // textDocument/didSave
+        let notification = match extract::<notification::DidSaveTextDocument>(notification)? {
+            Ok(params) => {
+                server.text_document_did_save_notification(&params);
+                return Ok(());
+            }
+            Err(notification) => notification,
+        };
+        // workspace/didChangeConfiguration
+        let notification = match extract::<notification::DidChangeConfiguration>(notification)? {
+            Ok(params) => {
+                server.workspace_did_change_configuration(&params);
+                return Ok(());
+            }
+            Err(notification) => notification,
+        };
+        // workspace/didChangeWorkspaceFolders
+        let notification = match extract::<notification::DidChangeWorkspaceFolders>(notification)? {
+            Ok(params) => {
+                server.workspace_did_change_workspace_folders(&params);
+                return Ok(());
+            }
             Err(notification) => notification,
         };
         // workspace.didChangeWatchedFiles
-        let notification = match extract::<notification::DidChangeWatchedFiles>(notification) {
-            Ok(params) => return server.workspace_did_change_watched_files(&params),
+        let notification = match extract::<notification::DidChangeWatchedFiles>(notification)? {
+            Ok(params) => {
+                server.workspace_did_change_watched_files(&params);
+                return Ok(());
+            }
             Err(notification) => notification,
         };
         // exit
-        let notification = match extract::<notification::Exit>(notification) {
-            Ok(_params) => return server.exit_notification(),
+        let notification = match extract::<notification::Exit>(notification)? {
+            Ok(_params) => {
+                server.exit_notification();
+                return Ok(());
+            }
             Err(notification) => notification,
         };
 
         if !notification.method.starts_with("$/") {
             debug!("Unhandled notification: {:?}", notification);
         }
+        Ok(())
     }
 
     /// Handle incoming responses (to requests sent by us) from the client.
-    fn handle_response(&self, _server: &mut VHDLServer, response: lsp_server::Response) {
+    fn handle_response(&self, server: &mut VHDLServer, response: lsp_server::Response) {
         trace!("Handling response: {:?}", response);
-        // We currently can ignore incoming responses as the implemented
-        // outgoing requests do not require confirmation by the client.
+        // The only responses with content are the settings of a workspace/configuration request
+        // and the selected action of a window/showMessageRequest,
+        // responses to other outgoing requests do not require handling.
+        if let Some(result) = response.result {
+            if let serde_json::Value::Array(items) = &result {
+                return server.configuration_response(items);
+            }
+            if let Ok(Some(action)) = serde_json::from_value::<Option<MessageActionItem>>(result) {
+                server.message_action_response(&action);
+            }
+        }
+    }
+}
+
+/// Returns true if a message that arrives during an analysis can be handled right away with the
+/// snapshot of the last analysis, i.e., if it is a request other than shutdown or an edit of
+/// a document. Messages after a deferred message are deferred as well to keep their order.
+fn is_served_during_analysis(message: &Message, deferred: &[Message]) -> bool {
+    let is_cancel = |message: &Message| {
+        matches!(message, Message::Notification(notification)
+            if notification.method == notification::Cancel::METHOD)
+    };
+    if !deferred.iter().all(is_cancel) {
+        return false;
+    }
+    match message {
+        Message::Request(request) => {
+            request.method != <request::Shutdown as request::Request>::METHOD
+        }
+        Message::Notification(notification) => {
+            notification.method == notification::DidChangeTextDocument::METHOD
+        }
+        Message::Response(_) => false,
+    }
+}
+
+/// Returns the ids of the requests that are cancelled by `$/cancelRequest` notifications
+fn cancelled_requests(messages: &[Message]) -> Vec<RequestId> {
+    messages
+        .iter()
+        .filter_map(|message| match message {
+            Message::Notification(notification)
+                if notification.method == notification::Cancel::METHOD =>
+            {
+                serde_json::from_value::<CancelParams>(notification.params.clone()).ok()
+            }
+            _ => None,
+        })
+        .map(|params| match params.id {
+            NumberOrString::Number(id) => RequestId::from(id),
+            NumberOrString::String(id) => RequestId::from(id),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vhdl_server::VHDLServerSettings;
+    use lsp_types::request::Request as _;
+    use lsp_types::{
+        HoverParams, Position, TextDocumentIdentifier, TextDocumentPositionParams, Url,
+        WorkDoneProgressParams,
+    };
+
+    fn hover_request(id: i32) -> Message {
+        let params = HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: Url::from_file_path(std::env::temp_dir().join("missing.vhd")).unwrap(),
+                },
+                position: Position::new(0, 0),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        };
+        Request::new(
+            RequestId::from(id),
+            request::HoverRequest::METHOD.to_owned(),
+            params,
+        )
+        .into()
+    }
+
+    #[test]
+    fn cancelled_requests_are_not_handled() {
+        let (connection, client) = Connection::memory();
+        let connection_rpc = ConnectionRpcChannel::new(connection);
+        let server = VHDLServer::new_settings(
+            SharedRpcChannel::new(Rc::new(connection_rpc.clone())),
+            VHDLServerSettings::default(),
+        );
+
+        client.sender.send(hover_request(1)).unwrap();
+        client
+            .sender
+            .send(
+                lsp_server::Notification::new(
+                    notification::Cancel::METHOD.to_owned(),
+                    CancelParams {
+                        id: NumberOrString::Number(1),
+                    },
+                )
+                .into(),
+            )
+            .unwrap();
+        client.sender.send(hover_request(2)).unwrap();
+        drop(client.sender);
+
+        connection_rpc.main_event_loop(server, Duration::ZERO);
+
+        let responses: Vec<_> = client
+            .receiver
+            .try_iter()
+            .map(|message| match message {
+                Message::Response(response) => (
+                    response.id,
+                    response.error.map(|err| err.code),
+                    response.result,
+                ),
+                message => panic!("Unexpected message {message:?}"),
+            })
+            .collect();
+        assert_eq!(
+            responses,
+            vec![
+                (
+                    RequestId::from(1),
+                    Some(lsp_server::ErrorCode::RequestCanceled as i32),
+                    None
+                ),
+                (RequestId::from(2), None, Some(Value::Null)),
+            ]
+        );
+    }
+
+    #[test]
+    fn requests_deferred_during_analysis_are_cancelled_immediately() {
+        let (connection, client) = Connection::memory();
+        let connection_rpc = ConnectionRpcChannel::new(connection);
+        let cancel: Message = lsp_server::Notification::new(
+            notification::Cancel::METHOD.to_owned(),
+            CancelParams {
+                id: NumberOrString::Number(1),
+            },
+        )
+        .into();
+
+        let mut deferred = Vec::new();
+        connection_rpc.defer(hover_request(1), &mut deferred);
+        connection_rpc.defer(hover_request(2), &mut deferred);
+        assert!(client.receiver.try_recv().is_err());
+        connection_rpc.defer(cancel, &mut deferred);
+
+        let Ok(Message::Response(response)) = client.receiver.try_recv() else {
+            panic!("Expected a response");
+        };
+        assert_eq!(response.id, RequestId::from(1));
+        assert_eq!(
+            response.error.map(|err| err.code),
+            Some(lsp_server::ErrorCode::RequestCanceled as i32)
+        );
+        assert!(matches!(
+            deferred.as_slice(),
+            [Message::Request(request), Message::Notification(_)] if request.id == RequestId::from(2)
+        ));
+    }
+
+    #[test]
+    fn requests_and_edits_are_served_during_analysis() {
+        let notification = |method: &str| -> Message {
+            lsp_server::Notification::new(method.to_owned(), Value::Null).into()
+        };
+        let shutdown: Message = Request::new(
+            RequestId::from(3),
+            request::Shutdown::METHOD.to_owned(),
+            Value::Null,
+        )
+        .into();
+        let did_change = notification(notification::DidChangeTextDocument::METHOD);
+        let did_open = notification(notification::DidOpenTextDocument::METHOD);
+        let cancel = notification(notification::Cancel::METHOD);
+
+        assert!(is_served_during_analysis(&hover_request(1), &[]));
+        assert!(is_served_during_analysis(&did_change, &[]));
+        assert!(!is_served_during_analysis(&shutdown, &[]));
+        assert!(!is_served_during_analysis(&did_open, &[]));
+
+        // The order of the messages is kept
+        assert!(is_served_during_analysis(&hover_request(1), &[cancel]));
+        assert!(!is_served_during_analysis(&hover_request(1), &[did_open]));
+    }
+
+    #[test]
+    fn malformed_params_do_not_stop_the_server() {
+        let (connection, client) = Connection::memory();
+        let connection_rpc = ConnectionRpcChannel::new(connection);
+        let server = VHDLServer::new_settings(
+            SharedRpcChannel::new(Rc::new(connection_rpc.clone())),
+            VHDLServerSettings::default(),
+        );
+
+        client
+            .sender
+            .send(
+                lsp_server::Notification::new(
+                    notification::DidOpenTextDocument::METHOD.to_owned(),
+                    serde_json::json!({ "textDocument": 1 }),
+                )
+                .into(),
+            )
+            .unwrap();
+        client
+            .sender
+            .send(
+                Request::new(
+                    RequestId::from(1),
+                    request::HoverRequest::METHOD.to_owned(),
+                    serde_json::json!({ "textDocument": 1 }),
+                )
+                .into(),
+            )
+            .unwrap();
+        client.sender.send(hover_request(2)).unwrap();
+        drop(client.sender);
+
+        connection_rpc.main_event_loop(server, Duration::ZERO);
+
+        let responses: Vec<_> = client
+            .receiver
+            .try_iter()
+            .map(|message| match message {
+                Message::Response(response) => (
+                    response.id,
+                    response.error.map(|err| err.code),
+                    response.result,
+                ),
+                message => panic!("Unexpected message {message:?}"),
+            })
+            .collect();
+        assert_eq!(
+            responses,
+            vec![
+                (
+                    RequestId::from(1),
+                    Some(lsp_server::ErrorCode::InvalidParams as i32),
+                    None
+                ),
+                (RequestId::from(2), None, Some(Value::Null)),
+            ]
+        );
+    }
+
+    #[test]
+    fn panics_of_handlers_are_caught() {
+        let (connection, _client) = Connection::memory();
+        let mut server = VHDLServer::new_settings(
+            SharedRpcChannel::new(Rc::new(ConnectionRpcChannel::new(connection))),
+            VHDLServerSettings::default(),
+        );
+
+        assert_eq!(isolate(&mut server, |_| 1), Ok(1));
+        assert_eq!(
+            isolate(&mut server, |_| -> () { panic!("analysis failed") }),
+            Err("analysis failed".to_string())
+        );
+        let value = 2;
+        assert_eq!(
+            isolate(&mut server, |_| -> () { panic!("value is {value}") }),
+            Err("value is 2".to_string())
+        );
+    }
+
+    #[test]
+    fn incidents_name_the_document() {
+        assert_eq!(
+            incident(
+                request::HoverRequest::METHOD,
+                &serde_json::json!({ "textDocument": { "uri": "file:///ent.vhd" } })
+            ),
+            "textDocument/hover of file:///ent.vhd"
+        );
+        assert_eq!(
+            incident(request::WorkspaceSymbolRequest::METHOD, &Value::Null),
+            "workspace/symbol"
+        );
     }
 }
@@ -7,23 +7,27 @@
 mod completion;
 mod diagnostics;
 mod lifecycle;
+mod progress;
 mod rename;
+mod settings;
 mod text_document;
 mod workspace;
 
 use lsp_types::*;
 
-use fnv::FnvHashMap;
+use fnv::{FnvHashMap, FnvHashSet};
 use vhdl_lang::ast::ObjectClass;
 
 use crate::rpc_channel::SharedRpcChannel;
+use crate::vhdl_server::settings::EditorSettings;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use std::io;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use vhdl_lang::{
     AnyEntKind, Concurrent, Config, EntHierarchy, EntRef, Message, MessageHandler, Object,
-    Overloaded, Project, SeverityMap, SrcPos, Token, Type, VHDLStandard,
+    Overloaded, Project, Psl, SeverityMap, Source, SrcPos, Token, Type, VHDLStandard,
 };
 
 /// Defines how the language server handles files
@@ -52,21 +56,40 @@ impl NonProjectFileHandling {
 pub struct VHDLServerSettings {
     pub no_lint: bool,
     pub silent: bool,
+    pub no_library_inference: bool,
     pub non_project_file_handling: NonProjectFileHandling,
     pub libraries_path: Option<String>,
+    /// Time without incoming messages after an edit before the project is analyzed
+    pub analysis_debounce: Duration,
 }
 
 pub struct VHDLServer {
     rpc: SharedRpcChannel,
     settings: VHDLServerSettings,
+    // Settings of the editor that override the settings of the command line
+    editor_settings: EditorSettings,
     // To have well defined unit tests that are not affected by environment
     use_external_config: bool,
     project: Project,
+    // Edited sources that are not yet parsed and analyzed
+    pending_sources: FnvHashMap<PathBuf, Source>,
+    // Files that are open in the editor, their contents are owned by the editor
+    open_files: FnvHashSet<PathBuf>,
+    // Open files that are not part of the project and are removed when closed
+    non_project_files: FnvHashSet<PathBuf>,
     diagnostic_cache: FnvHashMap<Url, Vec<vhdl_lang::Diagnostic>>,
     init_params: Option<InitializeParams>,
-    config_file: Option<PathBuf>,
+    // The root folders of the workspace, each with its own configuration file
+    workspace_folders: Vec<PathBuf>,
+    config_files: Vec<PathBuf>,
+    // Imported files that the client has been asked to watch
+    watched_files: Vec<PathBuf>,
+    // Library mappings inferred from the workspace folders without a config file
+    inferred_configs: Vec<(PathBuf, Config)>,
     severity_map: SeverityMap,
     string_matcher: SkimMatcherV2,
+    // Number of work done progress tokens created so far
+    progress_tokens: usize,
 }
 
 impl VHDLServer {
@@ -74,13 +97,21 @@ impl VHDLServer {
         VHDLServer {
             rpc,
             settings,
+            editor_settings: EditorSettings::default(),
             use_external_config: true,
             project: Project::new(VHDLStandard::default()),
+            pending_sources: FnvHashMap::default(),
+            open_files: FnvHashSet::default(),
+            non_project_files: FnvHashSet::default(),
             diagnostic_cache: FnvHashMap::default(),
             init_params: None,
-            config_file: None,
+            workspace_folders: Vec::new(),
+            config_files: Vec::new(),
+            watched_files: Vec::new(),
+            inferred_configs: Vec::new(),
             severity_map: SeverityMap::default(),
             string_matcher: SkimMatcherV2::default().use_cache(true).ignore_case(),
+            progress_tokens: 0,
         }
     }
 
@@ -89,24 +120,26 @@ impl VHDLServer {
         VHDLServer {
             rpc,
             settings: Default::default(),
+            editor_settings: EditorSettings::default(),
             use_external_config,
             project: Project::new(VHDLStandard::default()),
+            pending_sources: FnvHashMap::default(),
+            open_files: FnvHashSet::default(),
+            non_project_files: FnvHashSet::default(),
             diagnostic_cache: Default::default(),
             init_params: None,
-            config_file: None,
+            workspace_folders: Vec::new(),
+            config_files: Vec::new(),
+            watched_files: Vec::new(),
+            inferred_configs: Vec::new(),
             severity_map: SeverityMap::default(),
             string_matcher: SkimMatcherV2::default(),
+            progress_tokens: 0,
         }
     }
 
-    /// Load the workspace root configuration file
-    fn load_root_uri_config(&self) -> io::Result<Config> {
-        let config_file = self.config_file.as_ref().ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                "Workspace root configuration file not set",
-            )
-        })?;
+    /// Load the configuration file of a workspace folder
+    fn load_root_uri_config(&self, config_file: &Path) -> io::Result<Config> {
         let config = Config::read_file_path(config_file)?;
 
         // Log which file was loaded
@@ -119,45 +152,120 @@ impl VHDLServer {
     }
 
     /// Load the configuration or use a default configuration if unsuccessful
+    /// The configurations of all workspace folders are merged into one project.
     /// Log info/error messages to the client
-    fn load_config(&self) -> Config {
+    fn load_config(&mut self) -> Config {
         let mut config = Config::default();
+        self.inferred_configs.clear();
 
         if self.use_external_config {
-            config.load_external_config(
-                &mut self.message_filter(),
-                self.settings.libraries_path.clone(),
-            );
+            config.load_external_config(&mut self.message_filter(), self.libraries_path());
         }
 
-        match self.load_root_uri_config() {
-            Ok(root_config) => {
-                config.append(&root_config, &mut self.message_filter());
-            }
-            Err(ref err) => {
-                if matches!(err.kind(), ErrorKind::NotFound) {
-                    self.message(Message::error(format!(
-                        "Library mapping is unknown due to missing vhdl_ls.toml config file in the workspace root path: {err}"
-                    )));
-                    self.message(Message::warning(
-                        "Without library mapping semantic analysis might be incorrect",
-                    ));
-                } else {
-                    self.message(Message::error(format!("Error loading vhdl_ls.toml: {err}")));
-                }
+        if self.config_files.is_empty() {
+            self.missing_config_messages("Workspace root configuration file not set");
+        }
+
+        // The first workspace folder replaces the external config, later folders are merged
+        // such that folders that define the same library do not replace each other
+        let mut is_first_folder = true;
+        let mut messages = self.message_filter();
+        let mut append = |config: &mut Config, folder_config: &Config| {
+            if std::mem::take(&mut is_first_folder) {
+                config.append(folder_config, &mut messages);
+            } else {
+                config.append_workspace_folder(folder_config, &mut messages);
             }
         };
+        for config_file in self.config_files.clone() {
+            match self.load_root_uri_config(&config_file) {
+                Ok(root_config) => {
+                    append(&mut config, &root_config);
+                }
+                Err(ref err) => {
+                    if matches!(err.kind(), ErrorKind::NotFound) {
+                        if let Some(inferred_config) = self.infer_config(&config, &config_file) {
+                            append(&mut config, &inferred_config);
+                            self.inferred_configs.push((config_file, inferred_config));
+                            continue;
+                        }
+                        self.missing_config_messages(err);
+                    } else {
+                        self.message(Message::error(format!("Error loading vhdl_ls.toml: {err}")));
+                    }
+                }
+            };
+        }
 
         config
     }
 
-    /// Extract path of workspace root configuration file from InitializeParams
-    fn root_uri_config_file(&self, params: &InitializeParams) -> Option<PathBuf> {
+    fn missing_config_messages(&self, err: impl std::fmt::Display) {
+        self.message(Message::error(format!(
+            "Library mapping is unknown due to missing vhdl_ls.toml config file in the workspace root path: {err}"
+        )));
+        self.message(Message::warning(
+            "Without library mapping semantic analysis might be incorrect",
+        ));
+    }
+
+    /// Infer the library mapping from the VHDL files of the folder of a missing config file
+    /// Returns `None` if inference is disabled or the folder contains no VHDL files
+    fn infer_config(&self, config: &Config, config_file: &Path) -> Option<Config> {
+        if self.settings.no_library_inference {
+            return None;
+        }
+        let root = config_file.parent()?;
+        let inferred_config = config.infer_from_directory(root);
+        let mut library_names: Vec<&str> = inferred_config
+            .iter_libraries()
+            .map(|library| library.name())
+            .collect();
+        if library_names.is_empty() {
+            return None;
+        }
+        library_names.sort();
+        let library_names = library_names.join(", ");
+        self.message(Message::log(format!(
+            "No vhdl_ls.toml found, inferred the libraries {library_names} from the files in {}",
+            root.to_string_lossy()
+        )));
+        Some(inferred_config)
+    }
+
+    /// Write the inferred library mappings to the configuration files of the workspace folders
+    fn write_inferred_config(&mut self) {
+        for (config_file, inferred_config) in std::mem::take(&mut self.inferred_configs) {
+            let parent = config_file.parent().unwrap_or(Path::new(""));
+            match std::fs::write(&config_file, inferred_config.to_toml_string(parent)) {
+                Ok(()) => self.message(Message::info(format!(
+                    "Wrote inferred library mapping to {}",
+                    config_file.to_string_lossy()
+                ))),
+                Err(err) => self.message(Message::error(format!(
+                    "Could not write {}: {err}",
+                    config_file.to_string_lossy()
+                ))),
+            }
+        }
+    }
+
+    /// Extract the paths of the workspace folders from InitializeParams.
+    /// Clients that do not support workspace folders only send the root uri.
+    fn root_uri_folders(&self, params: &InitializeParams) -> Vec<PathBuf> {
+        if let Some(workspace_folders) = &params.workspace_folders {
+            if !workspace_folders.is_empty() {
+                return workspace_folders
+                    .iter()
+                    .filter_map(|folder| self.workspace_folder_path(folder))
+                    .collect();
+            }
+        }
+
         #[allow(deprecated)]
         match params.root_uri.clone() {
             Some(root_uri) => root_uri
                 .to_file_path()
-                .map(|root_path| root_path.join("vhdl_ls.toml"))
                 .map_err(|_| {
                     self.message(Message::error(format!(
                         "{} {} {:?} ",
@@ -166,16 +274,41 @@ impl VHDLServer {
                         root_uri,
                     )))
                 })
-                .ok(),
+                .into_iter()
+                .collect(),
             None => {
                 self.message(Message::error(
                     "Cannot load workspace: Initialize request is missing rootUri parameter.",
                 ));
-                None
+                Vec::new()
             }
         }
     }
 
+    fn workspace_folder_path(&self, folder: &WorkspaceFolder) -> Option<PathBuf> {
+        folder
+            .uri
+            .to_file_path()
+            .map_err(|_| {
+                self.message(Message::error(format!(
+                    "Cannot load workspace folder {}: {:?} is not a valid file path",
+                    folder.name, folder.uri,
+                )))
+            })
+            .ok()
+    }
+
+    /// The configuration file of each workspace folder
+    fn update_config_files(&mut self) {
+        // The config file of the editor settings is relative to the workspace folder
+        let config_file = self.editor_settings.config_file.as_deref();
+        self.config_files = self
+            .workspace_folders
+            .iter()
+            .map(|folder| folder.join(config_file.unwrap_or("vhdl_ls.toml")))
+            .collect();
+    }
+
     fn client_supports_related_information(&self) -> bool {
         let try_fun = || {
             self.init_params
@@ -483,6 +616,8 @@ fn to_symbol_kind(kind: &AnyEntKind) -> SymbolKind {
         AnyEntKind::Concurrent(_) => SymbolKind::NAMESPACE,
         AnyEntKind::Library => SymbolKind::NAMESPACE,
         AnyEntKind::View(_) => SymbolKind::INTERFACE,
+        AnyEntKind::Psl(Psl::Parameter) => SymbolKind::TYPE_PARAMETER,
+        AnyEntKind::Psl(_) => SymbolKind::PROPERTY,
         AnyEntKind::Design(d) => match d {
             vhdl_lang::Design::Entity(_, _) => SymbolKind::MODULE,
             vhdl_lang::Design::Architecture(..) => SymbolKind::MODULE,
@@ -569,6 +704,45 @@ mod tests {
         initialize_server(&mut server, root_uri);
     }
 
+    #[test]
+    fn initialize_with_inferred_libraries() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let root = root_uri.to_file_path().unwrap();
+        std::fs::create_dir(root.join("mylib")).unwrap();
+        write_file(&root_uri, "mylib/pkg.vhd", "package pkg is\nend package;\n");
+        write_file(
+            &root_uri,
+            "ent.vhd",
+            "library mylib;\nuse mylib.pkg.all;\nentity ent is\nend entity;\n",
+        );
+
+        mock.expect_message_contains(
+            "No vhdl_ls.toml found, inferred the libraries defaultlib, mylib",
+        );
+        mock.expect_request(
+            "window/showMessageRequest",
+            ShowMessageRequestParams {
+                typ: MessageType::INFO,
+                message: "The library mapping was inferred from the workspace since no vhdl_ls.toml file was found".to_owned(),
+                actions: Some(vec![MessageActionItem {
+                    title: "Write vhdl_ls.toml".to_owned(),
+                    properties: Default::default(),
+                }]),
+            },
+        );
+        initialize_server(&mut server, root_uri.clone());
+
+        mock.expect_message_contains("Wrote inferred library mapping to");
+        server.message_action_response(&MessageActionItem {
+            title: "Write vhdl_ls.toml".to_owned(),
+            properties: Default::default(),
+        });
+        let config = Config::read_file_path(&root.join("vhdl_ls.toml")).unwrap();
+        assert!(config.get_library("mylib").is_some());
+        assert!(config.get_library("defaultlib").is_some());
+    }
+
     #[test]
     fn did_open_no_diagnostics() {
         let (mock, mut server) = setup_server();
@@ -647,6 +821,7 @@ end entity ent2;
 
         mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics);
         server.text_document_did_open_notification(&did_open);
+        server.analyse_pending_sources();
 
         let code = "
 entity ent is
@@ -674,6 +849,117 @@ end entity ent;
 
         mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics);
         server.text_document_did_change_notification(&did_change);
+        server.analyse_pending_sources();
+    }
+
+    /// The diagnostics of a single line entity whose end identifier does not match its name
+    fn end_identifier_mismatch(file_uri: Url, code: &str, name: &str) -> PublishDiagnosticsParams {
+        let end = code.rfind(';').unwrap();
+        let start = code[..end].rfind(' ').unwrap() + 1;
+        PublishDiagnosticsParams {
+            uri: file_uri,
+            diagnostics: vec![lsp_types::Diagnostic {
+                range: Range {
+                    start: lsp_types::Position {
+                        line: 0,
+                        character: start as u32,
+                    },
+                    end: lsp_types::Position {
+                        line: 0,
+                        character: end as u32,
+                    },
+                },
+                code: Some(NumberOrString::String("syntax_error".to_owned())),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("vhdl ls".to_owned()),
+                message: format!("End identifier mismatch, expected {name}"),
+                ..Default::default()
+            }],
+            version: None,
+        }
+    }
+
+    fn no_diagnostics(file_uri: Url) -> PublishDiagnosticsParams {
+        PublishDiagnosticsParams {
+            uri: file_uri,
+            diagnostics: vec![],
+            version: None,
+        }
+    }
+
+    #[test]
+    fn did_close_reverts_to_contents_on_disk() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file_uri = write_file(&root_uri, "ent.vhd", "entity ent is end entity ent;");
+        let config_uri = write_config(&root_uri, "[libraries]\nlib.files = ['*.vhd']");
+        expect_loaded_config_messages(&mock, &config_uri);
+        initialize_server(&mut server, root_uri);
+
+        let unsaved = "entity ent is end entity ent2;";
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            end_identifier_mismatch(file_uri.clone(), unsaved, "ent"),
+        );
+        server.text_document_did_open_notification(&DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: file_uri.clone(),
+                language_id: "vhdl".to_owned(),
+                version: 0,
+                text: unsaved.to_owned(),
+            },
+        });
+        server.analyse_pending_sources();
+
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            no_diagnostics(file_uri.clone()),
+        );
+        server.text_document_did_close_notification(&DidCloseTextDocumentParams {
+            text_document: TextDocumentIdentifier { uri: file_uri },
+        });
+        server.analyse_pending_sources();
+    }
+
+    #[test]
+    fn watched_files_are_added_reloaded_and_removed() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        write_file(&root_uri, "ent.vhd", "entity ent is end entity ent;");
+        let config_uri = write_config(&root_uri, "[libraries]\nlib.files = ['*.vhd']");
+        expect_loaded_config_messages(&mock, &config_uri);
+        initialize_server(&mut server, root_uri.clone());
+
+        let file_name = root_uri.to_file_path().unwrap().join("ent2.vhd");
+        let watched_file_changed = |typ| DidChangeWatchedFilesParams {
+            changes: vec![FileEvent {
+                uri: Url::from_file_path(&file_name).unwrap(),
+                typ,
+            }],
+        };
+
+        // A file that is created after startup is added to the library that matches it
+        let code = "entity ent2 is end entity ent;";
+        let file_uri = write_file(&root_uri, "ent2.vhd", code);
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            end_identifier_mismatch(file_uri.clone(), code, "ent2"),
+        );
+        server.workspace_did_change_watched_files(&watched_file_changed(FileChangeType::CREATED));
+        assert!(server.project.get_source(&file_name).is_some());
+
+        write_file(&root_uri, "ent2.vhd", "entity ent2 is end entity ent2;");
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            no_diagnostics(file_uri.clone()),
+        );
+        server.workspace_did_change_watched_files(&watched_file_changed(FileChangeType::CHANGED));
+
+        // The diagnostics of a deleted file are cleared
+        std::fs::remove_file(&file_name).unwrap();
+        mock.expect_notification("textDocument/publishDiagnostics", no_diagnostics(file_uri));
+        server.workspace_did_change_watched_files(&watched_file_changed(FileChangeType::DELETED));
+        assert!(server.project.get_source(&file_name).is_none());
     }
 
     pub(crate) fn write_file(
@@ -811,7 +1097,7 @@ end package;
                 "
 [libraries]
 std.files = [
-'{}/../vhdl_libraries/std/*.vhd',
+'{}/../vhdl_lang/vhdl_libraries/std/*.vhd',
 ]
 lib.files = [
   '*.vhd'
@@ -860,6 +1146,66 @@ lib.files = [
         assert_eq!(response, Some(expected));
     }
 
+    #[test]
+    fn text_document_hover_shows_comments_and_interface() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+
+        let file_url = write_file(
+            &root_uri,
+            "ent.vhd",
+            "\
+-- A register
+entity ent is
+  generic (width : natural := 8);
+  port (d : in bit_vector(width - 1 downto 0));
+end entity;
+
+architecture a of ent is
+begin
+  assert d(0) = '0';
+end architecture;
+",
+        );
+        let config_uri = write_config(
+            &root_uri,
+            format!(
+                "
+[libraries]
+std.files = [
+'{}/../vhdl_lang/vhdl_libraries/std/*.vhd',
+]
+lib.files = [
+  '*.vhd'
+]
+",
+                std::env::var("CARGO_MANIFEST_DIR").unwrap()
+            ),
+        );
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        initialize_server(&mut server, root_uri);
+
+        let hover = server.text_document_hover(&TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: file_url },
+            position: lsp_types::Position {
+                line: 1,
+                character: "entity e".len() as u32,
+            },
+        });
+        let Some(Hover {
+            contents: HoverContents::Markup(MarkupContent { value, .. }),
+            ..
+        }) = hover
+        else {
+            panic!("Expected markdown hover, got {hover:?}");
+        };
+        assert!(value.starts_with("```vhdl\nentity ent is"));
+        assert!(value.contains("```\n\nA register\n\n**Generics**"));
+        assert!(value.contains("| `width` |  | `natural` | `8` |"));
+        assert!(value.contains("| `d` | in | `bit_vector(width - 1 downto 0)` |  |"));
+    }
+
     #[test]
     fn client_register_capability() {
         let (mock, mut server) = setup_server();
@@ -873,10 +1219,16 @@ lib.files = [
         );
 
         let register_options = DidChangeWatchedFilesRegistrationOptions {
-            watchers: vec![FileSystemWatcher {
-                glob_pattern: GlobPattern::String("**/vhdl_ls.toml".to_owned()),
-                kind: None,
-            }],
+            watchers: vec![
+                FileSystemWatcher {
+                    glob_pattern: GlobPattern::String("**/vhdl_ls.toml".to_owned()),
+                    kind: None,
+                },
+                FileSystemWatcher {
+                    glob_pattern: GlobPattern::String("**/*.{vhd,vhdl,v,vh,sv,svh}".to_owned()),
+                    kind: None,
+                },
+            ],
         };
         let register_capability = RegistrationParams {
             registrations: vec![Registration {
@@ -910,6 +1262,72 @@ lib.files = [
         server.initialized_notification();
     }
 
+    #[test]
+    fn initial_analysis_reports_work_done_progress() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        write_file(&root_uri, "file.vhd", "entity ent is\nend entity;\n");
+        let config_uri = write_config(
+            &root_uri,
+            "
+[libraries]
+lib.files = ['file.vhd']
+",
+        );
+
+        let token = NumberOrString::String("vhdl_ls/0".to_owned());
+        let progress = |value: WorkDoneProgress| ProgressParams {
+            token: token.clone(),
+            value: ProgressParamsValue::WorkDone(value),
+        };
+        let report = |message: &str, percentage: u32| {
+            progress(WorkDoneProgress::Report(WorkDoneProgressReport {
+                cancellable: None,
+                message: Some(message.to_owned()),
+                percentage: Some(percentage),
+            }))
+        };
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        mock.expect_request(
+            "window/workDoneProgress/create",
+            WorkDoneProgressCreateParams {
+                token: token.clone(),
+            },
+        );
+        mock.expect_notification(
+            "$/progress",
+            progress(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                title: "Analyzing VHDL project".to_owned(),
+                cancellable: None,
+                message: None,
+                percentage: Some(0),
+            })),
+        );
+        mock.expect_notification("$/progress", report("Parsing lib (1/1)", 50));
+        mock.expect_notification("$/progress", report("Analyzing lib (1/1)", 100));
+        mock.expect_notification(
+            "$/progress",
+            progress(WorkDoneProgress::End(WorkDoneProgressEnd { message: None })),
+        );
+
+        let capabilities = ClientCapabilities {
+            window: Some(WindowClientCapabilities {
+                work_done_progress: Some(true),
+                ..WindowClientCapabilities::default()
+            }),
+            ..ClientCapabilities::default()
+        };
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            root_uri: Some(root_uri),
+            capabilities,
+            ..Default::default()
+        };
+        server.initialize_request(initialize_params);
+        server.initialized_notification();
+    }
+
     #[test]
     fn update_config_file() {
         let (mock, mut server) = setup_server();
@@ -1000,4 +1418,185 @@ lib.files = [
             }],
         });
     }
+
+    #[test]
+    fn workspace_folders_are_merged_into_one_project() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir_a, folder_a) = temp_root_uri();
+        let (_tempdir_b, folder_b) = temp_root_uri();
+        let pkg_uri = write_file(&folder_a, "pkg.vhd", "package pkg is end package;");
+        let config_a_uri = write_config(&folder_a, "[libraries]\nlib_a.files = ['pkg.vhd']");
+        let ent_uri = write_file(
+            &folder_b,
+            "ent.vhd",
+            "library lib_a; use lib_a.pkg.all; entity ent is end entity;",
+        );
+        let config_b_uri = write_config(&folder_b, "[libraries]\nlib_b.files = ['ent.vhd']");
+        let workspace_folder = |uri: &Url| WorkspaceFolder {
+            uri: uri.clone(),
+            name: uri.path().to_owned(),
+        };
+
+        expect_loaded_config_messages(&mock, &config_b_uri);
+        mock.expect_notification_contains(
+            "textDocument/publishDiagnostics",
+            "No declaration of 'lib_a'",
+        );
+        #[allow(deprecated)]
+        server.initialize_request(InitializeParams {
+            root_uri: Some(folder_b.clone()),
+            workspace_folders: Some(vec![workspace_folder(&folder_b)]),
+            ..Default::default()
+        });
+        server.initialized_notification();
+
+        // Libraries of other folders are visible once the folder is added
+        mock.expect_message_contains("Workspace folders have changed, reloading project...");
+        expect_loaded_config_messages(&mock, &config_b_uri);
+        expect_loaded_config_messages(&mock, &config_a_uri);
+        mock.expect_notification("textDocument/publishDiagnostics", no_diagnostics(ent_uri));
+        server.workspace_did_change_workspace_folders(&DidChangeWorkspaceFoldersParams {
+            event: WorkspaceFoldersChangeEvent {
+                added: vec![workspace_folder(&folder_a)],
+                removed: vec![],
+            },
+        });
+        let pkg_file_name = pkg_uri.to_file_path().unwrap();
+        let pkg_source = server.project.get_source(&pkg_file_name).unwrap();
+        let library_names: Vec<String> = server
+            .project
+            .library_mapping_of(&pkg_source)
+            .iter()
+            .map(|library_name| library_name.name_utf8())
+            .collect();
+        assert_eq!(library_names, vec!["lib_a".to_owned()]);
+    }
+
+    #[test]
+    fn workspace_folders_defining_the_same_library_keep_their_files() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir_a, folder_a) = temp_root_uri();
+        let (_tempdir_b, folder_b) = temp_root_uri();
+        let pkg_uri = write_file(&folder_a, "pkg.vhd", "package pkg is end package;");
+        let config_a_uri = write_config(&folder_a, "[libraries]\nlib.files = ['pkg.vhd']");
+        let ent_uri = write_file(
+            &folder_b,
+            "ent.vhd",
+            "library lib; use lib.pkg.all; entity ent is end entity;",
+        );
+        let config_b_uri = write_config(&folder_b, "[libraries]\nlib.files = ['ent.vhd']");
+        let workspace_folder = |uri: &Url| WorkspaceFolder {
+            uri: uri.clone(),
+            name: uri.path().to_owned(),
+        };
+
+        expect_loaded_config_messages(&mock, &config_a_uri);
+        expect_loaded_config_messages(&mock, &config_b_uri);
+        #[allow(deprecated)]
+        server.initialize_request(InitializeParams {
+            root_uri: Some(folder_a.clone()),
+            workspace_folders: Some(vec![
+                workspace_folder(&folder_a),
+                workspace_folder(&folder_b),
+            ]),
+            ..Default::default()
+        });
+        server.initialized_notification();
+
+        for uri in [pkg_uri, ent_uri] {
+            let source = server
+                .project
+                .get_source(&uri.to_file_path().unwrap())
+                .unwrap();
+            let library_names: Vec<String> = server
+                .project
+                .library_mapping_of(&source)
+                .iter()
+                .map(|library_name| library_name.name_utf8())
+                .collect();
+            assert_eq!(library_names, vec!["lib".to_owned()]);
+        }
+    }
+
+    #[test]
+    fn update_vunit_export_file() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        write_file(&root_uri, "file1.vhd", "entity ent1 is\nend entity;\n");
+        write_file(&root_uri, "file2.vhd", "entity ent2 is\nend entity;\n");
+        let vunit_uri = write_file(
+            &root_uri,
+            "vunit.json",
+            r#"{"files": [{"file_name": "file1.vhd", "library_name": "lib"}]}"#,
+        );
+        let config_uri = write_config(&root_uri, "vunit_export = 'vunit.json'");
+
+        let register_options = DidChangeWatchedFilesRegistrationOptions {
+            watchers: vec![
+                FileSystemWatcher {
+                    glob_pattern: GlobPattern::String("**/vhdl_ls.toml".to_owned()),
+                    kind: None,
+                },
+                FileSystemWatcher {
+                    glob_pattern: GlobPattern::String("**/*.{vhd,vhdl,v,vh,sv,svh}".to_owned()),
+                    kind: None,
+                },
+                FileSystemWatcher {
+                    glob_pattern: GlobPattern::String("**/vunit.json".to_owned()),
+                    kind: None,
+                },
+            ],
+        };
+        let register_capability = RegistrationParams {
+            registrations: vec![Registration {
+                id: "workspace/didChangeWatchedFiles".to_owned(),
+                method: "workspace/didChangeWatchedFiles".to_owned(),
+                register_options: serde_json::to_value(register_options).ok(),
+            }],
+        };
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        mock.expect_request("client/registerCapability", register_capability);
+        mock.expect_message_contains("Configuration file has changed, reloading project...");
+        expect_loaded_config_messages(&mock, &config_uri);
+
+        let capabilities = ClientCapabilities {
+            workspace: Some(WorkspaceClientCapabilities {
+                did_change_watched_files: Some(DidChangeWatchedFilesClientCapabilities {
+                    dynamic_registration: Some(true),
+                    relative_pattern_support: Some(false),
+                }),
+                ..WorkspaceClientCapabilities::default()
+            }),
+            ..ClientCapabilities::default()
+        };
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            root_uri: Some(root_uri.clone()),
+            capabilities,
+            ..Default::default()
+        };
+        server.initialize_request(initialize_params);
+        server.initialized_notification();
+        assert!(server
+            .project
+            .get_source(&root_uri.to_file_path().unwrap().join("file2.vhd"))
+            .is_none());
+
+        write_file(
+            &root_uri,
+            "vunit.json",
+            r#"{"files": [{"file_name": "file2.vhd", "library_name": "lib"}]}"#,
+        );
+        server.workspace_did_change_watched_files(&DidChangeWatchedFilesParams {
+            changes: vec![FileEvent {
+                typ: FileChangeType::CHANGED,
+                uri: vunit_uri,
+            }],
+        });
+        assert!(server
+            .project
+            .get_source(&root_uri.to_file_path().unwrap().join("file2.vhd"))
+            .is_some());
+    }
 }
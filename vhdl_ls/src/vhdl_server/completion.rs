@@ -4,7 +4,7 @@ use lsp_types::{
     InsertTextFormat, MarkupContent, MarkupKind,
 };
 use vhdl_lang::ast::{Designator, ObjectClass};
-use vhdl_lang::{kind_str, AnyEntKind, Design, EntRef, InterfaceEnt, Overloaded};
+use vhdl_lang::{kind_str, AnyEntKind, Design, EntRef, InterfaceEnt, Overloaded, Psl};
 
 impl VHDLServer {
     fn completion_item_to_lsp_item(
@@ -222,5 +222,7 @@ fn entity_kind_to_completion_kind(kind: &AnyEntKind) -> CompletionItemKind {
         AnyEntKind::Library => CompletionItemKind::MODULE,
         AnyEntKind::Design(_) => CompletionItemKind::MODULE,
         AnyEntKind::View(_) => CompletionItemKind::INTERFACE,
+        AnyEntKind::Psl(Psl::Parameter) => CompletionItemKind::TYPE_PARAMETER,
+        AnyEntKind::Psl(_) => CompletionItemKind::PROPERTY,
     }
 }
@@ -1,16 +1,70 @@
 use crate::vhdl_server::{file_name_to_uri, to_lsp_range, VHDLServer};
 use fnv::FnvHashMap;
 use lsp_types::{
-    DiagnosticRelatedInformation, DiagnosticSeverity, Location, NumberOrString,
+    DiagnosticRelatedInformation, DiagnosticSeverity, DiagnosticTag, Location, NumberOrString,
     PublishDiagnosticsParams, Url,
 };
 use std::collections::hash_map::Entry;
-use vhdl_lang::{Diagnostic, Severity, SeverityMap};
+use std::path::PathBuf;
+use vhdl_lang::{Diagnostic, ErrorCode, ProgressHandler, Project, Severity, SeverityMap, Source};
 
 impl VHDLServer {
+    /// Returns true if there are edited sources that have not been analyzed yet
+    pub fn has_pending_sources(&self) -> bool {
+        !self.pending_sources.is_empty()
+    }
+
+    /// The files whose changes have not been analyzed yet
+    pub(crate) fn pending_files(&self) -> Vec<PathBuf> {
+        self.pending_sources.keys().cloned().collect()
+    }
+
+    /// Parse the edited sources, analyze the project and publish the diagnostics.
+    /// The event loop does the same on a worker thread.
+    #[cfg(test)]
+    pub fn analyse_pending_sources(&mut self) {
+        if self.has_pending_sources() {
+            let mut project = self.take_project_for_analysis();
+            let diagnostics = project.analyse();
+            self.finish_analysis(project, diagnostics);
+        }
+    }
+
+    /// Take the project such that the edited sources can be applied and analyzed on another
+    /// thread. In the meantime, requests and edits are served from a snapshot of the last
+    /// analysis. The project must be given back with `finish_analysis` before any other
+    /// notification is handled.
+    pub(crate) fn take_project_for_analysis(&mut self) -> Project {
+        let snapshot = self.project.snapshot();
+        let mut project = std::mem::replace(&mut self.project, snapshot);
+        update_sources(&mut project, std::mem::take(&mut self.pending_sources));
+        project
+    }
+
+    /// Give back the project after an analysis on another thread and publish the diagnostics
+    pub(crate) fn finish_analysis(&mut self, project: Project, diagnostics: Vec<Diagnostic>) {
+        self.project = project;
+        self.publish(diagnostics);
+    }
+
+    fn update_pending_sources(&mut self) {
+        update_sources(&mut self.project, std::mem::take(&mut self.pending_sources));
+    }
+
     pub fn publish_diagnostics(&mut self) {
+        self.update_pending_sources();
         let diagnostics = self.project.analyse();
+        self.publish(diagnostics);
+    }
+
+    /// Like `publish_diagnostics` but reports the progress of the analysis
+    pub(crate) fn publish_diagnostics_with_progress(&mut self, progress: &mut dyn ProgressHandler) {
+        self.update_pending_sources();
+        let diagnostics = self.project.analyse_with_progress(progress);
+        self.publish(diagnostics);
+    }
 
+    fn publish(&mut self, diagnostics: Vec<Diagnostic>) {
         if self.settings.no_lint {
             return;
         }
@@ -79,6 +133,16 @@ impl VHDLServer {
     }
 }
 
+/// Parse the edited sources again
+fn update_sources(project: &mut Project, sources: FnvHashMap<PathBuf, Source>) {
+    for (file_name, source) in sources {
+        // The file might have been removed from the project by a configuration change
+        if project.get_source(&file_name).is_some() {
+            project.update_source(&source);
+        }
+    }
+}
+
 fn diagnostics_by_uri(diagnostics: Vec<Diagnostic>) -> FnvHashMap<Url, Vec<Diagnostic>> {
     let mut map: FnvHashMap<Url, Vec<Diagnostic>> = FnvHashMap::default();
 
@@ -133,6 +197,13 @@ fn to_lsp_diagnostic(
         None
     };
 
+    // Allows clients to grey out code that is excluded by conditional analysis
+    let tags = if diagnostic.code == ErrorCode::InactiveRegion {
+        Some(vec![DiagnosticTag::UNNECESSARY])
+    } else {
+        None
+    };
+
     Some(lsp_types::Diagnostic {
         range: to_lsp_range(diagnostic.pos.range()),
         severity: Some(severity),
@@ -140,6 +211,7 @@ fn to_lsp_diagnostic(
         source: Some("vhdl ls".to_owned()),
         message: diagnostic.message,
         related_information,
+        tags,
         ..Default::default()
     })
 }
@@ -232,6 +304,59 @@ lib.files = [
                 range_length: None,
                 text: "3".to_string(),
             }],
-        })
+        });
+        server.analyse_pending_sources();
+    }
+
+    #[test]
+    fn analysis_is_deferred_until_pending_sources_are_analysed() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let file_uri = write_file(
+            &root_uri,
+            "file.vhd",
+            "\
+entity ent is
+end entity;
+",
+        );
+        let config_uri = write_config(
+            &root_uri,
+            "
+[libraries]
+lib.files = [
+  'file.vhd'
+]
+",
+        );
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        initialize_server(&mut server, root_uri.clone());
+
+        // Typing does not trigger analysis
+        for text in ["2", "3"] {
+            server.text_document_did_change_notification(&DidChangeTextDocumentParams {
+                text_document: VersionedTextDocumentIdentifier::new(file_uri.clone(), 0),
+                content_changes: vec![TextDocumentContentChangeEvent {
+                    range: Some(Range::new(
+                        Position::new(1, "end entity".len() as u32),
+                        Position::new(1, "end entity".len() as u32),
+                    )),
+                    range_length: None,
+                    text: format!(" ent{text}"),
+                }],
+            });
+        }
+        assert!(server.has_pending_sources());
+
+        mock.expect_notification_contains(
+            "textDocument/publishDiagnostics",
+            "End identifier mismatch, expected ent",
+        );
+        server.analyse_pending_sources();
+        assert!(!server.has_pending_sources());
+
+        // Nothing left to analyse
+        server.analyse_pending_sources();
     }
 }
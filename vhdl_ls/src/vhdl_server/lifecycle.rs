@@ -1,34 +1,56 @@
-use crate::vhdl_server::{NonProjectFileHandling, VHDLServer};
+use crate::vhdl_server::VHDLServer;
 use lsp_types::*;
-use serde_json::Value;
+use std::path::PathBuf;
 use vhdl_lang::{Message, Project};
 
+const WRITE_CONFIG_ACTION: &str = "Write vhdl_ls.toml";
+/// VHDL and Verilog files are watched such that files that are created,
+/// changed or deleted outside of the editor are added, reloaded or removed
+const SOURCE_FILES: &str = "*.{vhd,vhdl,v,vh,sv,svh}";
+
 impl VHDLServer {
-    fn apply_initial_options(&mut self, options: &Value) {
-        let Some(non_project_file_handling) = options.get("nonProjectFiles") else {
-            return;
-        };
-        match non_project_file_handling {
-            Value::String(handling) => match NonProjectFileHandling::from_string(handling) {
-                None => self.message(Message::error(format!(
-                    "Illegal setting {handling} for nonProjectFiles setting"
-                ))),
-                Some(handling) => self.settings.non_project_file_handling = handling,
-            },
-            _ => self.message(Message::error("nonProjectFiles must be a string")),
-        }
+    /// The config files of the workspace folders followed by the files that libraries are
+    /// imported from
+    fn files_to_watch(&self) -> Vec<PathBuf> {
+        self.config_files
+            .iter()
+            .chain(self.project.config().imported_files())
+            .cloned()
+            .collect()
     }
 
     /// Register capabilities on the client side:
-    /// - watch workspace config file for changes
+    /// - watch workspace config files and the files libraries are imported from for changes
     fn register_capabilities(&mut self) {
         if self.client_supports_did_change_watched_files() {
-            let register_options = DidChangeWatchedFilesRegistrationOptions {
-                watchers: vec![FileSystemWatcher {
-                    glob_pattern: GlobPattern::String("**/vhdl_ls.toml".to_owned()),
+            self.watched_files = self.files_to_watch();
+            let (config_files, imported_files) =
+                self.watched_files.split_at(self.config_files.len());
+            let mut file_names: Vec<String> = Vec::new();
+            for file_name in config_files
+                .iter()
+                .filter_map(|file_name| Some(file_name.file_name()?.to_string_lossy().into_owned()))
+            {
+                // The config files of the workspace folders usually share the same name
+                if !file_names.contains(&file_name) {
+                    file_names.push(file_name);
+                }
+            }
+            if file_names.is_empty() {
+                file_names.push("vhdl_ls.toml".to_owned());
+            }
+            file_names.push(SOURCE_FILES.to_owned());
+            let watchers = file_names
+                .into_iter()
+                .chain(imported_files.iter().filter_map(|file_name| {
+                    Some(file_name.file_name()?.to_string_lossy().into_owned())
+                }))
+                .map(|file_name| FileSystemWatcher {
+                    glob_pattern: GlobPattern::String(format!("**/{file_name}")),
                     kind: None,
-                }],
-            };
+                })
+                .collect();
+            let register_options = DidChangeWatchedFilesRegistrationOptions { watchers };
             let params = RegistrationParams {
                 registrations: vec![Registration {
                     id: "workspace/didChangeWatchedFiles".to_owned(),
@@ -40,26 +62,81 @@ impl VHDLServer {
         }
     }
 
+    /// Re-register the file watchers when the config file or the files libraries are imported
+    /// from have changed
+    pub(crate) fn update_registered_capabilities(&mut self) {
+        if !self.client_supports_did_change_watched_files()
+            || self.watched_files == self.files_to_watch()
+        {
+            return;
+        }
+        let params = UnregistrationParams {
+            unregisterations: vec![Unregistration {
+                id: "workspace/didChangeWatchedFiles".to_owned(),
+                method: "workspace/didChangeWatchedFiles".to_owned(),
+            }],
+        };
+        self.rpc.send_request("client/unregisterCapability", params);
+        self.register_capabilities();
+    }
+
+    /// Offer to write the inferred library mapping to a vhdl_ls.toml file
+    fn offer_inferred_config(&mut self) {
+        if self.inferred_configs.is_empty() {
+            return;
+        }
+        let params = ShowMessageRequestParams {
+            typ: MessageType::INFO,
+            message: "The library mapping was inferred from the workspace since no vhdl_ls.toml file was found".to_owned(),
+            actions: Some(vec![MessageActionItem {
+                title: WRITE_CONFIG_ACTION.to_owned(),
+                properties: Default::default(),
+            }]),
+        };
+        self.rpc.send_request("window/showMessageRequest", params);
+    }
+
+    /// Handle the action that the user selected in response to a `window/showMessageRequest`
+    pub fn message_action_response(&mut self, action: &MessageActionItem) {
+        if action.title == WRITE_CONFIG_ACTION {
+            self.write_inferred_config();
+        }
+    }
+
+    /// Load and analyze the project.
+    /// This is done after the initialize request such that the progress can be shown by the client.
     pub fn initialized_notification(&mut self) {
+        let config = self.load_config();
+        let mut progress = self.begin_work_done_progress("Analyzing VHDL project");
+        self.project = Project::new(config.standard());
+        self.project
+            .update_config_with_progress(config, &mut self.message_filter(), &mut progress);
+        self.update_severity_map();
+        self.update_linters();
         self.register_capabilities();
-        self.publish_diagnostics();
+        self.register_configuration();
+        self.offer_inferred_config();
+        self.publish_diagnostics_with_progress(&mut progress);
+        progress.end();
     }
 
     pub fn initialize_request(&mut self, init_params: InitializeParams) -> InitializeResult {
-        self.config_file = self.root_uri_config_file(&init_params);
-        let config = self.load_config();
-        self.severity_map = *config.severities();
-        self.project = Project::from_config(config, &mut self.message_filter());
-        self.project.enable_all_linters();
         if let Some(options) = &init_params.initialization_options {
             self.apply_initial_options(options)
         }
+        self.workspace_folders = self.root_uri_folders(&init_params);
+        self.update_config_files();
         self.init_params = Some(init_params);
         let trigger_chars: Vec<String> = r"'.".chars().map(|ch| ch.to_string()).collect();
 
         let capabilities = ServerCapabilities {
-            text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                TextDocumentSyncKind::INCREMENTAL,
+            text_document_sync: Some(TextDocumentSyncCapability::Options(
+                TextDocumentSyncOptions {
+                    open_close: Some(true),
+                    change: Some(TextDocumentSyncKind::INCREMENTAL),
+                    save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                    ..Default::default()
+                },
             )),
             declaration_provider: Some(DeclarationCapability::Simple(true)),
             definition_provider: Some(OneOf::Left(true)),
@@ -81,6 +158,13 @@ impl VHDLServer {
                 }),
                 ..Default::default()
             }),
+            workspace: Some(WorkspaceServerCapabilities {
+                workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                    supported: Some(true),
+                    change_notifications: Some(OneOf::Left(true)),
+                }),
+                file_operations: None,
+            }),
             ..Default::default()
         };
 
@@ -100,4 +184,19 @@ impl VHDLServer {
             None => ::std::process::exit(0),
         }
     }
+
+    /// Log a panic that occurred while handling `incident` and analyze the project again,
+    /// since the panic may have left it half-updated.
+    pub(crate) fn recover_from_panic(&mut self, incident: &str, panic_message: &str) {
+        self.message(Message::error(format!(
+            "vhdl_ls crashed while handling {incident}: {panic_message}. Analyzing the project again"
+        )));
+        if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| self.reload_project())).is_err()
+        {
+            self.message(Message::error(
+                "vhdl_ls could not analyze the project again, restart the server to recover"
+                    .to_string(),
+            ));
+        }
+    }
 }
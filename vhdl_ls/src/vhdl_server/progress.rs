@@ -0,0 +1,104 @@
+use crate::rpc_channel::SharedRpcChannel;
+use crate::vhdl_server::VHDLServer;
+use lsp_types::{
+    NumberOrString, ProgressParams, ProgressParamsValue, ProgressToken, WorkDoneProgress,
+    WorkDoneProgressBegin, WorkDoneProgressCreateParams, WorkDoneProgressEnd,
+    WorkDoneProgressReport,
+};
+use vhdl_lang::{Progress, ProgressHandler, ProgressStage};
+
+/// Reports the progress of parsing and analysis to the client as work done progress.
+/// Does nothing if the client does not support work done progress.
+pub(crate) struct WorkDoneProgressReporter {
+    rpc: Option<SharedRpcChannel>,
+    token: ProgressToken,
+    percentage: u32,
+}
+
+impl VHDLServer {
+    fn client_supports_work_done_progress(&self) -> bool {
+        let try_fun = || {
+            self.init_params
+                .as_ref()?
+                .capabilities
+                .window
+                .as_ref()?
+                .work_done_progress
+        };
+        try_fun().unwrap_or(false)
+    }
+
+    /// Create a work done progress on the client and begin it
+    pub(crate) fn begin_work_done_progress(&mut self, title: &str) -> WorkDoneProgressReporter {
+        let token = NumberOrString::String(format!("vhdl_ls/{}", self.progress_tokens));
+        self.progress_tokens += 1;
+        if !self.client_supports_work_done_progress() {
+            return WorkDoneProgressReporter {
+                rpc: None,
+                token,
+                percentage: 0,
+            };
+        }
+
+        self.rpc.send_request(
+            "window/workDoneProgress/create",
+            WorkDoneProgressCreateParams {
+                token: token.clone(),
+            },
+        );
+        let reporter = WorkDoneProgressReporter {
+            rpc: Some(self.rpc.clone()),
+            token,
+            percentage: 0,
+        };
+        reporter.send(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+            title: title.to_owned(),
+            cancellable: None,
+            message: None,
+            percentage: Some(0),
+        }));
+        reporter
+    }
+}
+
+impl WorkDoneProgressReporter {
+    fn send(&self, value: WorkDoneProgress) {
+        if let Some(rpc) = self.rpc.as_ref() {
+            rpc.send_notification(
+                "$/progress",
+                ProgressParams {
+                    token: self.token.clone(),
+                    value: ProgressParamsValue::WorkDone(value),
+                },
+            );
+        }
+    }
+
+    pub fn end(self) {
+        self.send(WorkDoneProgress::End(WorkDoneProgressEnd { message: None }));
+    }
+}
+
+impl ProgressHandler for WorkDoneProgressReporter {
+    fn progress(&mut self, progress: &Progress) {
+        // Parsing is the first half and analysis the second half of the work
+        let offset = match progress.stage {
+            ProgressStage::Parsing => 0,
+            ProgressStage::Analyzing => 50,
+        };
+        let percentage = offset + (progress.done * 50 / progress.total.max(1)) as u32;
+        // Avoid flooding the client with a notification for every file or design unit
+        if percentage <= self.percentage && progress.done < progress.total {
+            return;
+        }
+        self.percentage = percentage;
+        self.send(WorkDoneProgress::Report(WorkDoneProgressReport {
+            cancellable: None,
+            message: Some(format!(
+                "{} {} ({}/{})",
+                progress.stage, progress.library, progress.library_done, progress.library_total
+            )),
+            percentage: Some(percentage),
+        }));
+    }
+}
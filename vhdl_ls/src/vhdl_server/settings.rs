@@ -0,0 +1,396 @@
+use crate::vhdl_server::{NonProjectFileHandling, VHDLServer};
+use lsp_types::{
+    ConfigurationItem, ConfigurationParams, DidChangeConfigurationParams, Registration,
+    RegistrationParams,
+};
+use serde_json::Value;
+use vhdl_lang::{ErrorCode, Message, Severity};
+
+/// The section of the editor settings that contains the settings of vhdl_ls
+const SETTINGS_SECTION: &str = "vhdl_ls";
+
+/// Settings that can be changed in the editor while the server is running.
+/// They take precedence over the command line and the configuration files.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(crate) struct EditorSettings {
+    // Severities that replace the severities of the configuration files
+    severities: Vec<(ErrorCode, Option<Severity>)>,
+    unused_declarations: bool,
+    sensitivity_list: bool,
+    libraries_path: Option<String>,
+    // The configuration file relative to the workspace root
+    pub(crate) config_file: Option<String>,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        EditorSettings {
+            severities: Vec::new(),
+            unused_declarations: true,
+            sensitivity_list: true,
+            libraries_path: None,
+            config_file: None,
+        }
+    }
+}
+
+impl EditorSettings {
+    /// Returns true if the project has to be loaded again when changing between the settings
+    fn requires_reload(&self, other: &EditorSettings) -> bool {
+        self.unused_declarations != other.unused_declarations
+            || self.sensitivity_list != other.sensitivity_list
+            || self.libraries_path != other.libraries_path
+            || self.config_file != other.config_file
+    }
+}
+
+impl VHDLServer {
+    /// Read the settings of the initialization options or of the editor.
+    /// Settings that are not present keep their value, `null` restores the default.
+    fn read_settings(&mut self, settings: &Value) -> EditorSettings {
+        let mut editor_settings = self.editor_settings.clone();
+
+        match settings.get("nonProjectFiles") {
+            None => {}
+            Some(Value::String(handling)) => match NonProjectFileHandling::from_string(handling) {
+                None => self.message(Message::error(format!(
+                    "Illegal setting {handling} for nonProjectFiles setting"
+                ))),
+                Some(handling) => self.settings.non_project_file_handling = handling,
+            },
+            Some(_) => self.message(Message::error("nonProjectFiles must be a string")),
+        }
+
+        match settings.get("lint") {
+            None => {}
+            Some(Value::Null) => editor_settings.severities.clear(),
+            Some(Value::Object(lint)) => match read_severities(lint) {
+                Ok(severities) => editor_settings.severities = severities,
+                Err(err) => self.message(Message::error(format!("Illegal lint setting: {err}"))),
+            },
+            Some(_) => self.message(Message::error("lint must be an object")),
+        }
+
+        match settings.get("linters") {
+            None => {}
+            Some(Value::Null) => {
+                editor_settings.unused_declarations = true;
+                editor_settings.sensitivity_list = true;
+            }
+            Some(Value::Object(linters)) => {
+                for (name, enabled) in linters {
+                    let linter = match name.as_str() {
+                        "unusedDeclarations" => &mut editor_settings.unused_declarations,
+                        "sensitivityList" => &mut editor_settings.sensitivity_list,
+                        _ => {
+                            self.message(Message::error(format!("Unknown linter {name}")));
+                            continue;
+                        }
+                    };
+                    match enabled {
+                        Value::Bool(enabled) => *linter = *enabled,
+                        _ => self
+                            .message(Message::error(format!("linters.{name} must be a boolean"))),
+                    }
+                }
+            }
+            Some(_) => self.message(Message::error("linters must be an object")),
+        }
+
+        for (key, setting) in [
+            ("librariesPath", &mut editor_settings.libraries_path),
+            ("configFile", &mut editor_settings.config_file),
+        ] {
+            match settings.get(key) {
+                None => {}
+                Some(Value::Null) => *setting = None,
+                Some(Value::String(value)) => *setting = Some(value.clone()),
+                Some(_) => self.message(Message::error(format!("{key} must be a string"))),
+            }
+        }
+
+        editor_settings
+    }
+
+    pub(crate) fn apply_initial_options(&mut self, options: &Value) {
+        self.editor_settings = self.read_settings(options);
+    }
+
+    /// Apply settings that were changed in the editor.
+    /// The project is loaded again if the configuration or the linters have changed.
+    fn change_settings(&mut self, settings: &Value) {
+        let previous = self.editor_settings.clone();
+        self.editor_settings = self.read_settings(settings);
+        if self.editor_settings == previous {
+            return;
+        }
+
+        if self.editor_settings.severities != previous.severities {
+            // The severity is not part of the cached diagnostics, all diagnostics are sent again
+            self.diagnostic_cache.values_mut().for_each(Vec::clear);
+        }
+
+        if self.editor_settings.requires_reload(&previous) {
+            self.message(Message::log("Settings have changed, reloading project..."));
+            self.update_config_files();
+            self.reload_project();
+        } else {
+            self.update_severity_map();
+            self.publish_diagnostics();
+        }
+    }
+
+    /// The libraries path of the editor or of the command line
+    pub(crate) fn libraries_path(&self) -> Option<String> {
+        self.editor_settings
+            .libraries_path
+            .clone()
+            .or_else(|| self.settings.libraries_path.clone())
+    }
+
+    /// The severities of the configuration files with the severities of the editor applied
+    pub(crate) fn update_severity_map(&mut self) {
+        self.severity_map = *self.project.config().severities();
+        for (error_code, severity) in self.editor_settings.severities.iter() {
+            self.severity_map[*error_code] = *severity;
+        }
+    }
+
+    pub(crate) fn update_linters(&mut self) {
+        if self.editor_settings.unused_declarations {
+            self.project.enable_unused_declaration_detection();
+        } else {
+            self.project.disable_unused_declaration_detection();
+        }
+        if self.editor_settings.sensitivity_list {
+            self.project.enable_sensitivity_list_linting();
+        } else {
+            self.project.disable_sensitivity_list_linting();
+        }
+    }
+
+    fn client_supports_configuration(&self) -> bool {
+        let try_fun = || {
+            self.init_params
+                .as_ref()?
+                .capabilities
+                .workspace
+                .as_ref()?
+                .configuration
+        };
+        try_fun().unwrap_or(false)
+    }
+
+    fn client_supports_did_change_configuration(&self) -> bool {
+        let try_fun = || {
+            self.init_params
+                .as_ref()?
+                .capabilities
+                .workspace
+                .as_ref()?
+                .did_change_configuration
+                .as_ref()?
+                .dynamic_registration
+        };
+        try_fun().unwrap_or(false)
+    }
+
+    /// Ask the client to notify the server of changed settings
+    /// and request the current settings if the client supports it
+    pub(crate) fn register_configuration(&mut self) {
+        if self.client_supports_did_change_configuration() {
+            let params = RegistrationParams {
+                registrations: vec![Registration {
+                    id: "workspace/didChangeConfiguration".to_owned(),
+                    method: "workspace/didChangeConfiguration".to_owned(),
+                    register_options: None,
+                }],
+            };
+            self.rpc.send_request("client/registerCapability", params);
+        }
+        self.request_configuration();
+    }
+
+    fn request_configuration(&self) {
+        if self.client_supports_configuration() {
+            let params = ConfigurationParams {
+                items: vec![ConfigurationItem {
+                    scope_uri: None,
+                    section: Some(SETTINGS_SECTION.to_owned()),
+                }],
+            };
+            self.rpc.send_request("workspace/configuration", params);
+        }
+    }
+
+    /// Clients that support `workspace/configuration` are asked for the settings,
+    /// other clients send the settings with the notification.
+    pub fn workspace_did_change_configuration(&mut self, params: &DidChangeConfigurationParams) {
+        if self.client_supports_configuration() {
+            self.request_configuration();
+        } else {
+            let settings = params
+                .settings
+                .get(SETTINGS_SECTION)
+                .unwrap_or(&params.settings);
+            self.change_settings(settings);
+        }
+    }
+
+    /// Handle the response to a `workspace/configuration` request
+    pub fn configuration_response(&mut self, items: &[Value]) {
+        if let Some(settings) = items.first() {
+            self.change_settings(settings);
+        }
+    }
+}
+
+/// Read severities in the format of the `lint` table of the configuration files
+fn read_severities(
+    lint: &serde_json::Map<String, Value>,
+) -> Result<Vec<(ErrorCode, Option<Severity>)>, String> {
+    let mut severities = Vec::with_capacity(lint.len());
+    for (name, severity) in lint {
+        let error_code = ErrorCode::try_from(name.as_str())
+            .map_err(|_| format!("'{name}' is not a valid error code"))?;
+        let severity = match severity {
+            Value::String(severity) => Some(
+                Severity::try_from(severity.as_str())
+                    .map_err(|_| format!("'{severity}' is not a valid severity level"))?,
+            ),
+            Value::Bool(false) => None,
+            // Showing a diagnostic keeps the severity of the configuration files
+            Value::Bool(true) => continue,
+            _ => return Err("severity must be a string or boolean".to_owned()),
+        };
+        severities.push((error_code, severity));
+    }
+    Ok(severities)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vhdl_server::tests::{
+        expect_loaded_config_messages, initialize_server, setup_server, temp_root_uri,
+        write_config, write_file,
+    };
+    use lsp_types::{
+        ClientCapabilities, DiagnosticSeverity, InitializeParams, NumberOrString, Position,
+        PublishDiagnosticsParams, Range, WorkspaceClientCapabilities,
+    };
+    use regex::Regex;
+    use serde_json::json;
+
+    #[test]
+    fn severities_of_the_editor_settings_are_applied() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        let code = "architecture rtl of ent is begin end;";
+        let file_uri = write_file(&root_uri, "file.vhd", code);
+        let config_uri = write_config(&root_uri, "[libraries]\nlib.files = ['file.vhd']");
+        let publish_diagnostics = |severity: Option<DiagnosticSeverity>| PublishDiagnosticsParams {
+            uri: file_uri.clone(),
+            diagnostics: Vec::from_iter(severity.map(|severity| lsp_types::Diagnostic {
+                range: Range::new(
+                    Position::new(0, "architecture rtl of ".len() as u32),
+                    Position::new(0, "architecture rtl of ent".len() as u32),
+                ),
+                code: Some(NumberOrString::String("unresolved".to_owned())),
+                severity: Some(severity),
+                source: Some("vhdl ls".to_owned()),
+                message: "No primary unit 'ent' within library 'lib'".to_owned(),
+                ..Default::default()
+            })),
+            version: None,
+        };
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            publish_diagnostics(Some(DiagnosticSeverity::ERROR)),
+        );
+        initialize_server(&mut server, root_uri);
+
+        mock.expect_notification(
+            "textDocument/publishDiagnostics",
+            publish_diagnostics(Some(DiagnosticSeverity::WARNING)),
+        );
+        let settings = json!({"vhdl_ls": {"lint": {"unresolved": "warning"}}});
+        server.workspace_did_change_configuration(&DidChangeConfigurationParams { settings });
+
+        mock.expect_notification("textDocument/publishDiagnostics", publish_diagnostics(None));
+        let settings = json!({"vhdl_ls": {"lint": {"unresolved": false}}});
+        server.workspace_did_change_configuration(&DidChangeConfigurationParams {
+            settings: settings.clone(),
+        });
+
+        // Nothing is sent if the settings did not change
+        server.workspace_did_change_configuration(&DidChangeConfigurationParams { settings });
+    }
+
+    #[test]
+    fn settings_are_pulled_from_the_client() {
+        let (mock, mut server) = setup_server();
+        let (_tempdir, root_uri) = temp_root_uri();
+        write_file(
+            &root_uri,
+            "file.vhd",
+            "architecture rtl of ent is begin end;",
+        );
+        let config_uri = write_config(&root_uri, "[libraries]");
+        let other_config_uri = write_file(
+            &root_uri,
+            "other.toml",
+            "[libraries]\nlib.files = ['file.vhd']",
+        );
+
+        expect_loaded_config_messages(&mock, &config_uri);
+        mock.expect_request(
+            "workspace/configuration",
+            ConfigurationParams {
+                items: vec![ConfigurationItem {
+                    scope_uri: None,
+                    section: Some("vhdl_ls".to_owned()),
+                }],
+            },
+        );
+        #[allow(deprecated)]
+        let initialize_params = InitializeParams {
+            root_uri: Some(root_uri),
+            capabilities: ClientCapabilities {
+                workspace: Some(WorkspaceClientCapabilities {
+                    configuration: Some(true),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        server.initialize_request(initialize_params);
+        server.initialized_notification();
+
+        // A changed config file loads the project again
+        mock.expect_message_contains("Settings have changed, reloading project...");
+        expect_loaded_config_messages(&mock, &other_config_uri);
+        mock.expect_notification_contains_regex(
+            "textDocument/publishDiagnostics",
+            Regex::new("No primary unit 'ent' within library 'lib'").unwrap(),
+        );
+        server.configuration_response(&[json!({"configFile": "other.toml"})]);
+
+        // Clients that support pulling are asked for the settings when they change
+        mock.expect_request(
+            "workspace/configuration",
+            ConfigurationParams {
+                items: vec![ConfigurationItem {
+                    scope_uri: None,
+                    section: Some("vhdl_ls".to_owned()),
+                }],
+            },
+        );
+        server.workspace_did_change_configuration(&DidChangeConfigurationParams {
+            settings: Value::Null,
+        });
+    }
+}
@@ -3,20 +3,23 @@ use crate::vhdl_server::{
     NonProjectFileHandling, VHDLServer,
 };
 use lsp_types::{
-    DidChangeTextDocumentParams, DidOpenTextDocumentParams, DocumentHighlight,
-    DocumentHighlightKind, GotoDefinitionResponse, Hover, HoverContents, Location, MarkupContent,
-    MarkupKind, ReferenceParams, TextDocumentItem, TextDocumentPositionParams,
+    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+    DidSaveTextDocumentParams, DocumentHighlight, DocumentHighlightKind, GotoDefinitionResponse,
+    Hover, HoverContents, Location, MarkupContent, MarkupKind, ReferenceParams, TextDocumentItem,
+    TextDocumentPositionParams,
 };
-use vhdl_lang::{Message, Source};
+use vhdl_lang::{DeclarationDetails, InterfaceDetails, Message, Source};
 
 impl VHDLServer {
     pub fn text_document_did_open_notification(&mut self, params: &DidOpenTextDocumentParams) {
         let TextDocumentItem { uri, text, .. } = &params.text_document;
         let file_name = uri_to_file_name(uri);
+        self.open_files.insert(file_name.clone());
         if let Some(source) = self.project.get_source(&file_name) {
             source.change(None, text);
-            self.project.update_source(&source);
-            self.publish_diagnostics();
+            // Open files of lazily analyzed libraries are analyzed as well
+            self.project.set_source_open(&source, true);
+            self.pending_sources.insert(file_name, source);
         } else {
             match self.settings.non_project_file_handling {
                 NonProjectFileHandling::Ignore => {}
@@ -25,14 +28,18 @@ impl VHDLServer {
                         "Opening file {} that is not part of the project",
                         file_name.to_string_lossy()
                     )));
-                    self.project
-                        .update_source(&Source::inline(&file_name, text));
-                    self.publish_diagnostics();
+                    let source = Source::inline(&file_name, text);
+                    self.project.update_source(&source);
+                    self.non_project_files.insert(file_name.clone());
+                    self.pending_sources.insert(file_name, source);
                 }
             }
         }
     }
 
+    /// Apply the changes to the text of the source.
+    /// The source is parsed and analyzed later on a worker thread of the event loop,
+    /// until then requests are served from the last analysis.
     pub fn text_document_did_change_notification(&mut self, params: &DidChangeTextDocumentParams) {
         let file_name = uri_to_file_name(&params.text_document.uri);
         if let Some(source) = self.project.get_source(&file_name) {
@@ -40,8 +47,7 @@ impl VHDLServer {
                 let range = content_change.range.map(from_lsp_range);
                 source.change(range.as_ref(), &content_change.text);
             }
-            self.project.update_source(&source);
-            self.publish_diagnostics();
+            self.pending_sources.insert(file_name, source);
         } else if self.settings.non_project_file_handling != NonProjectFileHandling::Ignore {
             self.message(Message::error(format!(
                 "Changing file {} that is not part of the project",
@@ -50,6 +56,45 @@ impl VHDLServer {
         }
     }
 
+    /// Discard the unsaved changes of a closed file by reading it from disk again.
+    /// Files that are not part of the project are removed.
+    pub fn text_document_did_close_notification(&mut self, params: &DidCloseTextDocumentParams) {
+        let file_name = uri_to_file_name(&params.text_document.uri);
+        self.open_files.remove(&file_name);
+        let Some(source) = self.project.get_source(&file_name) else {
+            return;
+        };
+        self.project.set_source_open(&source, false);
+        if self.non_project_files.remove(&file_name) || source.reload().is_err() {
+            // The file is not part of the project or it was deleted while it was open
+            self.pending_sources.remove(&file_name);
+            self.project.remove_file(&file_name);
+            self.publish_diagnostics();
+        } else {
+            self.pending_sources.insert(file_name, source);
+        }
+    }
+
+    /// The contents of the editor and the disk are the same after saving.
+    /// A file that is saved where it matches the file patterns of a library,
+    /// e.g., a new file, is added to the project.
+    pub fn text_document_did_save_notification(&mut self, params: &DidSaveTextDocumentParams) {
+        let file_name = uri_to_file_name(&params.text_document.uri);
+        if self.project.get_source(&file_name).is_some()
+            && !self.non_project_files.contains(&file_name)
+        {
+            return;
+        }
+        if let Some(source) = self
+            .project
+            .add_file(&file_name, &mut self.message_filter())
+        {
+            self.non_project_files.remove(&file_name);
+            self.project.set_source_open(&source, true);
+            self.publish_diagnostics();
+        }
+    }
+
     pub fn text_document_declaration(
         &mut self,
         params: &TextDocumentPositionParams,
@@ -105,12 +150,12 @@ impl VHDLServer {
             .project
             .find_declaration(&source, from_lsp_pos(params.position))?;
 
-        let value = self.project.format_declaration(ent)?;
+        let details = self.project.declaration_details(ent)?;
 
         Some(Hover {
             contents: HoverContents::Markup(MarkupContent {
                 kind: MarkupKind::Markdown,
-                value: format!("```vhdl\n{value}\n```"),
+                value: hover_markdown(&details),
             }),
             range: None,
         })
@@ -164,3 +209,50 @@ impl VHDLServer {
         )
     }
 }
+
+/// Render the declaration followed by its comments, the binding of instances,
+/// the base type and range of types and the generics and ports of entities and components
+fn hover_markdown(details: &DeclarationDetails) -> String {
+    let mut sections = vec![format!("```vhdl\n{}\n```", details.declaration)];
+    if !details.comments.is_empty() {
+        sections.push(details.comments.join("\n"));
+    }
+    if let Some(binding) = &details.binding {
+        sections.push(format!("Bound to `{binding}`"));
+    }
+    let type_lines: Vec<_> = [("Base type", &details.base_type), ("Range", &details.range)]
+        .into_iter()
+        .filter_map(|(label, value)| Some(format!("{label}: `{}`", value.as_ref()?)))
+        .collect();
+    if !type_lines.is_empty() {
+        // Two trailing spaces force a line break in markdown
+        sections.push(type_lines.join("  \n"));
+    }
+    for (title, interface) in [("Generics", &details.generics), ("Ports", &details.ports)] {
+        if !interface.is_empty() {
+            sections.push(interface_table(title, interface));
+        }
+    }
+    sections.join("\n\n")
+}
+
+fn interface_table(title: &str, interface: &[InterfaceDetails]) -> String {
+    fn cell(value: &str) -> String {
+        if value.is_empty() {
+            String::new()
+        } else {
+            format!("`{}`", value.replace('|', "\\|"))
+        }
+    }
+    let mut table = format!("**{title}**\n\n| Name | Mode | Type | Default |\n|---|---|---|---|");
+    for item in interface {
+        table.push_str(&format!(
+            "\n| {} | {} | {} | {} |",
+            cell(&item.name),
+            item.mode.as_deref().unwrap_or_default(),
+            cell(&item.typ),
+            cell(item.default.as_deref().unwrap_or_default()),
+        ));
+    }
+    table
+}
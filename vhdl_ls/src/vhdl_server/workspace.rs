@@ -1,32 +1,105 @@
 use crate::vhdl_server::{srcpos_to_location, to_symbol_kind, uri_to_file_name, VHDLServer};
 use fuzzy_matcher::FuzzyMatcher;
 use lsp_types::{
-    DidChangeWatchedFilesParams, OneOf, WorkspaceSymbol, WorkspaceSymbolParams,
-    WorkspaceSymbolResponse,
+    DidChangeWatchedFilesParams, DidChangeWorkspaceFoldersParams, FileChangeType, OneOf,
+    WorkspaceSymbol, WorkspaceSymbolParams, WorkspaceSymbolResponse,
 };
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
+use std::path::{Path, PathBuf};
 use vhdl_lang::ast::Designator;
 use vhdl_lang::{EntRef, Message};
 
 impl VHDLServer {
     pub fn workspace_did_change_watched_files(&mut self, params: &DidChangeWatchedFilesParams) {
-        if let Some(config_file) = &self.config_file {
-            let config_file_has_changed = params
-                .changes
-                .iter()
-                .any(|change| uri_to_file_name(&change.uri).as_path() == config_file);
-            if config_file_has_changed {
-                self.message(Message::log(
-                    "Configuration file has changed, reloading project...",
-                ));
-                let config = self.load_config();
-                self.severity_map = *config.severities();
+        let imported_files = self.project.config().imported_files();
+        let config_file_has_changed = params.changes.iter().any(|change| {
+            let file_name = uri_to_file_name(&change.uri);
+            self.config_files.contains(&file_name) || imported_files.contains(&file_name)
+        });
+        if config_file_has_changed {
+            self.message(Message::log(
+                "Configuration file has changed, reloading project...",
+            ));
+            self.reload_project();
+            return;
+        }
+
+        let mut has_changed = false;
+        for change in params.changes.iter() {
+            let file_name = uri_to_file_name(&change.uri);
+            // The contents of open files are owned by the editor
+            if self.open_files.contains(&file_name) {
+                continue;
+            }
+            has_changed |= if change.typ == FileChangeType::DELETED {
+                self.project.remove_file(&file_name)
+            } else {
+                self.reload_file(&file_name)
+            };
+        }
+        if has_changed {
+            self.publish_diagnostics();
+        }
+    }
 
-                self.project
-                    .update_config(config, &mut self.message_filter());
-                self.publish_diagnostics();
+    /// Load the configurations of the added workspace folders
+    /// and remove the libraries of the removed folders
+    pub fn workspace_did_change_workspace_folders(
+        &mut self,
+        params: &DidChangeWorkspaceFoldersParams,
+    ) {
+        let removed: Vec<PathBuf> = params
+            .event
+            .removed
+            .iter()
+            .filter_map(|folder| self.workspace_folder_path(folder))
+            .collect();
+        self.workspace_folders
+            .retain(|folder| !removed.contains(folder));
+        for folder in params.event.added.iter() {
+            if let Some(folder) = self.workspace_folder_path(folder) {
+                if !self.workspace_folders.contains(&folder) {
+                    self.workspace_folders.push(folder);
+                }
+            }
+        }
+        self.update_config_files();
+        self.message(Message::log(
+            "Workspace folders have changed, reloading project...",
+        ));
+        self.reload_project();
+    }
+
+    /// Load the configuration and analyze the project again
+    pub(crate) fn reload_project(&mut self) {
+        let config = self.load_config();
+        let mut progress = self.begin_work_done_progress("Analyzing VHDL project");
+        self.project
+            .update_config_with_progress(config, &mut self.message_filter(), &mut progress);
+        self.update_severity_map();
+        self.update_linters();
+        self.update_registered_capabilities();
+        self.publish_diagnostics_with_progress(&mut progress);
+        progress.end();
+    }
+
+    /// Read a file of the project that was created or changed outside of the editor.
+    /// New files are added to the libraries whose file patterns match them.
+    fn reload_file(&mut self, file_name: &Path) -> bool {
+        match self.project.get_source(file_name) {
+            Some(source) if !self.project.library_mapping_of(&source).is_empty() => {
+                if let Err(err) = source.reload() {
+                    self.message(Message::file_error(err.to_string(), file_name));
+                    return false;
+                }
+                self.project.update_source(&source);
+                true
             }
+            _ => self
+                .project
+                .add_file(file_name, &mut self.message_filter())
+                .is_some(),
         }
     }
 